@@ -0,0 +1,412 @@
+//! Layout of Clarity values in the memory of the modules generated by
+//! clar2wasm.
+//!
+//! This crate only depends on `core` and `alloc`, so that light clients can
+//! decode values from the memory of a contract without the compiler, the
+//! runtime or the Clarity VM. clar2wasm re-exports it as `clar2wasm::layout`.
+//!
+//! The types are described by [`LayoutType`], a subset of the Clarity type
+//! signatures which only keeps what determines the layout.
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+// Bytes for principal version
+pub const PRINCIPAL_VERSION_BYTES: usize = 1;
+// Number of bytes in principal hash
+pub const PRINCIPAL_HASH_BYTES: usize = 20;
+// Standard principal version + hash
+pub const PRINCIPAL_BYTES: usize = PRINCIPAL_VERSION_BYTES + PRINCIPAL_HASH_BYTES;
+// Number of bytes used to store the length of the contract name
+pub const CONTRACT_NAME_LENGTH_BYTES: usize = 1;
+// 1 byte for version, 20 bytes for hash, 4 bytes for contract name length (0)
+pub const STANDARD_PRINCIPAL_BYTES: usize = PRINCIPAL_BYTES + CONTRACT_NAME_LENGTH_BYTES;
+// Max length of a contract name
+pub const CONTRACT_NAME_MAX_LENGTH: usize = 128;
+// Standard principal, but at most 128 character function name
+pub const PRINCIPAL_BYTES_MAX: usize = STANDARD_PRINCIPAL_BYTES + CONTRACT_NAME_MAX_LENGTH;
+
+/// The type of a value, as far as its layout is concerned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutType {
+    Int,
+    UInt,
+    Bool,
+    /// Principals, callables and trait references.
+    Principal,
+    /// A buffer of at most this number of bytes.
+    Buffer(u32),
+    /// A `string-ascii` of at most this number of characters.
+    StringAscii(u32),
+    /// A `string-utf8` of at most this number of characters.
+    StringUtf8(u32),
+    /// A list of at most `max_len` elements.
+    List(Box<LayoutType>, u32),
+    Optional(Box<LayoutType>),
+    Response(Box<LayoutType>, Box<LayoutType>),
+    /// The types of the fields of a tuple, sorted by field name.
+    Tuple(Vec<LayoutType>),
+    NoType,
+}
+
+impl LayoutType {
+    /// Return the number of bytes required to representation of a value of
+    /// this type.
+    ///
+    /// For in-memory types, this is just the size of the offset and
+    /// length. For non-in-memory types, this is the size of the value itself.
+    pub fn size(&self) -> i32 {
+        match self {
+            LayoutType::Int | LayoutType::UInt => 16, // low: i64, high: i64
+            LayoutType::Bool | LayoutType::NoType => 4, // i32
+            LayoutType::Principal
+            | LayoutType::Buffer(_)
+            | LayoutType::StringAscii(_)
+            | LayoutType::StringUtf8(_)
+            | LayoutType::List(..) => 8, // offset: i32, length: i32
+            LayoutType::Optional(inner) => 4 + inner.size(), // indicator: i32, value: inner
+            LayoutType::Response(ok, err) => 4 + ok.size() + err.size(),
+            LayoutType::Tuple(fields) => fields.iter().map(LayoutType::size).sum(),
+        }
+    }
+
+    /// Return the number of bytes required to store a value of this type.
+    pub fn in_memory_size(&self, include_repr: bool) -> i32 {
+        let repr = if include_repr { 8 } else { 0 }; // offset + length
+        match self {
+            LayoutType::Int | LayoutType::UInt => 16,
+            LayoutType::Bool | LayoutType::NoType => 4,
+            LayoutType::Principal => PRINCIPAL_BYTES_MAX as i32 + repr,
+            LayoutType::Buffer(length) | LayoutType::StringAscii(length) => *length as i32 + repr,
+            LayoutType::StringUtf8(length) => *length as i32 * 4 + repr,
+            LayoutType::List(elem, max_len) => {
+                if include_repr {
+                    repr + *max_len as i32 * elem.in_memory_size(true)
+                } else {
+                    *max_len as i32 * elem.size()
+                }
+            }
+            LayoutType::Optional(inner) => 4 + inner.in_memory_size(include_repr),
+            LayoutType::Response(ok, err) => {
+                4 + ok.in_memory_size(include_repr) + err.in_memory_size(include_repr)
+            }
+            LayoutType::Tuple(fields) => fields
+                .iter()
+                .map(|field| field.in_memory_size(include_repr))
+                .sum(),
+        }
+    }
+
+    /// Return true if the value of this type stays in memory, and false if
+    /// it is stored on the data stack.
+    pub fn is_in_memory(&self) -> bool {
+        matches!(
+            self,
+            LayoutType::Principal
+                | LayoutType::Buffer(_)
+                | LayoutType::StringAscii(_)
+                | LayoutType::StringUtf8(_)
+                | LayoutType::List(..)
+        )
+    }
+}
+
+/// A value decoded from memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutValue {
+    Int(i128),
+    UInt(u128),
+    Bool(bool),
+    Principal {
+        version: u8,
+        hash: [u8; PRINCIPAL_HASH_BYTES],
+        /// The name of the contract, for a contract principal.
+        contract_name: Option<String>,
+    },
+    Buffer(Vec<u8>),
+    StringAscii(Vec<u8>),
+    /// The unicode scalars of the string.
+    StringUtf8(Vec<u32>),
+    List(Vec<LayoutValue>),
+    Optional(Option<Box<LayoutValue>>),
+    Response(Result<Box<LayoutValue>, Box<LayoutValue>>),
+    /// The values of the fields, sorted by field name.
+    Tuple(Vec<LayoutValue>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The value is not entirely in the memory.
+    OutOfBounds {
+        offset: i32,
+        length: i32,
+    },
+    InvalidIndicator(i32),
+    InvalidNoType,
+    InvalidContractName,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::OutOfBounds { offset, length } => {
+                write!(f, "{length} bytes at offset {offset} are out of bounds")
+            }
+            LayoutError::InvalidIndicator(indicator) => write!(f, "invalid indicator {indicator}"),
+            LayoutError::InvalidNoType => write!(f, "invalid NoType in value"),
+            LayoutError::InvalidContractName => write!(f, "invalid contract name"),
+        }
+    }
+}
+
+fn bytes(memory: &[u8], offset: i32, length: i32) -> Result<&[u8], LayoutError> {
+    usize::try_from(offset)
+        .ok()
+        .zip(usize::try_from(length).ok())
+        .and_then(|(start, len)| memory.get(start..start.checked_add(len)?))
+        .ok_or(LayoutError::OutOfBounds { offset, length })
+}
+
+fn read_i32(memory: &[u8], offset: i32) -> Result<i32, LayoutError> {
+    let mut buffer = [0u8; 4];
+    buffer.copy_from_slice(bytes(memory, offset, 4)?);
+    Ok(i32::from_le_bytes(buffer))
+}
+
+fn read_u128(memory: &[u8], offset: i32) -> Result<u128, LayoutError> {
+    let mut buffer = [0u8; 16];
+    buffer.copy_from_slice(bytes(memory, offset, 16)?);
+    Ok(u128::from_le_bytes(buffer))
+}
+
+/// Read a value of type `ty` from `memory` at `offset`.
+///
+/// In-memory values require one extra level of indirection, so this function
+/// will read the offset and length from the memory, then read the actual
+/// value.
+pub fn read_value_indirect(
+    memory: &[u8],
+    ty: &LayoutType,
+    mut offset: i32,
+) -> Result<LayoutValue, LayoutError> {
+    let mut length = ty.size();
+    if ty.is_in_memory() {
+        (offset, length) = (read_i32(memory, offset)?, read_i32(memory, offset + 4)?);
+    }
+    read_value(memory, ty, offset, length)
+}
+
+/// Read a value of type `ty` from `memory` at `offset` with `length`.
+pub fn read_value(
+    memory: &[u8],
+    ty: &LayoutType,
+    offset: i32,
+    length: i32,
+) -> Result<LayoutValue, LayoutError> {
+    match ty {
+        LayoutType::Int => Ok(LayoutValue::Int(read_u128(memory, offset)? as i128)),
+        LayoutType::UInt => Ok(LayoutValue::UInt(read_u128(memory, offset)?)),
+        LayoutType::Bool => Ok(LayoutValue::Bool(read_i32(memory, offset)? != 0)),
+        LayoutType::Buffer(_) => Ok(LayoutValue::Buffer(bytes(memory, offset, length)?.to_vec())),
+        LayoutType::StringAscii(_) => Ok(LayoutValue::StringAscii(
+            bytes(memory, offset, length)?.to_vec(),
+        )),
+        LayoutType::StringUtf8(_) => Ok(LayoutValue::StringUtf8(
+            bytes(memory, offset, length)?
+                .chunks_exact(4)
+                .map(|scalar| u32::from_be_bytes([scalar[0], scalar[1], scalar[2], scalar[3]]))
+                .collect(),
+        )),
+        LayoutType::Principal => {
+            let principal = bytes(memory, offset, STANDARD_PRINCIPAL_BYTES as i32)?;
+            let mut hash = [0u8; PRINCIPAL_HASH_BYTES];
+            hash.copy_from_slice(&principal[PRINCIPAL_VERSION_BYTES..PRINCIPAL_BYTES]);
+            let name_length = principal[PRINCIPAL_BYTES] as i32;
+            let contract_name = if name_length == 0 {
+                None
+            } else {
+                let name = bytes(
+                    memory,
+                    offset + STANDARD_PRINCIPAL_BYTES as i32,
+                    name_length,
+                )?;
+                Some(
+                    String::from_utf8(name.to_vec())
+                        .map_err(|_| LayoutError::InvalidContractName)?,
+                )
+            };
+            Ok(LayoutValue::Principal {
+                version: principal[0],
+                hash,
+                contract_name,
+            })
+        }
+        LayoutType::List(elem, _) => {
+            // Check the bounds of the whole list before reading its elements.
+            bytes(memory, offset, length)?;
+            (offset..offset + length)
+                .step_by(elem.size() as usize)
+                .map(|elem_offset| read_value_indirect(memory, elem, elem_offset))
+                .collect::<Result<_, _>>()
+                .map(LayoutValue::List)
+        }
+        LayoutType::Optional(inner) => match read_i32(memory, offset)? {
+            0 => Ok(LayoutValue::Optional(None)),
+            1 => Ok(LayoutValue::Optional(Some(Box::new(read_value_indirect(
+                memory,
+                inner,
+                offset + 4,
+            )?)))),
+            indicator => Err(LayoutError::InvalidIndicator(indicator)),
+        },
+        LayoutType::Response(ok, err) => match read_i32(memory, offset)? {
+            0 => Ok(LayoutValue::Response(Err(Box::new(read_value_indirect(
+                memory,
+                err,
+                offset + 4 + ok.size(),
+            )?)))),
+            1 => Ok(LayoutValue::Response(Ok(Box::new(read_value_indirect(
+                memory,
+                ok,
+                offset + 4,
+            )?)))),
+            indicator => Err(LayoutError::InvalidIndicator(indicator)),
+        },
+        LayoutType::Tuple(fields) => {
+            let mut field_offset = offset;
+            fields
+                .iter()
+                .map(|field| {
+                    let value = read_value_indirect(memory, field, field_offset);
+                    field_offset += field.size();
+                    value
+                })
+                .collect::<Result<_, _>>()
+                .map(LayoutValue::Tuple)
+        }
+        LayoutType::NoType => Err(LayoutError::InvalidNoType),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn read_values() {
+        let mut memory = vec![0u8; 256];
+        // A response of an optional int, and a tuple of a bool and a buffer
+        // stored at 100.
+        memory[0..4].copy_from_slice(&1i32.to_le_bytes());
+        memory[4..8].copy_from_slice(&1i32.to_le_bytes());
+        memory[8..24].copy_from_slice(&(-42i128).to_le_bytes());
+        memory[24..28].copy_from_slice(&1i32.to_le_bytes());
+        memory[28..32].copy_from_slice(&100i32.to_le_bytes());
+        memory[32..36].copy_from_slice(&3i32.to_le_bytes());
+        memory[100..103].copy_from_slice(b"abc");
+
+        let ty = LayoutType::Response(
+            Box::new(LayoutType::Optional(Box::new(LayoutType::Int))),
+            Box::new(LayoutType::Tuple(vec![
+                LayoutType::Bool,
+                LayoutType::Buffer(10),
+            ])),
+        );
+        assert_eq!(
+            read_value_indirect(&memory, &ty, 0),
+            Ok(LayoutValue::Response(Ok(Box::new(LayoutValue::Optional(
+                Some(Box::new(LayoutValue::Int(-42)))
+            )))))
+        );
+
+        memory[0..4].copy_from_slice(&0i32.to_le_bytes());
+        assert_eq!(
+            read_value_indirect(&memory, &ty, 0),
+            Ok(LayoutValue::Response(Err(Box::new(LayoutValue::Tuple(
+                vec![
+                    LayoutValue::Bool(true),
+                    LayoutValue::Buffer(b"abc".to_vec())
+                ]
+            )))))
+        );
+
+        memory[0..4].copy_from_slice(&2i32.to_le_bytes());
+        assert_eq!(
+            read_value_indirect(&memory, &ty, 0),
+            Err(LayoutError::InvalidIndicator(2))
+        );
+    }
+
+    #[test]
+    fn read_list_of_principals() {
+        let mut memory = vec![0u8; 256];
+        // The list at 0 holds the offsets and lengths of the principals.
+        let contract = [&[0x1a][..], &[7; 20], &[3], b"foo"].concat();
+        let standard = [&[0x16][..], &[9; 20], &[0]].concat();
+        memory[100..100 + contract.len()].copy_from_slice(&contract);
+        memory[200..200 + standard.len()].copy_from_slice(&standard);
+        for (i, (offset, len)) in [(100i32, contract.len()), (200, standard.len())]
+            .into_iter()
+            .enumerate()
+        {
+            memory[i * 8..i * 8 + 4].copy_from_slice(&offset.to_le_bytes());
+            memory[i * 8 + 4..i * 8 + 8].copy_from_slice(&(len as i32).to_le_bytes());
+        }
+
+        let ty = LayoutType::List(Box::new(LayoutType::Principal), 5);
+        assert_eq!(
+            read_value(&memory, &ty, 0, 16),
+            Ok(LayoutValue::List(vec![
+                LayoutValue::Principal {
+                    version: 0x1a,
+                    hash: [7; 20],
+                    contract_name: Some("foo".into()),
+                },
+                LayoutValue::Principal {
+                    version: 0x16,
+                    hash: [9; 20],
+                    contract_name: None,
+                },
+            ]))
+        );
+    }
+
+    #[test]
+    fn read_out_of_bounds() {
+        let memory = [0u8; 16];
+        assert_eq!(
+            read_value(&memory, &LayoutType::UInt, 8, 16),
+            Err(LayoutError::OutOfBounds {
+                offset: 8,
+                length: 16
+            })
+        );
+        assert_eq!(
+            read_value(&memory, &LayoutType::Buffer(4), -1, 4),
+            Err(LayoutError::OutOfBounds {
+                offset: -1,
+                length: 4
+            })
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        let ty = LayoutType::Tuple(vec![
+            LayoutType::Optional(Box::new(LayoutType::UInt)),
+            LayoutType::List(Box::new(LayoutType::StringUtf8(2)), 3),
+        ]);
+        assert_eq!(ty.size(), 20 + 8);
+        assert_eq!(ty.in_memory_size(false), 20 + 3 * 8);
+        assert_eq!(ty.in_memory_size(true), 20 + 8 + 3 * (8 + 8));
+        assert!(!ty.is_in_memory());
+    }
+}
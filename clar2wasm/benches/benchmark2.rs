@@ -1,8 +1,8 @@
 #![allow(clippy::expect_used, clippy::unwrap_used)]
-use clar2wasm::compile;
 use clar2wasm::datastore::{BurnDatastore, Datastore, StacksConstants};
 use clar2wasm::initialize::initialize_contract;
 use clar2wasm::wasm_utils::call_function;
+use clar2wasm::{compile, CompileOptions};
 use clarity::consts::CHAIN_ID_TESTNET;
 use clarity::types::StacksEpochId;
 use clarity::vm::analysis::{run_analysis, AnalysisDatabase};
@@ -44,6 +44,7 @@ fn wasm_fold_add_square(c: &mut Criterion) {
                 ClarityVersion::latest(),
                 StacksEpochId::latest(),
                 analysis_db,
+                CompileOptions::default(),
             )
             .map_err(|_| CheckErrors::Expects("Compilation failure".to_string()))
         })
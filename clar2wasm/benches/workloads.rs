@@ -0,0 +1,273 @@
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+//! Representative contract workloads, run with both the compiled Wasm and
+//! the interpreter. Each workload is a criterion group containing a `wasm`
+//! and an `interpreter` benchmark, so their throughput can be compared and
+//! tracked across releases with criterion baselines.
+
+use clar2wasm::datastore::{BurnDatastore, Datastore, StacksConstants};
+use clar2wasm::initialize::initialize_contract;
+use clar2wasm::wasm_utils::call_function;
+use clar2wasm::{compile, CompileOptions};
+use clarity::consts::CHAIN_ID_TESTNET;
+use clarity::types::StacksEpochId;
+use clarity::vm::analysis::{run_analysis, AnalysisDatabase};
+use clarity::vm::ast::build_ast_with_diagnostics;
+use clarity::vm::contexts::GlobalContext;
+use clarity::vm::costs::LimitedCostTracker;
+use clarity::vm::database::{ClarityDatabase, MemoryBackingStore};
+use clarity::vm::errors::CheckErrors;
+use clarity::vm::types::{QualifiedContractIdentifier, StandardPrincipalData};
+use clarity::vm::{
+    eval_all, CallStack, ClarityVersion, ContractContext, ContractName, Environment, Value,
+};
+use criterion::measurement::WallTime;
+use criterion::{criterion_group, criterion_main, BenchmarkGroup, Criterion};
+
+struct Workload {
+    name: &'static str,
+    contract: &'static str,
+    function: &'static str,
+    args: fn() -> Vec<Value>,
+}
+
+const WORKLOADS: &[Workload] = &[
+    Workload {
+        name: "token-transfer",
+        contract: include_str!("../tests/contracts/token-transfer-bench.clar"),
+        function: "transfer",
+        args: || vec![Value::UInt(1)],
+    },
+    Workload {
+        name: "amm-swap",
+        contract: include_str!("../tests/contracts/amm-swap-bench.clar"),
+        function: "swap-x-for-y",
+        args: || vec![Value::UInt(1_000_000), Value::UInt(1)],
+    },
+    Workload {
+        name: "nft-mint",
+        contract: include_str!("../tests/contracts/nft-mint-bench.clar"),
+        function: "mint",
+        args: Vec::new,
+    },
+    Workload {
+        name: "list-processing",
+        contract: include_str!("../tests/contracts/list-processing-bench.clar"),
+        function: "sum-even-squares",
+        args: || {
+            vec![
+                Value::cons_list_unsanitized((1..=4096).map(Value::UInt).collect())
+                    .expect("failed to construct list argument"),
+            ]
+        },
+    },
+    Workload {
+        name: "nested-responses",
+        contract: include_str!("../tests/contracts/nested-response-bench.clar"),
+        function: "summaries",
+        args: || {
+            vec![
+                Value::cons_list_unsanitized((1..=256).map(Value::UInt).collect())
+                    .expect("failed to construct list argument"),
+            ]
+        },
+    },
+];
+
+fn contract_id(workload: &Workload) -> QualifiedContractIdentifier {
+    QualifiedContractIdentifier::new(
+        StandardPrincipalData::transient(),
+        ContractName::from(workload.name),
+    )
+}
+
+/// Checks the result of a first call, outside of the benchmark: a workload
+/// which fails would only measure the error path.
+fn check_result(workload: &Workload, result: Value) {
+    assert!(
+        result.clone().expect_result_ok().is_ok(),
+        "{}: unexpected result {result}",
+        workload.name
+    );
+}
+
+fn bench_wasm(group: &mut BenchmarkGroup<WallTime>, workload: &Workload) {
+    let contract_id = contract_id(workload);
+    let mut datastore = Datastore::new();
+    let constants = StacksConstants::default();
+    let burn_datastore = BurnDatastore::new(constants);
+    let mut clarity_store = MemoryBackingStore::new();
+    let mut conn = ClarityDatabase::new(&mut datastore, &burn_datastore, &burn_datastore);
+    conn.begin();
+    conn.set_clarity_epoch_version(StacksEpochId::latest())
+        .unwrap();
+    conn.commit().unwrap();
+    let mut contract_context = ContractContext::new(contract_id.clone(), ClarityVersion::latest());
+
+    let mut compile_result = clarity_store
+        .as_analysis_db()
+        .execute(|analysis_db| {
+            compile(
+                workload.contract,
+                &contract_id,
+                LimitedCostTracker::new_free(),
+                ClarityVersion::latest(),
+                StacksEpochId::latest(),
+                analysis_db,
+                CompileOptions::default(),
+            )
+            .map_err(|_| CheckErrors::Expects("Compilation failure".to_string()))
+        })
+        .expect("Failed to compile contract.");
+
+    contract_context.set_wasm_module(compile_result.module.emit_wasm());
+
+    let mut global_context = GlobalContext::new(
+        false,
+        CHAIN_ID_TESTNET,
+        conn,
+        compile_result.contract_analysis.cost_track.take().unwrap(),
+        StacksEpochId::latest(),
+    );
+
+    global_context.begin();
+
+    {
+        initialize_contract(
+            &mut global_context,
+            &mut contract_context,
+            None,
+            &compile_result.contract_analysis,
+        )
+        .expect("Failed to initialize contract");
+
+        let mut call_stack = CallStack::new();
+        let args = (workload.args)();
+
+        let mut call = || {
+            call_function(
+                workload.function,
+                &args,
+                &mut global_context,
+                &contract_context,
+                &mut call_stack,
+                Some(StandardPrincipalData::transient().into()),
+                Some(StandardPrincipalData::transient().into()),
+                None,
+            )
+            .expect("Function call failed")
+        };
+
+        check_result(workload, call());
+
+        group.bench_function("wasm", |b| {
+            b.iter(&mut call);
+        });
+    }
+
+    global_context.commit().unwrap();
+}
+
+fn bench_interpreter(group: &mut BenchmarkGroup<WallTime>, workload: &Workload) {
+    let contract_id = contract_id(workload);
+    let mut datastore = Datastore::new();
+    let constants = StacksConstants::default();
+    let burn_datastore = BurnDatastore::new(constants);
+    let mut clarity_store = MemoryBackingStore::new();
+    let mut conn = ClarityDatabase::new(&mut datastore, &burn_datastore, &burn_datastore);
+    conn.begin();
+    conn.set_clarity_epoch_version(StacksEpochId::latest())
+        .unwrap();
+    conn.commit().unwrap();
+    let mut cost_tracker = LimitedCostTracker::new_free();
+    let mut contract_context = ContractContext::new(contract_id.clone(), ClarityVersion::latest());
+
+    // Parse the contract
+    let (ast, _, success) = build_ast_with_diagnostics(
+        &contract_id,
+        workload.contract,
+        &mut cost_tracker,
+        ClarityVersion::latest(),
+        StacksEpochId::latest(),
+    );
+
+    if !success {
+        panic!("Failed to parse contract");
+    }
+
+    // Create a new analysis database
+    let mut analysis_db = AnalysisDatabase::new(&mut clarity_store);
+
+    // Run the analysis passes
+    let mut contract_analysis = run_analysis(
+        &contract_id,
+        &ast.expressions,
+        &mut analysis_db,
+        false,
+        cost_tracker,
+        StacksEpochId::latest(),
+        ClarityVersion::latest(),
+        true,
+    )
+    .expect("Failed to run analysis");
+
+    let mut global_context = GlobalContext::new(
+        false,
+        CHAIN_ID_TESTNET,
+        conn,
+        contract_analysis.cost_track.take().unwrap(),
+        StacksEpochId::latest(),
+    );
+
+    global_context.begin();
+
+    {
+        // Initialize the contract
+        eval_all(
+            &ast.expressions,
+            &mut contract_context,
+            &mut global_context,
+            None,
+        )
+        .expect("Failed to interpret the contract");
+
+        let func = contract_context
+            .lookup_function(workload.function)
+            .expect("failed to lookup function");
+
+        let mut call_stack = CallStack::new();
+        let mut env = Environment::new(
+            &mut global_context,
+            &contract_context,
+            &mut call_stack,
+            Some(StandardPrincipalData::transient().into()),
+            Some(StandardPrincipalData::transient().into()),
+            None,
+        );
+        let args = (workload.args)();
+
+        let mut call = || {
+            func.execute_apply(&args, &mut env)
+                .expect("Function call failed")
+        };
+
+        check_result(workload, call());
+
+        group.bench_function("interpreter", |b| {
+            b.iter(&mut call);
+        });
+    }
+
+    global_context.commit().unwrap();
+}
+
+fn workloads(c: &mut Criterion) {
+    for workload in WORKLOADS {
+        let mut group = c.benchmark_group(workload.name);
+        bench_wasm(&mut group, workload);
+        bench_interpreter(&mut group, workload);
+        group.finish();
+    }
+}
+
+criterion_group!(benches, workloads);
+criterion_main!(benches);
@@ -1,6 +1,16 @@
-/// Generate the standard library as a Wasm binary from the WAT source.
+/// Generate the standard library as a Wasm binary from the WAT source, or copy
+/// the precompiled standard library named by `CLAR2WASM_STDLIB`, which skips
+/// the parsing of the WAT source.
 #[allow(clippy::expect_used)]
 fn main() {
+    println!("cargo:rerun-if-env-changed=CLAR2WASM_STDLIB");
+    if let Ok(stdlib) = std::env::var("CLAR2WASM_STDLIB") {
+        std::fs::copy(&stdlib, "src/standard/standard.wasm")
+            .expect("Failed to copy standard library");
+        println!("cargo:rerun-if-changed={stdlib}");
+        return;
+    }
+
     match wat::parse_file("src/standard/standard.wat") {
         Ok(binary) => {
             std::fs::write("src/standard/standard.wasm", binary)
@@ -0,0 +1,18 @@
+//! Compiles arbitrary source strings. The first byte of the input selects the
+//! Clarity version and the epoch, the rest is the source.
+
+#![no_main]
+
+use clar2wasm_fuzz::{compile_and_validate, target};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&byte, source)) = data.split_first() else {
+        return;
+    };
+    let Ok(source) = std::str::from_utf8(source) else {
+        return;
+    };
+    let (clarity_version, epoch) = target(byte);
+    compile_and_validate(source, clarity_version, epoch);
+});
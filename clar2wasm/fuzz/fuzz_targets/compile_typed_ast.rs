@@ -0,0 +1,14 @@
+//! Compiles well-typed contracts built from the input, see
+//! [`clar2wasm_fuzz::typed_ast`].
+
+#![no_main]
+
+use clar2wasm_fuzz::typed_ast::Contract;
+use clar2wasm_fuzz::{compile_and_validate, target};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (u8, Contract)| {
+    let (byte, contract) = input;
+    let (clarity_version, epoch) = target(byte);
+    compile_and_validate(&contract.source(), clarity_version, epoch);
+});
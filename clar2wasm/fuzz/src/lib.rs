@@ -0,0 +1,45 @@
+//! Helpers shared by the fuzz targets of clar2wasm.
+//!
+//! The targets compile their input with [`compile`], and check that the
+//! compilation doesn't panic and that every module it produces is valid.
+//! Contracts rejected by the analysis or the generator are not failures.
+
+pub mod typed_ast;
+
+use clar2wasm::{compile, CompileOptions};
+use clarity::types::StacksEpochId;
+use clarity::vm::costs::LimitedCostTracker;
+use clarity::vm::database::MemoryBackingStore;
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::ClarityVersion;
+
+/// The Clarity version and the epoch selected by `byte`, which is the first
+/// byte of the inputs of the fuzz targets.
+pub fn target(byte: u8) -> (ClarityVersion, StacksEpochId) {
+    match byte % 3 {
+        0 => (ClarityVersion::Clarity1, StacksEpochId::Epoch2_05),
+        1 => (ClarityVersion::Clarity2, StacksEpochId::Epoch25),
+        _ => (ClarityVersion::Clarity3, StacksEpochId::Epoch30),
+    }
+}
+
+/// Compiles `source`, and panics if the compilation produces an invalid
+/// module.
+pub fn compile_and_validate(source: &str, clarity_version: ClarityVersion, epoch: StacksEpochId) {
+    let mut datastore = MemoryBackingStore::new();
+    let Ok(mut result) = compile(
+        source,
+        &QualifiedContractIdentifier::transient(),
+        LimitedCostTracker::new_free(),
+        clarity_version,
+        epoch,
+        &mut datastore.as_analysis_db(),
+        CompileOptions::default(),
+    ) else {
+        return;
+    };
+
+    if let Err(e) = wasmparser::validate(&result.module.emit_wasm()) {
+        panic!("invalid module for {source:?}: {e}");
+    }
+}
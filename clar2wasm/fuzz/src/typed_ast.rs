@@ -0,0 +1,249 @@
+//! Well-typed Clarity contracts built from the fuzzer input with [`arbitrary`].
+//!
+//! Each enum holds expressions of one Clarity type, and each variant builds
+//! an expression of that type from operands of the right types, so the
+//! analysis only rejects the contracts exceeding its limits (e.g. the size of
+//! a value), and the inputs reach the generator.
+
+use std::fmt::Write;
+
+use arbitrary::Arbitrary;
+
+/// Depth after which the expressions are rendered as literals, below the
+/// maximum nesting depth of the parser.
+const MAX_DEPTH: usize = 16;
+
+/// Maximum number of bytes of a buffer literal, or items of a list literal.
+const MAX_LITERAL_LEN: usize = 16;
+
+/// A contract with a read-only function computing an `int` from its
+/// arguments, `a` an `int` and `b` a `uint`, and a public function updating
+/// a data variable and a map with the same arguments.
+#[derive(Arbitrary, Debug)]
+pub struct Contract {
+    read_only: IntExpr,
+    var_value: IntExpr,
+    map_key: IntExpr,
+    result: BoolExpr,
+}
+
+impl Contract {
+    /// The source of the contract.
+    pub fn source(&self) -> String {
+        format!(
+            "(define-data-var counter int 0)
+(define-map entries int int)
+(define-read-only (read (a int) (b uint))
+  {})
+(define-public (write (a int) (b uint))
+  (begin
+    (var-set counter {})
+    (map-set entries {} (var-get counter))
+    (ok {})))",
+            self.read_only.render(0),
+            self.var_value.render(0),
+            self.map_key.render(0),
+            self.result.render(0),
+        )
+    }
+}
+
+/// Expressions of type `int`.
+#[derive(Arbitrary, Debug)]
+pub enum IntExpr {
+    Literal(i64),
+    Argument,
+    Variable,
+    Add(Box<IntExpr>, Box<IntExpr>),
+    Sub(Box<IntExpr>, Box<IntExpr>),
+    Mul(Box<IntExpr>, Box<IntExpr>),
+    Div(Box<IntExpr>, Box<IntExpr>),
+    If(Box<BoolExpr>, Box<IntExpr>, Box<IntExpr>),
+    Let(Box<IntExpr>, Box<IntExpr>),
+    ToInt(Box<UIntExpr>),
+    DefaultTo(Box<IntExpr>, Box<OptionalExpr>),
+    Fold(Box<ListExpr>, Box<IntExpr>),
+}
+
+impl IntExpr {
+    fn render(&self, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return "0".to_owned();
+        }
+        let d = depth + 1;
+        match self {
+            IntExpr::Literal(n) => n.to_string(),
+            IntExpr::Argument => "a".to_owned(),
+            IntExpr::Variable => "(var-get counter)".to_owned(),
+            IntExpr::Add(a, b) => format!("(+ {} {})", a.render(d), b.render(d)),
+            IntExpr::Sub(a, b) => format!("(- {} {})", a.render(d), b.render(d)),
+            IntExpr::Mul(a, b) => format!("(* {} {})", a.render(d), b.render(d)),
+            IntExpr::Div(a, b) => format!("(/ {} {})", a.render(d), b.render(d)),
+            IntExpr::If(c, a, b) => {
+                format!("(if {} {} {})", c.render(d), a.render(d), b.render(d))
+            }
+            // The depth keeps the names fresh: Clarity rejects shadowing, and
+            // the bindings in scope are all at a lower depth.
+            IntExpr::Let(a, b) => format!(
+                "(let ((x{depth} {})) (+ x{depth} {}))",
+                a.render(d),
+                b.render(d)
+            ),
+            IntExpr::ToInt(u) => format!("(to-int {})", u.render(d)),
+            IntExpr::DefaultTo(a, o) => format!("(default-to {} {})", a.render(d), o.render(d)),
+            IntExpr::Fold(l, a) => format!("(fold + {} {})", l.render(d), a.render(d)),
+        }
+    }
+}
+
+/// Expressions of type `uint`.
+#[derive(Arbitrary, Debug)]
+pub enum UIntExpr {
+    Literal(u64),
+    Argument,
+    Add(Box<UIntExpr>, Box<UIntExpr>),
+    ToUInt(Box<IntExpr>),
+    Len(Box<ListExpr>),
+    BuffLen(Box<BuffExpr>),
+}
+
+impl UIntExpr {
+    fn render(&self, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return "u0".to_owned();
+        }
+        let d = depth + 1;
+        match self {
+            UIntExpr::Literal(n) => format!("u{n}"),
+            UIntExpr::Argument => "b".to_owned(),
+            UIntExpr::Add(a, b) => format!("(+ {} {})", a.render(d), b.render(d)),
+            UIntExpr::ToUInt(i) => format!("(to-uint {})", i.render(d)),
+            UIntExpr::Len(l) => format!("(len {})", l.render(d)),
+            UIntExpr::BuffLen(b) => format!("(len {})", b.render(d)),
+        }
+    }
+}
+
+/// Expressions of type `bool`.
+#[derive(Arbitrary, Debug)]
+pub enum BoolExpr {
+    Literal(bool),
+    Not(Box<BoolExpr>),
+    And(Box<BoolExpr>, Box<BoolExpr>),
+    Or(Box<BoolExpr>, Box<BoolExpr>),
+    Lt(Box<IntExpr>, Box<IntExpr>),
+    IsEq(Box<IntExpr>, Box<IntExpr>),
+    IsEqBuff(Box<BuffExpr>, Box<BuffExpr>),
+    IsSome(Box<OptionalExpr>),
+}
+
+impl BoolExpr {
+    fn render(&self, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return "true".to_owned();
+        }
+        let d = depth + 1;
+        match self {
+            BoolExpr::Literal(b) => b.to_string(),
+            BoolExpr::Not(b) => format!("(not {})", b.render(d)),
+            BoolExpr::And(a, b) => format!("(and {} {})", a.render(d), b.render(d)),
+            BoolExpr::Or(a, b) => format!("(or {} {})", a.render(d), b.render(d)),
+            BoolExpr::Lt(a, b) => format!("(< {} {})", a.render(d), b.render(d)),
+            BoolExpr::IsEq(a, b) => format!("(is-eq {} {})", a.render(d), b.render(d)),
+            BoolExpr::IsEqBuff(a, b) => format!("(is-eq {} {})", a.render(d), b.render(d)),
+            BoolExpr::IsSome(o) => format!("(is-some {})", o.render(d)),
+        }
+    }
+}
+
+/// Expressions of type `(buff n)`.
+#[derive(Arbitrary, Debug)]
+pub enum BuffExpr {
+    Literal(Vec<u8>),
+    Concat(Box<BuffExpr>, Box<BuffExpr>),
+    Sha256(Box<IntExpr>),
+    Keccak256(Box<BuffExpr>),
+}
+
+impl BuffExpr {
+    fn render(&self, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return "0x".to_owned();
+        }
+        let d = depth + 1;
+        match self {
+            BuffExpr::Literal(bytes) => {
+                bytes
+                    .iter()
+                    .take(MAX_LITERAL_LEN)
+                    .fold("0x".to_owned(), |mut hex, byte| {
+                        let _ = write!(hex, "{byte:02x}");
+                        hex
+                    })
+            }
+            BuffExpr::Concat(a, b) => format!("(concat {} {})", a.render(d), b.render(d)),
+            BuffExpr::Sha256(i) => format!("(sha256 {})", i.render(d)),
+            BuffExpr::Keccak256(b) => format!("(keccak256 {})", b.render(d)),
+        }
+    }
+}
+
+/// Expressions of type `(optional int)`.
+#[derive(Arbitrary, Debug)]
+pub enum OptionalExpr {
+    None,
+    Some(Box<IntExpr>),
+    MapGet(Box<IntExpr>),
+    ElementAt(Box<ListExpr>, Box<UIntExpr>),
+}
+
+impl OptionalExpr {
+    fn render(&self, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return "none".to_owned();
+        }
+        let d = depth + 1;
+        match self {
+            OptionalExpr::None => "none".to_owned(),
+            OptionalExpr::Some(i) => format!("(some {})", i.render(d)),
+            OptionalExpr::MapGet(k) => format!("(map-get? entries {})", k.render(d)),
+            OptionalExpr::ElementAt(l, u) => {
+                format!("(element-at {} {})", l.render(d), u.render(d))
+            }
+        }
+    }
+}
+
+/// Expressions of type `(list n int)`.
+#[derive(Arbitrary, Debug)]
+pub enum ListExpr {
+    Literal(Vec<IntExpr>),
+    Append(Box<ListExpr>, Box<IntExpr>),
+    Concat(Box<ListExpr>, Box<ListExpr>),
+    Map(Box<ListExpr>, Box<ListExpr>),
+}
+
+impl ListExpr {
+    fn render(&self, depth: usize) -> String {
+        if depth > MAX_DEPTH {
+            return "(list 0)".to_owned();
+        }
+        let d = depth + 1;
+        match self {
+            ListExpr::Literal(items) => {
+                items
+                    .iter()
+                    .take(MAX_LITERAL_LEN)
+                    .fold("(list".to_owned(), |mut list, item| {
+                        list.push(' ');
+                        list.push_str(&item.render(d));
+                        list
+                    })
+                    + ")"
+            }
+            ListExpr::Append(l, i) => format!("(append {} {})", l.render(d), i.render(d)),
+            ListExpr::Concat(a, b) => format!("(concat {} {})", a.render(d), b.render(d)),
+            ListExpr::Map(a, b) => format!("(map + {} {})", a.render(d), b.render(d)),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+mod utils;
+use std::collections::BTreeMap;
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+
+use clap::Parser;
+use clar2wasm::{CompileError, CompileOptions};
+use clarity::types::StacksEpochId;
+use clarity::vm::costs::LimitedCostTracker;
+use clarity::vm::database::MemoryBackingStore;
+use clarity::vm::diagnostic::Diagnostic;
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::ClarityVersion;
+use utils::{WrappedClarityVersion, WrappedEpochId};
+
+/// corpus compiles every contract of a directory, for example scraped from
+/// mainnet, and reports the failures grouped by the word which caused them.
+#[derive(Parser)]
+#[command(name = "corpus", version = env!("CARGO_PKG_VERSION"))]
+struct Args {
+    /// Directory containing the Clarity source files, searched recursively
+    input: PathBuf,
+    /// Clarity version to use (1, 2 or 3)
+    #[arg(short, long)]
+    clarity_version: Option<WrappedClarityVersion>,
+    /// Stacks epoch to use (1.0, 2.0, 2.05, 2.1, 2.2, 2.3, 2.4, 2.5 or 3.0)
+    #[arg(short, long)]
+    stacks_epoch: Option<WrappedEpochId>,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let mut contracts = vec![];
+    if let Err(error) = collect_contracts(&args.input, &mut contracts) {
+        eprintln!("Error reading directory: {}", error);
+        std::process::exit(1);
+    }
+    contracts.sort();
+
+    let clarity_version = args.clarity_version.unwrap_or_default().into();
+    let epoch = args.stacks_epoch.unwrap_or_default().into();
+
+    // Panics are reported as failures, the default hook would only add noise.
+    panic::set_hook(Box::new(|_| {}));
+
+    let mut failures: BTreeMap<String, Vec<&Path>> = BTreeMap::new();
+    for contract in &contracts {
+        let failure = match fs::read_to_string(contract) {
+            Ok(source) => compile(&source, clarity_version, epoch).err(),
+            Err(error) => Some(format!("unreadable: {error}")),
+        };
+        if let Some(failure) = failure {
+            failures.entry(failure).or_default().push(contract);
+        }
+    }
+
+    let failed: usize = failures.values().map(Vec::len).sum();
+    println!(
+        "{} contracts, {} compiled, {} failed",
+        contracts.len(),
+        contracts.len() - failed,
+        failed
+    );
+
+    let mut failures: Vec<_> = failures.into_iter().collect();
+    failures.sort_by(|(_, a), (_, b)| b.len().cmp(&a.len()));
+    for (failure, contracts) in failures {
+        println!("\n{} ({} contracts)", failure, contracts.len());
+        for contract in contracts {
+            println!("    {}", contract.display());
+        }
+    }
+}
+
+fn collect_contracts(dir: &Path, contracts: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_contracts(&path, contracts)?;
+        } else if path.extension().is_some_and(|ext| ext == "clar") {
+            contracts.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Compiles `source`, returning the key under which its failure is grouped.
+fn compile(
+    source: &str,
+    clarity_version: ClarityVersion,
+    epoch: StacksEpochId,
+) -> Result<(), String> {
+    let mut datastore = MemoryBackingStore::new();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        clar2wasm::compile(
+            source,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            clarity_version,
+            epoch,
+            &mut datastore.as_analysis_db(),
+            CompileOptions::default(),
+        )
+    }));
+
+    match result {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(CompileError::Generic { diagnostics, .. })) => Err(diagnostics
+            .first()
+            .map_or_else(|| "unknown error".to_owned(), |d| failure_key(source, d))),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            Err(format!("panic: {message}"))
+        }
+    }
+}
+
+/// Generator errors are grouped by their code and the word of the expression
+/// which raised them, other errors by their message.
+fn failure_key(source: &str, diagnostic: &Diagnostic) -> String {
+    let code = diagnostic
+        .message
+        .strip_prefix('[')
+        .and_then(|message| message.split_once(']'))
+        .map(|(code, _)| code);
+    match (code, diagnostic.spans.first()) {
+        (Some(code), Some(span)) => format!("[{code}] in `{}`", word_at(source, span)),
+        _ => diagnostic.message.clone(),
+    }
+}
+
+/// The atom in function position of the expression starting at `span`.
+fn word_at(source: &str, span: &clarity::vm::representations::Span) -> String {
+    source
+        .lines()
+        .nth((span.start_line as usize).saturating_sub(1))
+        .and_then(|line| line.get((span.start_column as usize).saturating_sub(1)..))
+        .map(|expr| {
+            expr.trim_start_matches('(')
+                .split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+                .next()
+                .unwrap_or_default()
+                .to_owned()
+        })
+        .unwrap_or_default()
+}
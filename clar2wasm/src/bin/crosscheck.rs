@@ -2,15 +2,16 @@ mod utils;
 use std::fs;
 
 use clap::Parser;
+use clar2wasm::repl_session::run_session;
 use clar2wasm::tools::crosscheck_compare_only_with_epoch_and_version;
 use utils::*;
 
 /// crosscheck is a tool to compare the results of the compiled and interpreted
-/// versions of a Clarity snippet.
+/// versions of a Clarity snippet, or of the inputs of a `clarity-repl` session.
 #[derive(Parser)]
 #[command(name = "crosscheck", version = env!("CARGO_PKG_VERSION"))]
 struct Args {
-    /// Clarity source file to compile
+    /// Clarity source file to compile, or session file (.session) to run
     input: String,
     /// Epoch of the stacks chain
     #[arg(long)]
@@ -23,9 +24,10 @@ struct Args {
 fn main() {
     let args = Args::parse();
 
-    // Require a .clar extension
-    if !args.input.ends_with(".clar") {
-        eprintln!("Input file must have a .clar extension");
+    // Require a .clar or .session extension
+    let is_session = args.input.ends_with(".session");
+    if !args.input.ends_with(".clar") && !is_session {
+        eprintln!("Input file must have a .clar or .session extension");
         std::process::exit(1);
     }
 
@@ -41,5 +43,15 @@ fn main() {
     let epoch = args.stacks_epoch.unwrap_or_default().into();
     let version = args.clarity_version.unwrap_or_default().into();
 
-    crosscheck_compare_only_with_epoch_and_version(&source, epoch, version);
+    if is_session {
+        if let Err(error) = run_session(&source, epoch, version) {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(timings) = crosscheck_compare_only_with_epoch_and_version(&source, epoch, version) {
+        println!("{timings}");
+    }
 }
@@ -2,7 +2,9 @@ mod utils;
 use std::fs;
 
 use clap::Parser;
-use clar2wasm::CompileError;
+use clar2wasm::disassemble::disassemble;
+use clar2wasm::target_features::TargetFeatures;
+use clar2wasm::{CompileError, CompileOptions};
 use clarity::vm::costs::LimitedCostTracker;
 use clarity::vm::database::MemoryBackingStore;
 use clarity::vm::types::QualifiedContractIdentifier;
@@ -23,6 +25,37 @@ struct Args {
     /// Output file to write compiled WebAssembly to
     #[arg(short, long)]
     output: Option<String>,
+    /// Replace the host functions with stubs, to run the module outside of a
+    /// Stacks host (for analysis only, never deploy such a module)
+    #[arg(long)]
+    standalone: bool,
+    /// Module from which the host functions are imported
+    #[arg(long, default_value = clar2wasm::DEFAULT_HOST_MODULE)]
+    host_module: String,
+    /// Check the values read from and written to the memory at runtime, to
+    /// catch memory layout bugs (for development only)
+    #[arg(long)]
+    memory_checks: bool,
+    /// Print the generated functions as pseudocode annotated with the Clarity
+    /// expressions, instead of writing the WebAssembly file
+    #[arg(long)]
+    disassemble: bool,
+    /// Write a JSON report of the compilation to this file: the cost, the
+    /// diagnostics, the statistics of the module and the contract interface
+    #[arg(long)]
+    report: Option<String>,
+    /// Precompiled standard library to build the module with, instead of the
+    /// bundled one (it must record the ABI version of this compiler)
+    #[arg(long)]
+    stdlib: Option<String>,
+    /// Only emit data derived from the contract, so that compiling the same
+    /// contract always produces the same bytes
+    #[arg(long)]
+    deterministic: bool,
+    /// WebAssembly features the module may use, as a comma-separated list of
+    /// feature names (e.g. `mvp,multi-value`), `mvp` or `all`
+    #[arg(long, default_value = "all")]
+    target_features: TargetFeatures,
 }
 
 fn main() {
@@ -48,18 +81,35 @@ fn main() {
     let clarity_version = args.clarity_version.unwrap_or_default().into();
     let epoch = args.stacks_epoch.unwrap_or_default().into();
 
+    let mut options = CompileOptions::default()
+        .standalone(args.standalone)
+        .host_module(args.host_module)
+        .memory_checks(args.memory_checks)
+        .deterministic(args.deterministic)
+        .target_features(args.target_features);
+    if let Some(stdlib) = &args.stdlib {
+        match fs::read(stdlib) {
+            Ok(wasm) => options = options.standard_library(wasm),
+            Err(error) => {
+                eprintln!("Error reading standard library: {}", error);
+                std::process::exit(1);
+            }
+        }
+    }
+
     // Setup a datastore and cost tracker
     let mut datastore = MemoryBackingStore::new();
     let cost_track = LimitedCostTracker::new_free();
 
     // Pass the source code to the compiler.
-    let result = clar2wasm::compile(
+    let mut result = clar2wasm::compile(
         &source,
         &contract_id,
         cost_track,
         clarity_version,
         epoch,
         &mut datastore.as_analysis_db(),
+        options,
     )
     .unwrap_or_else(|err| match err {
         CompileError::Generic {
@@ -74,6 +124,21 @@ fn main() {
         }
     });
 
+    if let Some(report) = &args.report {
+        if let Err(error) = fs::write(report, result.report().to_json()) {
+            eprintln!("Error writing report, {}: {}", report, error);
+            std::process::exit(1);
+        }
+    }
+
+    if args.disassemble {
+        print!(
+            "{}",
+            disassemble(&result.module, &result.contract_analysis.expressions)
+        );
+        return;
+    }
+
     let mut module = result.module;
 
     // Write the compiled WebAssembly to a file.
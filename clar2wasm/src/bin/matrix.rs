@@ -0,0 +1,140 @@
+mod utils;
+use std::ffi::OsString;
+use std::process::Command;
+
+use clap::{Parser, ValueEnum};
+use clar2wasm::tools::{TEST_CLARITY_VERSION_VAR, TEST_EPOCH_VAR};
+use clarity::types::StacksEpochId;
+use clarity::vm::ClarityVersion;
+use utils::{WrappedClarityVersion, WrappedEpochId};
+
+/// matrix runs the crosscheck tests for every combination of a set of epochs
+/// and Clarity versions, and reports the failures of each combination.
+#[derive(Parser)]
+#[command(name = "matrix", version = env!("CARGO_PKG_VERSION"))]
+struct Args {
+    /// Stacks epochs to run the tests in
+    #[arg(short, long, value_delimiter = ',', default_values = ["2.0", "2.05", "2.1", "2.4", "3.0"])]
+    stacks_epochs: Vec<WrappedEpochId>,
+    /// Clarity versions to run the tests with
+    #[arg(short, long, value_delimiter = ',', default_values = ["1", "2", "3"])]
+    clarity_versions: Vec<WrappedClarityVersion>,
+    /// Filter of the tests to run, as for `cargo test`
+    filter: Option<String>,
+}
+
+/// Outcome of the tests of a combination.
+enum Outcome {
+    /// The tests ran, with this number of passed tests and these failures.
+    Ran { passed: usize, failed: Vec<String> },
+    /// The tests did not build or did not run.
+    Broken(String),
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let mut combinations = vec![];
+    for epoch in &args.stacks_epochs {
+        for version in &args.clarity_versions {
+            let (epoch_name, version_name) = (name(epoch), name(version));
+            let (epoch, version) = (epoch.clone().into(), version.clone().into());
+            // A contract can only use the Clarity versions of its epoch.
+            if is_supported(epoch, version) {
+                combinations.push((epoch_name, version_name));
+            }
+        }
+    }
+
+    let mut failures = 0;
+    let mut report = vec![];
+    for (epoch, version) in combinations {
+        println!("running the tests in epoch {epoch} with Clarity {version}");
+        let outcome = run_tests(&epoch, &version, args.filter.as_deref());
+        if !matches!(&outcome, Outcome::Ran { failed, .. } if failed.is_empty()) {
+            failures += 1;
+        }
+        report.push((epoch, version, outcome));
+    }
+
+    println!();
+    for (epoch, version, outcome) in &report {
+        match outcome {
+            Outcome::Ran { passed, failed } => {
+                println!(
+                    "epoch {epoch}, Clarity {version}: {passed} passed, {} failed",
+                    failed.len()
+                );
+                for test in failed {
+                    println!("    {test}");
+                }
+            }
+            Outcome::Broken(error) => println!("epoch {epoch}, Clarity {version}: {error}"),
+        }
+    }
+
+    if failures > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// Name of a value of an option, as passed on the command line.
+fn name(value: &impl ValueEnum) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_owned())
+        .unwrap_or_default()
+}
+
+fn is_supported(epoch: StacksEpochId, version: ClarityVersion) -> bool {
+    match version {
+        ClarityVersion::Clarity1 => true,
+        ClarityVersion::Clarity2 => epoch >= StacksEpochId::Epoch21,
+        ClarityVersion::Clarity3 => epoch >= StacksEpochId::Epoch30,
+    }
+}
+
+/// Runs the tests of the crate in `epoch` with Clarity `version`.
+fn run_tests(epoch: &str, version: &str, filter: Option<&str>) -> Outcome {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo"));
+    let mut command = Command::new(cargo);
+    // The feature selects the tests written for the version, the variables
+    // the version and the epoch of the crosschecks.
+    command
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(["test", "--lib", "--tests", "--no-fail-fast"])
+        .args(["--features", &format!("test-clarity-v{version}")])
+        .arg("--")
+        .args(filter)
+        .env(TEST_EPOCH_VAR, epoch)
+        .env(TEST_CLARITY_VERSION_VAR, version);
+
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(error) => return Outcome::Broken(format!("cargo did not run: {error}")),
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    let mut passed = 0;
+    let mut failed = vec![];
+    for line in stdout.lines() {
+        let Some(test) = line.strip_prefix("test ") else {
+            continue;
+        };
+        if test.ends_with(" ... ok") {
+            passed += 1;
+        } else if let Some(test) = test.strip_suffix(" ... FAILED") {
+            failed.push(test.to_owned());
+        }
+    }
+
+    if !output.status.success() && passed == 0 && failed.is_empty() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let error = stderr
+            .lines()
+            .find(|line| line.starts_with("error"))
+            .unwrap_or("the tests did not run");
+        return Outcome::Broken(error.to_owned());
+    }
+    Outcome::Ran { passed, failed }
+}
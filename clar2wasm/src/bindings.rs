@@ -0,0 +1,282 @@
+//! Generation of typed Rust bindings to the public interface of a contract,
+//! to call its functions in tests run with [`crate::tools::TestEnvironment`]
+//! without constructing the `Value`s of the arguments manually.
+//!
+//! A build script compiles the contract, and writes the bindings generated
+//! from its analysis to a file:
+//!
+//! ```ignore
+//! let bindings = contract_bindings("counter", &compile_result.contract_analysis);
+//! std::fs::write(Path::new(&env::var("OUT_DIR")?).join("counter.rs"), bindings)?;
+//! ```
+//!
+//! which the tests include, and call once the contract is initialized in the
+//! environment with the same name:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/counter.rs"));
+//!
+//! env.init_contract_with_snippet("counter", COUNTER_SOURCE)?;
+//! counter::increment(&mut env, 5)?;
+//! ```
+//!
+//! The crates including the bindings must depend on `clar2wasm` and `clarity`.
+
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use clarity::vm::analysis::ContractAnalysis;
+use clarity::vm::types::{
+    FixedFunction, FunctionType, SequenceSubtype, StringSubtype, TypeSignature,
+};
+
+/// Rust keywords, strict and reserved, which can't be used as the name of a
+/// binding.
+const RUST_KEYWORDS: &[&str] = &[
+    "Self", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// The parameter of the bindings taking the `TestEnvironment`.
+const ENV_PARAM: &str = "env";
+
+/// Returns the source of a Rust module named after `contract_name`, with a
+/// function for each public and read-only function of the contract analyzed
+/// in `analysis`.
+///
+/// Each function takes the `TestEnvironment` in which the contract is
+/// initialized as `contract_name`, then the arguments of the Clarity
+/// function: an `i128` for an `int`, a `u128` for a `uint`, a `bool`, a
+/// `PrincipalData`, a `&[u8]` for a buffer, a `&str` for a string, and a
+/// `Value` for the other types. It returns the result of the call.
+///
+/// The names of the functions and arguments are converted to Rust
+/// identifiers (`get-count` becomes `get_count`). A name whose identifier is
+/// already taken in the module or the function, like `get_count` after
+/// `get-count`, or an argument named `env`, gets a numbered suffix:
+/// `get_count_2`.
+pub fn contract_bindings(contract_name: &str, analysis: &ContractAnalysis) -> String {
+    let mut source = String::new();
+    let _ = writeln!(
+        source,
+        "/// Bindings to the public interface of the contract `{contract_name}`."
+    );
+    let _ = writeln!(source, "pub mod {} {{", rust_identifier(contract_name));
+
+    let functions = analysis
+        .public_function_types
+        .iter()
+        .map(|(name, ty)| (name, "public", ty))
+        .chain(
+            analysis
+                .read_only_function_types
+                .iter()
+                .map(|(name, ty)| (name, "read-only", ty)),
+        );
+    let mut function_identifiers = HashSet::new();
+    for (name, kind, ty) in functions {
+        let FunctionType::Fixed(FixedFunction { args, .. }) = ty else {
+            continue;
+        };
+        let identifier = unique_identifier(name, &mut function_identifiers);
+
+        let mut arg_identifiers = HashSet::from([ENV_PARAM.to_owned()]);
+        let args: Vec<_> = args
+            .iter()
+            .map(|arg| (unique_identifier(&arg.name, &mut arg_identifiers), arg))
+            .collect();
+        let params: String = args
+            .iter()
+            .map(|(param, arg)| format!(", {param}: {}", rust_type(&arg.signature)))
+            .collect();
+        let values = args
+            .iter()
+            .map(|(param, arg)| clarity_value(param, &arg.signature))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let name = name.as_str();
+        let _ = writeln!(
+            source,
+            "
+    /// Calls the {kind} function `{name}` of `{contract_name}`.
+    pub fn {identifier}(
+        {ENV_PARAM}: &mut ::clar2wasm::tools::TestEnvironment{params}
+    ) -> ::std::result::Result<::clarity::vm::Value, ::clarity::vm::errors::Error> {{
+        {ENV_PARAM}.call_function({contract_name:?}, {name:?}, &[{values}])
+    }}",
+        );
+    }
+
+    let _ = writeln!(source, "}}");
+    source
+}
+
+/// Returns a Rust identifier for the Clarity name `name`, in which the
+/// characters which are not allowed are replaced by `_`.
+fn rust_identifier(name: &str) -> String {
+    let mut identifier: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        identifier.insert(0, '_');
+    }
+    if RUST_KEYWORDS.contains(&identifier.as_str()) {
+        identifier.push('_');
+    }
+    identifier
+}
+
+/// Returns the Rust identifier of `name` (see [`rust_identifier`]), with a
+/// numbered suffix if it is already in `taken`, and adds it to `taken`.
+fn unique_identifier(name: &str, taken: &mut HashSet<String>) -> String {
+    let base = rust_identifier(name);
+    let mut identifier = base.clone();
+    let mut suffix = 1;
+    while taken.contains(&identifier) {
+        suffix += 1;
+        identifier = format!("{base}_{suffix}");
+    }
+    taken.insert(identifier.clone());
+    identifier
+}
+
+/// The Rust type of an argument of Clarity type `ty`.
+fn rust_type(ty: &TypeSignature) -> &'static str {
+    match ty {
+        TypeSignature::IntType => "i128",
+        TypeSignature::UIntType => "u128",
+        TypeSignature::BoolType => "bool",
+        TypeSignature::PrincipalType => "::clarity::vm::types::PrincipalData",
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(_)) => "&[u8]",
+        TypeSignature::SequenceType(SequenceSubtype::StringType(_)) => "&str",
+        _ => "::clarity::vm::Value",
+    }
+}
+
+/// The Rust expression of the Clarity value of the argument `arg` of Clarity
+/// type `ty`.
+fn clarity_value(arg: &str, ty: &TypeSignature) -> String {
+    match ty {
+        TypeSignature::IntType => format!("::clarity::vm::Value::Int({arg})"),
+        TypeSignature::UIntType => format!("::clarity::vm::Value::UInt({arg})"),
+        TypeSignature::BoolType => format!("::clarity::vm::Value::Bool({arg})"),
+        TypeSignature::PrincipalType => format!("::clarity::vm::Value::Principal({arg})"),
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(_)) => {
+            format!("::clarity::vm::Value::buff_from({arg}.to_vec())?")
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(_))) => {
+            format!("::clarity::vm::Value::string_ascii_from_bytes({arg}.as_bytes().to_vec())?")
+        }
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(_))) => {
+            format!("::clarity::vm::Value::string_utf8_from_bytes({arg}.as_bytes().to_vec())?")
+        }
+        _ => arg.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+
+    use super::*;
+    use crate::{compile, CompileOptions};
+
+    const COUNTER: &str = r#"
+        (define-data-var count int 0)
+        (define-public (increment (n int))
+            (begin (var-set count (+ (var-get count) n)) (ok (var-get count))))
+        (define-read-only (get-count) (var-get count))
+        (define-read-only (describe (label (string-ascii 10)) (owner principal) (data (buff 4))) label)
+    "#;
+
+    fn analysis(source: &str) -> ContractAnalysis {
+        compile(
+            source,
+            &QualifiedContractIdentifier::local("counter").unwrap(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .expect("failed to compile contract")
+        .contract_analysis
+    }
+
+    #[test]
+    fn bindings_of_public_interface() {
+        let bindings = contract_bindings("counter", &analysis(COUNTER));
+
+        assert!(bindings.starts_with(
+            "/// Bindings to the public interface of the contract `counter`.\npub mod counter {"
+        ));
+        assert!(bindings.contains(
+            "pub fn increment(\n        env: &mut ::clar2wasm::tools::TestEnvironment, n: i128\n    )"
+        ));
+        assert!(bindings.contains(
+            r#"env.call_function("counter", "increment", &[::clarity::vm::Value::Int(n)])"#
+        ));
+        assert!(bindings.contains(r#"pub fn get_count("#));
+        assert!(bindings.contains(r#"env.call_function("counter", "get-count", &[])"#));
+        assert!(bindings
+            .contains("label: &str, owner: ::clarity::vm::types::PrincipalData, data: &[u8]"));
+        assert!(bindings.contains(
+            "::clarity::vm::Value::string_ascii_from_bytes(label.as_bytes().to_vec())?, \
+            ::clarity::vm::Value::Principal(owner), \
+            ::clarity::vm::Value::buff_from(data.to_vec())?"
+        ));
+        assert!(bindings.ends_with("}\n"));
+    }
+
+    #[test]
+    fn rust_identifiers() {
+        assert_eq!(rust_identifier("get-count"), "get_count");
+        assert_eq!(rust_identifier("is-valid?"), "is_valid_");
+        assert_eq!(rust_identifier("1st"), "_1st");
+        assert_eq!(rust_identifier("type"), "type_");
+        assert_eq!(rust_identifier("Self"), "Self_");
+        assert_eq!(rust_identifier("macro"), "macro_");
+    }
+
+    #[test]
+    fn unique_identifiers() {
+        let mut taken = HashSet::from(["env".to_owned()]);
+        assert_eq!(unique_identifier("env", &mut taken), "env_2");
+        assert_eq!(unique_identifier("a-b", &mut taken), "a_b");
+        assert_eq!(unique_identifier("a_b", &mut taken), "a_b_2");
+        assert_eq!(unique_identifier("a-b?", &mut taken), "a_b_");
+        assert_eq!(unique_identifier("a_b!", &mut taken), "a_b__2");
+    }
+
+    #[test]
+    fn bindings_disambiguate_identifiers() {
+        let bindings = contract_bindings(
+            "counter",
+            &analysis(
+                "(define-read-only (get-count (env int) (a-b int) (a_b int)) (+ env a-b a_b))
+                (define-read-only (get_count) 0)",
+            ),
+        );
+
+        assert!(bindings.contains(
+            "pub fn get_count(\n        env: &mut ::clar2wasm::tools::TestEnvironment, \
+            env_2: i128, a_b: i128, a_b_2: i128\n    )"
+        ));
+        assert!(bindings.contains(
+            "env.call_function(\"counter\", \"get-count\", &[::clarity::vm::Value::Int(env_2), \
+            ::clarity::vm::Value::Int(a_b), ::clarity::vm::Value::Int(a_b_2)])"
+        ));
+        assert!(bindings.contains("pub fn get_count_2(\n"));
+        assert!(bindings.contains(r#"env.call_function("counter", "get_count", &[])"#));
+    }
+}
@@ -0,0 +1,418 @@
+//! Detection of breaking changes between two versions of a contract.
+//!
+//! Contracts are immutable once deployed, so upgradable patterns deploy a new
+//! version of a contract and redirect callers to it. This module compares the
+//! public interfaces and the persisted data of two versions of a contract and
+//! reports the changes which would break existing callers or data.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use clarity::vm::analysis::ContractAnalysis;
+use clarity::vm::types::{FixedFunction, FunctionType, TypeSignature};
+use clarity::vm::ClarityName;
+
+/// Kind of a function that is part of the public interface of a contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FunctionKind {
+    Public,
+    ReadOnly,
+}
+
+impl fmt::Display for FunctionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FunctionKind::Public => write!(f, "public"),
+            FunctionKind::ReadOnly => write!(f, "read-only"),
+        }
+    }
+}
+
+/// A change between two versions of a contract which breaks compatibility.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BreakingChange {
+    RemovedFunction {
+        name: ClarityName,
+        kind: FunctionKind,
+    },
+    ChangedFunctionKind {
+        name: ClarityName,
+        old: FunctionKind,
+        new: FunctionKind,
+    },
+    ChangedFunctionSignature {
+        name: ClarityName,
+        old: FunctionType,
+        new: FunctionType,
+    },
+    RemovedMap {
+        name: ClarityName,
+    },
+    ChangedMapKeyType {
+        name: ClarityName,
+        old: TypeSignature,
+        new: TypeSignature,
+    },
+    ChangedMapValueType {
+        name: ClarityName,
+        old: TypeSignature,
+        new: TypeSignature,
+    },
+    RemovedDataVar {
+        name: ClarityName,
+    },
+    ChangedDataVarType {
+        name: ClarityName,
+        old: TypeSignature,
+        new: TypeSignature,
+    },
+    RemovedFungibleToken {
+        name: ClarityName,
+    },
+    RemovedNonFungibleToken {
+        name: ClarityName,
+    },
+    ChangedNonFungibleTokenType {
+        name: ClarityName,
+        old: TypeSignature,
+        new: TypeSignature,
+    },
+}
+
+impl fmt::Display for BreakingChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakingChange::RemovedFunction { name, kind } => {
+                write!(f, "{kind} function `{name}` was removed")
+            }
+            BreakingChange::ChangedFunctionKind { name, old, new } => {
+                write!(f, "function `{name}` changed from {old} to {new}")
+            }
+            BreakingChange::ChangedFunctionSignature { name, old, new } => {
+                write!(
+                    f,
+                    "function `{name}` changed signature from {} to {}",
+                    FunctionSignature(old),
+                    FunctionSignature(new)
+                )
+            }
+            BreakingChange::RemovedMap { name } => write!(f, "map `{name}` was removed"),
+            BreakingChange::ChangedMapKeyType { name, old, new } => {
+                write!(f, "map `{name}` changed key type from {old} to {new}")
+            }
+            BreakingChange::ChangedMapValueType { name, old, new } => {
+                write!(f, "map `{name}` changed value type from {old} to {new}")
+            }
+            BreakingChange::RemovedDataVar { name } => {
+                write!(f, "data-var `{name}` was removed")
+            }
+            BreakingChange::ChangedDataVarType { name, old, new } => {
+                write!(f, "data-var `{name}` changed type from {old} to {new}")
+            }
+            BreakingChange::RemovedFungibleToken { name } => {
+                write!(f, "fungible token `{name}` was removed")
+            }
+            BreakingChange::RemovedNonFungibleToken { name } => {
+                write!(f, "non-fungible token `{name}` was removed")
+            }
+            BreakingChange::ChangedNonFungibleTokenType { name, old, new } => {
+                write!(
+                    f,
+                    "non-fungible token `{name}` changed asset type from {old} to {new}"
+                )
+            }
+        }
+    }
+}
+
+/// Formats a function type as `(arg-type ...) -> return-type`.
+struct FunctionSignature<'a>(&'a FunctionType);
+
+impl fmt::Display for FunctionSignature<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            FunctionType::Fixed(FixedFunction { args, returns }) => {
+                write!(f, "(")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "{}", arg.signature)?;
+                }
+                write!(f, ") -> {returns}")
+            }
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// Compares the public interfaces and data layouts of two versions of a
+/// contract, and returns the breaking changes introduced by `new`.
+///
+/// Additions (new functions, maps, variables or tokens) are never breaking.
+pub fn check_compatibility(old: &ContractAnalysis, new: &ContractAnalysis) -> Vec<BreakingChange> {
+    let mut changes = vec![];
+
+    check_functions(old, new, &mut changes);
+
+    for (name, (old_key, old_value)) in &old.map_types {
+        match new.map_types.get(name) {
+            None => changes.push(BreakingChange::RemovedMap { name: name.clone() }),
+            Some((new_key, new_value)) => {
+                if old_key != new_key {
+                    changes.push(BreakingChange::ChangedMapKeyType {
+                        name: name.clone(),
+                        old: old_key.clone(),
+                        new: new_key.clone(),
+                    });
+                }
+                if old_value != new_value {
+                    changes.push(BreakingChange::ChangedMapValueType {
+                        name: name.clone(),
+                        old: old_value.clone(),
+                        new: new_value.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    for (name, old_ty) in &old.persisted_variable_types {
+        match new.persisted_variable_types.get(name) {
+            None => changes.push(BreakingChange::RemovedDataVar { name: name.clone() }),
+            Some(new_ty) if new_ty != old_ty => changes.push(BreakingChange::ChangedDataVarType {
+                name: name.clone(),
+                old: old_ty.clone(),
+                new: new_ty.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for name in old.fungible_tokens.difference(&new.fungible_tokens) {
+        changes.push(BreakingChange::RemovedFungibleToken { name: name.clone() });
+    }
+
+    for (name, old_ty) in &old.non_fungible_tokens {
+        match new.non_fungible_tokens.get(name) {
+            None => changes.push(BreakingChange::RemovedNonFungibleToken { name: name.clone() }),
+            Some(new_ty) if new_ty != old_ty => {
+                changes.push(BreakingChange::ChangedNonFungibleTokenType {
+                    name: name.clone(),
+                    old: old_ty.clone(),
+                    new: new_ty.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    changes
+}
+
+fn public_interface(
+    analysis: &ContractAnalysis,
+) -> BTreeMap<&ClarityName, (FunctionKind, &FunctionType)> {
+    analysis
+        .public_function_types
+        .iter()
+        .map(|(name, ty)| (name, (FunctionKind::Public, ty)))
+        .chain(
+            analysis
+                .read_only_function_types
+                .iter()
+                .map(|(name, ty)| (name, (FunctionKind::ReadOnly, ty))),
+        )
+        .collect()
+}
+
+fn check_functions(
+    old: &ContractAnalysis,
+    new: &ContractAnalysis,
+    changes: &mut Vec<BreakingChange>,
+) {
+    let new_functions = public_interface(new);
+
+    for (name, (old_kind, old_ty)) in public_interface(old) {
+        let Some((new_kind, new_ty)) = new_functions.get(name) else {
+            changes.push(BreakingChange::RemovedFunction {
+                name: name.clone(),
+                kind: old_kind,
+            });
+            continue;
+        };
+
+        if old_kind != *new_kind {
+            changes.push(BreakingChange::ChangedFunctionKind {
+                name: name.clone(),
+                old: old_kind,
+                new: *new_kind,
+            });
+        }
+
+        if !same_signature(old_ty, new_ty) {
+            changes.push(BreakingChange::ChangedFunctionSignature {
+                name: name.clone(),
+                old: old_ty.clone(),
+                new: (*new_ty).clone(),
+            });
+        }
+    }
+}
+
+/// Renaming arguments doesn't affect callers, so only the argument types and
+/// the return type are compared.
+fn same_signature(old: &FunctionType, new: &FunctionType) -> bool {
+    match (old, new) {
+        (
+            FunctionType::Fixed(FixedFunction {
+                args: old_args,
+                returns: old_returns,
+            }),
+            FunctionType::Fixed(FixedFunction {
+                args: new_args,
+                returns: new_returns,
+            }),
+        ) => {
+            old_returns == new_returns
+                && old_args.len() == new_args.len()
+                && old_args
+                    .iter()
+                    .zip(new_args)
+                    .all(|(old_arg, new_arg)| old_arg.signature == new_arg.signature)
+        }
+        _ => old == new,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+
+    use super::*;
+    use crate::{compile, CompileOptions};
+
+    fn analyze(source: &str) -> ContractAnalysis {
+        compile(
+            source,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .expect("failed to compile contract")
+        .contract_analysis
+    }
+
+    #[test]
+    fn additions_are_compatible() {
+        let old = analyze("(define-public (foo (a int)) (ok a))");
+        let new = analyze(
+            "
+            (define-public (foo (renamed int)) (ok renamed))
+            (define-read-only (bar) u1)
+            (define-map m int int)
+            (define-data-var v bool true)
+            ",
+        );
+
+        assert!(check_compatibility(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn removed_and_changed_functions() {
+        let old = analyze(
+            "
+            (define-public (foo (a int)) (ok a))
+            (define-public (bar (a int)) (ok a))
+            (define-read-only (baz) u1)
+            ",
+        );
+        let new = analyze(
+            "
+            (define-public (foo (a uint)) (ok a))
+            (define-read-only (baz) u1)
+            (define-private (bar (a int)) (ok a))
+            ",
+        );
+
+        let changes = check_compatibility(&old, &new);
+        assert_eq!(changes.len(), 2);
+        assert!(changes.contains(&BreakingChange::RemovedFunction {
+            name: "bar".into(),
+            kind: FunctionKind::Public,
+        }));
+        assert!(changes.iter().any(|change| matches!(
+            change,
+            BreakingChange::ChangedFunctionSignature { name, .. } if name.as_str() == "foo"
+        )));
+    }
+
+    #[test]
+    fn function_kind_change() {
+        let old = analyze("(define-read-only (foo) (ok u1))");
+        let new = analyze("(define-public (foo) (ok u1))");
+
+        assert_eq!(
+            check_compatibility(&old, &new),
+            vec![BreakingChange::ChangedFunctionKind {
+                name: "foo".into(),
+                old: FunctionKind::ReadOnly,
+                new: FunctionKind::Public,
+            }]
+        );
+    }
+
+    #[test]
+    fn data_layout_changes() {
+        let old = analyze(
+            "
+            (define-map m1 int int)
+            (define-map m2 int int)
+            (define-data-var v1 int 0)
+            (define-data-var v2 int 0)
+            (define-fungible-token ft)
+            (define-non-fungible-token nft uint)
+            ",
+        );
+        let new = analyze(
+            "
+            (define-map m1 int uint)
+            (define-data-var v1 uint u0)
+            (define-non-fungible-token nft int)
+            ",
+        );
+
+        let changes = check_compatibility(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                BreakingChange::ChangedMapValueType {
+                    name: "m1".into(),
+                    old: TypeSignature::IntType,
+                    new: TypeSignature::UIntType,
+                },
+                BreakingChange::RemovedMap { name: "m2".into() },
+                BreakingChange::ChangedDataVarType {
+                    name: "v1".into(),
+                    old: TypeSignature::IntType,
+                    new: TypeSignature::UIntType,
+                },
+                BreakingChange::RemovedDataVar { name: "v2".into() },
+                BreakingChange::RemovedFungibleToken { name: "ft".into() },
+                BreakingChange::ChangedNonFungibleTokenType {
+                    name: "nft".into(),
+                    old: TypeSignature::UIntType,
+                    new: TypeSignature::IntType,
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Costs of the host functions.
+//!
+//! The operations done by the host functions, like reading a data var or
+//! transferring a token, are charged the same cost as in the interpreter. Each
+//! host function charges the cost function mapped to its name in
+//! [`HOST_COSTS`], with an input size which depends on the operation (e.g. the
+//! size of the value read).
+
+use clarity::vm::costs::cost_functions::ClarityCostFunction;
+
+/// The cost function charged by each host function imported by the standard
+/// library, or `None` for the host functions which have no cost of their own:
+/// the definitions which are not charged by the interpreter either, and the
+/// helpers which are part of the evaluation of another expression.
+pub const HOST_COSTS: &[(&str, Option<ClarityCostFunction>)] = &[
+    ("define_function", None),
+    ("define_variable", Some(ClarityCostFunction::CreateVar)),
+    ("define_ft", Some(ClarityCostFunction::CreateFt)),
+    ("define_nft", Some(ClarityCostFunction::CreateNft)),
+    ("define_map", Some(ClarityCostFunction::CreateMap)),
+    ("define_trait", None),
+    ("impl_trait", None),
+    ("get_variable", Some(ClarityCostFunction::FetchVar)),
+    ("set_variable", Some(ClarityCostFunction::SetVar)),
+    ("print", Some(ClarityCostFunction::Print)),
+    ("enter_as_contract", Some(ClarityCostFunction::AsContract)),
+    ("exit_as_contract", None),
+    ("enter_at_block", Some(ClarityCostFunction::AtBlock)),
+    ("exit_at_block", None),
+    ("stx_get_balance", Some(ClarityCostFunction::StxBalance)),
+    ("stx_account", Some(ClarityCostFunction::StxGetAccount)),
+    ("stx_burn", Some(ClarityCostFunction::StxTransfer)),
+    ("stx_transfer", Some(ClarityCostFunction::StxTransfer)),
+    ("ft_get_supply", Some(ClarityCostFunction::FtSupply)),
+    ("ft_get_balance", Some(ClarityCostFunction::FtBalance)),
+    ("ft_burn", Some(ClarityCostFunction::FtBurn)),
+    ("ft_mint", Some(ClarityCostFunction::FtMint)),
+    ("ft_transfer", Some(ClarityCostFunction::FtTransfer)),
+    ("nft_get_owner", Some(ClarityCostFunction::NftOwner)),
+    ("nft_burn", Some(ClarityCostFunction::NftBurn)),
+    ("nft_mint", Some(ClarityCostFunction::NftMint)),
+    ("nft_transfer", Some(ClarityCostFunction::NftTransfer)),
+    ("map_get", Some(ClarityCostFunction::FetchEntry)),
+    // Each key is charged as a `map_get`.
+    ("map_get_batch", None),
+    ("map_set", Some(ClarityCostFunction::SetEntry)),
+    ("map_insert", Some(ClarityCostFunction::SetEntry)),
+    ("map_delete", Some(ClarityCostFunction::SetEntry)),
+    ("get_block_info", Some(ClarityCostFunction::BlockInfo)),
+    (
+        "get_burn_block_info",
+        Some(ClarityCostFunction::GetBurnBlockInfo),
+    ),
+    (
+        "get_stacks_block_info",
+        Some(ClarityCostFunction::BlockInfo),
+    ),
+    ("get_tenure_info", Some(ClarityCostFunction::BlockInfo)),
+    // The load of the contract and the application of the function are
+    // charged when the function is executed.
+    ("contract_call", Some(ClarityCostFunction::ContractCall)),
+    ("begin_public_call", None),
+    ("begin_read_only_call", None),
+    ("commit_call", None),
+    ("roll_back_call", None),
+    ("keccak256", Some(ClarityCostFunction::Keccak256)),
+    ("sha512", Some(ClarityCostFunction::Sha512)),
+    ("sha512_256", Some(ClarityCostFunction::Sha512t256)),
+    (
+        "secp256k1_recover",
+        Some(ClarityCostFunction::Secp256k1recover),
+    ),
+    (
+        "secp256k1_verify",
+        Some(ClarityCostFunction::Secp256k1verify),
+    ),
+    ("principal_of", Some(ClarityCostFunction::PrincipalOf)),
+    // Imported by the experimental `contract-source-hash?`, charged as the
+    // hash of the source of the contract.
+    (
+        "contract_source_hash",
+        Some(ClarityCostFunction::Sha512t256),
+    ),
+    ("tx_sender", None),
+    ("contract_caller", None),
+    ("tx_sponsor", None),
+    ("block_height", None),
+    ("stacks_block_height", None),
+    ("tenure_height", None),
+    ("burn_block_height", None),
+    ("stx_liquid_supply", None),
+    ("is_in_regtest", None),
+    ("is_in_mainnet", None),
+    ("chain_id", None),
+    ("save_constant", None),
+    ("load_constant", None),
+    ("skip_list", None),
+    ("debug_print", None),
+];
+
+/// Returns the cost function charged by the host function `name`.
+pub fn host_cost_function(name: &str) -> Option<ClarityCostFunction> {
+    HOST_COSTS
+        .iter()
+        .find(|(host_function, _)| *host_function == name)
+        .and_then(|(_, cost_function)| *cost_function)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn host_costs_cover_standard_imports() {
+        let module = walrus::Module::from_buffer(include_bytes!("standard/standard.wasm")).unwrap();
+
+        for import in module.imports.iter().filter(|i| i.module == "clarity") {
+            assert!(
+                HOST_COSTS.iter().any(|(name, _)| *name == import.name),
+                "missing cost of host function {}",
+                import.name
+            );
+        }
+    }
+
+    #[test]
+    fn host_cost_function_lookup() {
+        assert_eq!(
+            host_cost_function("map_get"),
+            Some(ClarityCostFunction::FetchEntry)
+        );
+        assert_eq!(host_cost_function("tx_sender"), None);
+        assert_eq!(host_cost_function("not_a_host_function"), None);
+    }
+}
@@ -1,15 +1,21 @@
 //! The `datastore` module contains simple in-memory imnplementations of the
 //! various data storage traits used during program execution.
 //!
+//! The contracts of a [`TestEnvironment`](crate::tools::TestEnvironment) are
+//! stored in a [`ChainStore`]: the in-memory [`Datastore`] with the blocks
+//! of a [`BurnDatastore`], or the backing store of a node wrapped in a
+//! [`NodeStore`] with the headers and burnchain state of the same node.
+//!
 //! It is intended for use in tooling and tests, but not intended to be used
 //! in production. The `datastore` module is only available when the
 //! `developer-mode` feature is enabled. Many of these methods are just
 //! mock implementations that do nothing.
 #![allow(clippy::expect_used, clippy::unwrap_used)]
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::marker::PhantomData;
 
-use clarity::consts::PEER_VERSION_EPOCH_2_5;
+use clarity::consts::{CHAIN_ID_MAINNET, CHAIN_ID_TESTNET, PEER_VERSION_EPOCH_2_5};
 use clarity::types::chainstate::{
     BlockHeaderHash, BurnchainHeaderHash, ConsensusHash, SortitionId, StacksAddress, StacksBlockId,
     VRFSeed,
@@ -51,7 +57,50 @@ pub struct BlockInfo {
     pox_payout_addrs: (Vec<TupleData>, u128),
 }
 
-#[derive(Clone, Debug, Default)]
+/// Chain id of the [`Network::Regtest`] profile. The nodes use the testnet
+/// chain id by default, and allow overriding it in their configuration: the
+/// profile sets its own, so that a contract can tell the networks apart.
+pub const CHAIN_ID_REGTEST: u32 = 0x8000_0001;
+
+/// Chain id of the [`Network::Mocknet`] profile, see [`CHAIN_ID_REGTEST`].
+pub const CHAIN_ID_MOCKNET: u32 = 0x8000_0002;
+
+/// The network a test environment simulates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    /// A local bitcoin regtest chain, as used by development nodes.
+    Regtest,
+    /// A node running without a burnchain.
+    Mocknet,
+}
+
+impl Network {
+    pub fn is_mainnet(&self) -> bool {
+        matches!(self, Network::Mainnet)
+    }
+
+    pub fn chain_id(&self) -> u32 {
+        match self {
+            Network::Mainnet => CHAIN_ID_MAINNET,
+            Network::Testnet => CHAIN_ID_TESTNET,
+            Network::Regtest => CHAIN_ID_REGTEST,
+            Network::Mocknet => CHAIN_ID_MOCKNET,
+        }
+    }
+
+    /// Height of the first burnchain block of the Stacks chain.
+    pub fn first_burn_block_height(&self) -> u32 {
+        match self {
+            Network::Mainnet => 666_050,
+            Network::Testnet => 2_000_000,
+            Network::Regtest | Network::Mocknet => 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct StacksConstants {
     pub burn_start_height: u32,
     pub pox_prepare_length: u32,
@@ -60,6 +109,53 @@ pub struct StacksConstants {
     pub epoch_21_start_height: u32,
 }
 
+impl StacksConstants {
+    /// Constants of the `network` profile. Mainnet, testnet and regtest use
+    /// the PoX parameters of the nodes of these networks. A mocknet has no
+    /// burnchain, its reward cycles are short ones of its own.
+    pub fn for_network(network: Network) -> Self {
+        let burn_start_height = network.first_burn_block_height();
+        match network {
+            Network::Mainnet => Self {
+                burn_start_height,
+                pox_prepare_length: 100,
+                pox_reward_cycle_length: 2100,
+                pox_rejection_fraction: 25,
+                epoch_21_start_height: 781_551,
+            },
+            Network::Testnet => Self {
+                burn_start_height,
+                pox_prepare_length: 50,
+                pox_reward_cycle_length: 1050,
+                pox_rejection_fraction: 12,
+                epoch_21_start_height: 2_422_101,
+            },
+            Network::Regtest => Self {
+                burn_start_height,
+                pox_prepare_length: 3,
+                pox_reward_cycle_length: 5,
+                pox_rejection_fraction: 3_333_333_333_333_333,
+                epoch_21_start_height: 0,
+            },
+            Network::Mocknet => Self {
+                burn_start_height,
+                pox_prepare_length: 2,
+                pox_reward_cycle_length: 10,
+                pox_rejection_fraction: 25,
+                epoch_21_start_height: 0,
+            },
+        }
+    }
+}
+
+/// The constants of testnet, the network of the test environments by
+/// default.
+impl Default for StacksConstants {
+    fn default() -> Self {
+        Self::for_network(Network::Testnet)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BurnDatastore {
     store: HashMap<StacksBlockId, BlockInfo>,
@@ -188,6 +284,152 @@ impl Datastore {
         self.current_chain_tip = self.open_chain_tip;
         self.chain_height
     }
+
+    /// Returns the key-value entries visible at the current chain tip, in key
+    /// order, with the values as serialized by the Clarity database.
+    pub fn entries(&self) -> BTreeMap<String, String> {
+        self.block_id_lookup
+            .get(&self.current_chain_tip)
+            .and_then(|lookup_id| self.store.get(lookup_id))
+            .map(|map| {
+                map.iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// The operations a [`TestEnvironment`](crate::tools::TestEnvironment) needs
+/// from the store of its contracts, on top of the key-value data, the
+/// metadata and the block lookups of a [`ClarityBackingStore`].
+pub trait ChainStore: ClarityBackingStore {
+    /// The headers and the burnchain state of the blocks of the store, which
+    /// must follow its chain tip.
+    type BurnStore: HeadersDB + BurnStateDB + Clone;
+
+    /// Moves the chain tip of the store and of its `burn_store` `count`
+    /// blocks forward, and returns its new height.
+    fn advance_chain_tip(&mut self, burn_store: &mut Self::BurnStore, count: u32) -> u32;
+}
+
+impl ChainStore for Datastore {
+    type BurnStore = BurnDatastore;
+
+    fn advance_chain_tip(&mut self, burn_store: &mut BurnDatastore, count: u32) -> u32 {
+        burn_store.advance_chain_tip(count);
+        Datastore::advance_chain_tip(self, count)
+    }
+}
+
+/// A [`ChainStore`] over the backing store `S` of a node, with the headers
+/// and burnchain state `B` of the same node. Its blocks are the blocks of the
+/// node: advancing the chain tip moves the reads to a later block, if the
+/// node has it, and the headers of the node already have all its blocks.
+pub struct NodeStore<S, B> {
+    store: S,
+    burn_store: PhantomData<fn() -> B>,
+}
+
+impl<S, B> NodeStore<S, B> {
+    pub fn new(store: S) -> Self {
+        Self {
+            store,
+            burn_store: PhantomData,
+        }
+    }
+}
+
+impl<S: ClarityBackingStore, B: HeadersDB + BurnStateDB + Clone> ChainStore for NodeStore<S, B> {
+    type BurnStore = B;
+
+    fn advance_chain_tip(&mut self, _burn_store: &mut B, count: u32) -> u32 {
+        let height = self.store.get_current_block_height().saturating_add(count);
+        if let Some(block) = self.store.get_block_at_height(height) {
+            // A node which can't read the block stays at its current tip.
+            let _ = self.store.set_block_hash(block);
+        }
+        self.store.get_current_block_height()
+    }
+}
+
+impl<S: ClarityBackingStore, B> ClarityBackingStore for NodeStore<S, B> {
+    fn put_all_data(&mut self, items: Vec<(String, String)>) -> Result<()> {
+        self.store.put_all_data(items)
+    }
+
+    fn get_data(&mut self, key: &str) -> Result<Option<String>> {
+        self.store.get_data(key)
+    }
+
+    fn has_entry(&mut self, key: &str) -> Result<bool> {
+        self.store.has_entry(key)
+    }
+
+    fn set_block_hash(&mut self, bhh: StacksBlockId) -> Result<StacksBlockId> {
+        self.store.set_block_hash(bhh)
+    }
+
+    fn get_block_at_height(&mut self, height: u32) -> Option<StacksBlockId> {
+        self.store.get_block_at_height(height)
+    }
+
+    fn get_current_block_height(&mut self) -> u32 {
+        self.store.get_current_block_height()
+    }
+
+    fn get_open_chain_tip_height(&mut self) -> u32 {
+        self.store.get_open_chain_tip_height()
+    }
+
+    fn get_open_chain_tip(&mut self) -> StacksBlockId {
+        self.store.get_open_chain_tip()
+    }
+
+    fn make_contract_commitment(&mut self, contract_hash: Sha512Trunc256Sum) -> String {
+        self.store.make_contract_commitment(contract_hash)
+    }
+
+    fn insert_metadata(
+        &mut self,
+        contract: &QualifiedContractIdentifier,
+        key: &str,
+        value: &str,
+    ) -> Result<()> {
+        self.store.insert_metadata(contract, key, value)
+    }
+
+    fn get_metadata(
+        &mut self,
+        contract: &QualifiedContractIdentifier,
+        key: &str,
+    ) -> Result<Option<String>> {
+        self.store.get_metadata(contract, key)
+    }
+
+    fn get_data_with_proof(&mut self, key: &str) -> Result<Option<(String, Vec<u8>)>> {
+        self.store.get_data_with_proof(key)
+    }
+
+    fn get_contract_hash(
+        &mut self,
+        contract: &QualifiedContractIdentifier,
+    ) -> Result<(StacksBlockId, Sha512Trunc256Sum)> {
+        self.store.get_contract_hash(contract)
+    }
+
+    fn get_metadata_manual(
+        &mut self,
+        at_height: u32,
+        contract: &QualifiedContractIdentifier,
+        key: &str,
+    ) -> Result<Option<String>> {
+        self.store.get_metadata_manual(at_height, contract, key)
+    }
+
+    fn get_side_store(&mut self) -> &Connection {
+        self.store.get_side_store()
+    }
 }
 
 impl Default for Datastore {
@@ -523,7 +765,7 @@ impl BurnStateDB for BurnDatastore {
 
     /// Returns the height of the burnchain when the Stacks chain started running.
     fn get_burn_start_height(&self) -> u32 {
-        0
+        self.constants.burn_start_height
     }
 
     fn get_v3_unlock_height(&self) -> u32 {
@@ -0,0 +1,232 @@
+//! Support for the `debug-print` pseudo-word.
+//!
+//! `(debug-print value)` or `(debug-print <level> value)`, where `<level>` is
+//! one of `trace`, `debug`, `info`, `warn` or `error`, logs `value` when the
+//! contract is compiled in developer mode, and evaluates to `value`.
+//!
+//! `debug-print` is not a Clarity builtin, so it is rewritten before the
+//! analysis passes run:
+//! - in production mode, the expression is replaced by `value`, so that the
+//!   generated code is identical to a contract without `debug-print`.
+//! - in developer mode, the expression is turned into `(begin value)`, which
+//!   type-checks, and its id is recorded so that the generator emits a call to
+//!   the `debug_print` host function instead.
+//!
+//! A contract defining its own `debug-print` is left untouched.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use clarity::vm::functions::define::DefineFunctions;
+use clarity::vm::{SymbolicExpression, SymbolicExpressionType};
+
+pub const DEBUG_PRINT: &str = "debug-print";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Trace = 0,
+    Debug = 1,
+    Info = 2,
+    Warn = 3,
+    Error = 4,
+}
+
+impl LogLevel {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "trace" => Some(LogLevel::Trace),
+            "debug" => Some(LogLevel::Debug),
+            "info" => Some(LogLevel::Info),
+            "warn" => Some(LogLevel::Warn),
+            "error" => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+
+    pub fn from_i32(level: i32) -> Option<Self> {
+        match level {
+            0 => Some(LogLevel::Trace),
+            1 => Some(LogLevel::Debug),
+            2 => Some(LogLevel::Info),
+            3 => Some(LogLevel::Warn),
+            4 => Some(LogLevel::Error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogLevel::Trace => write!(f, "TRACE"),
+            LogLevel::Debug => write!(f, "DEBUG"),
+            LogLevel::Info => write!(f, "INFO"),
+            LogLevel::Warn => write!(f, "WARN"),
+            LogLevel::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+/// Rewrites the `debug-print` expressions of a contract, see the module
+/// documentation. Returns the ids of the rewritten expressions with their
+/// level, which is empty in production mode.
+pub(crate) fn rewrite_debug_prints(
+    expressions: &mut [SymbolicExpression],
+    developer_mode: bool,
+) -> HashMap<u64, LogLevel> {
+    let mut debug_prints = HashMap::new();
+    if !defines_debug_print(expressions) {
+        for expr in expressions.iter_mut() {
+            rewrite(expr, developer_mode, &mut debug_prints);
+        }
+    }
+    debug_prints
+}
+
+fn defines_debug_print(expressions: &[SymbolicExpression]) -> bool {
+    expressions.iter().any(|expr| {
+        let Some([define, signature, ..]) = expr.match_list() else {
+            return false;
+        };
+        let is_define = define
+            .match_atom()
+            .is_some_and(|name| DefineFunctions::lookup_by_name(name).is_some());
+        let name = signature
+            .match_list()
+            .and_then(|signature| signature.first())
+            .unwrap_or(signature)
+            .match_atom();
+        is_define && name.is_some_and(|name| name.as_str() == DEBUG_PRINT)
+    })
+}
+
+fn rewrite(
+    expr: &mut SymbolicExpression,
+    developer_mode: bool,
+    debug_prints: &mut HashMap<u64, LogLevel>,
+) {
+    let SymbolicExpressionType::List(list) = &mut expr.expr else {
+        return;
+    };
+
+    for inner in list.iter_mut() {
+        rewrite(inner, developer_mode, debug_prints);
+    }
+
+    if list
+        .first()
+        .and_then(|first| first.match_atom())
+        .map(|name| name.as_str())
+        != Some(DEBUG_PRINT)
+    {
+        return;
+    }
+
+    // Malformed calls are left as-is, and will be reported by the analysis
+    // as calls to an unknown function.
+    let (level, value) = match &list[1..] {
+        [value] => (LogLevel::Debug, value.clone()),
+        [level, value] => match level.match_atom().and_then(|l| LogLevel::from_name(l)) {
+            Some(level) => (level, value.clone()),
+            None => return,
+        },
+        _ => return,
+    };
+
+    if developer_mode {
+        let mut begin = list[0].clone();
+        begin.expr = SymbolicExpressionType::Atom("begin".into());
+        *list = vec![begin, value];
+        debug_prints.insert(expr.id, level);
+    } else {
+        *expr = value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::ast::build_ast;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+
+    use super::*;
+    use crate::{compile, CompileOptions};
+
+    fn parse(source: &str) -> Vec<SymbolicExpression> {
+        build_ast(
+            &QualifiedContractIdentifier::transient(),
+            source,
+            &mut LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+        )
+        .unwrap()
+        .expressions
+    }
+
+    fn to_source(exprs: &[SymbolicExpression]) -> String {
+        exprs
+            .iter()
+            .map(|expr| expr.to_string().replace(char::is_whitespace, ""))
+            .collect()
+    }
+
+    fn imports_debug_print(source: &str, developer_mode: bool) -> bool {
+        let module = compile(
+            source,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default().developer_mode(developer_mode),
+        )
+        .expect("failed to compile contract")
+        .module;
+        module.imports.iter().any(|i| i.name == "debug_print")
+    }
+
+    #[test]
+    fn production_mode_strips_debug_print() {
+        let mut exprs = parse("(+ (debug-print warn 1) (debug-print 2))");
+        let debug_prints = rewrite_debug_prints(&mut exprs, false);
+
+        assert!(debug_prints.is_empty());
+        assert_eq!(to_source(&exprs), to_source(&parse("(+ 1 2)")));
+    }
+
+    #[test]
+    fn developer_mode_records_debug_print() {
+        let mut exprs = parse("(+ (debug-print warn 1) (debug-print 2))");
+        let debug_prints = rewrite_debug_prints(&mut exprs, true);
+
+        let mut levels: Vec<_> = debug_prints.values().copied().collect();
+        levels.sort();
+        assert_eq!(levels, vec![LogLevel::Debug, LogLevel::Warn]);
+        assert_eq!(
+            to_source(&exprs),
+            to_source(&parse("(+ (begin 1) (begin 2))"))
+        );
+    }
+
+    #[test]
+    fn user_defined_debug_print_is_untouched() {
+        let source = "(define-private (debug-print (a int)) a) (debug-print 1)";
+        let mut exprs = parse(source);
+        let debug_prints = rewrite_debug_prints(&mut exprs, true);
+
+        assert!(debug_prints.is_empty());
+        assert_eq!(to_source(&exprs), to_source(&parse(source)));
+    }
+
+    #[test]
+    fn debug_print_host_import() {
+        let source = "(define-read-only (foo) (debug-print info (list 1 2 3))) (foo)";
+        assert!(imports_debug_print(source, true));
+        assert!(!imports_debug_print(source, false));
+    }
+}
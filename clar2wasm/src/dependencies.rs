@@ -0,0 +1,254 @@
+//! Extraction of the dependencies of a contract on other contracts.
+//!
+//! A contract can only be compiled and deployed once the contracts it calls
+//! with a static `contract-call?`, and the contracts defining the traits it
+//! uses or implements, are deployed. Deployment tooling can use
+//! [`contract_dependencies`] on each contract of a project, then
+//! [`deployment_order`] to find an order in which to deploy them.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use clarity::vm::ast::ContractAST;
+use clarity::vm::representations::SymbolicExpressionType;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, TraitIdentifier};
+use clarity::vm::{SymbolicExpression, Value};
+
+/// The contracts and traits a contract depends on.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContractDependencies {
+    /// Contracts called with a static `contract-call?`.
+    pub called_contracts: BTreeSet<QualifiedContractIdentifier>,
+    /// Traits imported with `use-trait`.
+    pub used_traits: BTreeSet<TraitIdentifier>,
+    /// Traits implemented with `impl-trait`.
+    pub implemented_traits: BTreeSet<TraitIdentifier>,
+}
+
+impl ContractDependencies {
+    /// Returns the contracts which must be deployed before this one.
+    pub fn contracts(&self) -> BTreeSet<QualifiedContractIdentifier> {
+        self.called_contracts
+            .iter()
+            .chain(
+                self.used_traits
+                    .iter()
+                    .chain(&self.implemented_traits)
+                    .map(|trait_id| &trait_id.contract_identifier),
+            )
+            .cloned()
+            .collect()
+    }
+
+    fn visit(&mut self, expr: &SymbolicExpression) {
+        let Some(list) = expr.match_list() else {
+            return;
+        };
+
+        match (
+            list.first()
+                .and_then(|e| e.match_atom())
+                .map(|a| a.as_str()),
+            list.get(1).map(|e| &e.expr),
+            list.get(2).map(|e| &e.expr),
+        ) {
+            (
+                Some("contract-call?"),
+                Some(SymbolicExpressionType::LiteralValue(Value::Principal(
+                    PrincipalData::Contract(contract_identifier),
+                ))),
+                _,
+            ) => {
+                self.called_contracts.insert(contract_identifier.clone());
+            }
+            (Some("use-trait"), _, Some(SymbolicExpressionType::Field(trait_identifier))) => {
+                self.used_traits.insert(trait_identifier.clone());
+            }
+            (Some("impl-trait"), Some(SymbolicExpressionType::Field(trait_identifier)), _) => {
+                self.implemented_traits.insert(trait_identifier.clone());
+            }
+            _ => {}
+        }
+
+        for expr in list {
+            self.visit(expr);
+        }
+    }
+}
+
+/// Returns the contracts and traits referenced by the contract `ast`.
+///
+/// Dynamic `contract-call?`s, through a trait reference, are resolved at
+/// runtime and are not dependencies.
+pub fn contract_dependencies(ast: &ContractAST) -> ContractDependencies {
+    let mut dependencies = ContractDependencies::default();
+    for expr in &ast.expressions {
+        dependencies.visit(expr);
+    }
+    dependencies
+}
+
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub enum DependencyError {
+    #[error("dependency cycle: {}", format_cycle(.0))]
+    Cycle(Vec<QualifiedContractIdentifier>),
+}
+
+fn format_cycle(cycle: &[QualifiedContractIdentifier]) -> String {
+    cycle
+        .iter()
+        .map(|contract| contract.to_string())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Returns the contracts of `contracts` in an order in which they can be
+/// deployed, each after its dependencies. The dependencies which are not in
+/// `contracts` are assumed to be already deployed.
+///
+/// The order is deterministic: among the contracts which can be deployed, the
+/// first in `contracts` comes first.
+pub fn deployment_order(
+    contracts: &BTreeMap<QualifiedContractIdentifier, ContractDependencies>,
+) -> Result<Vec<QualifiedContractIdentifier>, DependencyError> {
+    fn visit<'a>(
+        contract: &'a QualifiedContractIdentifier,
+        contracts: &'a BTreeMap<QualifiedContractIdentifier, ContractDependencies>,
+        path: &mut Vec<&'a QualifiedContractIdentifier>,
+        order: &mut Vec<QualifiedContractIdentifier>,
+    ) -> Result<(), DependencyError> {
+        if order.contains(contract) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|c| *c == contract) {
+            let mut cycle: Vec<_> = path[start..].iter().map(|c| (*c).clone()).collect();
+            cycle.push(contract.clone());
+            return Err(DependencyError::Cycle(cycle));
+        }
+
+        let Some((contract, dependencies)) = contracts.get_key_value(contract) else {
+            return Ok(());
+        };
+        path.push(contract);
+        for dependency in dependencies.contracts() {
+            if dependency != *contract {
+                if let Some((dependency, _)) = contracts.get_key_value(&dependency) {
+                    visit(dependency, contracts, path, order)?;
+                }
+            }
+        }
+        path.pop();
+        order.push(contract.clone());
+        Ok(())
+    }
+
+    let mut order = Vec::with_capacity(contracts.len());
+    for contract in contracts.keys() {
+        visit(contract, contracts, &mut vec![], &mut order)?;
+    }
+    Ok(order)
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::ast::build_ast_with_diagnostics;
+    use clarity::vm::ClarityVersion;
+
+    use super::*;
+
+    fn contract(name: &str) -> QualifiedContractIdentifier {
+        QualifiedContractIdentifier::local(name).unwrap()
+    }
+
+    fn dependencies_of(source: &str) -> ContractDependencies {
+        let (ast, _, success) = build_ast_with_diagnostics(
+            &QualifiedContractIdentifier::transient(),
+            source,
+            &mut (),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+        );
+        assert!(success, "failed to parse contract");
+        contract_dependencies(&ast)
+    }
+
+    #[test]
+    fn extract_dependencies() {
+        let dependencies = dependencies_of(
+            r#"
+            (use-trait ft-trait 'SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.sip-010-trait-ft-standard.sip-010-trait)
+            (impl-trait .my-traits.my-trait)
+            (define-public (foo (token <ft-trait>))
+                (begin
+                    (try! (contract-call? .bar baz u1))
+                    (contract-call? token get-name)
+                )
+            )
+            "#,
+        );
+
+        let sip_010 = QualifiedContractIdentifier::parse(
+            "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.sip-010-trait-ft-standard",
+        )
+        .unwrap();
+        assert_eq!(
+            dependencies,
+            ContractDependencies {
+                called_contracts: [contract("bar")].into(),
+                used_traits: [TraitIdentifier {
+                    name: "sip-010-trait".into(),
+                    contract_identifier: sip_010.clone(),
+                }]
+                .into(),
+                implemented_traits: [TraitIdentifier {
+                    name: "my-trait".into(),
+                    contract_identifier: contract("my-traits"),
+                }]
+                .into(),
+            }
+        );
+        assert_eq!(
+            dependencies.contracts(),
+            [contract("bar"), contract("my-traits"), sip_010].into()
+        );
+    }
+
+    #[test]
+    fn order_dependencies_first() {
+        let contracts: BTreeMap<_, _> = [
+            ("a", "(contract-call? .b foo)"),
+            ("b", "(impl-trait .c.t) (contract-call? .external foo)"),
+            ("c", "(define-trait t ())"),
+        ]
+        .into_iter()
+        .map(|(name, source)| (contract(name), dependencies_of(source)))
+        .collect();
+
+        assert_eq!(
+            deployment_order(&contracts),
+            Ok(vec![contract("c"), contract("b"), contract("a")])
+        );
+    }
+
+    #[test]
+    fn detect_cycles() {
+        let contracts: BTreeMap<_, _> = [
+            ("a", "(contract-call? .b foo)"),
+            ("b", "(contract-call? .c foo)"),
+            ("c", "(contract-call? .a foo)"),
+        ]
+        .into_iter()
+        .map(|(name, source)| (contract(name), dependencies_of(source)))
+        .collect();
+
+        assert_eq!(
+            deployment_order(&contracts),
+            Err(DependencyError::Cycle(vec![
+                contract("a"),
+                contract("b"),
+                contract("c"),
+                contract("a")
+            ]))
+        );
+    }
+}
@@ -185,6 +185,7 @@ impl WasmGenerator {
             .local_tee(type_prefix);
 
         // Check for the standard principal prefix (0x05)
+        let copy_memory = self.copy_memory()?;
         block
             .i32_const(TypePrefix::PrincipalStandard as i32)
             .binop(BinaryOp::I32Eq)
@@ -206,8 +207,8 @@ impl WasmGenerator {
                         .local_get(offset_local)
                         .i32_const(1)
                         .binop(BinaryOp::I32Add)
-                        .i32_const(PRINCIPAL_BYTES as i32)
-                        .memory_copy(memory, memory);
+                        .i32_const(PRINCIPAL_BYTES as i32);
+                    copy_memory.emit(then);
 
                     // Write the contract name length (0)
                     then.local_get(result_offset).i32_const(0).store(
@@ -820,7 +821,7 @@ impl WasmGenerator {
             .local_get(offset_local)
             .i32_const(1)
             .binop(BinaryOp::I32Add)
-            .call(self.func_by_name("stdlib.load-i32-be"))
+            .call(self.func_by_name("stdlib.load-i32-be")?)
             .local_tee(length);
 
         // Verify that the length is within the specified type
@@ -1092,7 +1093,7 @@ impl WasmGenerator {
                     .local_get(offset_local)
                     .i32_const(1)
                     .binop(BinaryOp::I32Add)
-                    .call(self.func_by_name("stdlib.load-i32-be"))
+                    .call(self.func_by_name("stdlib.load-i32-be")?)
                     .local_tee(remaining_fields)
                     .i32_const(tuple_ty.get_type_map().len() as i32)
                     .binop(BinaryOp::I32LtU)
@@ -1169,7 +1170,7 @@ impl WasmGenerator {
                             .i32_const(keys_len as i32)
                             .local_get(offset_local)
                             .local_get(name_size)
-                            .call(self.func_by_name("stdlib.bsearch-clarity-name"));
+                            .call(self.func_by_name("stdlib.bsearch-clarity-name")?);
 
                         // update the offset local to point after the field name
                         switch_block
@@ -1257,7 +1258,7 @@ impl WasmGenerator {
                         .local_get(name_size)
                         .binop(BinaryOp::I32Sub)
                         .local_get(name_size)
-                        .call(self.func_by_name("stdlib.check-clarity-name"))
+                        .call(self.func_by_name("stdlib.check-clarity-name")?)
                         .unop(UnaryOp::I32Eqz)
                         .br_if(done_block_id);
 
@@ -1265,7 +1266,7 @@ impl WasmGenerator {
                     loop_
                         .local_get(offset_local)
                         .local_get(end_local)
-                        .call(self.func_by_name("stdlib.skip-unknown-value"))
+                        .call(self.func_by_name("stdlib.skip-unknown-value")?)
                         .local_tee(offset_local)
                         .unop(UnaryOp::I32Eqz)
                         .br_if(done_block_id);
@@ -1348,6 +1349,8 @@ impl WasmGenerator {
         end_local: LocalId,
         type_length: u32,
     ) -> Result<(), GeneratorError> {
+        let load_i32_be = self.func_by_name("stdlib.load-i32-be")?;
+
         // Create a block for the body of this operation, so that we can
         // early exit as needed.
         let block_ty = InstrSeqType::new(
@@ -1399,7 +1402,7 @@ impl WasmGenerator {
                     then.local_get(offset_local)
                         .i32_const(1)
                         .binop(BinaryOp::I32Add)
-                        .call(self.func_by_name("stdlib.load-i32-be"))
+                        .call(load_i32_be)
                         .local_tee(buffer_length);
 
                     // Verify that the buffer length is within the
@@ -1481,6 +1484,9 @@ impl WasmGenerator {
         end_local: LocalId,
         type_length: u32,
     ) -> Result<(), GeneratorError> {
+        let load_i32_be = self.func_by_name("stdlib.load-i32-be")?;
+        let is_valid_string_ascii = self.func_by_name("stdlib.is-valid-string-ascii")?;
+
         // Create a block for the body of this operation, so that we can
         // early exit as needed.
         let block_ty = InstrSeqType::new(
@@ -1532,7 +1538,7 @@ impl WasmGenerator {
                     then.local_get(offset_local)
                         .i32_const(1)
                         .binop(BinaryOp::I32Add)
-                        .call(self.func_by_name("stdlib.load-i32-be"))
+                        .call(load_i32_be)
                         .local_tee(string_length);
 
                     // Verify that the string length is within the
@@ -1578,7 +1584,7 @@ impl WasmGenerator {
                         .i32_const(5)
                         .binop(BinaryOp::I32Add)
                         .local_get(string_length)
-                        .call(self.func_by_name("stdlib.is-valid-string-ascii"))
+                        .call(is_valid_string_ascii)
                         .unop(UnaryOp::I32Eqz)
                         .if_else(
                             None,
@@ -1642,6 +1648,9 @@ impl WasmGenerator {
             }
         };
 
+        let load_i32_be = self.func_by_name("stdlib.load-i32-be")?;
+        let utf8_to_string_utf8 = self.func_by_name("stdlib.utf8-to-string-utf8")?;
+
         // Verify that reading 5 bytes from the offset is within the buffer
         builder
             .local_get(offset_local)
@@ -1678,7 +1687,7 @@ impl WasmGenerator {
                 then.local_get(offset_local)
                     .i32_const(1)
                     .binop(BinaryOp::I32Add)
-                    .call(self.func_by_name("stdlib.load-i32-be"))
+                    .call(load_i32_be)
                     .local_tee(string_length);
 
                 // If the (string length <= remaining bytes), we can try deserializing,
@@ -1701,7 +1710,7 @@ impl WasmGenerator {
                             .local_get(string_length)
                             .local_get(offset_result)
                             .i32_const(max_len as i32)
-                            .call(self.func_by_name("stdlib.utf8-to-string-utf8"));
+                            .call(utf8_to_string_utf8);
 
                         // move offset-local to the end of the utf8 representation
                         then.local_get(offset_local)
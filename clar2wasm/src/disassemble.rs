@@ -0,0 +1,332 @@
+//! Disassembly of the functions generated for a contract into readable
+//! pseudocode, to audit the output of the code generation.
+//!
+//! Each instruction is printed with the names of the functions, locals and
+//! globals it uses, from the name section, and is grouped under the Clarity
+//! expression which generated it:
+//!
+//! ```text
+//! func add-3
+//!   ;; 2:5 (+ ...)
+//!   local.get a
+//!   local.get b
+//!   call stdlib.add-int
+//! ```
+//!
+//! The functions of the standard library are not printed.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use clarity::vm::representations::SymbolicExpressionType;
+use clarity::vm::SymbolicExpression;
+use walrus::ir::{ExtendedLoad, Instr, InstrSeqId, LoadKind, StoreKind, Value};
+use walrus::{ExportItem, FunctionKind, LocalFunction, Module};
+
+/// Returns the pseudocode of the functions of `module` generated from the
+/// contract `expressions`.
+pub fn disassemble(module: &Module, expressions: &[SymbolicExpression]) -> String {
+    let mut labels = HashMap::new();
+    for expr in expressions {
+        collect_labels(expr, &mut labels);
+    }
+
+    let export_names: HashMap<_, _> = module
+        .exports
+        .iter()
+        .filter_map(|export| match export.item {
+            ExportItem::Function(id) => Some((id, export.name.as_str())),
+            _ => None,
+        })
+        .collect();
+
+    let mut disassembler = Disassembler {
+        module,
+        labels,
+        output: String::new(),
+        annotation: None,
+    };
+    for func in module.funcs.iter() {
+        let FunctionKind::Local(local) = &func.kind else {
+            continue;
+        };
+        let name = func
+            .name
+            .as_deref()
+            .or_else(|| export_names.get(&func.id()).copied());
+        if name.map_or(false, |name| name.starts_with("stdlib.")) {
+            continue;
+        }
+
+        match name {
+            Some(name) => {
+                let _ = writeln!(disassembler.output, "func {name}");
+            }
+            None => {
+                let _ = writeln!(disassembler.output, "func [{}]", func.id().index());
+            }
+        }
+        disassembler.annotation = None;
+        disassembler.seq(local, local.entry_block(), None, 1);
+        disassembler.output.push('\n');
+    }
+    disassembler.output
+}
+
+/// Records the label of `expr` and of its nested expressions.
+fn collect_labels(expr: &SymbolicExpression, labels: &mut HashMap<u32, String>) {
+    let description = match &expr.expr {
+        SymbolicExpressionType::List(list) => {
+            for expr in list.iter() {
+                collect_labels(expr, labels);
+            }
+            match list.first().and_then(|e| e.match_atom()) {
+                Some(name) => format!("({name} ...)"),
+                None => "(...)".to_owned(),
+            }
+        }
+        SymbolicExpressionType::Atom(name) => name.to_string(),
+        SymbolicExpressionType::LiteralValue(value) => value.to_string(),
+        _ => expr.to_string(),
+    };
+    if let Ok(id) = u32::try_from(expr.id) {
+        labels.insert(
+            id,
+            format!(
+                "{}:{} {description}",
+                expr.span.start_line, expr.span.start_column
+            ),
+        );
+    }
+}
+
+struct Disassembler<'a> {
+    module: &'a Module,
+    /// Labels of the expressions, by id.
+    labels: HashMap<u32, String>,
+    output: String,
+    /// Id of the expression annotating the last printed instruction.
+    annotation: Option<u32>,
+}
+
+impl Disassembler<'_> {
+    /// Prints the instructions of the sequence `seq_id` of `func`. The
+    /// instructions without a location were generated for the expression
+    /// `parent` of the enclosing block.
+    fn seq(&mut self, func: &LocalFunction, seq_id: InstrSeqId, parent: Option<u32>, depth: usize) {
+        for (instr, loc) in &func.block(seq_id).instrs {
+            let expr = if loc.is_default() {
+                parent
+            } else {
+                Some(loc.data())
+            };
+            self.annotate(expr, depth);
+
+            match instr {
+                Instr::Block(block) => {
+                    self.line(depth, &format!("block @{}", block.seq.index()));
+                    self.seq(func, block.seq, expr, depth + 1);
+                    self.line(depth, "end");
+                }
+                Instr::Loop(loop_) => {
+                    self.line(depth, &format!("loop @{}", loop_.seq.index()));
+                    self.seq(func, loop_.seq, expr, depth + 1);
+                    self.line(depth, "end");
+                }
+                Instr::IfElse(if_else) => {
+                    self.line(depth, &format!("if @{}", if_else.consequent.index()));
+                    self.seq(func, if_else.consequent, expr, depth + 1);
+                    self.line(depth, &format!("else @{}", if_else.alternative.index()));
+                    self.seq(func, if_else.alternative, expr, depth + 1);
+                    self.line(depth, "end");
+                }
+                instr => {
+                    let line = self.instr(instr);
+                    self.line(depth, &line);
+                }
+            }
+        }
+    }
+
+    /// Prints the label of the expression `expr`, if it is not the one of
+    /// the previous instruction.
+    fn annotate(&mut self, expr: Option<u32>, depth: usize) {
+        if expr.is_none() || expr == self.annotation {
+            return;
+        }
+        self.annotation = expr;
+        let label = expr
+            .and_then(|id| self.labels.get(&id))
+            .map_or("<unknown expression>", String::as_str)
+            .to_owned();
+        self.line(depth, &format!(";; {label}"));
+    }
+
+    fn line(&mut self, depth: usize, line: &str) {
+        let _ = writeln!(self.output, "{:indent$}{line}", "", indent = depth * 2);
+    }
+
+    /// Returns the text of an instruction without nested sequences.
+    fn instr(&self, instr: &Instr) -> String {
+        match instr {
+            Instr::Call(call) => format!("call {}", self.func_name(call.func)),
+            Instr::LocalGet(get) => format!("local.get {}", self.local_name(get.local)),
+            Instr::LocalSet(set) => format!("local.set {}", self.local_name(set.local)),
+            Instr::LocalTee(tee) => format!("local.tee {}", self.local_name(tee.local)),
+            Instr::GlobalGet(get) => format!("global.get {}", self.global_name(get.global)),
+            Instr::GlobalSet(set) => format!("global.set {}", self.global_name(set.global)),
+            Instr::Const(c) => match c.value {
+                Value::I32(v) => format!("i32.const {v}"),
+                Value::I64(v) => format!("i64.const {v}"),
+                Value::F32(v) => format!("f32.const {v}"),
+                Value::F64(v) => format!("f64.const {v}"),
+                Value::V128(v) => format!("v128.const {v:#x}"),
+            },
+            Instr::Binop(binop) => op_name(&format!("{:?}", binop.op)),
+            Instr::Unop(unop) => op_name(&format!("{:?}", unop.op)),
+            Instr::Load(load) => format!("{} offset={}", load_name(&load.kind), load.arg.offset),
+            Instr::Store(store) => {
+                format!("{} offset={}", store_name(&store.kind), store.arg.offset)
+            }
+            Instr::Br(br) => format!("br @{}", br.block.index()),
+            Instr::BrIf(br_if) => format!("br_if @{}", br_if.block.index()),
+            Instr::Drop(_) => "drop".to_owned(),
+            Instr::Select(_) => "select".to_owned(),
+            Instr::Return(_) => "return".to_owned(),
+            Instr::Unreachable(_) => "unreachable".to_owned(),
+            Instr::MemoryCopy(_) => "memory.copy".to_owned(),
+            Instr::MemoryFill(_) => "memory.fill".to_owned(),
+            Instr::MemorySize(_) => "memory.size".to_owned(),
+            Instr::MemoryGrow(_) => "memory.grow".to_owned(),
+            instr => format!("{instr:?}"),
+        }
+    }
+
+    fn func_name(&self, id: walrus::FunctionId) -> String {
+        self.module
+            .funcs
+            .get(id)
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("[{}]", id.index()))
+    }
+
+    fn local_name(&self, id: walrus::LocalId) -> String {
+        self.module
+            .locals
+            .get(id)
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("[{}]", id.index()))
+    }
+
+    fn global_name(&self, id: walrus::GlobalId) -> String {
+        self.module
+            .globals
+            .get(id)
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("[{}]", id.index()))
+    }
+}
+
+/// Returns the WAT name of the operator whose `Debug` name is `op`, for
+/// example `i32.add` for `I32Add` and `i64.lt_u` for `I64LtU`.
+fn op_name(op: &str) -> String {
+    let (ty, name) = match op.find(|c: char| c.is_ascii_digit()) {
+        Some(start) => {
+            let end = op[start..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(op.len(), |end| start + end);
+            op.split_at(end)
+        }
+        None => return op.to_lowercase(),
+    };
+
+    let mut wat = ty.to_lowercase();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            wat.push(if i == 0 { '.' } else { '_' });
+        }
+        wat.push(c.to_ascii_lowercase());
+    }
+    wat
+}
+
+fn load_name(kind: &LoadKind) -> String {
+    let extension = |kind: &ExtendedLoad| match kind {
+        ExtendedLoad::SignExtend => "s",
+        _ => "u",
+    };
+    match kind {
+        LoadKind::I32 { .. } => "i32.load".to_owned(),
+        LoadKind::I64 { .. } => "i64.load".to_owned(),
+        LoadKind::F32 => "f32.load".to_owned(),
+        LoadKind::F64 => "f64.load".to_owned(),
+        LoadKind::V128 => "v128.load".to_owned(),
+        LoadKind::I32_8 { kind } => format!("i32.load8_{}", extension(kind)),
+        LoadKind::I32_16 { kind } => format!("i32.load16_{}", extension(kind)),
+        LoadKind::I64_8 { kind } => format!("i64.load8_{}", extension(kind)),
+        LoadKind::I64_16 { kind } => format!("i64.load16_{}", extension(kind)),
+        LoadKind::I64_32 { kind } => format!("i64.load32_{}", extension(kind)),
+    }
+}
+
+fn store_name(kind: &StoreKind) -> &'static str {
+    match kind {
+        StoreKind::I32 { .. } => "i32.store",
+        StoreKind::I64 { .. } => "i64.store",
+        StoreKind::F32 => "f32.store",
+        StoreKind::F64 => "f64.store",
+        StoreKind::V128 => "v128.store",
+        StoreKind::I32_8 { .. } => "i32.store8",
+        StoreKind::I32_16 { .. } => "i32.store16",
+        StoreKind::I64_8 { .. } => "i64.store8",
+        StoreKind::I64_16 { .. } => "i64.store16",
+        StoreKind::I64_32 { .. } => "i64.store32",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+
+    use super::*;
+    use crate::{compile, CompileOptions};
+
+    #[test]
+    fn disassemble_contract_functions() {
+        let result = compile(
+            "(define-private (add (a int) (b int))\n  (+ a b))\n(add 1 2)",
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .expect("failed to compile contract");
+
+        let pseudocode = disassemble(&result.module, &result.contract_analysis.expressions);
+
+        assert!(pseudocode.contains("func add\n"), "{pseudocode}");
+        assert!(pseudocode.contains("func .top-level\n"), "{pseudocode}");
+        assert!(!pseudocode.contains("func stdlib."), "{pseudocode}");
+        assert!(pseudocode.contains(";; 2:3 (+ ...)\n"), "{pseudocode}");
+        assert!(pseudocode.contains(";; 3:1 (add ...)\n"), "{pseudocode}");
+        assert!(pseudocode.contains("call stdlib.add-int\n"), "{pseudocode}");
+        assert!(pseudocode.contains("call add\n"), "{pseudocode}");
+    }
+
+    #[test]
+    fn operator_names() {
+        assert_eq!(op_name("I32Add"), "i32.add");
+        assert_eq!(op_name("I64LtU"), "i64.lt_u");
+        assert_eq!(op_name("I32Eqz"), "i32.eqz");
+    }
+}
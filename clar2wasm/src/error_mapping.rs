@@ -5,7 +5,7 @@ use clarity::vm::{ClarityVersion, Value};
 use wasmtime::{AsContextMut, Instance, Trap};
 
 use crate::wasm_utils::{
-    read_bytes_from_wasm, read_from_wasm_indirect, read_identifier_from_wasm, signature_from_string,
+    read_from_wasm_indirect, read_identifier_from_wasm, signature_from_string,
 };
 
 const LOG2_ERROR_MESSAGE: &str = "log2 must be passed a positive integer";
@@ -78,6 +78,18 @@ pub enum ErrorMap {
     /// Indicates an attempt to use a function with too many arguments
     ArgumentCountAtMost = 15,
 
+    /// Indicates a value read from or written to the memory outside of its bounds,
+    /// or written while not valid for its type, with [`crate::CompileOptions::memory_checks`].
+    MemoryCheckFailed = 16,
+
+    /// Indicates a sequence read from the memory longer than the maximum length of its
+    /// type, whose elements would be accessed out of its bounds.
+    SequenceOutOfBounds = 17,
+
+    /// Indicates a variant indicator or a bool read from the memory, written by the host,
+    /// from which no value of its type can be constructed.
+    InvalidHostData = 18,
+
     /// A catch-all for errors that are not mapped to specific error codes.
     /// This might be used for unexpected or unclassified errors.
     NotMapped = 99,
@@ -103,19 +115,64 @@ impl From<i32> for ErrorMap {
             13 => ErrorMap::ArgumentCountMismatch,
             14 => ErrorMap::ArgumentCountAtLeast,
             15 => ErrorMap::ArgumentCountAtMost,
+            16 => ErrorMap::MemoryCheckFailed,
+            17 => ErrorMap::SequenceOutOfBounds,
+            18 => ErrorMap::InvalidHostData,
             _ => ErrorMap::NotMapped,
         }
     }
 }
 
+/// Context attached to the failures of a host function, like a database
+/// error, to tell them apart from the runtime errors of the contract.
+///
+/// The failure is returned as a [`WasmError::Runtime`], whose error can be
+/// downcast to a `HostFunctionError` to get the name of the host function,
+/// and whose root cause is the error returned by the host function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostFunctionError {
+    /// Name of the host function which failed.
+    pub name: &'static str,
+}
+
+impl std::fmt::Display for HostFunctionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "host function `{}` failed", self.name)
+    }
+}
+
+/// Returns true if `error`, returned by a host function, is a failure of the
+/// host rather than an error of the contract. Failures already attributed to
+/// a host function, in a nested contract call, are kept as they are.
+fn is_host_failure(error: &Error) -> bool {
+    match error {
+        Error::Interpreter(_) => true,
+        Error::Wasm(WasmError::Runtime(e)) => e.downcast_ref::<HostFunctionError>().is_none(),
+        Error::Wasm(_) => true,
+        _ => false,
+    }
+}
+
+/// Converts the error `e` of a call to a contract into a Clarity `Error`.
+///
+/// `host_function` is the last host function called by the contract, see
+/// [`crate::initialize::ClarityWasmContext::last_host_call`]: the errors which
+/// are not traps of the contract itself were returned by it.
 pub(crate) fn resolve_error(
     e: wasmtime::Error,
     instance: Instance,
     mut store: impl AsContextMut,
     epoch_id: &StacksEpochId,
     clarity_version: &ClarityVersion,
+    host_function: Option<&'static str>,
 ) -> Error {
+    let host_error = host_function.map(|name| HostFunctionError { name });
+
     if let Some(vm_error) = e.root_cause().downcast_ref::<Error>() {
+        if let Some(host_error) = host_error.filter(|_| is_host_failure(vm_error)) {
+            return Error::Wasm(WasmError::Runtime(e.context(host_error)));
+        }
+
         // SAFETY:
         //
         // This unsafe operation returns the value of a location pointed by `*mut T`.
@@ -171,11 +228,20 @@ pub(crate) fn resolve_error(
     // In this case, runtime errors are handled
     // by being mapped to the corresponding ClarityWasm Errors.
     if let Some(Trap::UnreachableCodeReached) = e.root_cause().downcast_ref::<Trap>() {
-        return from_runtime_error_code(instance, &mut store, e, epoch_id, clarity_version);
+        // A module missing the runtime error globals can't tell which error
+        // occurred: the failure to read them is returned instead.
+        return from_runtime_error_code(instance, &mut store, e, epoch_id, clarity_version)
+            .unwrap_or_else(|failure| failure);
     }
 
-    // All other errors are treated as general runtime errors.
-    Error::Wasm(WasmError::Runtime(e))
+    // Errors which are not traps of the contract were returned by the host.
+    match host_error {
+        Some(host_error) if e.root_cause().downcast_ref::<Trap>().is_none() => {
+            Error::Wasm(WasmError::Runtime(e.context(host_error)))
+        }
+        // All other errors are treated as general runtime errors.
+        _ => Error::Wasm(WasmError::Runtime(e)),
+    }
 }
 
 /// Converts a WebAssembly runtime error code into a Clarity `Error`.
@@ -187,7 +253,8 @@ pub(crate) fn resolve_error(
 /// # Returns
 ///
 /// Returns a Clarity `Error` that corresponds to the runtime error encountered during
-/// WebAssembly execution.
+/// WebAssembly execution, or an `Error` if the details of the runtime error could not
+/// be read from the instance.
 ///
 fn from_runtime_error_code(
     instance: Instance,
@@ -195,10 +262,10 @@ fn from_runtime_error_code(
     e: wasmtime::Error,
     epoch_id: &StacksEpochId,
     clarity_version: &ClarityVersion,
-) -> Error {
-    let runtime_error_code = get_global_i32(&instance, &mut store, "runtime-error-code");
+) -> Result<Error, Error> {
+    let runtime_error_code = get_global_i32(&instance, &mut store, "runtime-error-code")?;
 
-    match ErrorMap::from(runtime_error_code) {
+    Ok(match ErrorMap::from(runtime_error_code) {
         ErrorMap::NotClarityError => Error::Wasm(WasmError::Runtime(e)),
         ErrorMap::ArithmeticOverflow => {
             Error::Runtime(RuntimeErrorType::ArithmeticOverflow, Some(Vec::new()))
@@ -226,7 +293,7 @@ fn from_runtime_error_code(
             Error::Runtime(RuntimeErrorType::UnwrapFailure, Some(Vec::new()))
         }
         ErrorMap::ShortReturnAssertionFailure => {
-            let clarity_val = short_return_value(&instance, &mut store, epoch_id, clarity_version);
+            let clarity_val = short_return_value(&instance, &mut store, epoch_id, clarity_version)?;
             Error::ShortReturn(ShortReturnType::AssertionFailed(clarity_val))
         }
         ErrorMap::ArithmeticPowError => Error::Runtime(
@@ -235,25 +302,24 @@ fn from_runtime_error_code(
         ),
         ErrorMap::NameAlreadyUsed => {
             let runtime_error_arg_offset =
-                get_global_i32(&instance, &mut store, "runtime-error-arg-offset");
+                get_global_i32(&instance, &mut store, "runtime-error-arg-offset")?;
             let runtime_error_arg_len =
-                get_global_i32(&instance, &mut store, "runtime-error-arg-len");
+                get_global_i32(&instance, &mut store, "runtime-error-arg-len")?;
 
             let memory = instance
                 .get_memory(&mut store, "memory")
-                .unwrap_or_else(|| panic!("Could not find wasm instance memory"));
+                .ok_or(Error::Wasm(WasmError::MemoryNotFound))?;
             let arg_name = read_identifier_from_wasm(
                 memory,
                 &mut store,
                 runtime_error_arg_offset,
                 runtime_error_arg_len,
-            )
-            .unwrap_or_else(|e| panic!("Could not recover arg_name: {e}"));
+            )?;
 
             Error::Unchecked(CheckErrors::NameAlreadyUsed(arg_name))
         }
         ErrorMap::ShortReturnExpectedValueResponse => {
-            let clarity_val = short_return_value(&instance, &mut store, epoch_id, clarity_version);
+            let clarity_val = short_return_value(&instance, &mut store, epoch_id, clarity_version)?;
             Error::ShortReturn(ShortReturnType::ExpectedValue(Value::Response(
                 ResponseData {
                     committed: false,
@@ -267,23 +333,50 @@ fn from_runtime_error_code(
             )))
         }
         ErrorMap::ShortReturnExpectedValue => {
-            let clarity_val = short_return_value(&instance, &mut store, epoch_id, clarity_version);
+            let clarity_val = short_return_value(&instance, &mut store, epoch_id, clarity_version)?;
             Error::ShortReturn(ShortReturnType::ExpectedValue(clarity_val))
         }
         ErrorMap::ArgumentCountMismatch => {
-            let (expected, got) = get_runtime_error_arg_lengths(&instance, &mut store);
+            let (expected, got) = get_runtime_error_arg_lengths(&instance, &mut store)?;
             Error::Unchecked(CheckErrors::IncorrectArgumentCount(expected, got))
         }
         ErrorMap::ArgumentCountAtLeast => {
-            let (expected, got) = get_runtime_error_arg_lengths(&instance, &mut store);
+            let (expected, got) = get_runtime_error_arg_lengths(&instance, &mut store)?;
             Error::Unchecked(CheckErrors::RequiresAtLeastArguments(expected, got))
         }
         ErrorMap::ArgumentCountAtMost => {
-            let (expected, got) = get_runtime_error_arg_lengths(&instance, &mut store);
+            let (expected, got) = get_runtime_error_arg_lengths(&instance, &mut store)?;
             Error::Unchecked(CheckErrors::RequiresAtMostArguments(expected, got))
         }
-        _ => panic!("Runtime error code {} not supported", runtime_error_code),
-    }
+        ErrorMap::MemoryCheckFailed => {
+            let runtime_error_arg_offset =
+                get_global_i32(&instance, &mut store, "runtime-error-arg-offset")?;
+            let runtime_error_arg_len =
+                get_global_i32(&instance, &mut store, "runtime-error-arg-len")?;
+
+            let memory = instance
+                .get_memory(&mut store, "memory")
+                .ok_or(Error::Wasm(WasmError::MemoryNotFound))?;
+            let expression = read_identifier_from_wasm(
+                memory,
+                &mut store,
+                runtime_error_arg_offset,
+                runtime_error_arg_len,
+            )?;
+
+            Error::Wasm(WasmError::WasmGeneratorError(format!(
+                "memory check failed for {expression}"
+            )))
+        }
+        ErrorMap::SequenceOutOfBounds => Error::Unchecked(CheckErrors::ValueOutOfBounds),
+        ErrorMap::InvalidHostData => {
+            Error::Runtime(RuntimeErrorType::BadTypeConstruction, Some(Vec::new()))
+        }
+        // Codes which are not emitted by the compiler come from a malformed module.
+        ErrorMap::NotMapped => Error::Wasm(WasmError::Runtime(
+            e.context(format!("unknown runtime error code {runtime_error_code}")),
+        )),
+    })
 }
 
 /// Retrieves the value of a 32-bit integer global variable from a WebAssembly instance.
@@ -294,35 +387,18 @@ fn from_runtime_error_code(
 ///
 /// # Returns
 ///
-/// Returns the value of the global variable as an `i32`.
+/// Returns the value of the global variable as an `i32`, or an error if the
+/// instance has no such global with an `i32` value.
 ///
-fn get_global_i32(instance: &Instance, store: &mut impl AsContextMut, name: &str) -> i32 {
+fn get_global_i32(
+    instance: &Instance,
+    store: &mut impl AsContextMut,
+    name: &str,
+) -> Result<i32, Error> {
     instance
         .get_global(&mut *store, name)
         .and_then(|glob| glob.get(store).i32())
-        .unwrap_or_else(|| panic!("Could not find ${} global with i32 value", name))
-}
-
-/// Retrieves the expected and actual argument counts from a byte-encoded string.
-///
-/// This function interprets a string as a sequence of bytes, where the first 4 bytes
-/// represent the expected number of arguments, and the bytes at positions 16 to 19
-/// represent the actual number of arguments received. It converts these byte sequences
-/// into `usize` values and returns them as a tuple.
-///
-/// # Returns
-///
-/// A tuple `(expected, got)` where:
-/// - `expected` is the number of arguments expected.
-/// - `got` is the number of arguments actually received.
-fn extract_expected_and_got(bytes: &[u8]) -> (usize, usize) {
-    // Assuming the first 4 bytes represent the expected value
-    let expected = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
-
-    // Assuming the next 4 bytes represent the got value
-    let got = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]) as usize;
-
-    (expected, got)
+        .ok_or_else(|| Error::Wasm(WasmError::GlobalNotFound(name.to_owned())))
 }
 
 /// Retrieves and deserializes a Clarity value from WebAssembly memory in the context of a short return.
@@ -333,57 +409,184 @@ fn extract_expected_and_got(bytes: &[u8]) -> (usize, usize) {
 ///
 /// # Returns
 ///
-/// Returns a deserialized Clarity `Value` representing the short return value.
+/// Returns a deserialized Clarity `Value` representing the short return value, or an
+/// error if it could not be read from the instance.
 ///
 fn short_return_value(
     instance: &Instance,
     store: &mut impl AsContextMut,
     epoch_id: &StacksEpochId,
     clarity_version: &ClarityVersion,
-) -> Value {
-    let val_offset = get_global_i32(instance, store, "runtime-error-value-offset");
-    let type_ser_offset = get_global_i32(instance, store, "runtime-error-type-ser-offset");
-    let type_ser_len = get_global_i32(instance, store, "runtime-error-type-ser-len");
+) -> Result<Value, Error> {
+    let val_offset = get_global_i32(instance, store, "runtime-error-value-offset")?;
+    let type_ser_offset = get_global_i32(instance, store, "runtime-error-type-ser-offset")?;
+    let type_ser_len = get_global_i32(instance, store, "runtime-error-type-ser-len")?;
 
     let memory = instance
         .get_memory(&mut *store, "memory")
-        .unwrap_or_else(|| panic!("Could not find wasm instance memory"));
-
-    let type_ser_str = read_identifier_from_wasm(memory, store, type_ser_offset, type_ser_len)
-        .unwrap_or_else(|e| panic!("Could not recover stringified type: {}", e));
-
-    let value_ty = signature_from_string(&type_ser_str, *clarity_version, *epoch_id)
-        .unwrap_or_else(|e| panic!("Could not recover thrown value: {}", e));
+        .ok_or(Error::Wasm(WasmError::MemoryNotFound))?;
 
+    let type_ser_str = read_identifier_from_wasm(memory, store, type_ser_offset, type_ser_len)?;
+    let value_ty = signature_from_string(&type_ser_str, *clarity_version, *epoch_id)?;
     read_from_wasm_indirect(memory, store, &value_ty, val_offset, *epoch_id)
-        .unwrap_or_else(|e| panic!("Could not read thrown value from memory: {}", e))
 }
 
-/// Retrieves the argument lengths from the runtime error global variables.
-///
-/// This function reads the global variables `runtime-error-arg-offset` and `runtime-error-arg-len`
-/// from the WebAssembly instance and constructs a string representing the argument lengths.
+/// Retrieves the argument counts of an argument count mismatch from the
+/// runtime error global variables `runtime-error-expected-args` and
+/// `runtime-error-actual-args`.
 ///
 /// # Returns
 ///
-/// A string representing the argument lengths.
+/// A tuple `(expected, got)` where:
+/// - `expected` is the number of arguments expected.
+/// - `got` is the number of arguments actually received.
 fn get_runtime_error_arg_lengths(
     instance: &Instance,
     store: &mut impl AsContextMut,
-) -> (usize, usize) {
-    let runtime_error_arg_offset = get_global_i32(instance, store, "runtime-error-arg-offset");
-    let runtime_error_arg_len = get_global_i32(instance, store, "runtime-error-arg-len");
+) -> Result<(usize, usize), Error> {
+    let expected = get_global_i32(instance, store, "runtime-error-expected-args")?;
+    let got = get_global_i32(instance, store, "runtime-error-actual-args")?;
+    Ok((expected as usize, got as usize))
+}
 
-    let memory = instance
-        .get_memory(&mut *store, "memory")
-        .unwrap_or_else(|| panic!("Could not find wasm instance memory"));
-    let arg_lengths = read_bytes_from_wasm(
-        memory,
-        store,
-        runtime_error_arg_offset,
-        runtime_error_arg_len,
-    )
-    .unwrap_or_else(|e| panic!("Could not recover arg_lengths: {e}"));
-
-    extract_expected_and_got(&arg_lengths)
+#[cfg(test)]
+mod tests {
+    use clarity::vm::errors::InterpreterError;
+    use wasmtime::{Caller, Engine, Linker, Module, Store};
+
+    use super::*;
+
+    /// Calls a module whose function `run` calls the host function `fail`,
+    /// which returns `error`, and resolves the resulting error.
+    fn resolve_host_error(error: fn() -> Error) -> Error {
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let mut linker = Linker::new(&engine);
+        linker
+            .func_wrap("clarity", "fail", move |_: Caller<'_, ()>| {
+                Err::<(), wasmtime::Error>(error().into())
+            })
+            .unwrap();
+        let module = Module::new(
+            &engine,
+            r#"(module
+                (import "clarity" "fail" (func $fail))
+                (func (export "run") call $fail))"#,
+        )
+        .unwrap();
+        let instance = linker.instantiate(&mut store, &module).unwrap();
+        let run = instance.get_func(&mut store, "run").unwrap();
+
+        let e = run.call(&mut store, &[], &mut []).unwrap_err();
+        resolve_error(
+            e,
+            instance,
+            &mut store,
+            &StacksEpochId::latest(),
+            &ClarityVersion::latest(),
+            Some("fail"),
+        )
+    }
+
+    /// Calls a module whose function `run` traps with the runtime error
+    /// `code`, and resolves the resulting error.
+    fn resolve_runtime_error(code: i32) -> Error {
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = Module::new(
+            &engine,
+            format!(
+                r#"(module
+                    (global (export "runtime-error-code") (mut i32) (i32.const -1))
+                    (func (export "run")
+                        (global.set 0 (i32.const {code}))
+                        unreachable))"#
+            ),
+        )
+        .unwrap();
+        let instance = Linker::new(&engine)
+            .instantiate(&mut store, &module)
+            .unwrap();
+        let run = instance.get_func(&mut store, "run").unwrap();
+
+        let e = run.call(&mut store, &[], &mut []).unwrap_err();
+        resolve_error(
+            e,
+            instance,
+            &mut store,
+            &StacksEpochId::latest(),
+            &ClarityVersion::latest(),
+            None,
+        )
+    }
+
+    #[test]
+    fn runtime_errors_of_memory_values() {
+        assert!(matches!(
+            resolve_runtime_error(ErrorMap::SequenceOutOfBounds as i32),
+            Error::Unchecked(CheckErrors::ValueOutOfBounds)
+        ));
+        assert!(matches!(
+            resolve_runtime_error(ErrorMap::InvalidHostData as i32),
+            Error::Runtime(RuntimeErrorType::BadTypeConstruction, _)
+        ));
+    }
+
+    #[test]
+    fn unknown_runtime_error_code() {
+        assert!(matches!(
+            resolve_runtime_error(42),
+            Error::Wasm(WasmError::Runtime(_))
+        ));
+    }
+
+    #[test]
+    fn missing_runtime_error_globals() {
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = Module::new(&engine, r#"(module (func (export "run") unreachable))"#).unwrap();
+        let instance = Linker::new(&engine)
+            .instantiate(&mut store, &module)
+            .unwrap();
+        let run = instance.get_func(&mut store, "run").unwrap();
+
+        let e = run.call(&mut store, &[], &mut []).unwrap_err();
+        let error = resolve_error(
+            e,
+            instance,
+            &mut store,
+            &StacksEpochId::latest(),
+            &ClarityVersion::latest(),
+            None,
+        );
+        assert!(matches!(
+            error,
+            Error::Wasm(WasmError::GlobalNotFound(ref name)) if name == "runtime-error-code"
+        ));
+    }
+
+    #[test]
+    fn host_failure_carries_function_name() {
+        let Error::Wasm(WasmError::Runtime(e)) = resolve_host_error(|| {
+            Error::Interpreter(InterpreterError::DBError("unavailable".to_owned()))
+        }) else {
+            panic!("expected a runtime error");
+        };
+        assert_eq!(
+            e.downcast_ref::<HostFunctionError>(),
+            Some(&HostFunctionError { name: "fail" })
+        );
+        assert!(matches!(
+            e.root_cause().downcast_ref::<Error>(),
+            Some(Error::Interpreter(InterpreterError::DBError(_)))
+        ));
+    }
+
+    #[test]
+    fn clarity_errors_of_host_functions_are_kept() {
+        assert!(matches!(
+            resolve_host_error(|| Error::Runtime(RuntimeErrorType::DivisionByZero, None)),
+            Error::Runtime(RuntimeErrorType::DivisionByZero, _)
+        ));
+    }
 }
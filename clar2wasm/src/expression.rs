@@ -0,0 +1,125 @@
+//! Compilation of a single expression, to use the Clarity expression language
+//! as a formula engine in other applications.
+//!
+//! The expression is compiled as the body of a read-only function exported as
+//! [`EXPRESSION_FUNCTION`], whose arguments are the given typed variables. The
+//! module is standalone (see [`crate::CompileOptions::standalone`]): it has no
+//! imports, and expressions reading the chain state get default values.
+
+use clarity::types::StacksEpochId;
+use clarity::vm::analysis::AnalysisDatabase;
+use clarity::vm::costs::LimitedCostTracker;
+use clarity::vm::database::MemoryBackingStore;
+use clarity::vm::diagnostic::{Diagnostic, Level};
+use clarity::vm::types::{QualifiedContractIdentifier, TypeSignature};
+use clarity::vm::{ClarityName, ClarityVersion};
+
+use crate::{compile, CompileError, CompileOptions, CompileResult};
+
+/// Name of the exported function evaluating the expression.
+pub const EXPRESSION_FUNCTION: &str = "expression";
+
+/// Compiles `expression` into a module exporting it as [`EXPRESSION_FUNCTION`],
+/// which takes the arguments `args`, in order.
+pub fn compile_expression(
+    expression: &str,
+    args: &[(ClarityName, TypeSignature)],
+    clarity_version: ClarityVersion,
+    epoch: StacksEpochId,
+) -> Result<CompileResult, CompileError> {
+    let params: String = args
+        .iter()
+        .map(|(name, ty)| format!(" ({} {ty})", name.as_str()))
+        .collect();
+    let source = format!("(define-read-only ({EXPRESSION_FUNCTION}{params})\n{expression}\n)");
+
+    let result = compile(
+        &source,
+        &QualifiedContractIdentifier::transient(),
+        LimitedCostTracker::new_free(),
+        clarity_version,
+        epoch,
+        &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+        CompileOptions::default().standalone(true),
+    )?;
+
+    // An unbalanced expression could close the function and add definitions.
+    if result.ast.expressions.len() != 1 {
+        return Err(CompileError::Generic {
+            ast: Box::new(result.ast),
+            diagnostics: vec![Diagnostic {
+                level: Level::Error,
+                message: "expected a single expression".to_owned(),
+                spans: vec![],
+                suggestion: None,
+            }],
+            cost_tracker: Box::new(LimitedCostTracker::new_free()),
+        });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use wasmtime::{Engine, Instance, Store, Val};
+
+    use super::*;
+
+    fn compile_expr(
+        expression: &str,
+        args: &[(&str, TypeSignature)],
+    ) -> Result<CompileResult, CompileError> {
+        let args: Vec<_> = args
+            .iter()
+            .map(|(name, ty)| (ClarityName::from(*name), ty.clone()))
+            .collect();
+        compile_expression(
+            expression,
+            &args,
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+        )
+    }
+
+    #[test]
+    fn evaluate_formula() {
+        let mut module = compile_expr(
+            "(+ (* a a) b)",
+            &[("a", TypeSignature::IntType), ("b", TypeSignature::IntType)],
+        )
+        .expect("failed to compile expression")
+        .module;
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = wasmtime::Module::from_binary(&engine, &module.emit_wasm()).unwrap();
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+
+        let expression = instance.get_func(&mut store, EXPRESSION_FUNCTION).unwrap();
+        let mut results = [Val::I64(0), Val::I64(0)];
+        expression
+            .call(
+                &mut store,
+                &[Val::I64(3), Val::I64(0), Val::I64(4), Val::I64(0)],
+                &mut results,
+            )
+            .unwrap();
+        assert_eq!(results[0].unwrap_i64(), 13);
+        assert_eq!(results[1].unwrap_i64(), 0);
+    }
+
+    #[test]
+    fn invalid_expression() {
+        // Type error
+        assert!(compile_expr("(+ a u1)", &[("a", TypeSignature::IntType)]).is_err());
+        // Unknown variable
+        assert!(compile_expr("(+ a b)", &[("a", TypeSignature::IntType)]).is_err());
+        // Definitions smuggled after the expression
+        assert!(compile_expr(
+            "a) (define-data-var b int 1",
+            &[("a", TypeSignature::IntType)]
+        )
+        .is_err());
+    }
+}
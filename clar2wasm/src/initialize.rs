@@ -1,5 +1,11 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
 use clarity::vm::analysis::ContractAnalysis;
-use clarity::vm::contexts::GlobalContext;
+use clarity::vm::contexts::{AssetMap, EventBatch, GlobalContext};
+use clarity::vm::costs::runtime_cost;
 use clarity::vm::errors::{Error, RuntimeErrorType, WasmError};
 use clarity::vm::events::*;
 use clarity::vm::types::{AssetIdentifier, BuffData, PrincipalData, QualifiedContractIdentifier};
@@ -7,8 +13,9 @@ use clarity::vm::{CallStack, ContractContext, Value};
 use stacks_common::types::chainstate::StacksBlockId;
 use wasmtime::{Linker, Module, Store};
 
+use crate::costs::host_cost_function;
 use crate::error_mapping;
-use crate::linker::link_host_functions;
+use crate::linker::{link_host_functions, link_memory_returns_host_functions};
 use crate::wasm_utils::*;
 
 // The context used when making calls into the Wasm module.
@@ -31,6 +38,111 @@ pub struct ClarityWasmContext<'a, 'b> {
     /// when initializing a contract. Should always be `Some` when initializing
     /// a contract, and `None` otherwise.
     pub contract_analysis: Option<&'a ContractAnalysis>,
+
+    /// Number of calls to each host function, only recorded when enabled by
+    /// [`Self::count_host_calls`].
+    host_calls: Option<HostCallCounts>,
+    /// Buffer recording the calls to the host functions, only set when they
+    /// are traced, see [`Self::trace_host_calls`].
+    host_call_tracer: Option<HostCallTracer>,
+    /// Name of the last host function called, see [`Self::last_host_call`].
+    last_host_call: Option<&'static str>,
+}
+
+/// Number of calls to each host function, by name.
+pub type HostCallCounts = BTreeMap<&'static str, u64>;
+
+/// A call to a host function, recorded by a [`HostCallTracer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostCall {
+    /// Name of the host function.
+    pub function: &'static str,
+    /// Hash of the arguments of the call, to tell apart the calls with
+    /// different arguments. The arguments are offsets and lengths in the
+    /// memory for most values, not the values themselves.
+    pub args_digest: u64,
+    /// Time spent in the host function, including the contracts it called.
+    pub duration: Duration,
+}
+
+/// The calls to the host functions recorded by a [`HostCallTracer`], with the
+/// output of the debugging host functions (`log`, `debug_msg` and
+/// `debug_print`) made meanwhile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostCallTrace {
+    /// The calls, in the order they returned.
+    pub calls: Vec<HostCall>,
+    /// The lines printed by the debugging host functions.
+    pub logs: Vec<String>,
+}
+
+/// Buffer recording the calls to the host functions made with the contexts
+/// tracing them, see [`ClarityWasmContext::trace_host_calls`].
+///
+/// The clones of a tracer share its buffer, so that the trace of an
+/// execution can be read once its context is gone.
+#[derive(Debug, Clone, Default)]
+pub struct HostCallTracer(Arc<Mutex<HostCallTrace>>);
+
+impl HostCallTracer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the call `call`.
+    pub fn record(&self, call: HostCall) {
+        self.lock().calls.push(call);
+    }
+
+    /// Records the debugging output `line`.
+    pub fn log(&self, line: String) {
+        self.lock().logs.push(line);
+    }
+
+    /// Returns the trace recorded so far, and empties the buffer.
+    pub fn take(&self) -> HostCallTrace {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HostCallTrace> {
+        // The buffer is only appended to, it is valid even if a thread
+        // panicked while holding the lock.
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Time spent initializing a contract, measured with
+/// [`Instrumentation::timings`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InitializationTimings {
+    /// Compilation of the module to machine code by wasmtime, which is
+    /// shorter when the module is found in the cache of the engine.
+    pub compilation: Duration,
+    /// Execution of the top-level expressions.
+    pub execution: Duration,
+}
+
+impl fmt::Display for InitializationTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "compilation {:?}, execution {:?}",
+            self.compilation, self.execution
+        )
+    }
+}
+
+/// Copy of the memory of a contract instance, taken after the top-level
+/// expressions ran, see [`Instrumentation::memory_snapshot`].
+#[derive(Debug, Clone, Default)]
+pub struct MemorySnapshot {
+    /// The linear memory of the instance.
+    pub memory: Vec<u8>,
+    /// Value of the stack pointer before running the top-level expressions,
+    /// which is the end of the literal memory.
+    pub literal_memory_end: i32,
+    /// Value of the stack pointer after running the top-level expressions.
+    pub stack_pointer: i32,
 }
 
 impl<'a, 'b> ClarityWasmContext<'a, 'b> {
@@ -55,6 +167,9 @@ impl<'a, 'b> ClarityWasmContext<'a, 'b> {
             caller_stack: vec![],
             bhh_stack: vec![],
             contract_analysis,
+            host_calls: None,
+            host_call_tracer: None,
+            last_host_call: None,
         }
     }
 
@@ -79,6 +194,9 @@ impl<'a, 'b> ClarityWasmContext<'a, 'b> {
             caller_stack: vec![],
             bhh_stack: vec![],
             contract_analysis,
+            host_calls: None,
+            host_call_tracer: None,
+            last_host_call: None,
         }
     }
 
@@ -126,6 +244,59 @@ impl<'a, 'b> ClarityWasmContext<'a, 'b> {
             )))
     }
 
+    /// Start recording the number of calls to each host function.
+    pub fn count_host_calls(&mut self) {
+        self.host_calls.get_or_insert_with(HostCallCounts::new);
+    }
+
+    /// Record a call to the host function `name`, and count it if enabled by
+    /// [`Self::count_host_calls`].
+    pub fn count_host_call(&mut self, name: &'static str) {
+        self.last_host_call = Some(name);
+        if let Some(host_calls) = &mut self.host_calls {
+            *host_calls.entry(name).or_default() += 1;
+        }
+    }
+
+    /// Returns the number of calls to each host function recorded since
+    /// [`Self::count_host_calls`], and stops recording them.
+    pub fn take_host_calls(&mut self) -> Option<HostCallCounts> {
+        self.host_calls.take()
+    }
+
+    /// Start recording the calls to the host functions, with their arguments
+    /// and duration, and the output of the debugging host functions, in
+    /// `tracer`. This adds some overhead to each host call.
+    pub fn trace_host_calls(&mut self, tracer: HostCallTracer) {
+        self.host_call_tracer = Some(tracer);
+    }
+
+    /// The tracer recording the calls to the host functions, if they are
+    /// traced, see [`Self::trace_host_calls`].
+    pub fn host_call_tracer(&self) -> Option<&HostCallTracer> {
+        self.host_call_tracer.as_ref()
+    }
+
+    /// Returns the name of the last host function called by the contract.
+    /// When a call to the contract fails without a trap, the error was
+    /// returned by this host function.
+    pub fn last_host_call(&self) -> Option<&'static str> {
+        self.last_host_call
+    }
+
+    /// Charge the cost of the operation of the host function `name`, for an
+    /// input of size `input`, like the interpreter does for the same
+    /// operation (see [`crate::costs`]).
+    pub fn charge_host_call(&mut self, name: &str, input: u64) -> Result<(), Error> {
+        match host_cost_function(name) {
+            Some(cost_function) => {
+                runtime_cost(cost_function, &mut self.global_context.cost_track, input)
+                    .map_err(Error::from)
+            }
+            None => Ok(()),
+        }
+    }
+
     /// Return an immutable reference to the contract_context
     pub fn contract_context(&self) -> &ContractContext {
         if let Some(contract_context) = &self.contract_context {
@@ -312,15 +483,158 @@ impl<'a, 'b> ClarityWasmContext<'a, 'b> {
     }
 }
 
+/// The outcome of the initialization of a contract, see [`initialize_contract`].
+#[derive(Debug, Clone)]
+pub struct ContractInitialization {
+    /// The value returned from the last top-level expression.
+    pub value: Option<Value>,
+    /// The events emitted by the top-level expressions, e.g. `print` or mints.
+    pub events: EventBatch,
+    /// The assets transferred by the top-level expressions.
+    pub asset_changes: AssetMap,
+    /// The number of calls to each host function, only recorded with
+    /// [`Instrumentation::count_host_calls`].
+    pub host_calls: Option<HostCallCounts>,
+    /// The calls to the host functions, with their arguments and duration,
+    /// and the debugging output, only recorded with
+    /// [`Instrumentation::trace_host_calls`].
+    pub host_call_trace: Option<HostCallTrace>,
+    /// The time spent compiling the module and running the top-level
+    /// expressions, only recorded with [`Instrumentation::timings`].
+    pub timings: Option<InitializationTimings>,
+}
+
+/// What to record while initializing a contract, see
+/// [`initialize_contract_with`]. Each recording adds some overhead, and is
+/// meant for tooling and tests.
+#[derive(Debug, Default)]
+pub struct Instrumentation<'s> {
+    /// Count the calls to each host function made by the top-level
+    /// expressions, see [`ContractInitialization::host_calls`].
+    pub count_host_calls: bool,
+    /// Record the calls to the host functions made by the top-level
+    /// expressions, with their arguments and duration, and the debugging
+    /// output, see [`ContractInitialization::host_call_trace`].
+    pub trace_host_calls: bool,
+    /// Measure the time spent compiling the module and running the top-level
+    /// expressions, see [`ContractInitialization::timings`].
+    pub timings: bool,
+    /// Copy the memory of the instance into this snapshot once the top-level
+    /// expressions ran, even if they failed.
+    pub memory_snapshot: Option<&'s mut MemorySnapshot>,
+}
+
 /// Initialize a contract, executing all of the top-level expressions and
 /// registering all of the definitions in the context. Returns the value
-/// returned from the last top-level expression.
+/// returned from the last top-level expression, with the events and asset
+/// changes it generated.
+///
+/// The initialization runs in a nested transaction of `global_context`,
+/// which is committed into the current one on success, and rolled back on
+/// error.
 pub fn initialize_contract(
     global_context: &mut GlobalContext,
     contract_context: &mut ContractContext,
     sponsor: Option<PrincipalData>,
     contract_analysis: &ContractAnalysis,
-) -> Result<Option<Value>, Error> {
+) -> Result<ContractInitialization, Error> {
+    initialize_contract_with(
+        global_context,
+        contract_context,
+        sponsor,
+        contract_analysis,
+        Instrumentation::default(),
+    )
+}
+
+/// Same as [`initialize_contract`], also counting and tracing the calls to
+/// the host functions made by the top-level expressions, and measuring the
+/// time spent compiling the module and running them. This adds some overhead
+/// to each host call, and is meant for tooling and tests.
+pub fn initialize_contract_instrumented(
+    global_context: &mut GlobalContext,
+    contract_context: &mut ContractContext,
+    sponsor: Option<PrincipalData>,
+    contract_analysis: &ContractAnalysis,
+) -> Result<ContractInitialization, Error> {
+    initialize_contract_with(
+        global_context,
+        contract_context,
+        sponsor,
+        contract_analysis,
+        Instrumentation {
+            count_host_calls: true,
+            trace_host_calls: true,
+            timings: true,
+            memory_snapshot: None,
+        },
+    )
+}
+
+/// Same as [`initialize_contract`], recording what `instrumentation`
+/// selects.
+pub fn initialize_contract_with(
+    global_context: &mut GlobalContext,
+    contract_context: &mut ContractContext,
+    sponsor: Option<PrincipalData>,
+    contract_analysis: &ContractAnalysis,
+    instrumentation: Instrumentation,
+) -> Result<ContractInitialization, Error> {
+    global_context.begin();
+    let (value, host_calls, host_call_trace, timings) = match run_top_level(
+        global_context,
+        contract_context,
+        sponsor,
+        contract_analysis,
+        instrumentation,
+    ) {
+        Ok(result) => result,
+        Err(e) => {
+            global_context.roll_back()?;
+            return Err(e);
+        }
+    };
+
+    // The nested transaction is merged into the current one on commit, so we
+    // need to take a copy of its events and assets beforehand.
+    let events = global_context
+        .event_batches
+        .last()
+        .cloned()
+        .unwrap_or_else(EventBatch::new);
+    let asset_changes = global_context
+        .asset_maps
+        .last()
+        .cloned()
+        .unwrap_or_else(AssetMap::new);
+    global_context.commit()?;
+
+    Ok(ContractInitialization {
+        value,
+        events,
+        asset_changes,
+        host_calls,
+        host_call_trace,
+        timings,
+    })
+}
+
+/// The value of the top-level expressions, with the host calls and the
+/// timings recorded while running them.
+type TopLevelOutcome = (
+    Option<Value>,
+    Option<HostCallCounts>,
+    Option<HostCallTrace>,
+    Option<InitializationTimings>,
+);
+
+fn run_top_level(
+    global_context: &mut GlobalContext,
+    contract_context: &mut ContractContext,
+    sponsor: Option<PrincipalData>,
+    contract_analysis: &ContractAnalysis,
+    instrumentation: Instrumentation,
+) -> Result<TopLevelOutcome, Error> {
     let publisher: PrincipalData = contract_context.contract_identifier.issuer.clone().into();
 
     let mut call_stack = CallStack::new();
@@ -336,17 +650,27 @@ pub fn initialize_contract(
         sponsor.clone(),
         Some(contract_analysis),
     );
+    let compilation_start = Instant::now();
     let module = init_context
         .contract_context()
         .with_wasm_module(|wasm_module| {
             Module::from_binary(&engine, wasm_module)
                 .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))
         })?;
+    let compilation = compilation_start.elapsed();
     let mut store = Store::new(&engine, init_context);
+    if instrumentation.count_host_calls {
+        store.data_mut().count_host_calls();
+    }
+    let tracer = instrumentation.trace_host_calls.then(HostCallTracer::new);
+    if let Some(tracer) = &tracer {
+        store.data_mut().trace_host_calls(tracer.clone());
+    }
     let mut linker = Linker::new(&engine);
 
     // Link in the host interface functions.
     link_host_functions(&mut linker)?;
+    link_memory_returns_host_functions(&mut linker, &mut store, &module)?;
 
     let instance = linker
         .instantiate(&mut store, &module)
@@ -366,11 +690,42 @@ pub fn initialize_contract(
         results.push(placeholder_for_type(result_ty));
     }
 
-    top_level
-        .call(&mut store, &[], results.as_mut_slice())
-        .map_err(|e| {
-            error_mapping::resolve_error(e, instance, &mut store, &epoch, &clarity_version)
-        })?;
+    let stack_pointer = instance.get_global(&mut store, "stack-pointer");
+    let literal_memory_end = stack_pointer
+        .and_then(|sp| sp.get(&mut store).i32())
+        .unwrap_or_default();
+
+    let execution_start = Instant::now();
+    let call_result = top_level.call(&mut store, &[], results.as_mut_slice());
+    let timings = instrumentation.timings.then(|| InitializationTimings {
+        compilation,
+        execution: execution_start.elapsed(),
+    });
+
+    if let Some(snapshot) = instrumentation.memory_snapshot {
+        *snapshot = MemorySnapshot {
+            memory: instance
+                .get_memory(&mut store, "memory")
+                .map(|memory| memory.data(&store).to_vec())
+                .unwrap_or_default(),
+            literal_memory_end,
+            stack_pointer: stack_pointer
+                .and_then(|sp| sp.get(&mut store).i32())
+                .unwrap_or_default(),
+        };
+    }
+
+    call_result.map_err(|e| {
+        let host_function = store.data().last_host_call();
+        error_mapping::resolve_error(
+            e,
+            instance,
+            &mut store,
+            &epoch,
+            &clarity_version,
+            host_function,
+        )
+    })?;
 
     // Save the compiled Wasm module into the contract context
     store.data_mut().contract_context_mut()?.set_wasm_module(
@@ -388,13 +743,29 @@ pub fn initialize_contract(
             .and_then(|type_map| type_map.get_type_expected(expr))
     });
 
-    if let Some(return_type) = return_type {
+    let value = if let Some(return_type) = return_type {
         let memory = instance
             .get_memory(&mut store, "memory")
             .ok_or(Error::Wasm(WasmError::MemoryNotFound))?;
+        // Compiled with memory returns, `.top-level` returns the offset of
+        // the value.
+        let types = clar2wasm_ty(return_type);
+        if types.len() > 1 && results.len() == 1 {
+            let offset = results[0]
+                .i32()
+                .ok_or(Error::Wasm(WasmError::ValueTypeMismatch))?;
+            results = read_memory_returns(memory, &mut store, offset, &types)?;
+        }
         wasm_to_clarity_value(return_type, 0, &results, memory, &mut &mut store, epoch)
-            .map(|(val, _offset)| val)
+            .map(|(val, _offset)| val)?
     } else {
-        Ok(None)
-    }
+        None
+    };
+
+    Ok((
+        value,
+        store.data_mut().take_host_calls(),
+        tracer.map(|tracer| tracer.take()),
+        timings,
+    ))
 }
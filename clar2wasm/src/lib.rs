@@ -1,5 +1,7 @@
 extern crate lazy_static;
 
+use std::collections::{BTreeMap, HashMap};
+
 use clarity::types::StacksEpochId;
 use clarity::vm::analysis::{run_analysis, AnalysisDatabase, ContractAnalysis};
 use clarity::vm::ast::{build_ast_with_diagnostics, ContractAST};
@@ -8,14 +10,39 @@ use clarity::vm::diagnostic::Diagnostic;
 use clarity::vm::types::{
     FixedFunction, ListTypeData, QualifiedContractIdentifier, SequenceSubtype, TypeSignature,
 };
-use clarity::vm::ClarityVersion;
+use clarity::vm::{ClarityName, ClarityVersion};
+use debug_print::LogLevel;
+pub use error_mapping::HostFunctionError;
+use target_features::{Feature, TargetFeatures};
 pub use walrus::Module;
-use wasm_generator::{GeneratorError, WasmGenerator};
+use walrus::{ExportId, ExportItem, FunctionId};
+use wasm_generator::{FunctionKind, GeneratorError, WasmGenerator};
+
+/// Layout of the values in memory, from the `no_std` `clar2wasm-layout`
+/// crate.
+pub use clar2wasm_layout as layout;
 
+pub mod bindings;
+pub mod compatibility;
+pub mod costs;
+pub mod dependencies;
 mod deserialize;
+pub mod disassemble;
+pub mod expression;
 pub mod initialize;
 pub mod linker;
+mod memory_returns;
+#[cfg(feature = "experimental-post-conditions")]
+pub mod post_conditions;
+pub mod pretty;
+pub mod repl_session;
+pub mod report;
+pub mod sections;
 mod serialize;
+mod standalone;
+pub mod statistics;
+pub mod target_features;
+mod trait_conformance;
 pub mod wasm_generator;
 pub mod wasm_utils;
 mod words;
@@ -24,6 +51,7 @@ pub mod datastore;
 pub mod tools;
 
 mod debug_msg;
+pub mod debug_print;
 mod error_mapping;
 
 // FIXME: This is copied from stacks-blockchain
@@ -42,6 +70,261 @@ pub struct CompileResult {
     pub diagnostics: Vec<Diagnostic>,
     pub module: Module,
     pub contract_analysis: ContractAnalysis,
+    /// The functions of the contract in `module`, by name, for the tools
+    /// post-processing specific functions.
+    pub functions: BTreeMap<ClarityName, ContractFunction>,
+}
+
+/// A function of the contract in the generated module, see
+/// [`CompileResult::functions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractFunction {
+    pub kind: FunctionKind,
+    pub id: FunctionId,
+    /// The export of the function, which only public and read-only functions
+    /// have. With [`CompileOptions::memory_returns`], the function returns
+    /// the offset of its results in memory.
+    pub export: Option<ExportId>,
+}
+
+impl CompileResult {
+    /// Emits the binary of the compiled module, with its custom sections.
+    pub fn serialize(&mut self) -> Vec<u8> {
+        self.module.emit_wasm()
+    }
+
+    /// Returns statistics about the size of the compiled module.
+    pub fn statistics(&mut self) -> statistics::ModuleStatistics {
+        statistics::module_statistics(&mut self.module, &self.functions)
+    }
+
+    /// Returns the report of the compilation: its cost, its diagnostics, the
+    /// statistics of the module and the interface of the contract.
+    pub fn report(&mut self) -> report::CompileReport {
+        report::CompileReport::new(self)
+    }
+
+    /// Compares the statistics of this module with the ones of `other`, a
+    /// later version of the contract or a compilation with another version
+    /// of the compiler.
+    pub fn compare_statistics(
+        &mut self,
+        other: &mut CompileResult,
+    ) -> statistics::StatisticsComparison {
+        statistics::StatisticsComparison {
+            before: self.statistics(),
+            after: other.statistics(),
+        }
+    }
+
+    /// Loads a module emitted by [`CompileResult::serialize`], possibly
+    /// transformed by other tools, and validates its custom sections.
+    pub fn deserialize(bytes: &[u8]) -> Result<Module, sections::ModuleError> {
+        let module =
+            Module::from_buffer(bytes).map_err(|e| sections::ModuleError::Parse(e.to_string()))?;
+        sections::validate(&module)?;
+        Ok(module)
+    }
+}
+
+/// Name of the module from which the generated contracts import the host
+/// functions, unless changed with [`CompileOptions::host_module`].
+pub const DEFAULT_HOST_MODULE: &str = "clarity";
+
+/// Options controlling how a contract is compiled, see [`compile`].
+///
+/// ```ignore
+/// let options = CompileOptions::default().emit_names(false).deterministic(true);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileOptions {
+    /// Keep the function names in the name section of the generated module.
+    emit_names: bool,
+    /// Only emit data that is derived from the contract, so that two compilations
+    /// of the same contract produce the same bytes regardless of the toolchain.
+    deterministic: bool,
+    /// Compile `debug-print` expressions to calls to the `debug_print` host
+    /// function, instead of stripping them.
+    developer_mode: bool,
+    /// Replace the host function imports with stubs, see [`standalone`].
+    standalone: bool,
+    /// Module from which the host functions are imported.
+    host_module: String,
+    /// Check the values read from and written to the memory, see
+    /// [`memory_checks`].
+    memory_checks: bool,
+    /// Fetch the keys of a list with one host call, see [`batch_map_reads`].
+    batch_map_reads: bool,
+    /// Return the results of the functions in memory, see [`memory_returns`].
+    memory_returns: bool,
+    /// Precompiled standard library replacing the bundled one, see
+    /// [`standard_library`].
+    standard_library: Option<Vec<u8>>,
+    /// WebAssembly features the module may use, see [`target_features`].
+    target_features: TargetFeatures,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            emit_names: true,
+            deterministic: false,
+            developer_mode: false,
+            standalone: false,
+            host_module: DEFAULT_HOST_MODULE.to_owned(),
+            memory_checks: false,
+            batch_map_reads: false,
+            memory_returns: false,
+            standard_library: None,
+            target_features: TargetFeatures::ALL,
+        }
+    }
+}
+
+impl CompileOptions {
+    pub fn emit_names(mut self, emit_names: bool) -> Self {
+        self.emit_names = emit_names;
+        self
+    }
+
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    pub fn developer_mode(mut self, developer_mode: bool) -> Self {
+        self.developer_mode = developer_mode;
+        self
+    }
+
+    pub fn standalone(mut self, standalone: bool) -> Self {
+        self.standalone = standalone;
+        self
+    }
+
+    /// Imports the host functions from `host_module` instead of
+    /// [`DEFAULT_HOST_MODULE`], for hosts using other naming conventions.
+    pub fn host_module(mut self, host_module: impl Into<String>) -> Self {
+        self.host_module = host_module.into();
+        self
+    }
+
+    /// Checks, before each value read from or written to the memory, that it
+    /// is within the memory, and that the variant indicator or bool written
+    /// is valid for its type. A failed check traps with an error naming the
+    /// expression being evaluated. The variant indicators, bools and lengths
+    /// read from the memory are always checked, with or without this option.
+    ///
+    /// This is meant to catch memory layout bugs while developing new words,
+    /// the checks make the generated code larger and slower.
+    pub fn memory_checks(mut self, memory_checks: bool) -> Self {
+        self.memory_checks = memory_checks;
+        self
+    }
+
+    /// Compiles `(map f keys)`, where `f` is a private or read-only function
+    /// only returning `(map-get? m key)` for its argument, to a single call
+    /// to the `map_get_batch` host function fetching all the keys, instead
+    /// of one `map_get` call per key. The costs are the same.
+    ///
+    /// `map_get_batch` is not imported by the standard library, it is only
+    /// imported by the modules using it, which can then only run on hosts
+    /// providing it.
+    pub fn batch_map_reads(mut self, batch_map_reads: bool) -> Self {
+        self.batch_map_reads = batch_map_reads;
+        self
+    }
+
+    /// Lowers the functions and blocks returning more than one value, so that
+    /// they write the values to the memory and return their offset as a
+    /// single `i32`, for the engines without multiple results. The host reads
+    /// the values with [`wasm_utils::read_memory_returns`], the functions of
+    /// [`wasm_utils`] calling a contract detect such exports, and the host
+    /// functions are linked with
+    /// [`linker::link_memory_returns_host_functions`]. Such a module can't be
+    /// changed by [`recompile_function`], and can't be the callee of a
+    /// `contract-call?`, which the Clarity VM runs with multiple results.
+    pub fn memory_returns(mut self, memory_returns: bool) -> Self {
+        self.memory_returns = memory_returns;
+        self
+    }
+
+    /// Builds the modules from the precompiled standard library `wasm`
+    /// instead of the one bundled with clar2wasm, so that the standard
+    /// library can be changed without rebuilding the compiler. `wasm` must
+    /// record the [`sections::ABI_VERSION`] of this version of clar2wasm in
+    /// its [`sections::ABI_VERSION_SECTION`], the compilation fails with an
+    /// internal error otherwise.
+    pub fn standard_library(mut self, wasm: Vec<u8>) -> Self {
+        self.standard_library = Some(wasm);
+        self
+    }
+
+    /// Restricts the WebAssembly features the module may use, for the
+    /// engines which don't implement all of them. The generated code uses
+    /// alternatives to the disabled features where it has one, e.g. a copy
+    /// routine instead of `memory.copy`, and the compilation fails with
+    /// [`GeneratorError::UnsupportedFeature`] if the module still uses one.
+    /// Without [`Feature::MultiValue`], the module is lowered like with
+    /// [`memory_returns`], with the same limitations. The SIMD variants of
+    /// the standard library routines, e.g. `stdlib.is-eq-bytes-simd`, are
+    /// only called when [`Feature::Simd`] is allowed.
+    ///
+    /// The bundled standard library uses the bulk memory operations, SIMD
+    /// and a mutable global export, a restricted target needs a
+    /// [`standard_library`] built without them.
+    pub fn target_features(mut self, target_features: TargetFeatures) -> Self {
+        self.target_features = target_features;
+        self
+    }
+
+    /// Creates the generator of a contract, with the standard library of the
+    /// options.
+    fn generator(
+        &self,
+        contract_analysis: ContractAnalysis,
+        epoch: StacksEpochId,
+        clarity_version: ClarityVersion,
+    ) -> Result<WasmGenerator, GeneratorError> {
+        match &self.standard_library {
+            Some(wasm) => WasmGenerator::with_standard_library(
+                contract_analysis,
+                epoch,
+                clarity_version,
+                wasm,
+            ),
+            None => WasmGenerator::new(contract_analysis, epoch, clarity_version),
+        }
+    }
+
+    /// Whether the functions return their results in memory, because of
+    /// [`memory_returns`] or of a target without multiple results.
+    fn returns_in_memory(&self) -> bool {
+        self.memory_returns || !self.target_features.allows(Feature::MultiValue)
+    }
+
+    /// Post-processes a generated module according to the options.
+    fn apply(&self, module: &mut Module) -> Result<(), GeneratorError> {
+        if self.standalone {
+            standalone::stub_host_imports(module);
+        }
+        // After the stubs, which are lowered like the other functions.
+        if self.returns_in_memory() {
+            memory_returns::return_results_in_memory(module)?;
+        }
+        if !self.emit_names {
+            module.name = None;
+            for func in module.funcs.iter_mut() {
+                func.name = None;
+            }
+        }
+        if self.deterministic {
+            // The producers section records the version of the tools which
+            // generated the module.
+            module.producers.clear();
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug)]
@@ -56,13 +339,213 @@ pub enum CompileError {
 pub fn compile(
     source: &str,
     contract_id: &QualifiedContractIdentifier,
-    mut cost_tracker: LimitedCostTracker,
+    cost_tracker: LimitedCostTracker,
     clarity_version: ClarityVersion,
     epoch: StacksEpochId,
     analysis_db: &mut AnalysisDatabase,
+    options: CompileOptions,
 ) -> Result<CompileResult, CompileError> {
+    let Analyzed {
+        ast,
+        mut diagnostics,
+        contract_analysis,
+        debug_prints,
+    } = analyze(
+        source,
+        contract_id,
+        cost_tracker,
+        clarity_version,
+        epoch,
+        analysis_db,
+        &options,
+    )?;
+
+    #[allow(clippy::expect_used)]
+    match options
+        .generator(contract_analysis.clone(), epoch, clarity_version)
+        .and_then(|mut generator| {
+            generator.debug_prints = debug_prints;
+            generator.memory_checks = options.memory_checks;
+            generator.batch_map_reads = options.batch_map_reads;
+            generator.target_features = options.target_features;
+            generator.set_host_module(&options.host_module);
+            generator.generate_with_diagnostics()
+        }) {
+        Ok((mut module, warnings)) => {
+            diagnostics.extend(warnings);
+            sections::add_abi_version(&mut module);
+            // The functions are found by name, before the options can remove
+            // the names.
+            let functions = contract_functions(&module, &contract_analysis);
+            match trait_conformance::check_trait_conformance(
+                analysis_db,
+                &contract_analysis,
+                &functions,
+                epoch,
+            ) {
+                Ok(warnings) => diagnostics.extend(warnings),
+                Err(e) => return Err(generation_error(ast, diagnostics, contract_analysis, e)),
+            }
+            if let Err(e) = options.apply(&mut module) {
+                return Err(generation_error(ast, diagnostics, contract_analysis, e));
+            }
+            if options.target_features != TargetFeatures::ALL {
+                if let Err(e) =
+                    target_features::validate(&module.emit_wasm(), options.target_features)
+                {
+                    return Err(generation_error(ast, diagnostics, contract_analysis, e));
+                }
+            }
+            Ok(CompileResult {
+                ast,
+                diagnostics,
+                module,
+                contract_analysis,
+                functions,
+            })
+        }
+        Err(e) => Err(generation_error(ast, diagnostics, contract_analysis, e)),
+    }
+}
+
+/// Finds the functions of the contract in the generated `module`, by the
+/// names the generator gave them.
+fn contract_functions(
+    module: &Module,
+    contract_analysis: &ContractAnalysis,
+) -> BTreeMap<ClarityName, ContractFunction> {
+    let public = contract_analysis.public_function_types.keys();
+    let read_only = contract_analysis.read_only_function_types.keys();
+    let private = contract_analysis.private_function_types.keys();
+    public
+        .map(|name| (name, FunctionKind::Public))
+        .chain(read_only.map(|name| (name, FunctionKind::ReadOnly)))
+        .chain(private.map(|name| (name, FunctionKind::Private)))
+        .filter_map(|(name, kind)| {
+            let id = module.funcs.by_name(name.as_str())?;
+            let export = module
+                .exports
+                .iter()
+                .find(|export| matches!(export.item, ExportItem::Function(func) if func == id))
+                .map(|export| export.id());
+            Some((name.clone(), ContractFunction { kind, id, export }))
+        })
+        .collect()
+}
+
+/// Recompiles only the function `function_name` of `result`, compiled by
+/// [`compile`] from a previous version of the contract, from its new
+/// `source`. The other functions and the literals of the module are kept, so
+/// an editor can quickly rebuild a large contract after an edit of one of its
+/// functions.
+///
+/// The new version is analyzed like in [`compile`], and the signature of the
+/// function must not have changed. `options` must be the options of the
+/// first compilation, which must have kept the function names. On failure,
+/// `result` is left unchanged.
+#[allow(clippy::too_many_arguments)]
+pub fn recompile_function(
+    result: &mut CompileResult,
+    function_name: &str,
+    source: &str,
+    contract_id: &QualifiedContractIdentifier,
+    cost_tracker: LimitedCostTracker,
+    clarity_version: ClarityVersion,
+    epoch: StacksEpochId,
+    analysis_db: &mut AnalysisDatabase,
+    options: CompileOptions,
+) -> Result<(), CompileError> {
+    let Analyzed {
+        ast,
+        diagnostics,
+        contract_analysis,
+        debug_prints,
+    } = analyze(
+        source,
+        contract_id,
+        cost_tracker,
+        clarity_version,
+        epoch,
+        analysis_db,
+        &options,
+    )?;
+
+    let signature = |analysis: &ContractAnalysis| {
+        [
+            analysis.get_public_function_type(function_name),
+            analysis.get_read_only_function_type(function_name),
+            analysis.get_private_function(function_name),
+        ]
+        .map(|ty| ty.cloned())
+    };
+    if signature(&result.contract_analysis) != signature(&contract_analysis) {
+        let e = GeneratorError::TypeError(format!(
+            "the signature of {function_name} changed, the contract must be recompiled"
+        ));
+        return Err(generation_error(ast, diagnostics, contract_analysis, e));
+    }
+    if options.returns_in_memory() {
+        // The module is already lowered, and the new function would call the
+        // others with their former signatures.
+        let e = GeneratorError::InternalError(format!(
+            "{function_name} can't be recompiled with memory returns, the contract must be recompiled"
+        ));
+        return Err(generation_error(ast, diagnostics, contract_analysis, e));
+    }
+
+    match options
+        .generator(contract_analysis.clone(), epoch, clarity_version)
+        .and_then(|mut generator| {
+            generator.debug_prints = debug_prints;
+            generator.memory_checks = options.memory_checks;
+            generator.batch_map_reads = options.batch_map_reads;
+            generator.target_features = options.target_features;
+            generator.set_host_module(&options.host_module);
+            generator.regenerate_function(&mut result.module, function_name)
+        }) {
+        Ok(()) => {
+            if let Err(e) = options.apply(&mut result.module) {
+                return Err(generation_error(ast, diagnostics, contract_analysis, e));
+            }
+            // The new function is checked like the whole module in
+            // `compile`.
+            if options.target_features != TargetFeatures::ALL {
+                if let Err(e) =
+                    target_features::validate(&result.module.emit_wasm(), options.target_features)
+                {
+                    return Err(generation_error(ast, diagnostics, contract_analysis, e));
+                }
+            }
+            result.ast = ast;
+            result.diagnostics = diagnostics;
+            result.contract_analysis = contract_analysis;
+            Ok(())
+        }
+        Err(e) => Err(generation_error(ast, diagnostics, contract_analysis, e)),
+    }
+}
+
+/// A contract parsed and analyzed, ready for the generation.
+struct Analyzed {
+    ast: ContractAST,
+    diagnostics: Vec<Diagnostic>,
+    contract_analysis: ContractAnalysis,
+    debug_prints: HashMap<u64, LogLevel>,
+}
+
+/// Parses and analyzes the contract `source`, as the first steps of
+/// [`compile`].
+fn analyze(
+    source: &str,
+    contract_id: &QualifiedContractIdentifier,
+    mut cost_tracker: LimitedCostTracker,
+    clarity_version: ClarityVersion,
+    epoch: StacksEpochId,
+    analysis_db: &mut AnalysisDatabase,
+    options: &CompileOptions,
+) -> Result<Analyzed, CompileError> {
     // Parse the contract
-    let (ast, mut diagnostics, success) = build_ast_with_diagnostics(
+    let (mut ast, mut diagnostics, success) = build_ast_with_diagnostics(
         contract_id,
         source,
         &mut cost_tracker,
@@ -78,6 +561,11 @@ pub fn compile(
         });
     }
 
+    let debug_prints =
+        debug_print::rewrite_debug_prints(&mut ast.expressions, options.developer_mode);
+    #[cfg(feature = "experimental-words")]
+    let experimental_calls = words::experimental::rewrite_for_analysis(&mut ast.expressions);
+
     // Run the analysis passes
     let mut contract_analysis = match run_analysis(
         contract_id,
@@ -100,6 +588,30 @@ pub fn compile(
         }
     };
 
+    // The generator compiles the experimental words themselves.
+    #[cfg(feature = "experimental-words")]
+    if let Some(expressions) = experimental_calls {
+        ast.expressions = expressions.clone();
+        contract_analysis.expressions = expressions;
+
+        #[allow(clippy::expect_used)]
+        if let Err(e) =
+            words::experimental::check_argument_types(&ast.expressions, &contract_analysis)
+        {
+            diagnostics.push(Diagnostic::err(&e.err));
+            return Err(CompileError::Generic {
+                ast: Box::new(ast),
+                diagnostics,
+                cost_tracker: Box::new(
+                    contract_analysis
+                        .cost_track
+                        .take()
+                        .expect("Failed to take cost tracker from contract analysis"),
+                ),
+            });
+        }
+    }
+
     typechecker_workaround(&ast, &mut contract_analysis);
 
     // Now that the typechecker pass is done, we can concretize the expressions types which
@@ -119,27 +631,34 @@ pub fn compile(
         });
     }
 
-    #[allow(clippy::expect_used)]
-    match WasmGenerator::new(contract_analysis.clone()).and_then(WasmGenerator::generate) {
-        Ok(module) => Ok(CompileResult {
-            ast,
-            diagnostics,
-            module,
-            contract_analysis,
-        }),
-        Err(e) => {
-            diagnostics.push(Diagnostic::err(&e));
-            Err(CompileError::Generic {
-                ast: Box::new(ast),
-                diagnostics,
-                cost_tracker: Box::new(
-                    contract_analysis
-                        .cost_track
-                        .take()
-                        .expect("Failed to take cost tracker from contract analysis"),
-                ),
-            })
-        }
+    Ok(Analyzed {
+        ast,
+        diagnostics,
+        contract_analysis,
+        debug_prints,
+    })
+}
+
+/// Returns the compile error for the failure `e` of the generation.
+#[allow(clippy::expect_used)]
+fn generation_error(
+    ast: ContractAST,
+    mut diagnostics: Vec<Diagnostic>,
+    mut contract_analysis: ContractAnalysis,
+    e: GeneratorError,
+) -> CompileError {
+    let mut diagnostic = Diagnostic::err(&e);
+    diagnostic.spans.extend(e.span().cloned());
+    diagnostics.push(diagnostic);
+    CompileError::Generic {
+        ast: Box::new(ast),
+        diagnostics,
+        cost_tracker: Box::new(
+            contract_analysis
+                .cost_track
+                .take()
+                .expect("Failed to take cost tracker from contract analysis"),
+        ),
     }
 }
 
@@ -237,7 +756,9 @@ fn typechecker_workaround(ast: &ContractAST, contract_analysis: &mut ContractAna
 }
 
 pub fn compile_contract(contract_analysis: ContractAnalysis) -> Result<Module, GeneratorError> {
-    let generator = WasmGenerator::new(contract_analysis)?;
+    let epoch = contract_analysis.epoch;
+    let clarity_version = contract_analysis.clarity_version;
+    let generator = WasmGenerator::new(contract_analysis, epoch, clarity_version)?;
     generator.generate()
 }
 
@@ -0,0 +1,838 @@
+//! Functions returning their results in memory, for the engines which don't
+//! implement multiple results.
+//!
+//! The functions of a contract and of the standard library return multiple
+//! values, for example the two `i64` halves of an `int`, or the offset and
+//! length of a sequence, and the blocks of their bodies take and return
+//! multiple values too. With
+//! [`CompileOptions::memory_returns`](crate::CompileOptions::memory_returns),
+//! the whole module is lowered so that it doesn't use multiple results:
+//!
+//! - each function returning more than one value writes its results to a
+//!   return area reserved in the memory, in order, each with the size of its
+//!   type, and returns the offset of the area as a single `i32`. The caller
+//!   reads them back right after the call. The exports keep their names, and
+//!   the host reads their results with
+//!   [`read_memory_returns`](crate::wasm_utils::read_memory_returns).
+//! - each host function returning more than one value is imported as
+//!   `<name>.memory-returns`, which takes the offset of the return area as an
+//!   extra argument, writes the results there and returns the offset, see
+//!   [`crate::linker::link_memory_returns_host_functions`].
+//! - the blocks taking values or returning more than one value pass them in
+//!   locals instead.
+//!
+//! The return area is reserved below the stack, which starts after it.
+
+use std::collections::HashMap;
+
+use walrus::ir::{
+    self, Instr, InstrLocId, InstrSeqId, InstrSeqType, LoadKind, MemArg, StoreKind, Value,
+};
+use walrus::{
+    FunctionBuilder, FunctionId, FunctionKind, GlobalKind, ImportKind, InitExpr, LocalFunction,
+    LocalId, MemoryId, Module, ModuleLocals, ModuleTypes, ValType,
+};
+
+use crate::wasm_generator::GeneratorError;
+use crate::wasm_utils::get_global;
+
+/// Suffix of the names of the host functions returning their results in
+/// memory.
+pub(crate) const MEMORY_RETURNS_SUFFIX: &str = ".memory-returns";
+
+/// A function returning more than one value, lowered to return them in
+/// memory.
+struct LoweredFunction {
+    results: Vec<ValType>,
+    /// The import replacing the function, if it is a host function.
+    import: Option<FunctionId>,
+}
+
+/// Lowers all the functions of `module` returning more than one value, and
+/// all the blocks taking values or returning more than one value.
+pub(crate) fn return_results_in_memory(module: &mut Module) -> Result<(), GeneratorError> {
+    let stack_pointer = get_global(module, "stack-pointer")?;
+    let memory = module
+        .memories
+        .iter()
+        .next()
+        .map(|memory| memory.id())
+        .ok_or_else(|| GeneratorError::InternalError("module without memory".to_owned()))?;
+
+    let mut lowered = HashMap::new();
+    let mut area_size = 0;
+    let funcs: Vec<_> = module
+        .funcs
+        .iter()
+        .map(|func| (func.id(), func.ty()))
+        .collect();
+    for (func, ty) in funcs {
+        let ty = module.types.get(ty);
+        if ty.results().len() <= 1 {
+            continue;
+        }
+        let (params, results) = (ty.params().to_vec(), ty.results().to_vec());
+        area_size = area_size.max(results_size(&results)?);
+
+        let import = match &module.funcs.get(func).kind {
+            FunctionKind::Import(imported) => Some(imported.import),
+            _ => None,
+        };
+        let import = match import {
+            Some(import) => {
+                let import = module.imports.get(import);
+                let (import_module, name) = (import.module.clone(), import.name.clone());
+                let params: Vec<_> = params.iter().copied().chain([ValType::I32]).collect();
+                let ty = module.types.add(&params, &[ValType::I32]);
+                let (new_func, _) = module.add_import_func(
+                    &import_module,
+                    &format!("{name}{MEMORY_RETURNS_SUFFIX}"),
+                    ty,
+                );
+                module.funcs.get_mut(new_func).name = module.funcs.get(func).name.clone();
+                Some(new_func)
+            }
+            None => None,
+        };
+        lowered.insert(func, LoweredFunction { results, import });
+    }
+
+    let area = reserve_return_area(module, stack_pointer, memory, area_size)?;
+
+    let locals: Vec<_> = module
+        .funcs
+        .iter()
+        .filter(|func| matches!(func.kind, FunctionKind::Local(_)))
+        .map(|func| func.id())
+        .collect();
+    for func in locals {
+        let ty = module.funcs.get(func).ty();
+        let FunctionKind::Local(local) = std::mem::replace(
+            &mut module.funcs.get_mut(func).kind,
+            FunctionKind::Uninitialized(ty),
+        ) else {
+            continue;
+        };
+        let ty = module.types.get(ty);
+        let (params, results) = (ty.params().to_vec(), ty.results().to_vec());
+        let builder = if results.len() > 1 {
+            FunctionBuilder::new(&mut module.types, &params, &[ValType::I32])
+        } else {
+            FunctionBuilder::new(&mut module.types, &params, &results)
+        };
+        let new_local = FunctionLowering {
+            types: &mut module.types,
+            locals: &mut module.locals,
+            lowered: &lowered,
+            memory,
+            area,
+            func: &local,
+            builder,
+            targets: HashMap::new(),
+            slots: HashMap::new(),
+            condition: None,
+            offset: None,
+        }
+        .lower(&results)?;
+        module.funcs.get_mut(func).kind = FunctionKind::Local(new_local);
+    }
+
+    // The host functions are now only called through their lowered imports.
+    for (func, lowered) in &lowered {
+        if lowered.import.is_some() {
+            if let FunctionKind::Import(imported) = &module.funcs.get(*func).kind {
+                module.imports.delete(imported.import);
+            }
+            module.funcs.delete(*func);
+        }
+    }
+    let multi_value_types: Vec<_> = module
+        .types
+        .iter()
+        .filter(|ty| ty.results().len() > 1)
+        .map(|ty| ty.id())
+        .collect();
+    for ty in multi_value_types {
+        module.types.delete(ty);
+    }
+    Ok(())
+}
+
+/// Reserves `size` bytes for the results of the functions at the start of the
+/// stack, and returns their offset. The stack starts after them, and the
+/// memory grows by a page to keep the space of the stack.
+fn reserve_return_area(
+    module: &mut Module,
+    stack_pointer: walrus::GlobalId,
+    memory: MemoryId,
+    size: u32,
+) -> Result<i32, GeneratorError> {
+    let GlobalKind::Local(InitExpr::Value(Value::I32(stack_start))) =
+        module.globals.get(stack_pointer).kind
+    else {
+        return Err(GeneratorError::InternalError(
+            "the stack pointer has no initial value".to_owned(),
+        ));
+    };
+    if size == 0 {
+        return Ok(stack_start);
+    }
+
+    let area = (stack_start + 7) & !7;
+    module.globals.get_mut(stack_pointer).kind =
+        GlobalKind::Local(InitExpr::Value(Value::I32(area + size as i32)));
+    let memory = module.memories.get_mut(memory);
+    memory.initial += 1;
+    if let Some(maximum) = memory.maximum.as_mut() {
+        *maximum = (*maximum).max(memory.initial);
+    }
+    Ok(area)
+}
+
+/// The size of the results of type `results` in the return area.
+fn results_size(results: &[ValType]) -> Result<u32, GeneratorError> {
+    results
+        .iter()
+        .map(|ty| access_kind(*ty).map(|(_, _, size)| size))
+        .sum()
+}
+
+/// The load and store instructions and the size of a result of type `ty`.
+fn access_kind(ty: ValType) -> Result<(LoadKind, StoreKind, u32), GeneratorError> {
+    match ty {
+        ValType::I32 => Ok((
+            LoadKind::I32 { atomic: false },
+            StoreKind::I32 { atomic: false },
+            4,
+        )),
+        ValType::I64 => Ok((
+            LoadKind::I64 { atomic: false },
+            StoreKind::I64 { atomic: false },
+            8,
+        )),
+        ValType::F32 => Ok((LoadKind::F32, StoreKind::F32, 4)),
+        ValType::F64 => Ok((LoadKind::F64, StoreKind::F64, 8)),
+        ValType::V128 => Ok((LoadKind::V128, StoreKind::V128, 16)),
+        ValType::Externref | ValType::Funcref => Err(GeneratorError::InternalError(format!(
+            "a {ty} result can't be returned in memory"
+        ))),
+    }
+}
+
+/// The new sequence of a block, and the values which its branches pass in
+/// the slots instead of the stack.
+struct Target {
+    seq: InstrSeqId,
+    slots: Vec<ValType>,
+}
+
+/// The lowering of the body of a function, into a new function.
+struct FunctionLowering<'a> {
+    types: &'a mut ModuleTypes,
+    locals: &'a mut ModuleLocals,
+    lowered: &'a HashMap<FunctionId, LoweredFunction>,
+    memory: MemoryId,
+    area: i32,
+    func: &'a LocalFunction,
+    builder: FunctionBuilder,
+    /// The new sequences, by the sequences of the old function.
+    targets: HashMap<InstrSeqId, Target>,
+    /// The locals holding the values passed between the blocks, by position
+    /// and type. A value is read back right after it is written, so the
+    /// blocks can share them.
+    slots: HashMap<(usize, ValType), LocalId>,
+    /// A local saving the condition of a branch.
+    condition: Option<LocalId>,
+    /// A local holding the offset of the results of a call.
+    offset: Option<LocalId>,
+}
+
+impl FunctionLowering<'_> {
+    /// Returns the lowered function, whose old body returns `results`.
+    fn lower(mut self, results: &[ValType]) -> Result<LocalFunction, GeneratorError> {
+        let func = self.func;
+        let entry = func.entry_block();
+        let new_entry = self.builder.func_body_id();
+
+        if results.len() <= 1 {
+            self.targets.insert(
+                entry,
+                Target {
+                    seq: new_entry,
+                    slots: vec![],
+                },
+            );
+            self.lower_seq(entry, new_entry, &[], &[])?;
+            return Ok(self.builder.local_func(func.args.clone()));
+        }
+
+        // All the exits of the body go to the end of a block, which writes
+        // the results to the return area.
+        let body = self.builder.dangling_instr_seq(None).id();
+        self.targets.insert(
+            entry,
+            Target {
+                seq: body,
+                slots: results.to_vec(),
+            },
+        );
+        self.lower_seq(entry, body, &[], results)?;
+
+        let loc = InstrLocId::default();
+        let mut instrs = vec![(Instr::Block(ir::Block { seq: body }), loc)];
+        let mut offset = 0;
+        for (index, ty) in results.iter().enumerate() {
+            let (_, store, size) = access_kind(*ty)?;
+            instrs.push((self.area_offset(), loc));
+            instrs.push((
+                Instr::LocalGet(ir::LocalGet {
+                    local: self.slot(index, *ty),
+                }),
+                loc,
+            ));
+            instrs.push((
+                Instr::Store(ir::Store {
+                    memory: self.memory,
+                    kind: store,
+                    arg: MemArg {
+                        align: size,
+                        offset,
+                    },
+                }),
+                loc,
+            ));
+            offset += size;
+        }
+        instrs.push((self.area_offset(), loc));
+        *self.builder.instr_seq(new_entry).instrs_mut() = instrs;
+
+        Ok(self.builder.local_func(func.args.clone()))
+    }
+
+    /// Lowers the sequence `seq` of the old function into `new_seq`, which
+    /// starts by pushing the values of the slots `start`, and ends by saving
+    /// the values of types `end` in the slots.
+    fn lower_seq(
+        &mut self,
+        seq: InstrSeqId,
+        new_seq: InstrSeqId,
+        start: &[ValType],
+        end: &[ValType],
+    ) -> Result<(), GeneratorError> {
+        let (func, lowered) = (self.func, self.lowered);
+        let mut instrs = vec![];
+        self.load_slots(&mut instrs, start, InstrLocId::default());
+
+        for (instr, loc) in &func.block(seq).instrs {
+            let loc = *loc;
+            match instr {
+                Instr::Block(ir::Block { seq }) => {
+                    let (params, results) = self.seq_type(*seq);
+                    let new = self.dangling_seq(&results);
+                    let slots = if results.len() > 1 {
+                        results.clone()
+                    } else {
+                        vec![]
+                    };
+                    self.targets.insert(*seq, Target { seq: new, slots });
+                    self.save_slots(&mut instrs, &params, loc);
+                    self.lower_seq(*seq, new, &params, &multiple(&results))?;
+                    instrs.push((Instr::Block(ir::Block { seq: new }), loc));
+                    self.load_slots(&mut instrs, &multiple(&results), loc);
+                }
+                Instr::Loop(ir::Loop { seq }) => {
+                    // The branches to a loop pass its parameters.
+                    let (params, results) = self.seq_type(*seq);
+                    let new = self.dangling_seq(&results);
+                    self.targets.insert(
+                        *seq,
+                        Target {
+                            seq: new,
+                            slots: params.clone(),
+                        },
+                    );
+                    self.save_slots(&mut instrs, &params, loc);
+                    self.lower_seq(*seq, new, &params, &multiple(&results))?;
+                    instrs.push((Instr::Loop(ir::Loop { seq: new }), loc));
+                    self.load_slots(&mut instrs, &multiple(&results), loc);
+                }
+                Instr::IfElse(ir::IfElse {
+                    consequent,
+                    alternative,
+                }) => {
+                    let (params, results) = self.seq_type(*consequent);
+                    let slots = if results.len() > 1 {
+                        results.clone()
+                    } else {
+                        vec![]
+                    };
+                    let new_consequent = self.dangling_seq(&results);
+                    let new_alternative = self.dangling_seq(&results);
+                    self.targets.insert(
+                        *consequent,
+                        Target {
+                            seq: new_consequent,
+                            slots: slots.clone(),
+                        },
+                    );
+                    self.targets.insert(
+                        *alternative,
+                        Target {
+                            seq: new_alternative,
+                            slots,
+                        },
+                    );
+                    if params.is_empty() {
+                        self.lower_seq(*consequent, new_consequent, &[], &multiple(&results))?;
+                        self.lower_seq(*alternative, new_alternative, &[], &multiple(&results))?;
+                    } else {
+                        let condition = self.condition();
+                        instrs.push((Instr::LocalSet(ir::LocalSet { local: condition }), loc));
+                        self.save_slots(&mut instrs, &params, loc);
+                        instrs.push((Instr::LocalGet(ir::LocalGet { local: condition }), loc));
+                        self.lower_seq(*consequent, new_consequent, &params, &multiple(&results))?;
+                        self.lower_seq(
+                            *alternative,
+                            new_alternative,
+                            &params,
+                            &multiple(&results),
+                        )?;
+                    }
+                    instrs.push((
+                        Instr::IfElse(ir::IfElse {
+                            consequent: new_consequent,
+                            alternative: new_alternative,
+                        }),
+                        loc,
+                    ));
+                    self.load_slots(&mut instrs, &multiple(&results), loc);
+                }
+                Instr::Br(ir::Br { block }) => {
+                    let (target, slots) = self.target(*block)?;
+                    self.save_slots(&mut instrs, &slots, loc);
+                    instrs.push((Instr::Br(ir::Br { block: target }), loc));
+                }
+                Instr::BrIf(ir::BrIf { block }) => {
+                    let (target, slots) = self.target(*block)?;
+                    if slots.is_empty() {
+                        instrs.push((Instr::BrIf(ir::BrIf { block: target }), loc));
+                    } else {
+                        // The values stay on the stack if the branch is not
+                        // taken.
+                        let condition = self.condition();
+                        instrs.push((Instr::LocalSet(ir::LocalSet { local: condition }), loc));
+                        self.save_slots(&mut instrs, &slots, loc);
+                        instrs.push((Instr::LocalGet(ir::LocalGet { local: condition }), loc));
+                        instrs.push((Instr::BrIf(ir::BrIf { block: target }), loc));
+                        self.load_slots(&mut instrs, &slots, loc);
+                    }
+                }
+                Instr::BrTable(ir::BrTable { blocks, default }) => {
+                    // All the targets take the same values.
+                    let (default, slots) = self.target(*default)?;
+                    let blocks = blocks
+                        .iter()
+                        .map(|block| self.target(*block).map(|(target, _)| target))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if !slots.is_empty() {
+                        let condition = self.condition();
+                        instrs.push((Instr::LocalSet(ir::LocalSet { local: condition }), loc));
+                        self.save_slots(&mut instrs, &slots, loc);
+                        instrs.push((Instr::LocalGet(ir::LocalGet { local: condition }), loc));
+                    }
+                    instrs.push((
+                        Instr::BrTable(ir::BrTable {
+                            blocks: blocks.into(),
+                            default,
+                        }),
+                        loc,
+                    ));
+                }
+                Instr::Return(_) => {
+                    // A return is a branch to the body of the function.
+                    let (target, slots) = self.target(func.entry_block())?;
+                    if slots.is_empty() {
+                        instrs.push((instr.clone(), loc));
+                    } else {
+                        self.save_slots(&mut instrs, &slots, loc);
+                        instrs.push((Instr::Br(ir::Br { block: target }), loc));
+                    }
+                }
+                Instr::Call(ir::Call { func }) => match lowered.get(func) {
+                    Some(LoweredFunction { results, import }) => {
+                        match import {
+                            Some(import) => {
+                                instrs.push((self.area_offset(), loc));
+                                instrs.push((Instr::Call(ir::Call { func: *import }), loc));
+                            }
+                            None => instrs.push((instr.clone(), loc)),
+                        }
+                        self.load_results(&mut instrs, results, loc)?;
+                    }
+                    None => instrs.push((instr.clone(), loc)),
+                },
+                Instr::CallIndirect(ir::CallIndirect { ty, table }) => {
+                    let ty = self.types.get(*ty);
+                    if ty.results().len() > 1 {
+                        let (params, results) = (ty.params().to_vec(), ty.results().to_vec());
+                        let ty = self.types.add(&params, &[ValType::I32]);
+                        instrs.push((
+                            Instr::CallIndirect(ir::CallIndirect { ty, table: *table }),
+                            loc,
+                        ));
+                        self.load_results(&mut instrs, &results, loc)?;
+                    } else {
+                        instrs.push((instr.clone(), loc));
+                    }
+                }
+                _ => instrs.push((instr.clone(), loc)),
+            }
+        }
+
+        self.save_slots(&mut instrs, end, InstrLocId::default());
+        *self.builder.instr_seq(new_seq).instrs_mut() = instrs;
+        Ok(())
+    }
+
+    /// The parameters and results of the sequence `seq` of the old function.
+    fn seq_type(&self, seq: InstrSeqId) -> (Vec<ValType>, Vec<ValType>) {
+        match self.func.block(seq).ty {
+            InstrSeqType::Simple(result) => (vec![], result.into_iter().collect()),
+            InstrSeqType::MultiValue(ty) => {
+                let ty = self.types.get(ty);
+                (ty.params().to_vec(), ty.results().to_vec())
+            }
+        }
+    }
+
+    /// Adds a sequence without parameters, returning its only result on the
+    /// stack.
+    fn dangling_seq(&mut self, results: &[ValType]) -> InstrSeqId {
+        let result = match results {
+            [result] => Some(*result),
+            _ => None,
+        };
+        self.builder.dangling_instr_seq(result).id()
+    }
+
+    /// The new sequence of the old `block`, and the values passed to it in
+    /// the slots.
+    fn target(&self, block: InstrSeqId) -> Result<(InstrSeqId, Vec<ValType>), GeneratorError> {
+        self.targets
+            .get(&block)
+            .map(|target| (target.seq, target.slots.clone()))
+            .ok_or_else(|| GeneratorError::InternalError("branch to an unknown block".to_owned()))
+    }
+
+    fn slot(&mut self, index: usize, ty: ValType) -> LocalId {
+        let locals = &mut *self.locals;
+        *self
+            .slots
+            .entry((index, ty))
+            .or_insert_with(|| locals.add(ty))
+    }
+
+    fn condition(&mut self) -> LocalId {
+        let locals = &mut *self.locals;
+        *self
+            .condition
+            .get_or_insert_with(|| locals.add(ValType::I32))
+    }
+
+    fn area_offset(&self) -> Instr {
+        Instr::Const(ir::Const {
+            value: Value::I32(self.area),
+        })
+    }
+
+    /// Saves the values of types `types`, on the top of the stack, in the
+    /// slots.
+    fn save_slots(
+        &mut self,
+        instrs: &mut Vec<(Instr, InstrLocId)>,
+        types: &[ValType],
+        loc: InstrLocId,
+    ) {
+        for (index, ty) in types.iter().enumerate().rev() {
+            let local = self.slot(index, *ty);
+            instrs.push((Instr::LocalSet(ir::LocalSet { local }), loc));
+        }
+    }
+
+    /// Pushes the values of types `types` saved in the slots.
+    fn load_slots(
+        &mut self,
+        instrs: &mut Vec<(Instr, InstrLocId)>,
+        types: &[ValType],
+        loc: InstrLocId,
+    ) {
+        for (index, ty) in types.iter().enumerate() {
+            let local = self.slot(index, *ty);
+            instrs.push((Instr::LocalGet(ir::LocalGet { local }), loc));
+        }
+    }
+
+    /// Pushes the `results` of a call from the memory, at the offset it
+    /// returned.
+    fn load_results(
+        &mut self,
+        instrs: &mut Vec<(Instr, InstrLocId)>,
+        results: &[ValType],
+        loc: InstrLocId,
+    ) -> Result<(), GeneratorError> {
+        let locals = &mut *self.locals;
+        let local = *self.offset.get_or_insert_with(|| locals.add(ValType::I32));
+        instrs.push((Instr::LocalSet(ir::LocalSet { local }), loc));
+        let mut offset = 0;
+        for ty in results {
+            let (load, _, size) = access_kind(*ty)?;
+            instrs.push((Instr::LocalGet(ir::LocalGet { local }), loc));
+            instrs.push((
+                Instr::Load(ir::Load {
+                    memory: self.memory,
+                    kind: load,
+                    arg: MemArg {
+                        align: size,
+                        offset,
+                    },
+                }),
+                loc,
+            ));
+            offset += size;
+        }
+        Ok(())
+    }
+}
+
+/// The `results` of a block if there are more than one, which are passed in
+/// the slots.
+fn multiple(results: &[ValType]) -> Vec<ValType> {
+    if results.len() > 1 {
+        results.to_vec()
+    } else {
+        vec![]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::Value;
+    use walrus::ExportItem;
+    use wasmparser::{Validator, WasmFeatures};
+
+    use crate::tools::{TestConfig, TestEnvironment};
+    use crate::{compile, CompileOptions, CompileResult};
+
+    const CONTRACT: &str = r#"
+        (define-read-only (sum (a int) (b int)) (+ a b))
+        (define-read-only (is-even (n uint)) (is-eq (mod n u2) u0))
+        (define-read-only (greet (name (string-ascii 8))) (concat "hi " name))
+        (define-public (check (n int)) (if (> n 0) (ok (some n)) (err u1)))
+        (define-private (double (n int)) (* n 2))
+        (define-read-only (doubled (xs (list 10 int))) (map double xs))
+        (define-read-only (total (xs (list 10 int))) (fold + xs 0))
+        (define-read-only (balance) (stx-get-balance tx-sender))
+        (define-read-only (settle (r (response int uint)))
+            (match r value (double value) code (to-int code)))
+        (define-read-only (spread (n int))
+            (let ((low (- n 1)) (high (+ n 1))) (- high low)))
+        (+ 1 2)
+    "#;
+
+    fn compile_contract(options: CompileOptions) -> CompileResult {
+        compile(
+            CONTRACT,
+            &QualifiedContractIdentifier::local("returns").unwrap(),
+            LimitedCostTracker::new_free(),
+            TestConfig::clarity_version(),
+            TestConfig::epoch(),
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            options,
+        )
+        .expect("failed to compile contract")
+    }
+
+    fn export_results(result: &CompileResult, name: &str) -> usize {
+        let module = &result.module;
+        let export = module
+            .exports
+            .iter()
+            .find(|export| export.name == name)
+            .unwrap();
+        let ExportItem::Function(func) = export.item else {
+            panic!("{name} is not a function");
+        };
+        module
+            .types
+            .get(module.funcs.get(func).ty())
+            .results()
+            .len()
+    }
+
+    #[test]
+    fn exports_return_single_values() {
+        let result = compile_contract(CompileOptions::default().memory_returns(true));
+
+        for name in ["sum", "greet", "check", "doubled", "balance"] {
+            assert_eq!(export_results(&result, name), 1, "{name}");
+        }
+        // The exports are the functions themselves, which return the offset
+        // of their results.
+        let sum = result.functions[&"sum".into()].id;
+        assert_eq!(
+            result
+                .module
+                .types
+                .get(result.module.funcs.get(sum).ty())
+                .results()
+                .len(),
+            1
+        );
+        assert_eq!(export_results(&result, "is-even"), 1);
+    }
+
+    #[test]
+    fn module_without_multiple_results() {
+        let mut result = compile_contract(CompileOptions::default().memory_returns(true));
+
+        // The functions of the contract, of the standard library and the host
+        // functions.
+        for func in result.module.funcs.iter() {
+            let results = result.module.types.get(func.ty()).results().len();
+            assert!(results <= 1, "{:?} returns {results} values", func.name);
+        }
+        let features = WasmFeatures::default().difference(WasmFeatures::MULTI_VALUE);
+        Validator::new_with_features(features)
+            .validate_all(&result.module.emit_wasm())
+            .expect("the module uses multiple results");
+
+        // Without the option, the same contract needs them.
+        let mut result = compile_contract(CompileOptions::default());
+        assert!(Validator::new_with_features(features)
+            .validate_all(&result.module.emit_wasm())
+            .is_err());
+    }
+
+    #[test]
+    fn calls_through_memory_returns() {
+        let mut result = compile_contract(CompileOptions::default().memory_returns(true));
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        assert_eq!(
+            env.deploy_precompiled(
+                &QualifiedContractIdentifier::local("returns").unwrap(),
+                &result.module.emit_wasm(),
+                &result.contract_analysis,
+            ),
+            Ok(Some(Value::Int(3)))
+        );
+
+        assert_eq!(
+            env.call_function("returns", "sum", &[Value::Int(-7), Value::Int(12)]),
+            Ok(Value::Int(5))
+        );
+        assert_eq!(
+            env.call_function("returns", "is-even", &[Value::UInt(4)]),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(
+            env.call_function(
+                "returns",
+                "greet",
+                &[Value::string_ascii_from_bytes(b"bob".to_vec()).unwrap()]
+            ),
+            Ok(Value::string_ascii_from_bytes(b"hi bob".to_vec()).unwrap())
+        );
+        assert_eq!(
+            env.call_function("returns", "check", &[Value::Int(3)]),
+            Ok(Value::okay(Value::some(Value::Int(3)).unwrap()).unwrap())
+        );
+        assert_eq!(
+            env.call_function("returns", "check", &[Value::Int(-3)]),
+            Ok(Value::error(Value::UInt(1)).unwrap())
+        );
+        let list = Value::cons_list_unsanitized(vec![Value::Int(1), Value::Int(-2), Value::Int(5)])
+            .unwrap();
+        assert_eq!(
+            env.call_function("returns", "doubled", &[list.clone()]),
+            Ok(
+                Value::cons_list_unsanitized(vec![Value::Int(2), Value::Int(-4), Value::Int(10)])
+                    .unwrap()
+            )
+        );
+        assert_eq!(
+            env.call_function("returns", "total", &[list]),
+            Ok(Value::Int(4))
+        );
+        assert_eq!(
+            env.call_function("returns", "balance", &[]),
+            Ok(Value::UInt(1_000_000_000))
+        );
+        assert_eq!(
+            env.call_function("returns", "settle", &[Value::okay(Value::Int(4)).unwrap()]),
+            Ok(Value::Int(8))
+        );
+        assert_eq!(
+            env.call_function(
+                "returns",
+                "settle",
+                &[Value::error(Value::UInt(9)).unwrap()]
+            ),
+            Ok(Value::Int(9))
+        );
+        assert_eq!(
+            env.call_function("returns", "spread", &[Value::Int(40)]),
+            Ok(Value::Int(2))
+        );
+    }
+
+    #[test]
+    fn contract_call_from_memory_returns() {
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        env.init_contract_with_snippet(
+            "callee",
+            "(define-read-only (sum (a int) (b int)) (+ a b))",
+        )
+        .unwrap();
+        env.init_contract_with_options(
+            "caller",
+            "(define-read-only (sum (a int)) (contract-call? .callee sum a 1))",
+            CompileOptions::default().memory_returns(true),
+        )
+        .unwrap();
+
+        assert_eq!(
+            env.call_function("caller", "sum", &[Value::Int(41)]),
+            Ok(Value::Int(42))
+        );
+    }
+
+    #[test]
+    fn contract_call_into_memory_returns() {
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        env.init_contract_with_options(
+            "callee",
+            "(define-read-only (sum (a int) (b int)) (+ a b))",
+            CompileOptions::default().memory_returns(true),
+        )
+        .unwrap();
+        env.init_contract_with_snippet(
+            "caller",
+            "(define-read-only (sum (a int)) (contract-call? .callee sum a 1))",
+        )
+        .unwrap();
+
+        // The callee of a `contract-call?` is run by the Clarity VM, which
+        // expects the results of the export on the stack.
+        assert!(env
+            .call_function("caller", "sum", &[Value::Int(41)])
+            .is_err());
+        assert_eq!(
+            env.call_function("callee", "sum", &[Value::Int(41), Value::Int(1)]),
+            Ok(Value::Int(42))
+        );
+    }
+}
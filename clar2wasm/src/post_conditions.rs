@@ -0,0 +1,381 @@
+//! Experimental evaluation of post-conditions at the runtime layer.
+//!
+//! The host functions moving assets (`stx-transfer?`, `ft-burn?`,
+//! `nft-transfer?`, ...) record each movement as an event of the current
+//! event batch of the global context. After a call, [`check_post_conditions`]
+//! evaluates the post-conditions of a transaction against these events, like
+//! a node does before committing the transaction.
+//!
+//! Only the assets sent by a principal are constrained: mints are not asset
+//! movements, and burns are counted as sent by the owner.
+
+use clarity::vm::events::{FTEventType, NFTEventType, STXEventType, StacksTransactionEvent};
+use clarity::vm::types::{AssetIdentifier, PrincipalData};
+use clarity::vm::Value;
+
+/// An asset which can be constrained by a post-condition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Asset {
+    Stx,
+    FungibleToken(AssetIdentifier),
+    /// The non-fungible token identified by the value.
+    NonFungibleToken(AssetIdentifier, Value),
+}
+
+/// An amount of an asset sent by a principal, to another principal or
+/// burnt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetMovement {
+    pub sender: PrincipalData,
+    pub asset: Asset,
+    /// Amount sent, always 1 for a non-fungible token.
+    pub amount: u128,
+}
+
+/// Comparison of the amount sent by a principal with the amount of a
+/// post-condition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparator {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Comparator {
+    fn holds(self, sent: u128, amount: u128) -> bool {
+        match self {
+            Comparator::Eq => sent == amount,
+            Comparator::Gt => sent > amount,
+            Comparator::Ge => sent >= amount,
+            Comparator::Lt => sent < amount,
+            Comparator::Le => sent <= amount,
+        }
+    }
+}
+
+/// A post-condition on the assets sent by a principal during a call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostCondition {
+    Stx {
+        principal: PrincipalData,
+        comparator: Comparator,
+        amount: u128,
+    },
+    FungibleToken {
+        principal: PrincipalData,
+        asset: AssetIdentifier,
+        comparator: Comparator,
+        amount: u128,
+    },
+    NonFungibleToken {
+        principal: PrincipalData,
+        asset: AssetIdentifier,
+        id: Value,
+        /// Whether the token must be sent, or must not be.
+        sent: bool,
+    },
+}
+
+impl PostCondition {
+    /// Returns true if `movement` is constrained by this post-condition.
+    fn covers(&self, movement: &AssetMovement) -> bool {
+        match (self, &movement.asset) {
+            (PostCondition::Stx { principal, .. }, Asset::Stx) => *principal == movement.sender,
+            (
+                PostCondition::FungibleToken {
+                    principal, asset, ..
+                },
+                Asset::FungibleToken(moved),
+            ) => *principal == movement.sender && asset == moved,
+            (
+                PostCondition::NonFungibleToken {
+                    principal,
+                    asset,
+                    id,
+                    ..
+                },
+                Asset::NonFungibleToken(moved, moved_id),
+            ) => *principal == movement.sender && asset == moved && id == moved_id,
+            _ => false,
+        }
+    }
+}
+
+/// What happens to the asset movements which are not constrained by any
+/// post-condition.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PostConditionMode {
+    /// They are allowed.
+    Allow,
+    /// They make the check fail.
+    #[default]
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PostConditionError {
+    #[error("post-condition not satisfied: {0:?}")]
+    Unsatisfied(PostCondition),
+    #[error("asset movement not allowed by the post-conditions: {0:?}")]
+    Disallowed(AssetMovement),
+}
+
+/// Returns the asset movements recorded in `events`, in order.
+pub fn asset_movements(events: &[StacksTransactionEvent]) -> Vec<AssetMovement> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(data)) => {
+                Some(AssetMovement {
+                    sender: data.sender.clone(),
+                    asset: Asset::Stx,
+                    amount: data.amount,
+                })
+            }
+            StacksTransactionEvent::STXEvent(STXEventType::STXBurnEvent(data)) => {
+                Some(AssetMovement {
+                    sender: data.sender.clone(),
+                    asset: Asset::Stx,
+                    amount: data.amount,
+                })
+            }
+            StacksTransactionEvent::FTEvent(FTEventType::FTTransferEvent(data)) => {
+                Some(AssetMovement {
+                    sender: data.sender.clone(),
+                    asset: Asset::FungibleToken(data.asset_identifier.clone()),
+                    amount: data.amount,
+                })
+            }
+            StacksTransactionEvent::FTEvent(FTEventType::FTBurnEvent(data)) => {
+                Some(AssetMovement {
+                    sender: data.sender.clone(),
+                    asset: Asset::FungibleToken(data.asset_identifier.clone()),
+                    amount: data.amount,
+                })
+            }
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTTransferEvent(data)) => {
+                Some(AssetMovement {
+                    sender: data.sender.clone(),
+                    asset: Asset::NonFungibleToken(
+                        data.asset_identifier.clone(),
+                        data.value.clone(),
+                    ),
+                    amount: 1,
+                })
+            }
+            StacksTransactionEvent::NFTEvent(NFTEventType::NFTBurnEvent(data)) => {
+                Some(AssetMovement {
+                    sender: data.sender.clone(),
+                    asset: Asset::NonFungibleToken(
+                        data.asset_identifier.clone(),
+                        data.value.clone(),
+                    ),
+                    amount: 1,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Checks the `post_conditions` of a call against the asset movements
+/// recorded in its `events`. In [`PostConditionMode::Deny`], every movement
+/// must also be constrained by one of the post-conditions.
+pub fn check_post_conditions(
+    events: &[StacksTransactionEvent],
+    post_conditions: &[PostCondition],
+    mode: PostConditionMode,
+) -> Result<(), PostConditionError> {
+    let movements = asset_movements(events);
+    let sent = |principal: &PrincipalData, asset: &Asset| {
+        movements
+            .iter()
+            .filter(|movement| movement.sender == *principal && movement.asset == *asset)
+            .fold(0u128, |sent, movement| sent.saturating_add(movement.amount))
+    };
+
+    for post_condition in post_conditions {
+        let holds = match post_condition {
+            PostCondition::Stx {
+                principal,
+                comparator,
+                amount,
+            } => comparator.holds(sent(principal, &Asset::Stx), *amount),
+            PostCondition::FungibleToken {
+                principal,
+                asset,
+                comparator,
+                amount,
+            } => comparator.holds(
+                sent(principal, &Asset::FungibleToken(asset.clone())),
+                *amount,
+            ),
+            PostCondition::NonFungibleToken {
+                principal,
+                asset,
+                id,
+                sent: must_be_sent,
+            } => {
+                let asset = Asset::NonFungibleToken(asset.clone(), id.clone());
+                (sent(principal, &asset) > 0) == *must_be_sent
+            }
+        };
+        if !holds {
+            return Err(PostConditionError::Unsatisfied(post_condition.clone()));
+        }
+    }
+
+    if mode == PostConditionMode::Deny {
+        if let Some(movement) = movements.iter().find(|movement| {
+            !post_conditions
+                .iter()
+                .any(|post_condition| post_condition.covers(movement))
+        }) {
+            return Err(PostConditionError::Disallowed(movement.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::vm::errors::{Error, WasmError};
+    use clarity::vm::types::{QualifiedContractIdentifier, StandardPrincipalData};
+
+    use super::*;
+    use crate::tools::TestEnvironment;
+
+    const CONTRACT: &str = r#"
+        (define-fungible-token gold)
+        (define-non-fungible-token badge uint)
+        (ft-mint? gold u100 tx-sender)
+        (nft-mint? badge u1 tx-sender)
+        (define-public (pay)
+            (begin
+                (try! (stx-transfer? u50 tx-sender 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM))
+                (try! (ft-burn? gold u10 tx-sender))
+                (nft-transfer? badge u1 tx-sender 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)))
+        (define-read-only (gold-balance) (ft-get-balance gold tx-sender))
+    "#;
+
+    fn sender() -> PrincipalData {
+        PrincipalData::Standard(StandardPrincipalData::transient())
+    }
+
+    fn asset(name: &str) -> AssetIdentifier {
+        AssetIdentifier {
+            contract_identifier: QualifiedContractIdentifier::new(
+                StandardPrincipalData::transient(),
+                "assets".into(),
+            ),
+            asset_name: name.into(),
+        }
+    }
+
+    fn pay(
+        post_conditions: &[PostCondition],
+        mode: PostConditionMode,
+    ) -> (TestEnvironment, Result<Value, Error>) {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet("assets", CONTRACT).unwrap();
+        let result =
+            env.call_function_with_post_conditions("assets", "pay", &[], post_conditions, mode);
+        (env, result)
+    }
+
+    fn all_post_conditions() -> Vec<PostCondition> {
+        vec![
+            PostCondition::Stx {
+                principal: sender(),
+                comparator: Comparator::Eq,
+                amount: 50,
+            },
+            PostCondition::FungibleToken {
+                principal: sender(),
+                asset: asset("gold"),
+                comparator: Comparator::Le,
+                amount: 10,
+            },
+            PostCondition::NonFungibleToken {
+                principal: sender(),
+                asset: asset("badge"),
+                id: Value::UInt(1),
+                sent: true,
+            },
+        ]
+    }
+
+    fn post_condition_error(result: Result<Value, Error>) -> PostConditionError {
+        match result {
+            Err(Error::Wasm(WasmError::Runtime(e))) => e
+                .downcast_ref::<PostConditionError>()
+                .cloned()
+                .unwrap_or_else(|| panic!("unexpected error: {e:?}")),
+            result => panic!("expected a post-condition failure, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn satisfied_post_conditions() {
+        let (_, result) = pay(&all_post_conditions(), PostConditionMode::Deny);
+        assert_eq!(result, Ok(Value::okay_true()));
+    }
+
+    #[test]
+    fn unsatisfied_post_condition_rolls_back() {
+        let mut post_conditions = all_post_conditions();
+        post_conditions[0] = PostCondition::Stx {
+            principal: sender(),
+            comparator: Comparator::Lt,
+            amount: 50,
+        };
+        let (mut env, result) = pay(&post_conditions, PostConditionMode::Deny);
+        assert_eq!(
+            post_condition_error(result),
+            PostConditionError::Unsatisfied(post_conditions[0].clone())
+        );
+        assert_eq!(
+            env.call_function("assets", "gold-balance", &[]),
+            Ok(Value::UInt(100))
+        );
+    }
+
+    #[test]
+    fn unconstrained_movements_depend_on_the_mode() {
+        let post_conditions = &all_post_conditions()[..2];
+        let (_, result) = pay(post_conditions, PostConditionMode::Deny);
+        assert_eq!(
+            post_condition_error(result),
+            PostConditionError::Disallowed(AssetMovement {
+                sender: sender(),
+                asset: Asset::NonFungibleToken(asset("badge"), Value::UInt(1)),
+                amount: 1,
+            })
+        );
+
+        let (_, result) = pay(post_conditions, PostConditionMode::Allow);
+        assert_eq!(result, Ok(Value::okay_true()));
+    }
+
+    #[test]
+    fn burns_are_sent_and_mints_are_not() {
+        let (mut env, _) = pay(&[], PostConditionMode::Allow);
+        let movements = asset_movements(&env.get_events()[1].events);
+        assert_eq!(
+            movements.iter().map(|m| &m.asset).collect::<Vec<_>>(),
+            [
+                &Asset::Stx,
+                &Asset::FungibleToken(asset("gold")),
+                &Asset::NonFungibleToken(asset("badge"), Value::UInt(1)),
+            ]
+        );
+        assert!(asset_movements(&env.get_events()[0].events).is_empty());
+        assert_eq!(
+            env.call_function("assets", "gold-balance", &[]),
+            Ok(Value::UInt(90))
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Pretty-printing of Clarity values, for the error messages of the tools and
+//! the developer logs.
+//!
+//! A value which fits in the width is printed on one line, exactly like the
+//! interpreter prints it. A larger list, tuple, optional or response is
+//! broken after its opening keyword, with each of its elements on its own
+//! indented line:
+//!
+//! ```text
+//! (tuple
+//!   (balances (u100 u200 u300))
+//!   (owner (some 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)))
+//! ```
+//!
+//! Strings, buffers and principals are never broken, and tuple fields are
+//! printed in the same order as the interpreter, sorted by name.
+
+use std::fmt::Write;
+
+use clarity::vm::types::{SequenceData, TupleData};
+use clarity::vm::Value;
+
+/// Width of the lines of the values printed in error messages and logs.
+pub const DEFAULT_WIDTH: usize = 100;
+
+/// Number of spaces by which the elements of a broken value are indented.
+const INDENT: usize = 2;
+
+/// Returns `value` printed in lines of at most `width` characters, unless an
+/// element which can't be broken is larger.
+pub fn pretty_print(value: &Value, width: usize) -> String {
+    let mut output = String::new();
+    print_value(&mut output, value, 0, width);
+    output
+}
+
+/// Returns `result`, as returned by the evaluation of a snippet, with its
+/// value pretty-printed.
+pub fn pretty_print_result<E: std::fmt::Debug>(result: &Result<Option<Value>, E>) -> String {
+    match result {
+        Ok(Some(value)) => format!("Ok({})", pretty_print(value, DEFAULT_WIDTH)),
+        Ok(None) => "Ok(no value)".to_owned(),
+        Err(e) => format!("Err({e:?})"),
+    }
+}
+
+/// Appends `value` to `output`, which ends `indent` characters into the
+/// current line.
+fn print_value(output: &mut String, value: &Value, indent: usize, width: usize) {
+    let flat = value.to_string();
+    if indent + flat.len() <= width {
+        output.push_str(&flat);
+        return;
+    }
+
+    match value {
+        Value::Sequence(SequenceData::List(list)) if !list.data.is_empty() => print_broken(
+            output,
+            "",
+            list.data.iter().map(Element::Value),
+            indent,
+            width,
+        ),
+        Value::Tuple(tuple) => print_broken(
+            output,
+            "tuple",
+            tuple_fields(tuple).map(|(name, value)| Element::Field(name, value)),
+            indent,
+            width,
+        ),
+        Value::Optional(optional) => match &optional.data {
+            Some(value) => print_broken(
+                output,
+                "some",
+                [Element::Value(value)].into_iter(),
+                indent,
+                width,
+            ),
+            None => output.push_str(&flat),
+        },
+        Value::Response(response) => print_broken(
+            output,
+            if response.committed { "ok" } else { "err" },
+            [Element::Value(&response.data)].into_iter(),
+            indent,
+            width,
+        ),
+        _ => output.push_str(&flat),
+    }
+}
+
+/// An element of a broken value.
+enum Element<'a> {
+    Value(&'a Value),
+    /// A field of a tuple, with its name.
+    Field(&'a str, &'a Value),
+}
+
+/// Appends `(<keyword>` to `output`, then each of the `elements` on its own
+/// line, indented, and the closing parenthesis.
+fn print_broken<'a>(
+    output: &mut String,
+    keyword: &str,
+    elements: impl Iterator<Item = Element<'a>>,
+    indent: usize,
+    width: usize,
+) {
+    let inner = indent + INDENT;
+    let _ = write!(output, "({keyword}");
+    for element in elements {
+        let _ = write!(output, "\n{:inner$}", "");
+        match element {
+            Element::Value(value) => print_value(output, value, inner, width),
+            Element::Field(name, value) => {
+                let flat = format!("({name} {value})");
+                if inner + flat.len() <= width {
+                    output.push_str(&flat);
+                } else {
+                    print_broken(
+                        output,
+                        name,
+                        [Element::Value(value)].into_iter(),
+                        inner,
+                        width,
+                    );
+                }
+            }
+        }
+    }
+    output.push(')');
+}
+
+/// The fields of `tuple`, in the order in which the interpreter prints them.
+fn tuple_fields(tuple: &TupleData) -> impl Iterator<Item = (&str, &Value)> {
+    tuple
+        .data_map
+        .iter()
+        .map(|(name, value)| (name.as_str(), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+
+    use super::*;
+    use crate::tools::evaluate;
+
+    fn value(snippet: &str) -> Value {
+        evaluate(snippet).unwrap().unwrap()
+    }
+
+    #[test]
+    fn small_values_are_printed_like_the_interpreter() {
+        for snippet in [
+            "-42",
+            "u42",
+            "true",
+            "0x0102ff",
+            r#""hello \"world\"""#,
+            r#"u"caf\u{e9} \u{1F98A}""#,
+            "(list 1 2 3)",
+            "(list)",
+            "{ zeta: u1, alpha: (some -1), beta: (list u2 u3) }",
+            "(err u404)",
+            "'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.contract",
+        ] {
+            let value = value(snippet);
+            assert_eq!(pretty_print(&value, DEFAULT_WIDTH), value.to_string());
+        }
+    }
+
+    #[test]
+    fn large_values_are_broken() {
+        let owner = value("'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM");
+        let value = value(
+            "{ owner: (some 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM), balances: (list u100 u200 u300) }",
+        );
+        assert_eq!(
+            pretty_print(&value, 40),
+            format!(
+                "(tuple
+  (balances (u100 u200 u300))
+  (owner
+    (some
+      {owner})))"
+            )
+        );
+    }
+
+    #[test]
+    fn unbreakable_values_exceed_the_width() {
+        let principal = Value::Principal(PrincipalData::Contract(
+            QualifiedContractIdentifier::local("a-contract-with-a-long-name").unwrap(),
+        ));
+        assert_eq!(pretty_print(&principal, 10), principal.to_string());
+    }
+
+    #[test]
+    fn pretty_print_results() {
+        assert_eq!(
+            pretty_print_result::<()>(&Ok(Some(Value::UInt(1)))),
+            "Ok(u1)"
+        );
+        assert_eq!(pretty_print_result::<()>(&Ok(None)), "Ok(no value)");
+        assert_eq!(
+            pretty_print_result(&Err::<Option<Value>, _>("e")),
+            "Err(\"e\")"
+        );
+    }
+}
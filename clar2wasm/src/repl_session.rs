@@ -0,0 +1,357 @@
+//! Runs sessions written in the command format of the `clarity-repl`
+//! console, with both the compiler and the interpreter, so that the test
+//! vectors written for the console can be checked against clar2wasm, and a
+//! divergence found by one tool can be replayed in the other.
+//!
+//! A session is a list of inputs, each on a line starting with `>>`, followed
+//! by the lines of its expected output, if any:
+//!
+//! ```text
+//! ;; Comments start with `;;`.
+//! >> (+ 1 2)
+//! 3
+//! >> ::advance_chain_tip 5
+//! 5 blocks simulated, new height: 5
+//! >> (list 1 2)
+//! ```
+//!
+//! An input is either a Clarity snippet, deployed as a new contract like the
+//! snippets of [`crate::tools::crosscheck`], or one of the console commands
+//! below. The state of the chain (balances, block height, contracts) is kept
+//! between the inputs, the `n`th snippet is the contract `snippet-n`.
+//!
+//! - `::advance_chain_tip <count>` (or `::advance_stacks_chain_tip`) mines
+//!   `count` blocks.
+//! - `::set_epoch <epoch>` sets the epoch, only before the first input.
+//! - `::set_clarity_version <version>` sets the Clarity version, only before
+//!   the first input.
+//!
+//! The output of a snippet is its value, printed like the console does, or
+//! nothing for a snippet without value. An input whose expected output is
+//! empty is only checked for divergences between the two tools.
+
+use clarity::types::StacksEpochId;
+use clarity::vm::errors::Error;
+use clarity::vm::{ClarityVersion, Value};
+
+use crate::tools::TestEnvironment;
+
+/// The error of the first input of a session which failed.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum SessionError {
+    #[error("line {line}: {message}")]
+    Parse { line: usize, message: String },
+    #[error("line {line}: unsupported command `{command}`")]
+    UnsupportedCommand { line: usize, command: String },
+    #[error("line {line}: `{input}` diverges\ncompiled: {compiled}\ninterpreted: {interpreted}")]
+    Divergence {
+        line: usize,
+        input: String,
+        compiled: String,
+        interpreted: String,
+    },
+    #[error("line {line}: `{input}` printed\n{actual}\ninstead of\n{expected}")]
+    UnexpectedOutput {
+        line: usize,
+        input: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// An input of a session, with its expected output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionStep {
+    /// The line of the input in the session.
+    pub line: usize,
+    pub input: String,
+    /// The expected output, empty if it is not checked.
+    pub expected: String,
+}
+
+/// Splits `session` in its inputs.
+pub fn parse_session(session: &str) -> Result<Vec<SessionStep>, SessionError> {
+    let mut steps: Vec<SessionStep> = Vec::new();
+    for (index, line) in session.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim_start().starts_with(";;") {
+            continue;
+        }
+        if let Some(input) = line.strip_prefix(">>") {
+            steps.push(SessionStep {
+                line: line_number,
+                input: input.trim().to_owned(),
+                expected: String::new(),
+            });
+            continue;
+        }
+        match steps.last_mut() {
+            Some(step) => {
+                if !step.expected.is_empty() {
+                    step.expected.push('\n');
+                }
+                step.expected.push_str(line.trim_end());
+            }
+            None if line.trim().is_empty() => {}
+            None => {
+                return Err(SessionError::Parse {
+                    line: line_number,
+                    message: "output before the first input".to_owned(),
+                })
+            }
+        }
+    }
+    for step in &mut steps {
+        step.expected = step.expected.trim().to_owned();
+    }
+    Ok(steps)
+}
+
+/// A session running each input on an environment with compiled contracts,
+/// and another one with interpreted contracts.
+struct Session {
+    compiled: TestEnvironment,
+    interpreted: TestEnvironment,
+    epoch: StacksEpochId,
+    version: ClarityVersion,
+    /// Number of snippets deployed so far.
+    snippets: usize,
+    started: bool,
+}
+
+impl Session {
+    fn new(epoch: StacksEpochId, version: ClarityVersion) -> Self {
+        Self {
+            compiled: TestEnvironment::new(epoch, version),
+            interpreted: TestEnvironment::new(epoch, version),
+            epoch,
+            version,
+            snippets: 0,
+            started: false,
+        }
+    }
+
+    /// Runs the input of `step`, and returns its output.
+    fn run(&mut self, step: &SessionStep) -> Result<String, SessionError> {
+        let Some(command) = step.input.strip_prefix("::") else {
+            self.started = true;
+            return self.evaluate(step);
+        };
+
+        let parse_error = |message: String| SessionError::Parse {
+            line: step.line,
+            message,
+        };
+        let (name, argument) = command
+            .split_once(char::is_whitespace)
+            .map_or((command, ""), |(name, argument)| (name, argument.trim()));
+        match name {
+            "advance_chain_tip" | "advance_stacks_chain_tip" => {
+                let count: u32 = argument
+                    .parse()
+                    .map_err(|_| parse_error(format!("invalid block count `{argument}`")))?;
+                self.started = true;
+                self.interpreted.advance_chain_tip(count);
+                let height = self.compiled.advance_chain_tip(count);
+                Ok(format!("{count} blocks simulated, new height: {height}"))
+            }
+            "set_epoch" | "set_clarity_version" if self.started => Err(parse_error(format!(
+                "`::{name}` must come before the first input"
+            ))),
+            "set_epoch" => {
+                self.epoch = parse_epoch(argument)
+                    .ok_or_else(|| parse_error(format!("invalid epoch `{argument}`")))?;
+                *self = Self::new(self.epoch, self.version);
+                Ok(format!("Epoch updated to: {argument}"))
+            }
+            "set_clarity_version" => {
+                self.version = parse_clarity_version(argument)
+                    .ok_or_else(|| parse_error(format!("invalid Clarity version `{argument}`")))?;
+                *self = Self::new(self.epoch, self.version);
+                Ok(format!("Clarity version updated to: {argument}"))
+            }
+            _ => Err(SessionError::UnsupportedCommand {
+                line: step.line,
+                command: step.input.clone(),
+            }),
+        }
+    }
+
+    fn evaluate(&mut self, step: &SessionStep) -> Result<String, SessionError> {
+        self.snippets += 1;
+        let name = format!("snippet-{}", self.snippets);
+        let interpreted = self
+            .interpreted
+            .interpret_contract_with_snippet(&name, &step.input);
+        let compiled = self.compiled.init_contract_with_snippet(&name, &step.input);
+        if compiled != interpreted {
+            return Err(SessionError::Divergence {
+                line: step.line,
+                input: step.input.clone(),
+                compiled: format!("{compiled:?}"),
+                interpreted: format!("{interpreted:?}"),
+            });
+        }
+        Ok(format_output(&compiled))
+    }
+}
+
+/// Formats the result of a snippet like the console.
+fn format_output(result: &Result<Option<Value>, Error>) -> String {
+    match result {
+        Ok(Some(value)) => value.to_string(),
+        Ok(None) => String::new(),
+        Err(e) => e.to_string(),
+    }
+}
+
+fn parse_epoch(epoch: &str) -> Option<StacksEpochId> {
+    Some(match epoch {
+        "2.0" => StacksEpochId::Epoch20,
+        "2.05" => StacksEpochId::Epoch2_05,
+        "2.1" => StacksEpochId::Epoch21,
+        "2.2" => StacksEpochId::Epoch22,
+        "2.3" => StacksEpochId::Epoch23,
+        "2.4" => StacksEpochId::Epoch24,
+        "2.5" => StacksEpochId::Epoch25,
+        "3.0" => StacksEpochId::Epoch30,
+        _ => return None,
+    })
+}
+
+fn parse_clarity_version(version: &str) -> Option<ClarityVersion> {
+    Some(match version {
+        "1" => ClarityVersion::Clarity1,
+        "2" => ClarityVersion::Clarity2,
+        "3" => ClarityVersion::Clarity3,
+        _ => return None,
+    })
+}
+
+/// Runs `session` with the compiler and the interpreter, starting at `epoch`
+/// with the Clarity version `version`, and returns the error of the first
+/// input whose results diverge or whose output is not the expected one.
+pub fn run_session(
+    session: &str,
+    epoch: StacksEpochId,
+    version: ClarityVersion,
+) -> Result<(), SessionError> {
+    let mut state = Session::new(epoch, version);
+    for step in parse_session(session)? {
+        let actual = state.run(&step)?;
+        if !step.expected.is_empty() && actual != step.expected {
+            return Err(SessionError::UnexpectedOutput {
+                line: step.line,
+                input: step.input,
+                expected: step.expected,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::TestConfig;
+
+    fn run(session: &str) -> Result<(), SessionError> {
+        run_session(session, TestConfig::epoch(), TestConfig::clarity_version())
+    }
+
+    #[test]
+    fn parse_inputs_and_outputs() {
+        let steps = parse_session(
+            ";; a session
+>> (+ 1 2)
+3
+>> ::advance_chain_tip 2
+
+>> (list 1 2)
+(1 2)
+",
+        )
+        .unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                SessionStep {
+                    line: 2,
+                    input: "(+ 1 2)".to_owned(),
+                    expected: "3".to_owned(),
+                },
+                SessionStep {
+                    line: 4,
+                    input: "::advance_chain_tip 2".to_owned(),
+                    expected: String::new(),
+                },
+                SessionStep {
+                    line: 6,
+                    input: "(list 1 2)".to_owned(),
+                    expected: "(1 2)".to_owned(),
+                },
+            ]
+        );
+
+        assert_eq!(
+            parse_session("3\n>> (+ 1 2)"),
+            Err(SessionError::Parse {
+                line: 1,
+                message: "output before the first input".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn session_with_expected_outputs() {
+        run(">> (+ 1 2)
+3
+>> (ok u1)
+(ok u1)
+>> (define-public (get-one) (ok 1))
+>> (contract-call? .snippet-3 get-one)
+(ok 1)
+>> ::advance_chain_tip 3
+3 blocks simulated, new height: 3
+>> (some 0x0102)
+(some 0x0102)
+")
+        .unwrap();
+    }
+
+    #[test]
+    fn session_with_unexpected_output() {
+        assert_eq!(
+            run(">> (+ 1 2)\n4"),
+            Err(SessionError::UnexpectedOutput {
+                line: 1,
+                input: "(+ 1 2)".to_owned(),
+                expected: "4".to_owned(),
+                actual: "3".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn session_commands() {
+        run(">> ::set_epoch 2.1
+>> ::set_clarity_version 1
+>> (+ 1 2)
+3
+")
+        .unwrap();
+
+        assert!(matches!(
+            run(">> (+ 1 2)\n>> ::set_epoch 2.1"),
+            Err(SessionError::Parse { line: 2, .. })
+        ));
+        assert_eq!(
+            run(">> ::set_tx_sender ST000000000000000000002AMW42H"),
+            Err(SessionError::UnsupportedCommand {
+                line: 1,
+                command: "::set_tx_sender ST000000000000000000002AMW42H".to_owned(),
+            })
+        );
+    }
+}
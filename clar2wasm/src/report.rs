@@ -0,0 +1,149 @@
+//! Report of a compiled contract, so that a pipeline can archive a single
+//! artifact per contract.
+//!
+//! The report gathers the cost of the parsing and the analysis, the
+//! diagnostics, the statistics of the module and the interface of the
+//! contract. It is displayed as a summary, and serialized as JSON with
+//! [`CompileReport::to_json`].
+
+use std::fmt;
+
+use clarity::vm::analysis::contract_interface_builder::{
+    build_contract_interface, ContractInterface,
+};
+use clarity::vm::costs::ExecutionCost;
+use clarity::vm::diagnostic::Diagnostic;
+use serde::Serialize;
+
+use crate::sections::ABI_VERSION;
+use crate::statistics::ModuleStatistics;
+use crate::CompileResult;
+
+/// Report of a compiled contract, see [`crate::CompileResult::report`].
+#[derive(Debug, Serialize)]
+pub struct CompileReport {
+    /// Identifier of the contract.
+    pub contract: String,
+    /// Version of the ABI between the module and its host, see
+    /// [`crate::sections::ABI_VERSION`].
+    pub abi_version: u32,
+    /// Cost of the parsing and the analysis of the contract, zero with a
+    /// free cost tracker.
+    pub cost: ExecutionCost,
+    pub diagnostics: Vec<Diagnostic>,
+    pub statistics: ModuleStatistics,
+    /// Functions, variables, maps and tokens of the contract, in the format
+    /// of the contract interfaces of the Stacks node.
+    pub interface: Option<ContractInterface>,
+}
+
+impl CompileReport {
+    /// Returns the report of `result`.
+    pub(crate) fn new(result: &mut CompileResult) -> Self {
+        CompileReport {
+            contract: result.contract_analysis.contract_identifier.to_string(),
+            abi_version: ABI_VERSION,
+            cost: result
+                .contract_analysis
+                .cost_track
+                .as_ref()
+                .map(|cost_track| cost_track.get_total())
+                .unwrap_or_else(ExecutionCost::zero),
+            diagnostics: result.diagnostics.clone(),
+            statistics: result.statistics(),
+            interface: build_contract_interface(&result.contract_analysis).ok(),
+        }
+    }
+
+    /// Serializes the report as JSON.
+    #[allow(clippy::expect_used)]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("the report is serializable")
+    }
+}
+
+impl fmt::Display for CompileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<22}{}", "contract", self.contract)?;
+        writeln!(f, "{:<22}{}", "abi version", self.abi_version)?;
+        writeln!(f, "{:<22}{}", "runtime cost", self.cost.runtime)?;
+        writeln!(
+            f,
+            "{:<22}{} ({} bytes)",
+            "reads", self.cost.read_count, self.cost.read_length
+        )?;
+        writeln!(
+            f,
+            "{:<22}{} ({} bytes)",
+            "writes", self.cost.write_count, self.cost.write_length
+        )?;
+        writeln!(f, "{:<22}{}", "module bytes", self.statistics.module_bytes)?;
+        writeln!(f, "{:<22}{}", "functions", self.statistics.functions)?;
+        writeln!(
+            f,
+            "{:<22}{} ({:.0}% library)",
+            "instructions",
+            self.statistics.instructions,
+            self.statistics.library_share() * 100.0
+        )?;
+        writeln!(f, "{:<22}{}", "data bytes", self.statistics.data_bytes)?;
+        if let Some((name, instructions)) = &self.statistics.largest_function {
+            writeln!(f, "{:<22}{name} ({instructions})", "largest function")?;
+        }
+        for diagnostic in &self.diagnostics {
+            writeln!(f, "{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+
+    use crate::sections::ABI_VERSION;
+    use crate::{compile, CompileOptions};
+
+    const CONTRACT: &str = r#"(define-map names principal (string-ascii 16))
+        (define-read-only (get-name (who principal)) (map-get? names who))
+        (define-public (set-name (name (string-ascii 16)))
+            (ok (map-set names tx-sender name)))"#;
+
+    #[test]
+    fn report_as_json() {
+        let report = compile(
+            CONTRACT,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .expect("failed to compile contract")
+        .report();
+        let json: serde_json::Value =
+            serde_json::from_str(&report.to_json()).expect("invalid JSON");
+
+        assert_eq!(json["abi_version"], ABI_VERSION);
+        assert_eq!(json["cost"]["runtime"], 0);
+        assert_eq!(
+            json["statistics"]["module_bytes"],
+            report.statistics.module_bytes
+        );
+        let functions = json["interface"]["functions"]
+            .as_array()
+            .expect("missing functions");
+        assert!(functions.iter().any(|f| f["name"] == "get-name"), "{json}");
+        assert!(functions.iter().any(|f| f["name"] == "set-name"), "{json}");
+
+        let summary = report.to_string();
+        assert!(summary.contains("runtime cost"), "{summary}");
+        assert!(summary.contains("largest function"), "{summary}");
+    }
+}
@@ -0,0 +1,241 @@
+//! Custom sections added by clar2wasm to the modules it generates.
+//!
+//! The sections are named with the `clar2wasm.` prefix. walrus keeps the
+//! custom sections it doesn't know about, so a module loaded with
+//! [`crate::CompileResult::deserialize`] can be transformed and re-emitted
+//! without losing them.
+
+use clarity::types::StacksEpochId;
+use clarity::vm::ClarityVersion;
+use walrus::{IdsToIndices, Module, RawCustomSection};
+
+/// Version of the interface between the generated modules and the host.
+/// It must be increased on any change to the imports, exports or memory
+/// layout expected by the host.
+pub const ABI_VERSION: u32 = 1;
+
+/// Name of the section holding the [`ABI_VERSION`] the module was generated
+/// for, as a little-endian `u32`.
+pub const ABI_VERSION_SECTION: &str = "clar2wasm.abi-version";
+
+/// Name of the section holding the epoch and the Clarity version the module
+/// was generated for, as two little-endian `u32`s.
+pub const TARGET_SECTION: &str = "clar2wasm.target";
+
+/// The Clarity versions, by their number in the [`TARGET_SECTION`].
+const CLARITY_VERSIONS: [ClarityVersion; 3] = [
+    ClarityVersion::Clarity1,
+    ClarityVersion::Clarity2,
+    ClarityVersion::Clarity3,
+];
+
+#[derive(Debug, thiserror::Error)]
+pub enum ModuleError {
+    #[error("invalid module: {0}")]
+    Parse(String),
+    #[error("missing custom section `{0}`")]
+    MissingSection(&'static str),
+    #[error("malformed custom section `{0}`")]
+    MalformedSection(&'static str),
+    #[error("unsupported ABI version {0}, expected {ABI_VERSION}")]
+    UnsupportedAbiVersion(u32),
+}
+
+pub(crate) fn add_abi_version(module: &mut Module) {
+    module.customs.add(RawCustomSection {
+        name: ABI_VERSION_SECTION.to_owned(),
+        data: ABI_VERSION.to_le_bytes().to_vec(),
+    });
+}
+
+/// Returns the ABI version recorded in `module`.
+pub fn abi_version(module: &Module) -> Result<u32, ModuleError> {
+    let data = section_data(module, ABI_VERSION_SECTION)?;
+    data.as_slice()
+        .try_into()
+        .map(u32::from_le_bytes)
+        .map_err(|_| ModuleError::MalformedSection(ABI_VERSION_SECTION))
+}
+
+pub(crate) fn add_target(module: &mut Module, epoch: StacksEpochId, version: ClarityVersion) {
+    let version = CLARITY_VERSIONS
+        .iter()
+        .position(|known| *known == version)
+        .map_or(0, |index| index as u32 + 1);
+    let mut data = (epoch as u32).to_le_bytes().to_vec();
+    data.extend(version.to_le_bytes());
+    module.customs.add(RawCustomSection {
+        name: TARGET_SECTION.to_owned(),
+        data,
+    });
+}
+
+/// Returns the epoch and the Clarity version `module` was generated for.
+pub fn target(module: &Module) -> Result<(StacksEpochId, ClarityVersion), ModuleError> {
+    let data = section_data(module, TARGET_SECTION)?;
+    let malformed = || ModuleError::MalformedSection(TARGET_SECTION);
+    let word = |bytes: &[u8]| {
+        bytes
+            .try_into()
+            .map(u32::from_le_bytes)
+            .map_err(|_| malformed())
+    };
+    if data.len() != 8 {
+        return Err(malformed());
+    }
+    let (epoch, version) = (word(&data[..4])?, word(&data[4..])?);
+    let epoch = StacksEpochId::try_from(epoch).map_err(|_| malformed())?;
+    let version = version
+        .checked_sub(1)
+        .and_then(|index| CLARITY_VERSIONS.get(index as usize))
+        .ok_or_else(malformed)?;
+    Ok((epoch, *version))
+}
+
+fn section_data(module: &Module, name: &'static str) -> Result<Vec<u8>, ModuleError> {
+    let (_, section) = module
+        .customs
+        .iter()
+        .find(|(_, section)| section.name() == name)
+        .ok_or(ModuleError::MissingSection(name))?;
+    Ok(section.data(&IdsToIndices::default()).into_owned())
+}
+
+/// Checks that the custom sections of `module` are those of a module
+/// generated by this version of clar2wasm.
+pub fn validate(module: &Module) -> Result<(), ModuleError> {
+    match abi_version(module)? {
+        ABI_VERSION => Ok(()),
+        version => Err(ModuleError::UnsupportedAbiVersion(version)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+
+    use super::*;
+    use crate::{compile, CompileOptions, CompileResult};
+
+    fn compile_snippet(source: &str) -> CompileResult {
+        compile(
+            source,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .expect("failed to compile contract")
+    }
+
+    fn set_abi_version(module: &mut Module, data: Vec<u8>) {
+        module.customs.remove_raw(ABI_VERSION_SECTION);
+        module.customs.add(RawCustomSection {
+            name: ABI_VERSION_SECTION.to_owned(),
+            data,
+        });
+    }
+
+    #[test]
+    fn custom_sections_round_trip() {
+        let bytes = compile_snippet("(define-read-only (foo) u1)").serialize();
+
+        let mut module = CompileResult::deserialize(&bytes).expect("failed to load module");
+        assert_eq!(abi_version(&module).unwrap(), ABI_VERSION);
+
+        // Re-emitting the module keeps the sections.
+        let module = CompileResult::deserialize(&module.emit_wasm()).unwrap();
+        assert_eq!(abi_version(&module).unwrap(), ABI_VERSION);
+    }
+
+    #[test]
+    fn missing_abi_version() {
+        let mut module = compile_snippet("(+ 1 2)").module;
+        module.customs.remove_raw(ABI_VERSION_SECTION);
+
+        assert!(matches!(
+            CompileResult::deserialize(&module.emit_wasm()),
+            Err(ModuleError::MissingSection(ABI_VERSION_SECTION))
+        ));
+    }
+
+    #[test]
+    fn invalid_abi_version() {
+        let mut module = compile_snippet("(+ 1 2)").module;
+
+        set_abi_version(&mut module, vec![1, 2]);
+        assert!(matches!(
+            CompileResult::deserialize(&module.emit_wasm()),
+            Err(ModuleError::MalformedSection(ABI_VERSION_SECTION))
+        ));
+
+        set_abi_version(&mut module, (ABI_VERSION + 1).to_le_bytes().to_vec());
+        assert!(matches!(
+            CompileResult::deserialize(&module.emit_wasm()),
+            Err(ModuleError::UnsupportedAbiVersion(v)) if v == ABI_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn target_round_trip() {
+        let module = compile_snippet("(define-read-only (foo) u1)").module;
+        assert_eq!(
+            target(&module).unwrap(),
+            (StacksEpochId::Epoch25, ClarityVersion::Clarity2)
+        );
+
+        let mut module = compile(
+            "(+ 1 2)",
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity1,
+            StacksEpochId::Epoch21,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .unwrap()
+        .module;
+        let module = CompileResult::deserialize(&module.emit_wasm()).unwrap();
+        assert_eq!(
+            target(&module).unwrap(),
+            (StacksEpochId::Epoch21, ClarityVersion::Clarity1)
+        );
+    }
+
+    #[test]
+    fn invalid_target() {
+        let mut module = compile_snippet("(+ 1 2)").module;
+        module.customs.remove_raw(TARGET_SECTION);
+        assert!(matches!(
+            target(&module),
+            Err(ModuleError::MissingSection(TARGET_SECTION))
+        ));
+
+        for data in [vec![1, 2], [u32::MAX, 2].map(u32::to_le_bytes).concat()] {
+            module.customs.remove_raw(TARGET_SECTION);
+            module.customs.add(RawCustomSection {
+                name: TARGET_SECTION.to_owned(),
+                data,
+            });
+            assert!(matches!(
+                target(&module),
+                Err(ModuleError::MalformedSection(TARGET_SECTION))
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_module() {
+        assert!(matches!(
+            CompileResult::deserialize(b"not wasm"),
+            Err(ModuleError::Parse(_))
+        ));
+    }
+}
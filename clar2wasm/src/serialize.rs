@@ -73,7 +73,7 @@ impl WasmGenerator {
         // Serialize the high to memory.
         builder
             .local_get(high)
-            .call(self.func_by_name("stdlib.store-i64-be"));
+            .call(self.func_by_name("stdlib.store-i64-be")?);
 
         // Adjust the write pointer
         builder
@@ -86,7 +86,7 @@ impl WasmGenerator {
         // Adjust the offset by 8, then serialize the low to memory.
         builder
             .local_get(low)
-            .call(self.func_by_name("stdlib.store-i64-be"));
+            .call(self.func_by_name("stdlib.store-i64-be")?);
         written += 8;
 
         // Push the written length onto the data stack
@@ -133,12 +133,13 @@ impl WasmGenerator {
         // Copy the standard principal part to the buffer, offset by 1
         // byte for the type prefix, which we will write next, so that
         // we don't need two branches.
+        let copy_memory = self.copy_memory()?;
         builder
             .i32_const(1)
             .binop(BinaryOp::I32Add)
             .local_get(poffset)
-            .i32_const(PRINCIPAL_BYTES as i32)
-            .memory_copy(memory, memory);
+            .i32_const(PRINCIPAL_BYTES as i32);
+        copy_memory.emit(builder);
 
         // If `plength` is greater than STANDARD_PRINCIPAL_BYTES, then
         // this is a contract principal, else, it's a standard
@@ -183,9 +184,9 @@ impl WasmGenerator {
                         // Compute the length
                         .local_get(plength)
                         .i32_const(STANDARD_PRINCIPAL_BYTES as i32)
-                        .binop(BinaryOp::I32Sub)
-                        // Copy the data
-                        .memory_copy(memory, memory);
+                        .binop(BinaryOp::I32Sub);
+                    // Copy the data
+                    copy_memory.emit(then);
 
                     // Push the total length written onto the data stack.
                     // It is the same as plength, plus 1 (the type prefix).
@@ -505,7 +506,7 @@ impl WasmGenerator {
                 .local_get(bytes_length)
                 .i32_const(element_size)
                 .binop(BinaryOp::I32DivU);
-            size_non_zero.call(self.func_by_name("stdlib.store-i32-be"));
+            size_non_zero.call(self.func_by_name("stdlib.store-i32-be")?);
 
             // Adjust the write pointer
             size_non_zero
@@ -610,7 +611,7 @@ impl WasmGenerator {
         // Serialize the length to memory (big endian)
         builder
             .local_get(length)
-            .call(self.func_by_name("stdlib.store-i32-be"));
+            .call(self.func_by_name("stdlib.store-i32-be")?);
 
         // Adjust the write pointer by 4
         builder
@@ -620,10 +621,8 @@ impl WasmGenerator {
             .local_tee(write_ptr);
 
         // Copy the buffer
-        builder
-            .local_get(read_ptr)
-            .local_get(length)
-            .memory_copy(memory, memory);
+        builder.local_get(read_ptr).local_get(length);
+        self.copy_memory()?.emit(builder);
 
         // Push the length written to the data stack:
         //  length    +    1    +    4
@@ -677,7 +676,7 @@ impl WasmGenerator {
         // Serialize the length to memory (big endian)
         builder
             .local_get(length)
-            .call(self.func_by_name("stdlib.store-i32-be"));
+            .call(self.func_by_name("stdlib.store-i32-be")?);
 
         // Adjust the write pointer by 4
         builder
@@ -687,10 +686,8 @@ impl WasmGenerator {
             .local_tee(write_ptr);
 
         // Copy the string
-        builder
-            .local_get(read_ptr)
-            .local_get(length)
-            .memory_copy(memory, memory);
+        builder.local_get(read_ptr).local_get(length);
+        self.copy_memory()?.emit(builder);
 
         // Push the length written to the data stack:
         //  length    +    1    +    4
@@ -750,7 +747,7 @@ impl WasmGenerator {
 
         // Call scalar to utf8 conversion function
         builder
-            .call(self.func_by_name("stdlib.convert-scalars-to-utf8"))
+            .call(self.func_by_name("stdlib.convert-scalars-to-utf8")?)
             .local_tee(utf8_length);
 
         // Serialize the length to memory (big endian)
@@ -759,7 +756,7 @@ impl WasmGenerator {
             .i32_const(offset as i32 + 1)
             .binop(BinaryOp::I32Add)
             .local_get(utf8_length)
-            .call(self.func_by_name("stdlib.store-i32-be"));
+            .call(self.func_by_name("stdlib.store-i32-be")?);
 
         // Push the length written to the data stack, the length of the serialized string is already on the stack
         //  length    +    1    +    4
@@ -817,7 +814,7 @@ impl WasmGenerator {
         // Serialize the length of the data map to memory (big endian)
         builder
             .i32_const(tuple_ty.get_type_map().len() as i32)
-            .call(self.func_by_name("stdlib.store-i32-be"));
+            .call(self.func_by_name("stdlib.store-i32-be")?);
 
         // Adjust the write pointer by 4
         builder
@@ -847,10 +844,8 @@ impl WasmGenerator {
 
             // Serialize the key name
             let (offset, length) = self.add_string_literal(key)?;
-            builder
-                .i32_const(offset as i32)
-                .i32_const(length as i32)
-                .memory_copy(memory, memory);
+            builder.i32_const(offset as i32).i32_const(length as i32);
+            self.copy_memory()?.emit(builder);
 
             // Adjust the write pointer
             builder
@@ -0,0 +1,94 @@
+//! Standalone modules, which can run outside of a Stacks host.
+//!
+//! A standalone module has no imports: each host function is replaced by a
+//! stub returning zeros, which the standard library reads as the default
+//! value of the expected type. The contract code can then be run in any Wasm
+//! runtime, for static analysis or fuzzing. The results of the host functions
+//! are meaningless, so a standalone module must never be deployed.
+
+use walrus::ir::Value;
+use walrus::{FunctionBuilder, FunctionKind, ImportKind, Module, ValType};
+
+/// Replaces all the function imports of `module` with stubs.
+pub(crate) fn stub_host_imports(module: &mut Module) {
+    let imports: Vec<_> = module
+        .imports
+        .iter()
+        .filter_map(|import| match import.kind {
+            ImportKind::Function(func) => Some((import.id(), func)),
+            _ => None,
+        })
+        .collect();
+
+    for (import, func) in imports {
+        let ty = module.types.get(module.funcs.get(func).ty());
+        let (params, results) = (ty.params().to_vec(), ty.results().to_vec());
+
+        let args = params.iter().map(|ty| module.locals.add(*ty)).collect();
+        let mut stub = FunctionBuilder::new(&mut module.types, &params, &results);
+        let mut body = stub.func_body();
+        for result in &results {
+            match result {
+                ValType::I32 => body.const_(Value::I32(0)),
+                ValType::I64 => body.const_(Value::I64(0)),
+                ValType::F32 => body.const_(Value::F32(0.0)),
+                ValType::F64 => body.const_(Value::F64(0.0)),
+                ValType::V128 => body.const_(Value::V128(0)),
+                ValType::Externref | ValType::Funcref => body.ref_null(*result),
+            };
+        }
+
+        module.funcs.get_mut(func).kind = FunctionKind::Local(stub.local_func(args));
+        module.imports.delete(import);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+    use wasmtime::{Engine, Instance, Store, Val};
+
+    use crate::{compile, CompileOptions};
+
+    fn compile_standalone(source: &str) -> walrus::Module {
+        compile(
+            source,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default().standalone(true),
+        )
+        .expect("failed to compile contract")
+        .module
+    }
+
+    #[test]
+    fn standalone_module_has_no_imports() {
+        let module = compile_standalone("(define-data-var foo int 42) (var-get foo)");
+        assert_eq!(module.imports.iter().count(), 0);
+    }
+
+    #[test]
+    fn standalone_module_runs_without_host() {
+        let mut module =
+            compile_standalone("(define-data-var foo int 42) (print (var-get foo)) (+ 1 2)");
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = wasmtime::Module::from_binary(&engine, &module.emit_wasm()).unwrap();
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+
+        let top_level = instance.get_func(&mut store, ".top-level").unwrap();
+        let mut results = [Val::I64(0), Val::I64(0)];
+        top_level.call(&mut store, &[], &mut results).unwrap();
+        assert_eq!(results[0].unwrap_i64(), 3);
+        assert_eq!(results[1].unwrap_i64(), 0);
+    }
+}
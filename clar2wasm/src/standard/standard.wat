@@ -171,6 +171,18 @@
                                                                        (param $height_hi i64)
                                                                        (param $return_offset i32)
                                                                        (param $return_length i32)))
+    (import "clarity" "get_stacks_block_info" (func $stdlib.get_stacks_block_info (param $name_offset i32)
+                                                                           (param $name_length i32)
+                                                                           (param $height_lo i64)
+                                                                           (param $height_hi i64)
+                                                                           (param $return_offset i32)
+                                                                           (param $return_length i32)))
+    (import "clarity" "get_tenure_info" (func $stdlib.get_tenure_info (param $name_offset i32)
+                                                               (param $name_length i32)
+                                                               (param $height_lo i64)
+                                                               (param $height_hi i64)
+                                                               (param $return_offset i32)
+                                                               (param $return_length i32)))
     (import "clarity" "contract_call" (func $stdlib.contract_call (param $trait_name_offset i32)
                                                            (param $trait_name_len i32)
                                                            (param $contract_id_offset i32)
@@ -264,6 +276,9 @@
     (global $runtime-error-value-offset (mut i32) (i32.const -1))
     (global $runtime-error-type-ser-offset (mut i32) (i32.const -1))
     (global $runtime-error-type-ser-len (mut i32) (i32.const -1))
+    ;; Argument counts of an argument count mismatch
+    (global $runtime-error-expected-args (mut i32) (i32.const -1))
+    (global $runtime-error-actual-args (mut i32) (i32.const -1))
 
     ;; (sha256) initial hash values: first 32 bits of the fractional parts of the square roots of the first 8 primes 2..19
     (data (i32.const 0) "\67\e6\09\6a\85\ae\67\bb\72\f3\6e\3c\3a\f5\4f\a5\7f\52\0e\51\8c\68\05\9b\ab\d9\83\1f\19\cd\e0\5b")
@@ -1462,6 +1477,10 @@
         )
     )
 
+    ;; The hash routines are in two variants: the default one only uses scalar
+    ;; instructions, the `-simd` one swaps the bytes of the message and of the
+    ;; hash with `v128` instructions, and is called instead when the target
+    ;; allows SIMD.
     (func $stdlib.sha256-buf (param $offset i32) (param $length i32) (param $offset-result i32) (result i32 i32)
         (local $i i32)
         ;; see this for an explanation: https://sha256algorithm.com/
@@ -1471,7 +1490,8 @@
 
         (local.set $i (i32.const 0))
         (loop
-            (call $block64 (local.get $i))
+            (call $load-block (local.get $i))
+            (call $block64)
             (call $working-vars)
             (br_if 0
                 (i32.lt_u
@@ -1481,26 +1501,52 @@
             )
         )
 
-        ;; store at result position with correct endianness
-        (v128.store
-            (local.get $offset-result)
-            (i8x16.swizzle
-                (v128.load (global.get $stack-pointer))
-                (v128.const i8x16 3 2 1 0 7 6 5 4 11 10 9 8 15 14 13 12)
-            )
-        )
-        (v128.store offset=16
-            (local.get $offset-result)
-            (i8x16.swizzle
-                (v128.load offset=16 (global.get $stack-pointer))
-                (v128.const i8x16 3 2 1 0 7 6 5 4 11 10 9 8 15 14 13 12)
+        (call $store-hash256 (local.get $offset-result))
+        (local.get $offset-result) (i32.const 32)
+    )
+
+    (func $stdlib.sha256-buf-simd (param $offset i32) (param $length i32) (param $offset-result i32) (result i32 i32)
+        (local $i i32)
+
+        (call $extend-data (local.get $offset) (local.get $length))
+        (local.set $length)
+
+        (local.set $i (i32.const 0))
+        (loop
+            (call $load-block-simd (local.get $i))
+            (call $block64)
+            (call $working-vars)
+            (br_if 0
+                (i32.lt_u
+                    (local.tee $i (i32.add (local.get $i) (i32.const 64)))
+                    (local.get $length)
+                )
             )
         )
 
+        (call $store-hash256-simd (local.get $offset-result))
         (local.get $offset-result) (i32.const 32)
     )
 
     (func $stdlib.sha256-int (param $lo i64) (param $hi i64) (param $offset-result i32) (result i32 i32)
+        (call $extend-int (local.get $lo) (local.get $hi))
+        (call $load-block (i32.const 0))
+        (call $block64)
+        (call $working-vars)
+        (call $store-hash256 (local.get $offset-result))
+        (local.get $offset-result) (i32.const 32)
+    )
+
+    (func $stdlib.sha256-int-simd (param $lo i64) (param $hi i64) (param $offset-result i32) (result i32 i32)
+        (call $extend-int (local.get $lo) (local.get $hi))
+        (call $load-block-simd (i32.const 0))
+        (call $block64)
+        (call $working-vars)
+        (call $store-hash256-simd (local.get $offset-result))
+        (local.get $offset-result) (i32.const 32)
+    )
+
+    (func $extend-int (param $lo i64) (param $hi i64)
         ;; Copy data to the working stack, so that it has this relative configuration:
         ;;   0..32 -> Initial hash vals (will be the result hash in the end)
         ;;   32..288 -> Space to store W
@@ -1512,10 +1558,28 @@
         (i32.store offset=304 (global.get $stack-pointer) (i32.const 0x80)) ;; offset = 288+16
         (memory.fill (i32.add (global.get $stack-pointer) (i32.const 308)) (i32.const 0) (i32.const 46)) ;; offset = 288+20
         (i32.store8 offset=351 (global.get $stack-pointer) (i32.const 0x80)) ;; offset = 288+63
+    )
 
-        (call $block64 (i32.const 0))
-        (call $working-vars)
+    ;; Stores the hash, at the start of the working stack, at $offset-result
+    ;; with the correct endianness.
+    (func $store-hash256 (param $offset-result i32)
+        (local $i i32)
+        (local.set $i (i32.const 0))
+        (loop
+            (call $stdlib.store-i32-be
+                (i32.add (local.get $offset-result) (local.get $i))
+                (i32.load (i32.add (global.get $stack-pointer) (local.get $i)))
+            )
+            (br_if 0
+                (i32.lt_u
+                    (local.tee $i (i32.add (local.get $i) (i32.const 4)))
+                    (i32.const 32)
+                )
+            )
+        )
+    )
 
+    (func $store-hash256-simd (param $offset-result i32)
         (v128.store
             (local.get $offset-result)
             (i8x16.swizzle
@@ -1530,8 +1594,6 @@
                 (v128.const i8x16 3 2 1 0 7 6 5 4 11 10 9 8 15 14 13 12)
             )
         )
-
-        (local.get $offset-result) (i32.const 32)
     )
 
     (func $extend-data (param $offset i32) (param $length i32) (result i32)
@@ -1598,15 +1660,37 @@
         (local.get $res_len)
     )
 
-    (func $block64 (param $data i32)
-        (local $origin i32)
-        (local $i i32) (local $tmp i32)
+    ;; Copies the 64 bytes of data at $data to the start of W, as i32 with
+    ;; endianness adjustment.
+    (func $load-block (param $data i32)
+        (local $origin i32) (local $i i32)
+
+        (local.set $origin (global.get $stack-pointer))
+        (local.set $data (i32.add (local.get $origin) (local.get $data)))
+
+        (local.set $i (i32.const 0))
+        (loop
+            (i32.store offset=32
+                (i32.add (local.get $origin) (local.get $i))
+                (call $stdlib.load-i32-be
+                    (i32.add (i32.add (local.get $data) (local.get $i)) (i32.const 288)))
+            )
+            (br_if 0
+                (i32.lt_u
+                    (local.tee $i (i32.add (local.get $i) (i32.const 4)))
+                    (i32.const 64)
+                )
+            )
+        )
+    )
+
+    (func $load-block-simd (param $data i32)
+        (local $origin i32) (local $i i32)
 
         (local.set $origin (global.get $stack-pointer))
         (local.set $data (i32.add (local.get $origin) (local.get $data)))
 
         (local.set $i (i32.const 0))
-        ;; copy first 64 bytes of data to offset as i32 with endianness adjustment
         ;; Using v128 to process more bytes at a time
         ;; TODO? : unroll this loop, since it's one instruction 4 times?
         (loop
@@ -1624,6 +1708,15 @@
                 )
             )
         )
+    )
+
+    ;; Extends the 16 words at the start of W, loaded by $load-block, to the
+    ;; 64 words of the message schedule.
+    (func $block64
+        (local $origin i32) (local $data i32)
+        (local $i i32) (local $tmp i32)
+
+        (local.set $origin (global.get $stack-pointer))
 
         (local.set $i (i32.const 0))
         (loop
@@ -2077,6 +2170,30 @@
         (i32.eqz (local.get $length_a))
     )
 
+    ;; SIMD variant of `is-eq-bytes`, called instead of it when the target
+    ;; allows SIMD: compares 16 bytes at a time, then the remaining bytes with
+    ;; `is-eq-bytes`.
+    (func $stdlib.is-eq-bytes-simd (param $offset_a i32) (param $length_a i32) (param $offset_b i32) (param $length_b i32) (result i32)
+        (if (i32.ne (local.get $length_a) (local.get $length_b)) (then (return (i32.const 0))))
+
+        (block $tail
+            (loop $chunks
+                (br_if $tail (i32.lt_u (local.get $length_a) (i32.const 16)))
+                (if (v128.any_true
+                        (v128.xor (v128.load (local.get $offset_a)) (v128.load (local.get $offset_b))))
+                    (then (return (i32.const 0)))
+                )
+                (local.set $offset_a (i32.add (local.get $offset_a) (i32.const 16)))
+                (local.set $offset_b (i32.add (local.get $offset_b) (i32.const 16)))
+                (local.set $length_a (i32.sub (local.get $length_a) (i32.const 16)))
+                (br $chunks)
+            )
+        )
+        (call $stdlib.is-eq-bytes
+            (local.get $offset_a) (local.get $length_a)
+            (local.get $offset_b) (local.get $length_a))
+    )
+
     ;;
     ;; `(principal-construct? version pkhash)` implementation
     ;; `version` is a `(buff 1)` and `pkhash` is a `(buff 20)`.
@@ -3893,6 +4010,8 @@
     (export "runtime-error-value-offset" (global $runtime-error-value-offset))
     (export "runtime-error-type-ser-offset" (global $runtime-error-type-ser-offset))
     (export "runtime-error-type-ser-len" (global $runtime-error-type-ser-len))
+    (export "runtime-error-expected-args" (global $runtime-error-expected-args))
+    (export "runtime-error-actual-args" (global $runtime-error-actual-args))
 
     ;; Functions
     (export "stdlib.add-uint" (func $stdlib.add-uint))
@@ -3937,6 +4056,8 @@
     (export "stdlib.pow-int" (func $stdlib.pow-int))
     (export "stdlib.sha256-buf" (func $stdlib.sha256-buf))
     (export "stdlib.sha256-int" (func $stdlib.sha256-int))
+    (export "stdlib.sha256-buf-simd" (func $stdlib.sha256-buf-simd))
+    (export "stdlib.sha256-int-simd" (func $stdlib.sha256-int-simd))
     (export "stdlib.hash160-buf" (func $stdlib.hash160-buf))
     (export "stdlib.hash160-int" (func $stdlib.hash160-int))
     (export "stdlib.store-i32-be" (func $stdlib.store-i32-be))
@@ -3947,6 +4068,7 @@
     (export "stdlib.not" (func $stdlib.not))
     (export "stdlib.is-eq-int" (func $stdlib.is-eq-int))
     (export "stdlib.is-eq-bytes" (func $stdlib.is-eq-bytes))
+    (export "stdlib.is-eq-bytes-simd" (func $stdlib.is-eq-bytes-simd))
     (export "stdlib.principal-construct" (func $stdlib.principal-construct))
     (export "stdlib.is-valid-contract-name" (func $stdlib.is-valid-contract-name))
     (export "stdlib.is-alpha" (func $stdlib.is-alpha))
@@ -0,0 +1,226 @@
+//! Statistics about the size of a compiled module, so that contract authors
+//! can track its growth across versions of the contract or of the compiler.
+//!
+//! The code of a module is split between the contract, its functions and its
+//! top-level expressions, and the library: the standard library and the
+//! helpers generated for some words.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use clarity::vm::ClarityName;
+use serde::Serialize;
+use walrus::ir::Instr;
+use walrus::{ExportItem, FunctionKind, InstrSeqId, LocalFunction, Module};
+
+use crate::ContractFunction;
+
+/// Size of a compiled module, see [`crate::CompileResult::statistics`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModuleStatistics {
+    /// Size of the binary of the module.
+    pub module_bytes: usize,
+    /// Number of functions defined in the module, without the imports.
+    pub functions: usize,
+    /// Number of instructions of all the functions.
+    pub instructions: usize,
+    /// Number of instructions of the functions of the library.
+    pub library_instructions: usize,
+    /// Name and number of instructions of the largest function of the
+    /// contract, if it has any code.
+    pub largest_function: Option<(String, usize)>,
+    /// Size of the data segments, which hold the literal memory.
+    pub data_bytes: usize,
+}
+
+impl ModuleStatistics {
+    /// Share of the instructions of the module which belong to the library.
+    pub fn library_share(&self) -> f64 {
+        if self.instructions == 0 {
+            0.0
+        } else {
+            self.library_instructions as f64 / self.instructions as f64
+        }
+    }
+}
+
+/// Returns the statistics of `module`, whose contract functions are
+/// `functions`.
+pub(crate) fn module_statistics(
+    module: &mut Module,
+    functions: &BTreeMap<ClarityName, ContractFunction>,
+) -> ModuleStatistics {
+    let mut contract_functions: HashMap<_, _> = functions
+        .iter()
+        .map(|(name, function)| (function.id, name.to_string()))
+        .collect();
+    for export in module.exports.iter() {
+        if let (".top-level", ExportItem::Function(id)) = (export.name.as_str(), export.item) {
+            contract_functions.insert(id, export.name.clone());
+        }
+    }
+
+    let mut statistics = ModuleStatistics {
+        module_bytes: 0,
+        functions: 0,
+        instructions: 0,
+        library_instructions: 0,
+        largest_function: None,
+        data_bytes: module.data.iter().map(|data| data.value.len()).sum(),
+    };
+    for func in module.funcs.iter() {
+        let FunctionKind::Local(local) = &func.kind else {
+            continue;
+        };
+        let instructions = count_instructions(local, local.entry_block());
+        statistics.functions += 1;
+        statistics.instructions += instructions;
+
+        match contract_functions.get(&func.id()) {
+            Some(name) => {
+                if statistics
+                    .largest_function
+                    .as_ref()
+                    .map_or(true, |(_, largest)| instructions > *largest)
+                {
+                    statistics.largest_function = Some((name.clone(), instructions));
+                }
+            }
+            None => statistics.library_instructions += instructions,
+        }
+    }
+    statistics.module_bytes = module.emit_wasm().len();
+    statistics
+}
+
+/// Returns the number of instructions of the sequence `seq_id` of `func`,
+/// including the nested sequences.
+fn count_instructions(func: &LocalFunction, seq_id: InstrSeqId) -> usize {
+    func.block(seq_id)
+        .instrs
+        .iter()
+        .map(|(instr, _)| {
+            1 + match instr {
+                Instr::Block(block) => count_instructions(func, block.seq),
+                Instr::Loop(loop_) => count_instructions(func, loop_.seq),
+                Instr::IfElse(if_else) => {
+                    count_instructions(func, if_else.consequent)
+                        + count_instructions(func, if_else.alternative)
+                }
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+/// Comparison of the statistics of two versions of a module, see
+/// [`crate::CompileResult::compare_statistics`]. It is displayed as a table
+/// of the sizes before and after, with their difference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatisticsComparison {
+    pub before: ModuleStatistics,
+    pub after: ModuleStatistics,
+}
+
+impl fmt::Display for StatisticsComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows = [
+            (
+                "module bytes",
+                self.before.module_bytes,
+                self.after.module_bytes,
+            ),
+            ("functions", self.before.functions, self.after.functions),
+            (
+                "instructions",
+                self.before.instructions,
+                self.after.instructions,
+            ),
+            (
+                "library instructions",
+                self.before.library_instructions,
+                self.after.library_instructions,
+            ),
+            ("data bytes", self.before.data_bytes, self.after.data_bytes),
+        ];
+        for (name, before, after) in rows {
+            let difference = after as i64 - before as i64;
+            writeln!(f, "{name:<22}{before:>10}{after:>10}{difference:>+10}")?;
+        }
+
+        let largest = |statistics: &ModuleStatistics| match &statistics.largest_function {
+            Some((name, instructions)) => format!("{name} ({instructions})"),
+            None => "-".to_owned(),
+        };
+        writeln!(
+            f,
+            "{:<22}{} -> {}",
+            "largest function",
+            largest(&self.before),
+            largest(&self.after)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::ClarityVersion;
+
+    use crate::{compile, CompileOptions, CompileResult};
+
+    fn compile_snippet(snippet: &str) -> CompileResult {
+        compile(
+            snippet,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .expect("failed to compile contract")
+    }
+
+    #[test]
+    fn statistics_of_a_module() {
+        let mut result = compile_snippet(
+            r#"(define-private (small) 1)
+               (define-read-only (large (a int)) (if (> a 0) (* a (+ a 2) (- a 1)) (- a (* 3 a) 7)))
+               (define-constant GREETING "hello")"#,
+        );
+        let statistics = result.statistics();
+
+        assert_eq!(statistics.module_bytes, result.module.emit_wasm().len());
+        assert!(statistics.functions > 3);
+        assert!(statistics.data_bytes >= "hello".len());
+        assert_eq!(
+            statistics
+                .largest_function
+                .as_ref()
+                .map(|(name, _)| name.as_str()),
+            Some("large")
+        );
+        assert!(statistics.library_share() > 0.0 && statistics.library_share() < 1.0);
+    }
+
+    #[test]
+    fn compare_statistics() {
+        let mut before = compile_snippet("(define-read-only (f) 1)");
+        let mut after = compile_snippet("(define-read-only (f) (+ 1 (* 2 3)))");
+        let comparison = before.compare_statistics(&mut after);
+
+        assert!(comparison.after.instructions > comparison.before.instructions);
+        assert_eq!(
+            comparison.after.library_instructions,
+            comparison.before.library_instructions
+        );
+        let table = comparison.to_string();
+        assert!(table.contains("module bytes"), "{table}");
+        assert!(table.contains("largest function"), "{table}");
+    }
+}
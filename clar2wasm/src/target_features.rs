@@ -0,0 +1,242 @@
+//! The WebAssembly features a generated module may use, for the engines which
+//! don't implement all the proposals standardized after the MVP.
+//!
+//! By default, the modules may use all the [`Feature`]s. With a restricted
+//! [`TargetFeatures`] set, see [`crate::CompileOptions::target_features`],
+//! the code generation avoids the disabled features where it has an
+//! alternative, and the emitted module is validated against the set.
+
+use std::fmt;
+use std::str::FromStr;
+
+use wasmparser::{Validator, WasmFeatures};
+
+use crate::wasm_generator::GeneratorError;
+
+/// A WebAssembly proposal standardized after the MVP.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Feature {
+    /// The `extend8_s` and similar instructions.
+    SignExtension,
+    /// `memory.copy`, `memory.fill` and the passive data segments.
+    BulkMemory,
+    /// Functions and blocks with several results.
+    MultiValue,
+    /// Imports and exports of mutable globals.
+    MutableGlobal,
+    /// The `v128` type and its instructions.
+    Simd,
+    /// The `funcref` and `externref` values, and several tables.
+    ReferenceTypes,
+    /// The `trunc_sat` instructions.
+    SaturatingFloatToInt,
+}
+
+impl Feature {
+    pub const ALL: [Feature; 7] = [
+        Feature::SignExtension,
+        Feature::BulkMemory,
+        Feature::MultiValue,
+        Feature::MutableGlobal,
+        Feature::Simd,
+        Feature::ReferenceTypes,
+        Feature::SaturatingFloatToInt,
+    ];
+
+    /// The name of the feature, as in the feature detection of the engines.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::SignExtension => "sign-ext",
+            Feature::BulkMemory => "bulk-memory",
+            Feature::MultiValue => "multi-value",
+            Feature::MutableGlobal => "mutable-global",
+            Feature::Simd => "simd",
+            Feature::ReferenceTypes => "reference-types",
+            Feature::SaturatingFloatToInt => "nontrapping-fptoint",
+        }
+    }
+
+    fn wasm_features(self) -> WasmFeatures {
+        match self {
+            Feature::SignExtension => WasmFeatures::SIGN_EXTENSION,
+            Feature::BulkMemory => WasmFeatures::BULK_MEMORY,
+            Feature::MultiValue => WasmFeatures::MULTI_VALUE,
+            Feature::MutableGlobal => WasmFeatures::MUTABLE_GLOBAL,
+            Feature::Simd => WasmFeatures::SIMD,
+            Feature::ReferenceTypes => WasmFeatures::REFERENCE_TYPES,
+            Feature::SaturatingFloatToInt => WasmFeatures::SATURATING_FLOAT_TO_INT,
+        }
+    }
+
+    fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.name() == s)
+            .ok_or_else(|| format!("unknown WebAssembly feature `{s}`"))
+    }
+}
+
+/// The set of [`Feature`]s a generated module may use, on top of the MVP.
+///
+/// It is written as a comma-separated list of feature names, where `mvp`
+/// stands for the empty set and `all` for all the features, e.g.
+/// `mvp,multi-value,mutable-global`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TargetFeatures(u8);
+
+impl TargetFeatures {
+    /// Only the WebAssembly MVP.
+    pub const MVP: TargetFeatures = TargetFeatures(0);
+    /// All the [`Feature`]s, the default.
+    pub const ALL: TargetFeatures = TargetFeatures((1 << Feature::ALL.len()) - 1);
+
+    /// Whether the modules may use `feature`.
+    pub fn allows(self, feature: Feature) -> bool {
+        self.0 & feature.bit() != 0
+    }
+
+    /// The set with `feature` added.
+    pub fn with(self, feature: Feature) -> Self {
+        TargetFeatures(self.0 | feature.bit())
+    }
+
+    /// The set with `feature` removed.
+    pub fn without(self, feature: Feature) -> Self {
+        TargetFeatures(self.0 & !feature.bit())
+    }
+
+    /// The features of the set.
+    pub fn features(self) -> impl Iterator<Item = Feature> {
+        Feature::ALL
+            .into_iter()
+            .filter(move |feature| self.allows(*feature))
+    }
+
+    fn wasm_features(self) -> WasmFeatures {
+        self.features()
+            .fold(WasmFeatures::FLOATS, |features, feature| {
+                features | feature.wasm_features()
+            })
+    }
+
+    fn accepts(self, wasm: &[u8]) -> bool {
+        Validator::new_with_features(self.wasm_features())
+            .validate_all(wasm)
+            .is_ok()
+    }
+}
+
+impl Default for TargetFeatures {
+    fn default() -> Self {
+        TargetFeatures::ALL
+    }
+}
+
+impl fmt::Display for TargetFeatures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self == TargetFeatures::MVP {
+            return f.write_str("mvp");
+        }
+        let names: Vec<_> = self.features().map(Feature::name).collect();
+        f.write_str(&names.join(","))
+    }
+}
+
+impl FromStr for TargetFeatures {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .try_fold(TargetFeatures::MVP, |target, name| match name {
+                "mvp" => Ok(target),
+                "all" => Ok(TargetFeatures::ALL),
+                name => name.parse().map(|feature| target.with(feature)),
+            })
+    }
+}
+
+/// Validates the module `wasm` against the `target` features. A module using
+/// a feature outside of the set fails with
+/// [`GeneratorError::UnsupportedFeature`], naming the first such feature.
+pub fn validate(wasm: &[u8], target: TargetFeatures) -> Result<(), GeneratorError> {
+    let Err(error) = Validator::new_with_features(target.wasm_features()).validate_all(wasm) else {
+        return Ok(());
+    };
+    // A feature is used if the module is invalid with all the others.
+    let unsupported = Feature::ALL
+        .into_iter()
+        .filter(|feature| !target.allows(*feature))
+        .find(|feature| !TargetFeatures::ALL.without(*feature).accepts(wasm));
+    match unsupported {
+        Some(feature) => Err(GeneratorError::UnsupportedFeature {
+            feature,
+            message: error.to_string(),
+        }),
+        None => Err(GeneratorError::InternalError(format!(
+            "invalid module for the target features {target}: {error}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use walrus::{FunctionBuilder, Module};
+
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!("mvp".parse(), Ok(TargetFeatures::MVP));
+        assert_eq!("all".parse(), Ok(TargetFeatures::ALL));
+        let target: TargetFeatures = "mvp, multi-value,mutable-global".parse().unwrap();
+        assert!(target.allows(Feature::MultiValue));
+        assert!(target.allows(Feature::MutableGlobal));
+        assert!(!target.allows(Feature::BulkMemory));
+        assert_eq!(target.to_string(), "multi-value,mutable-global");
+        assert_eq!(TargetFeatures::MVP.to_string(), "mvp");
+        assert_eq!(
+            "threads".parse::<TargetFeatures>(),
+            Err("unknown WebAssembly feature `threads`".to_owned())
+        );
+    }
+
+    #[test]
+    fn validate_names_the_unsupported_feature() {
+        let mut module = Module::default();
+        let memory = module.memories.add_local(false, 1, None);
+        let mut func = FunctionBuilder::new(&mut module.types, &[], &[]);
+        func.func_body()
+            .i32_const(0)
+            .i32_const(0)
+            .i32_const(0)
+            .memory_copy(memory, memory);
+        let copy = func.finish(vec![], &mut module.funcs);
+        module.exports.add("copy", copy);
+        let wasm = module.emit_wasm();
+
+        assert!(validate(&wasm, TargetFeatures::ALL).is_ok());
+        assert!(validate(&wasm, TargetFeatures::MVP.with(Feature::BulkMemory)).is_ok());
+        assert!(matches!(
+            validate(&wasm, TargetFeatures::ALL.without(Feature::BulkMemory)),
+            Err(GeneratorError::UnsupportedFeature {
+                feature: Feature::BulkMemory,
+                ..
+            })
+        ));
+    }
+}
@@ -4,43 +4,138 @@
 #![allow(clippy::expect_used, clippy::unwrap_used)]
 
 use std::collections::HashMap;
-use std::sync::LazyLock;
+use std::fmt::Write;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock};
 
-use clarity::consts::{CHAIN_ID_MAINNET, CHAIN_ID_TESTNET};
 use clarity::types::StacksEpochId;
-use clarity::vm::analysis::run_analysis;
+use clarity::vm::analysis::types::TypeMap;
+use clarity::vm::analysis::{run_analysis, AnalysisDatabase, ContractAnalysis};
 use clarity::vm::ast::build_ast;
-use clarity::vm::contexts::{EventBatch, GlobalContext};
+use clarity::vm::contexts::{Environment, EventBatch, GlobalContext};
 use clarity::vm::contracts::Contract;
-use clarity::vm::costs::LimitedCostTracker;
-use clarity::vm::database::ClarityDatabase;
+use clarity::vm::costs::{ExecutionCost, LimitedCostTracker};
+use clarity::vm::database::{ClarityDatabase, DataMapMetadata};
 use clarity::vm::errors::{CheckErrors, Error, WasmError};
 use clarity::vm::events::{SmartContractEventData, StacksTransactionEvent};
-use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, StandardPrincipalData};
-use clarity::vm::{eval_all, ClarityVersion, ContractContext, ContractName, Value};
+use clarity::vm::types::{
+    OptionalData, PrincipalData, QualifiedContractIdentifier, ResponseData, SequenceData,
+    StandardPrincipalData, TypeSignature,
+};
+use clarity::vm::{
+    eval_all, CallStack, ClarityVersion, ContractContext, ContractName, SymbolicExpression, Value,
+};
 use regex::Regex;
-
-use crate::compile;
-use crate::datastore::{BurnDatastore, Datastore, StacksConstants};
-use crate::initialize::initialize_contract;
-
+use wasmtime::{Config, Engine, Linker, Store};
+
+pub use crate::datastore::Network;
+use crate::datastore::{BurnDatastore, ChainStore, Datastore, StacksConstants};
+use crate::error_mapping;
+use crate::initialize::{
+    initialize_contract_with, HostCallCounts, HostCallTrace, HostCallTracer, InitializationTimings,
+    Instrumentation, MemorySnapshot,
+};
+use crate::linker::link_stub_host_functions;
+#[cfg(feature = "experimental-post-conditions")]
+use crate::post_conditions::{check_post_conditions, PostCondition, PostConditionMode};
+use crate::pretty::pretty_print_result;
+use crate::wasm_generator::{WasmGenerator, END_OF_STANDARD_DATA};
+use crate::wasm_utils::memory::hex_dump;
+use crate::wasm_utils::{
+    call_function_prepared_counted, call_function_traced, call_read_only, placeholder_for_type,
+    wasm_to_clarity_value, PreparedContract,
+};
+use crate::{compile, CompileOptions, BLOCK_LIMIT_MAINNET_21};
+
+/// A simulated chain to deploy and call contracts in tests.
+///
+/// It is `Send` and `Sync`: a clone can be moved to each thread of a test,
+/// to run calls in parallel from the same deployed contracts. The contract
+/// contexts, which don't change once deployed, are shared by the clones.
+///
+/// The contracts are stored in the in-memory [`Datastore`] by default, or in
+/// any other [`ChainStore`], see [`TestEnvironment::with_store`].
 #[derive(Clone)]
-pub struct TestEnvironment {
-    contract_contexts: HashMap<String, ContractContext>,
+pub struct TestEnvironment<S: ChainStore = Datastore> {
+    contract_contexts: HashMap<String, Arc<ContractContext>>,
     epoch: StacksEpochId,
     version: ClarityVersion,
-    datastore: Datastore,
-    burn_datastore: BurnDatastore,
+    datastore: S,
+    burn_datastore: S::BurnStore,
     cost_tracker: LimitedCostTracker,
     events: Vec<EventBatch>,
+    host_calls: HashMap<String, HostCallCounts>,
+    host_call_traces: HashMap<String, HostCallTrace>,
+    memory_snapshots: HashMap<String, MemorySnapshot>,
+    timings: HashMap<String, InitializationTimings>,
+    /// The modules of the compiled contracts, linked once for all the calls.
+    prepared_contracts: HashMap<String, PreparedContract>,
     network: Network,
 }
 
 impl TestEnvironment {
     pub fn new_with_amount(amount: u128, epoch: StacksEpochId, version: ClarityVersion) -> Self {
-        let constants = StacksConstants::default();
-        let burn_datastore = BurnDatastore::new(constants.clone());
-        let mut datastore = Datastore::new();
+        Self::new_with_stores(
+            Datastore::new(),
+            BurnDatastore::new(StacksConstants::for_network(Network::Testnet)),
+            amount,
+            epoch,
+            version,
+            Network::Testnet,
+        )
+    }
+
+    pub fn new(epoch: StacksEpochId, version: ClarityVersion) -> Self {
+        Self::new_with_amount(1_000_000_000, epoch, version)
+    }
+
+    pub fn new_with_network(
+        epoch: StacksEpochId,
+        version: ClarityVersion,
+        network: Network,
+    ) -> Self {
+        Self::new_with_stores(
+            Datastore::new(),
+            BurnDatastore::new(StacksConstants::for_network(network)),
+            1_000_000_000,
+            epoch,
+            version,
+            network,
+        )
+    }
+}
+
+impl<S: ChainStore> TestEnvironment<S> {
+    /// Creates an environment storing the contracts in `store`, with the
+    /// headers and burnchain state of its blocks in `burn_store`, for example
+    /// the store of a node wrapped in a [`NodeStore`](crate::datastore::NodeStore).
+    /// Like [`TestEnvironment::new`], it gives a starting balance to the
+    /// account calling the contracts, which is written to `store`.
+    pub fn with_store(
+        store: S,
+        burn_store: S::BurnStore,
+        epoch: StacksEpochId,
+        version: ClarityVersion,
+    ) -> Self {
+        Self::new_with_stores(
+            store,
+            burn_store,
+            1_000_000_000,
+            epoch,
+            version,
+            Network::Testnet,
+        )
+    }
+
+    fn new_with_stores(
+        mut datastore: S,
+        burn_datastore: S::BurnStore,
+        amount: u128,
+        epoch: StacksEpochId,
+        version: ClarityVersion,
+        network: Network,
+    ) -> Self {
         let cost_tracker = LimitedCostTracker::new_free();
 
         let mut db = ClarityDatabase::new(&mut datastore, &burn_datastore, &burn_datastore);
@@ -68,37 +163,37 @@ impl TestEnvironment {
             burn_datastore,
             cost_tracker,
             events: vec![],
-            network: Network::Testnet,
+            host_calls: HashMap::new(),
+            host_call_traces: HashMap::new(),
+            memory_snapshots: HashMap::new(),
+            timings: HashMap::new(),
+            prepared_contracts: HashMap::new(),
+            network,
         }
     }
 
-    pub fn new(epoch: StacksEpochId, version: ClarityVersion) -> Self {
-        Self::new_with_amount(1_000_000_000, epoch, version)
-    }
-
-    pub fn new_with_network(
-        epoch: StacksEpochId,
-        version: ClarityVersion,
-        network: Network,
-    ) -> Self {
-        let mut env = Self::new(epoch, version);
-        env.network = network;
-        env
+    pub fn init_contract_with_snippet(
+        &mut self,
+        contract_name: &str,
+        snippet: &str,
+    ) -> Result<Option<Value>, Error> {
+        self.init_contract_with_options(contract_name, snippet, CompileOptions::default())
     }
 
-    pub fn init_contract_with_snippet(
+    /// Compiles the contract `snippet` with `options`, and deploys it like
+    /// [`TestEnvironment::init_contract_with_snippet`].
+    pub fn init_contract_with_options(
         &mut self,
         contract_name: &str,
         snippet: &str,
+        options: CompileOptions,
     ) -> Result<Option<Value>, Error> {
         let contract_id = QualifiedContractIdentifier::new(
             StandardPrincipalData::transient(),
             (*contract_name).into(),
         );
 
-        let mut compile_result = self
-            .datastore
-            .as_analysis_db()
+        let mut compile_result = AnalysisDatabase::new(&mut self.datastore)
             .execute(|analysis_db| {
                 compile(
                     snippet,
@@ -107,21 +202,51 @@ impl TestEnvironment {
                     self.version,
                     self.epoch,
                     analysis_db,
+                    options,
                 )
                 .map_err(|e| CheckErrors::Expects(format!("Compilation failure {:?}", e)))
             })
             .map_err(|e| Error::Wasm(WasmError::WasmGeneratorError(format!("{:?}", e))))?;
 
-        self.datastore
-            .as_analysis_db()
-            .execute(|analysis_db| {
-                analysis_db.insert_contract(&contract_id, &compile_result.contract_analysis)
-            })
+        // compile_result.module.emit_wasm_file("test.wasm").unwrap();
+        self.deploy(
+            &contract_id,
+            compile_result.module.emit_wasm(),
+            &compile_result.contract_analysis,
+            snippet,
+        )
+    }
+
+    /// Deploys the contract `contract_id` from a module already compiled, for
+    /// example loaded from a cache, with its analysis, without recompiling it.
+    /// Like the contracts initialized from a snippet, it can then be found by
+    /// its name.
+    pub fn deploy_precompiled(
+        &mut self,
+        contract_id: &QualifiedContractIdentifier,
+        wasm_bytes: &[u8],
+        analysis: &ContractAnalysis,
+    ) -> Result<Option<Value>, Error> {
+        // The source of the contract is not known, its hash is the one of an
+        // empty source.
+        self.deploy(contract_id, wasm_bytes.to_vec(), analysis, "")
+    }
+
+    fn deploy(
+        &mut self,
+        contract_id: &QualifiedContractIdentifier,
+        wasm_module: Vec<u8>,
+        contract_analysis: &ContractAnalysis,
+        source: &str,
+    ) -> Result<Option<Value>, Error> {
+        let contract_name = contract_id.name.as_str();
+
+        AnalysisDatabase::new(&mut self.datastore)
+            .execute(|analysis_db| analysis_db.insert_contract(contract_id, contract_analysis))
             .expect("Failed to insert contract analysis.");
 
         let mut contract_context = ContractContext::new(contract_id.clone(), self.version);
-        // compile_result.module.emit_wasm_file("test.wasm").unwrap();
-        contract_context.set_wasm_module(compile_result.module.emit_wasm());
+        contract_context.set_wasm_module(wasm_module);
 
         let mut cost_tracker = LimitedCostTracker::new_free();
         std::mem::swap(&mut self.cost_tracker, &mut cost_tracker);
@@ -132,64 +257,463 @@ impl TestEnvironment {
             &self.burn_datastore,
         );
 
-        let (is_mainnet, chain_id) = match self.network {
-            Network::Mainnet => (true, CHAIN_ID_MAINNET),
-            Network::Testnet => (false, CHAIN_ID_TESTNET),
-        };
-
-        let mut global_context =
-            GlobalContext::new(is_mainnet, chain_id, conn, cost_tracker, self.epoch);
+        let mut global_context = GlobalContext::new(
+            self.network.is_mainnet(),
+            self.network.chain_id(),
+            conn,
+            cost_tracker,
+            self.epoch,
+        );
+        use_cached_engine(&mut global_context);
         global_context.begin();
         global_context
-            .execute(|g| g.database.insert_contract_hash(&contract_id, snippet))
+            .execute(|g| g.database.insert_contract_hash(contract_id, source))
             .expect("Failed to insert contract hash.");
 
-        let return_val = initialize_contract(
+        let mut snapshot = MemorySnapshot::default();
+        let initialization = initialize_contract_with(
             &mut global_context,
             &mut contract_context,
             None,
-            &compile_result.contract_analysis,
-        )?;
+            contract_analysis,
+            Instrumentation {
+                count_host_calls: true,
+                trace_host_calls: true,
+                timings: true,
+                memory_snapshot: Some(&mut snapshot),
+            },
+        );
+        self.memory_snapshots
+            .insert(contract_name.to_string(), snapshot);
+        let initialization = initialization?;
+        let prepared = PreparedContract::new(&global_context.engine, &contract_context)?;
 
         let data_size = contract_context.data_size;
         global_context.database.insert_contract(
-            &contract_id,
+            contract_id,
             Contract {
                 contract_context: contract_context.clone(),
             },
         )?;
         global_context
             .database
-            .set_contract_data_size(&contract_id, data_size)
+            .set_contract_data_size(contract_id, data_size)
             .expect("Failed to set contract data size.");
 
-        let (_, events) = global_context.commit().unwrap();
-        if let Some(events) = events {
-            self.events.push(events);
-        }
+        global_context.commit().unwrap();
+        self.events.push(initialization.events);
+        self.host_calls.insert(
+            contract_name.to_string(),
+            initialization.host_calls.unwrap_or_default(),
+        );
+        self.host_call_traces.insert(
+            contract_name.to_string(),
+            initialization.host_call_trace.unwrap_or_default(),
+        );
+        self.timings.insert(
+            contract_name.to_string(),
+            initialization.timings.unwrap_or_default(),
+        );
         self.cost_tracker = global_context.cost_track;
 
         self.contract_contexts
-            .insert(contract_name.to_string(), contract_context);
+            .insert(contract_name.to_string(), Arc::new(contract_context));
+        self.prepared_contracts
+            .insert(contract_name.to_string(), prepared);
 
-        Ok(return_val)
+        Ok(initialization.value)
     }
 
     pub fn evaluate(&mut self, snippet: &str) -> Result<Option<Value>, Error> {
         self.init_contract_with_snippet("snippet", snippet)
     }
 
+    /// Calls the function `function_name` of the compiled contract
+    /// `contract_name` with `args`, from the account with a starting balance.
+    /// The changes made by the call are rolled back if it fails or returns an
+    /// `err`.
+    pub fn call_function(
+        &mut self,
+        contract_name: &str,
+        function_name: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let prepared = self.prepared_contract(contract_name);
+        let mut host_calls = self.host_calls.remove(contract_name).unwrap_or_default();
+        let result = self.transaction(contract_name, |global_context, contract_context, sender| {
+            let prepared = prepared.map_or_else(
+                || PreparedContract::new(&global_context.engine, contract_context),
+                Ok,
+            )?;
+            call_function_prepared_counted(
+                &prepared,
+                function_name,
+                args,
+                global_context,
+                contract_context,
+                &mut CallStack::new(),
+                Some(sender.clone()),
+                Some(sender),
+                None,
+                &mut host_calls,
+            )
+        });
+        if self.contract_contexts.contains_key(contract_name) {
+            self.host_calls
+                .insert(contract_name.to_string(), host_calls);
+        }
+        result
+    }
+
+    /// Like [`Self::call_function`], but records the calls to the host
+    /// functions made by the contract in `tracer`, see
+    /// [`crate::wasm_utils::call_function_traced`].
+    pub fn call_function_traced(
+        &mut self,
+        contract_name: &str,
+        function_name: &str,
+        args: &[Value],
+        tracer: &HostCallTracer,
+    ) -> Result<Value, Error> {
+        self.transaction(contract_name, |global_context, contract_context, sender| {
+            call_function_traced(
+                function_name,
+                args,
+                global_context,
+                contract_context,
+                &mut CallStack::new(),
+                Some(sender.clone()),
+                Some(sender),
+                None,
+                tracer,
+            )
+        })
+    }
+
+    /// Like [`Self::call_function`], but checks the `post_conditions` of the
+    /// call against its asset movements before committing it. A call which
+    /// violates them is rolled back, and fails with a
+    /// [`PostConditionError`](crate::post_conditions::PostConditionError).
+    #[cfg(feature = "experimental-post-conditions")]
+    pub fn call_function_with_post_conditions(
+        &mut self,
+        contract_name: &str,
+        function_name: &str,
+        args: &[Value],
+        post_conditions: &[PostCondition],
+        mode: PostConditionMode,
+    ) -> Result<Value, Error> {
+        let prepared = self.prepared_contract(contract_name);
+        let mut host_calls = self.host_calls.remove(contract_name).unwrap_or_default();
+        let result = self.transaction(contract_name, |global_context, contract_context, sender| {
+            let prepared = prepared.map_or_else(
+                || PreparedContract::new(&global_context.engine, contract_context),
+                Ok,
+            )?;
+            let value = call_function_prepared_counted(
+                &prepared,
+                function_name,
+                args,
+                global_context,
+                contract_context,
+                &mut CallStack::new(),
+                Some(sender.clone()),
+                Some(sender),
+                None,
+                &mut host_calls,
+            )?;
+
+            let events = global_context
+                .event_batches
+                .last()
+                .map_or(&[][..], |batch| batch.events.as_slice());
+            check_post_conditions(events, post_conditions, mode)
+                .map_err(|e| Error::Wasm(WasmError::Runtime(e.into())))?;
+            Ok(value)
+        });
+        if self.contract_contexts.contains_key(contract_name) {
+            self.host_calls
+                .insert(contract_name.to_string(), host_calls);
+        }
+        result
+    }
+
+    /// Calls the read-only function `function_name` of the compiled contract
+    /// `contract_name` with `args`, as a view call on the current state of
+    /// the chain (see [`call_read_only`]).
+    pub fn call_read_only(
+        &mut self,
+        contract_name: &str,
+        function_name: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        let no_such_contract = || CheckErrors::NoSuchContract(contract_name.to_string());
+        let contract_context = self
+            .contract_contexts
+            .get(contract_name)
+            .cloned()
+            .ok_or_else(no_such_contract)?;
+        let prepared = self
+            .prepared_contract(contract_name)
+            .ok_or_else(no_such_contract)?;
+
+        let database = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+        call_read_only(
+            &prepared,
+            &contract_context,
+            database,
+            self.network.is_mainnet(),
+            self.network.chain_id(),
+            self.epoch,
+            function_name,
+            args,
+            PrincipalData::Standard(StandardPrincipalData::transient()),
+        )
+    }
+
+    /// The module of the compiled contract `contract_name`, deserialized
+    /// when it was deployed.
+    fn prepared_contract(&self, contract_name: &str) -> Option<PreparedContract> {
+        self.prepared_contracts.get(contract_name).cloned()
+    }
+
+    /// Like [`Self::call_function`], but runs the function with the
+    /// interpreter, which requires `contract_name` to be initialized with
+    /// [`Self::interpret_contract_with_snippet`].
+    pub fn interpret_function(
+        &mut self,
+        contract_name: &str,
+        function_name: &str,
+        args: &[Value],
+    ) -> Result<Value, Error> {
+        self.transaction(contract_name, |global_context, contract_context, sender| {
+            let function = contract_context
+                .lookup_function(function_name)
+                .ok_or_else(|| CheckErrors::UndefinedFunction(function_name.to_string()))?;
+            let mut call_stack = CallStack::new();
+            Environment::new(
+                global_context,
+                contract_context,
+                &mut call_stack,
+                Some(sender.clone()),
+                Some(sender),
+                None,
+            )
+            .execute_function_as_transaction(&function, args, None, false)
+        })
+    }
+
+    /// Runs `call` on the contract `contract_name`, from the account with a
+    /// starting balance, and commits its changes unless it fails or returns
+    /// an `err`.
+    fn transaction(
+        &mut self,
+        contract_name: &str,
+        call: impl FnOnce(&mut GlobalContext, &ContractContext, PrincipalData) -> Result<Value, Error>,
+    ) -> Result<Value, Error> {
+        let contract_context = self
+            .contract_contexts
+            .get(contract_name)
+            .cloned()
+            .ok_or_else(|| CheckErrors::NoSuchContract(contract_name.to_string()))?;
+
+        let mut cost_tracker = LimitedCostTracker::new_free();
+        std::mem::swap(&mut self.cost_tracker, &mut cost_tracker);
+
+        let conn = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+
+        let mut global_context = GlobalContext::new(
+            self.network.is_mainnet(),
+            self.network.chain_id(),
+            conn,
+            cost_tracker,
+            self.epoch,
+        );
+        use_cached_engine(&mut global_context);
+        global_context.begin();
+
+        let sender = PrincipalData::Standard(StandardPrincipalData::transient());
+        let result = call(&mut global_context, &contract_context, sender);
+
+        let committed = !matches!(
+            result,
+            Err(_)
+                | Ok(Value::Response(ResponseData {
+                    committed: false,
+                    ..
+                }))
+        );
+        if committed {
+            let (_, events) = global_context.commit()?;
+            if let Some(events) = events {
+                self.events.push(events);
+            }
+        } else {
+            global_context.roll_back()?;
+        }
+        self.cost_tracker = global_context.cost_track;
+
+        result
+    }
+
+    /// Reads the entry at `key` of the map `map_name` of the contract
+    /// `contract_name` from the database, like the interpreter does for
+    /// `map-get?`, whichever runtime wrote it.
+    pub fn get_map_entry(
+        &mut self,
+        contract_name: &str,
+        map_name: &str,
+        key: &Value,
+    ) -> Result<Value, Error> {
+        let (contract_id, map_descriptor) = self.map_descriptor(contract_name, map_name)?;
+        let epoch = self.epoch;
+        let mut conn = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+        execute(&mut conn, |database| {
+            database.fetch_entry(&contract_id, map_name, key, &map_descriptor, &epoch)
+        })
+    }
+
+    /// Writes `value` at `key` in the map `map_name` of the contract
+    /// `contract_name` in the database, like the interpreter does for
+    /// `map-set`, whichever runtime initialized the contract.
+    pub fn set_map_entry(
+        &mut self,
+        contract_name: &str,
+        map_name: &str,
+        key: Value,
+        value: Value,
+    ) -> Result<Value, Error> {
+        let (contract_id, map_descriptor) = self.map_descriptor(contract_name, map_name)?;
+        let epoch = self.epoch;
+        let mut conn = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+        execute(&mut conn, |database| {
+            database.set_entry(&contract_id, map_name, key, value, &map_descriptor, &epoch)
+        })
+    }
+
+    fn map_descriptor(
+        &self,
+        contract_name: &str,
+        map_name: &str,
+    ) -> Result<(QualifiedContractIdentifier, DataMapMetadata), Error> {
+        let contract_context = self
+            .contract_contexts
+            .get(contract_name)
+            .ok_or_else(|| CheckErrors::NoSuchContract(contract_name.to_string()))?;
+        let map_descriptor = contract_context
+            .meta_data_map
+            .get(map_name)
+            .ok_or_else(|| CheckErrors::NoSuchMap(map_name.to_string()))?
+            .clone();
+        Ok((contract_context.contract_identifier.clone(), map_descriptor))
+    }
+
     pub fn get_contract_context(&self, contract_name: &str) -> Option<&ContractContext> {
-        self.contract_contexts.get(contract_name)
+        self.contract_contexts.get(contract_name).map(Arc::as_ref)
     }
 
     pub fn get_events(&self) -> &Vec<EventBatch> {
         &self.events
     }
 
+    /// The number of calls to each host function made by the compiled
+    /// contract `contract_name`, while initializing it and in the calls of
+    /// [`Self::call_function`].
+    pub fn get_host_calls(&self, contract_name: &str) -> Option<&HostCallCounts> {
+        self.host_calls.get(contract_name)
+    }
+
+    /// The calls to the host functions made while initializing the compiled
+    /// contract `contract_name`, in order, with the debugging output.
+    pub fn get_host_call_trace(&self, contract_name: &str) -> Option<&HostCallTrace> {
+        self.host_call_traces.get(contract_name)
+    }
+
+    /// The memory of the compiled contract `contract_name` after its
+    /// initialization, even if it failed.
+    pub fn get_memory_snapshot(&self, contract_name: &str) -> Option<&MemorySnapshot> {
+        self.memory_snapshots.get(contract_name)
+    }
+
+    /// The time spent compiling the module of the compiled contract
+    /// `contract_name` to machine code, and running its top-level
+    /// expressions.
+    pub fn get_timings(&self, contract_name: &str) -> Option<&InitializationTimings> {
+        self.timings.get(contract_name)
+    }
+
+    /// Replaces the free cost tracker of the environment with a tracker
+    /// limited to a block of Stacks 2.1, which charges the costs of the
+    /// deployments and calls from now on, like a node does. Their total can
+    /// then be read with [`Self::get_total_cost`].
+    pub fn track_costs(&mut self) -> Result<(), Error> {
+        let mut conn = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+        self.cost_tracker = LimitedCostTracker::new(
+            self.network.is_mainnet(),
+            self.network.chain_id(),
+            BLOCK_LIMIT_MAINNET_21,
+            &mut conn,
+            self.epoch,
+        )?;
+        Ok(())
+    }
+
+    /// The total cost charged by the deployments and calls since the costs
+    /// are tracked, see [`Self::track_costs`].
+    pub fn get_total_cost(&self) -> ExecutionCost {
+        self.cost_tracker.get_total()
+    }
+
+    /// Moves the chain to `epoch`: the contracts already deployed are called
+    /// in this epoch from now on, and the new ones are deployed in it.
+    pub fn set_epoch(&mut self, epoch: StacksEpochId) {
+        let mut conn = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+        execute(&mut conn, |database| {
+            database.set_clarity_epoch_version(epoch)
+        })
+        .expect("Failed to set epoch version.");
+        self.epoch = epoch;
+    }
+
+    /// Sets the tenure height stored in the Clarity state, starting in epoch
+    /// 3.0.
+    pub fn set_tenure_height(&mut self, height: u32) {
+        let mut conn = ClarityDatabase::new(
+            &mut self.datastore,
+            &self.burn_datastore,
+            &self.burn_datastore,
+        );
+        execute(&mut conn, |database| database.set_tenure_height(height))
+            .expect("Failed to set tenure height.");
+    }
+
     pub fn advance_chain_tip(&mut self, count: u32) -> u32 {
-        self.burn_datastore.advance_chain_tip(count);
-        self.datastore.advance_chain_tip(count)
+        self.datastore
+            .advance_chain_tip(&mut self.burn_datastore, count)
     }
 
     pub fn interpret_contract_with_snippet(
@@ -205,33 +729,33 @@ impl TestEnvironment {
         let mut cost_tracker = LimitedCostTracker::new_free();
         std::mem::swap(&mut self.cost_tracker, &mut cost_tracker);
 
-        let mut contract_analysis = self.datastore.as_analysis_db().execute(|analysis_db| {
-            // Parse the contract
-            let ast = build_ast(
-                &contract_id,
-                snippet,
-                &mut self.cost_tracker,
-                self.version,
-                self.epoch,
-            )
-            .map_err(|e| Error::Wasm(WasmError::WasmGeneratorError(format!("{:?}", e))))?;
+        let mut contract_analysis =
+            AnalysisDatabase::new(&mut self.datastore).execute(|analysis_db| {
+                // Parse the contract
+                let ast = build_ast(
+                    &contract_id,
+                    snippet,
+                    &mut self.cost_tracker,
+                    self.version,
+                    self.epoch,
+                )
+                .map_err(|e| Error::Wasm(WasmError::WasmGeneratorError(format!("{:?}", e))))?;
 
-            // Run the analysis passes
-            run_analysis(
-                &contract_id,
-                &ast.expressions,
-                analysis_db,
-                false,
-                cost_tracker,
-                self.epoch,
-                self.version,
-                true,
-            )
-            .map_err(|(e, _)| Error::Wasm(WasmError::WasmGeneratorError(format!("{:?}", e))))
-        })?;
+                // Run the analysis passes
+                run_analysis(
+                    &contract_id,
+                    &ast.expressions,
+                    analysis_db,
+                    false,
+                    cost_tracker,
+                    self.epoch,
+                    self.version,
+                    true,
+                )
+                .map_err(|(e, _)| Error::Wasm(WasmError::WasmGeneratorError(format!("{:?}", e))))
+            })?;
 
-        self.datastore
-            .as_analysis_db()
+        AnalysisDatabase::new(&mut self.datastore)
             .execute(|analysis_db| analysis_db.insert_contract(&contract_id, &contract_analysis))
             .expect("Failed to insert contract analysis");
 
@@ -243,14 +767,9 @@ impl TestEnvironment {
             &self.burn_datastore,
         );
 
-        let (is_mainnet, chain_id) = match self.network {
-            Network::Mainnet => (true, CHAIN_ID_MAINNET),
-            Network::Testnet => (false, CHAIN_ID_TESTNET),
-        };
-
         let mut global_context = GlobalContext::new(
-            is_mainnet,
-            chain_id,
+            self.network.is_mainnet(),
+            self.network.chain_id(),
             conn,
             contract_analysis.cost_track.take().unwrap(),
             self.epoch,
@@ -287,7 +806,9 @@ impl TestEnvironment {
         self.cost_tracker = global_context.cost_track;
 
         self.contract_contexts
-            .insert(contract_name.to_owned(), contract_context);
+            .insert(contract_name.to_owned(), Arc::new(contract_context));
+        // The contract has no compiled module.
+        self.prepared_contracts.remove(contract_name);
 
         Ok(result)
     }
@@ -303,6 +824,38 @@ impl Default for TestEnvironment {
     }
 }
 
+/// Environment variable enabling the compilation cache of wasmtime for the
+/// compiled contracts of the test environments: the path of a wasmtime cache
+/// configuration file, or `default` for the default configuration. The
+/// machine code of the modules is then stored on disk, and the test processes
+/// compiling the same modules load it instead of compiling them again.
+pub const WASMTIME_CACHE_ENV: &str = "CLAR2WASM_WASMTIME_CACHE";
+
+/// The engine of the test environments when [`WASMTIME_CACHE_ENV`] is set,
+/// shared by all of them so that the modules are compiled with the same
+/// configuration, and can be found in the cache.
+static CACHED_ENGINE: LazyLock<Option<Engine>> = LazyLock::new(|| {
+    let path = std::env::var_os(WASMTIME_CACHE_ENV)?;
+    let mut config = Config::new();
+    let loaded = if path == "default" {
+        config.cache_config_load_default()
+    } else {
+        config.cache_config_load(&path)
+    };
+    let engine = loaded
+        .and_then(|config| Engine::new(config))
+        .unwrap_or_else(|e| panic!("invalid {WASMTIME_CACHE_ENV} {path:?}: {e}"));
+    Some(engine)
+});
+
+/// Replaces the engine of `global_context` with the cached engine, if the
+/// cache is enabled.
+fn use_cached_engine(global_context: &mut GlobalContext) {
+    if let Some(engine) = CACHED_ENGINE.as_ref() {
+        global_context.engine = engine.clone();
+    }
+}
+
 pub fn execute<F, T, E>(conn: &mut ClarityDatabase, f: F) -> std::result::Result<T, E>
 where
     F: FnOnce(&mut ClarityDatabase) -> std::result::Result<T, E>,
@@ -373,11 +926,181 @@ pub fn interpret(snippet: &str) -> Result<Option<Value>, Error> {
     interpret_at(snippet, StacksEpochId::latest(), ClarityVersion::latest())
 }
 
-struct TestConfig;
+/// Evaluates a single invocation of the word `word` on `args`, without going
+/// through the contract analysis: the invocation is compiled directly with
+/// the [`WasmGenerator`], using the type of each argument value, and
+/// `result_ty` as the type of the invocation. The generated code runs against
+/// the stub host functions of [`link_stub_host_functions`].
+///
+/// This is much faster than [`evaluate`] and is meant for targeted tests of
+/// the code generated by a word. Words calling host functions only see the
+/// stubs, so they should be tested with [`crosscheck`] instead.
+pub fn evaluate_word(
+    word: &str,
+    args: &[Value],
+    result_ty: TypeSignature,
+) -> Result<Option<Value>, Error> {
+    let epoch = TestConfig::epoch();
+    let version = TestConfig::clarity_version();
+
+    let mut synthetic = SyntheticExpressions::new();
+    let args = args
+        .iter()
+        .map(|arg| synthetic.value(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+    let invocation = synthetic.call(word, args);
+    let invocation = synthetic.typed(invocation, result_ty.clone())?;
+
+    let mut contract_analysis = ContractAnalysis::new(
+        QualifiedContractIdentifier::transient(),
+        vec![invocation],
+        LimitedCostTracker::new_free(),
+        epoch,
+        version,
+    );
+    contract_analysis.type_map = Some(synthetic.type_map);
+
+    let mut module = WasmGenerator::new(contract_analysis, epoch, version)
+        .and_then(WasmGenerator::generate)
+        .map_err(|e| Error::Wasm(WasmError::WasmGeneratorError(format!("{:?}", e))))?;
+
+    let engine = Engine::default();
+    let mut store = Store::new(&engine, ());
+    let mut linker = Linker::new(&engine);
+    link_stub_host_functions(&mut linker)
+        .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))?;
+
+    let module = wasmtime::Module::from_binary(&engine, &module.emit_wasm())
+        .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))?;
+    let instance = linker
+        .instantiate(&mut store, &module)
+        .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))?;
+
+    let top_level = instance
+        .get_func(&mut store, ".top-level")
+        .ok_or(Error::Wasm(WasmError::DefinesNotFound))?;
+    let mut results: Vec<_> = top_level
+        .ty(&store)
+        .results()
+        .map(placeholder_for_type)
+        .collect();
+    top_level.call(&mut store, &[], &mut results).map_err(|e| {
+        error_mapping::resolve_error(e, instance, &mut store, &epoch, &version, None)
+    })?;
+
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .ok_or(Error::Wasm(WasmError::MemoryNotFound))?;
+    wasm_to_clarity_value(&result_ty, 0, &results, memory, &mut store, epoch).map(|(val, _)| val)
+}
+
+/// Builds the expressions of a word invocation for [`evaluate_word`], along
+/// with the type map that the contract analysis would have produced.
+struct SyntheticExpressions {
+    type_map: TypeMap,
+    next_id: u64,
+}
+
+impl SyntheticExpressions {
+    fn new() -> Self {
+        Self {
+            type_map: TypeMap::new(true),
+            next_id: 0,
+        }
+    }
+
+    fn next(&mut self, mut expr: SymbolicExpression) -> SymbolicExpression {
+        self.next_id += 1;
+        expr.id = self.next_id;
+        expr
+    }
+
+    fn typed(
+        &mut self,
+        expr: SymbolicExpression,
+        ty: TypeSignature,
+    ) -> Result<SymbolicExpression, Error> {
+        let expr = self.next(expr);
+        self.type_map
+            .set_type(&expr, ty)
+            .map_err(|e| Error::Wasm(WasmError::WasmGeneratorError(format!("{:?}", e))))?;
+        Ok(expr)
+    }
+
+    fn call(&mut self, name: &str, args: Vec<SymbolicExpression>) -> SymbolicExpression {
+        let name = self.next(SymbolicExpression::atom(name.into()));
+        SymbolicExpression::list(std::iter::once(name).chain(args).collect())
+    }
+
+    /// Returns an expression evaluating to `value`, with the same type.
+    fn value(&mut self, value: &Value) -> Result<SymbolicExpression, Error> {
+        let expr = match value {
+            Value::Int(_)
+            | Value::UInt(_)
+            | Value::Principal(_)
+            | Value::Sequence(SequenceData::Buffer(_) | SequenceData::String(_)) => {
+                SymbolicExpression::literal_value(value.clone())
+            }
+            Value::Bool(b) => SymbolicExpression::atom(if *b { "true" } else { "false" }.into()),
+            Value::Optional(OptionalData { data: None }) => SymbolicExpression::atom("none".into()),
+            Value::Optional(OptionalData { data: Some(inner) }) => {
+                let inner = self.value(inner)?;
+                self.call("some", vec![inner])
+            }
+            Value::Response(ResponseData { committed, data }) => {
+                let inner = self.value(data)?;
+                self.call(if *committed { "ok" } else { "err" }, vec![inner])
+            }
+            Value::Sequence(SequenceData::List(list)) => {
+                let items = list
+                    .data
+                    .iter()
+                    .map(|item| self.value(item))
+                    .collect::<Result<_, _>>()?;
+                self.call("list", items)
+            }
+            Value::Tuple(tuple) => {
+                let mut fields = Vec::with_capacity(tuple.data_map.len());
+                for (name, field) in &tuple.data_map {
+                    let name = self.next(SymbolicExpression::atom(name.clone()));
+                    let field = self.value(field)?;
+                    fields.push(self.next(SymbolicExpression::list(vec![name, field])));
+                }
+                self.call("tuple", fields)
+            }
+            Value::CallableContract(_) => {
+                return Err(Error::Wasm(WasmError::WasmGeneratorError(
+                    "callable contracts cannot be synthesized".to_owned(),
+                )))
+            }
+        };
+        let ty = TypeSignature::type_of(value)?;
+        self.typed(expr, ty)
+    }
+}
+
+/// Environment variable overriding the epoch of the crosschecks, named as in
+/// the `--stacks-epoch` option of the tools (`2.05`, `2.1`, ...).
+pub const TEST_EPOCH_VAR: &str = "CLAR2WASM_TEST_EPOCH";
+
+/// Environment variable overriding the Clarity version of the crosschecks,
+/// `1`, `2` or `3`, over the `test-clarity-v*` features.
+pub const TEST_CLARITY_VERSION_VAR: &str = "CLAR2WASM_TEST_CLARITY_VERSION";
+
+pub(crate) struct TestConfig;
 
 impl TestConfig {
-    /// Select a Clarity version based on enabled features.
+    /// Select a Clarity version based on [`TEST_CLARITY_VERSION_VAR`] or on
+    /// the enabled features.
     pub fn clarity_version() -> ClarityVersion {
+        if let Ok(version) = std::env::var(TEST_CLARITY_VERSION_VAR) {
+            return match version.as_str() {
+                "1" => ClarityVersion::Clarity1,
+                "2" => ClarityVersion::Clarity2,
+                "3" => ClarityVersion::Clarity3,
+                _ => panic!("invalid {TEST_CLARITY_VERSION_VAR}: {version}"),
+            };
+        }
         match () {
             _ if cfg!(feature = "test-clarity-v1") => ClarityVersion::Clarity1,
             _ if cfg!(feature = "test-clarity-v2") => ClarityVersion::Clarity2,
@@ -386,9 +1109,23 @@ impl TestConfig {
         }
     }
 
-    /// Latest Stacks epoch.
-    pub fn latest_epoch() -> StacksEpochId {
-        StacksEpochId::latest()
+    /// Stacks epoch set in [`TEST_EPOCH_VAR`], or the latest one.
+    pub fn epoch() -> StacksEpochId {
+        let Ok(epoch) = std::env::var(TEST_EPOCH_VAR) else {
+            return StacksEpochId::latest();
+        };
+        match epoch.as_str() {
+            "1.0" => StacksEpochId::Epoch10,
+            "2.0" => StacksEpochId::Epoch20,
+            "2.05" => StacksEpochId::Epoch2_05,
+            "2.1" => StacksEpochId::Epoch21,
+            "2.2" => StacksEpochId::Epoch22,
+            "2.3" => StacksEpochId::Epoch23,
+            "2.4" => StacksEpochId::Epoch24,
+            "2.5" => StacksEpochId::Epoch25,
+            "3.0" => StacksEpochId::Epoch30,
+            _ => panic!("invalid {TEST_EPOCH_VAR}: {epoch}"),
+        }
     }
 }
 
@@ -443,10 +1180,37 @@ impl KnownBug {
 
 impl CrossEvalResult {
     fn compare(&self, snippet: &str) {
+        if self.compiled != self.interpreted {
+            let dump = self
+                .env_compiled
+                .get_memory_snapshot("snippet")
+                .map(|snapshot| {
+                    write_memory_dump(
+                        snippet,
+                        &self.compiled,
+                        &self.interpreted,
+                        snapshot,
+                        self.env_compiled.get_timings("snippet"),
+                    )
+                });
+            match dump {
+                Some(Ok(path)) => println!("memory dump written to {}", path.display()),
+                Some(Err(e)) => println!("failed to write the memory dump: {e}"),
+                None => {}
+            }
+        }
+        let (mut compiled, mut interpreted) = (
+            pretty_print_result(&self.compiled),
+            pretty_print_result(&self.interpreted),
+        );
+        // The results can differ only by their types, which are not printed.
+        if compiled == interpreted {
+            compiled = format!("{:?}", self.compiled);
+            interpreted = format!("{:?}", self.interpreted);
+        }
         assert_eq!(
             self.compiled, self.interpreted,
-            "Compiled and interpreted results diverge! {snippet}\ncompiled: {:?}\ninterpreted: {:?}",
-            self.compiled, self.interpreted
+            "Compiled and interpreted results diverge! {snippet}\ncompiled: {compiled}\ninterpreted: {interpreted}"
         );
         compare_events(
             self.env_interpreted.get_events(),
@@ -455,6 +1219,89 @@ impl CrossEvalResult {
     }
 }
 
+/// Environment variable setting the directory in which the memory dumps of
+/// the diverging crosschecks are written, instead of the temporary directory.
+pub const MEMORY_DUMP_DIR_VAR: &str = "CLAR2WASM_MEMORY_DUMP_DIR";
+
+/// Writes a dump of the memory of a compiled snippet whose result diverges
+/// from the interpreter, and returns its path. The file is named after a hash
+/// of the snippet, so that running a test again overwrites its dump.
+fn write_memory_dump(
+    snippet: &str,
+    compiled: &Result<Option<Value>, Error>,
+    interpreted: &Result<Option<Value>, Error>,
+    snapshot: &MemorySnapshot,
+    timings: Option<&InitializationTimings>,
+) -> std::io::Result<PathBuf> {
+    let dir = std::env::var_os(MEMORY_DUMP_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::temp_dir().join("clar2wasm-memory-dumps"));
+    std::fs::create_dir_all(&dir)?;
+
+    let mut hasher = DefaultHasher::new();
+    snippet.hash(&mut hasher);
+    let path = dir.join(format!("crosscheck-{:016x}.txt", hasher.finish()));
+
+    std::fs::write(
+        &path,
+        format_memory_dump(snippet, compiled, interpreted, snapshot, timings),
+    )?;
+    Ok(path)
+}
+
+/// Formats the memory of a compiled snippet as an hexdump, split in the
+/// standard data, the literal memory and the call stack, after a header with
+/// the compiled and the expected (interpreted) results, and the time spent
+/// compiling and running the module.
+fn format_memory_dump(
+    snippet: &str,
+    compiled: &Result<Option<Value>, Error>,
+    interpreted: &Result<Option<Value>, Error>,
+    snapshot: &MemorySnapshot,
+    timings: Option<&InitializationTimings>,
+) -> String {
+    let mut dump = String::new();
+    for line in snippet.lines() {
+        let _ = writeln!(dump, ";; {line}");
+    }
+    let _ = writeln!(dump, ";;");
+    let _ = writeln!(dump, ";; compiled: {compiled:?}");
+    let _ = writeln!(dump, ";; expected: {interpreted:?}");
+    if let Some(timings) = timings {
+        let _ = writeln!(dump, ";; {timings}");
+    }
+    let _ = writeln!(dump, ";; end of standard data: {END_OF_STANDARD_DATA}");
+    let _ = writeln!(
+        dump,
+        ";; end of literal memory: {}",
+        snapshot.literal_memory_end
+    );
+    let _ = writeln!(dump, ";; stack pointer: {}", snapshot.stack_pointer);
+
+    // The call stack ends with the last byte written, the rest of the memory
+    // is never used.
+    let memory = &snapshot.memory;
+    let used = memory
+        .iter()
+        .rposition(|&b| b != 0)
+        .map_or(0, |last| last + 1)
+        .max(snapshot.stack_pointer.max(0) as usize)
+        .min(memory.len());
+    let standard_end = (END_OF_STANDARD_DATA as usize).min(used);
+    let literal_end = (snapshot.literal_memory_end.max(0) as usize).clamp(standard_end, used);
+
+    for (name, start, end) in [
+        ("standard data", 0, standard_end),
+        ("literal memory", standard_end, literal_end),
+        ("call stack", literal_end, used),
+    ] {
+        let _ = writeln!(dump, "\n;; {name} [{start}, {end})");
+        dump.push_str(&hex_dump(&memory[start..end], start));
+    }
+
+    dump
+}
+
 fn crosseval(snippet: &str, env: TestEnvironment) -> Result<CrossEvalResult, KnownBug> {
     let mut env_interpreted = env.clone();
     let interpreted = env_interpreted.interpret(snippet);
@@ -496,7 +1343,7 @@ fn execute_crosscheck(
 
 pub fn crosscheck(snippet: &str, expected: Result<Option<Value>, Error>) {
     if let Some(eval) = execute_crosscheck(
-        TestEnvironment::new(TestConfig::latest_epoch(), TestConfig::clarity_version()),
+        TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version()),
         snippet,
         |_| {},
     ) {
@@ -512,7 +1359,7 @@ pub fn crosscheck_with_amount(snippet: &str, amount: u128, expected: Result<Opti
     if let Some(eval) = execute_crosscheck(
         TestEnvironment::new_with_amount(
             amount,
-            TestConfig::latest_epoch(),
+            TestConfig::epoch(),
             TestConfig::clarity_version(),
         ),
         snippet,
@@ -526,10 +1373,15 @@ pub fn crosscheck_with_amount(snippet: &str, amount: u128, expected: Result<Opti
     }
 }
 
-fn crosscheck_compare_only_with_env(snippet: &str, env: TestEnvironment) {
+/// Returns the time spent compiling and running the compiled snippet, unless
+/// the snippet triggered a known bug.
+fn crosscheck_compare_only_with_env(
+    snippet: &str,
+    env: TestEnvironment,
+) -> Option<InitializationTimings> {
     // to avoid false positives when both the compiled and interpreted fail,
     // we don't allow failures in these tests
-    execute_crosscheck(env, snippet, |result| {
+    let result = execute_crosscheck(env, snippet, |result| {
         // If both interpreted and compiled results have errors, panic and
         // show both errors.
         // If only one fails, panic with the error from the failing one.
@@ -550,22 +1402,33 @@ fn crosscheck_compare_only_with_env(snippet: &str, env: TestEnvironment) {
                 // Both succeeded; no action needed.
             }
         }
-    });
+    })?;
+    result.env_compiled.get_timings("snippet").copied()
 }
 
 pub fn crosscheck_compare_only(snippet: &str) {
     crosscheck_compare_only_with_env(
         snippet,
-        TestEnvironment::new(TestConfig::latest_epoch(), TestConfig::clarity_version()),
+        TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version()),
+    );
+}
+
+pub fn crosscheck_compare_only_with_epoch(snippet: &str, epoch: StacksEpochId) {
+    crosscheck_compare_only_with_env(
+        snippet,
+        TestEnvironment::new(epoch, TestConfig::clarity_version()),
     );
 }
 
+/// Crosschecks `snippet` like [`crosscheck_compare_only`], and returns the
+/// time spent compiling and running the compiled snippet.
+
 pub fn crosscheck_compare_only_with_epoch_and_version(
     snippet: &str,
     epoch: StacksEpochId,
     version: ClarityVersion,
-) {
-    crosscheck_compare_only_with_env(snippet, TestEnvironment::new(epoch, version));
+) -> Option<InitializationTimings> {
+    crosscheck_compare_only_with_env(snippet, TestEnvironment::new(epoch, version))
 }
 
 pub fn crosscheck_compare_only_with_expected_error<E: Fn(&Error) -> bool>(
@@ -573,7 +1436,7 @@ pub fn crosscheck_compare_only_with_expected_error<E: Fn(&Error) -> bool>(
     expected: E,
 ) {
     execute_crosscheck(
-        TestEnvironment::new(TestConfig::latest_epoch(), TestConfig::clarity_version()),
+        TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version()),
         snippet,
         |result| {
             if let Err(e) = &result.compiled {
@@ -588,7 +1451,7 @@ pub fn crosscheck_compare_only_with_expected_error<E: Fn(&Error) -> bool>(
 /// Advance the block height to `count`, and uses identical TestEnvironment copies
 /// to assert the results of a contract snippet running against the compiler and the interpreter.
 pub fn crosscheck_compare_only_advancing_tip(snippet: &str, count: u32) {
-    let mut env = TestEnvironment::new(TestConfig::latest_epoch(), TestConfig::clarity_version());
+    let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
     env.advance_chain_tip(count);
     execute_crosscheck(env, snippet, |_| {});
 }
@@ -617,7 +1480,7 @@ pub fn crosscheck_with_clarity_version(
     version: ClarityVersion,
 ) {
     if let Some(eval) = execute_crosscheck(
-        TestEnvironment::new(TestConfig::latest_epoch(), version),
+        TestEnvironment::new(TestConfig::epoch(), version),
         snippet,
         |_| {},
     ) {
@@ -629,9 +1492,47 @@ pub fn crosscheck_with_clarity_version(
     }
 }
 
+/// The displayed message of an error, without the stack trace of runtime
+/// errors, which is only recorded by the interpreter.
+fn error_message(err: &Error) -> String {
+    match err {
+        Error::Runtime(err, _) => err.to_string(),
+        err => err.to_string(),
+    }
+}
+
+/// Like [`crosscheck`], also asserting that the snippet fails with the same
+/// error message in the compiled and interpreted versions, and that this
+/// message contains `expected_message`.
+pub fn crosscheck_error_message(snippet: &str, expected_message: &str) {
+    execute_crosscheck(
+        TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version()),
+        snippet,
+        |result| {
+            match (&result.compiled, &result.interpreted) {
+            (Err(compiled), Err(interpreted)) => {
+                let compiled = error_message(compiled);
+                assert_eq!(
+                    compiled,
+                    error_message(interpreted),
+                    "Compiled and interpreted error messages diverge! {snippet}"
+                );
+                assert!(
+                    compiled.contains(expected_message),
+                    "error message {compiled:?} does not contain {expected_message:?}"
+                );
+            }
+            (compiled, interpreted) => panic!(
+                "Expected both snippets to fail: {snippet}\ncompiled: {compiled:?}\ninterpreted: {interpreted:?}"
+            ),
+        }
+        },
+    );
+}
+
 pub fn crosscheck_validate<V: Fn(Value)>(snippet: &str, validator: V) {
     if let Some(eval) = execute_crosscheck(
-        TestEnvironment::new(TestConfig::latest_epoch(), TestConfig::clarity_version()),
+        TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version()),
         snippet,
         |_| {},
     ) {
@@ -680,6 +1581,48 @@ pub fn crosscheck_multi_contract(
     compare_events(interpreted_env.get_events(), compiled_env.get_events());
 }
 
+/// Initializes the contract `snippet` with the compiler and with the
+/// interpreter, and checks that both leave byte-identical entries in their
+/// datastores. Then swaps the datastores, and checks that the read-only
+/// functions `getters` return the same values in each runtime when reading
+/// the state written by the other.
+pub fn crosscheck_state(snippet: &str, getters: &[&str]) {
+    let mut compiled_env = TestEnvironment::default();
+    let compiled = compiled_env.init_contract_with_snippet("contract", snippet);
+
+    let mut interpreted_env = TestEnvironment::default();
+    let interpreted = interpreted_env.interpret_contract_with_snippet("contract", snippet);
+
+    assert_eq!(
+        compiled, interpreted,
+        "Compiled and interpreted results diverge\ncompiled: {compiled:?}\ninterpreted: {interpreted:?}"
+    );
+
+    let compiled_entries = compiled_env.datastore.entries();
+    let interpreted_entries = interpreted_env.datastore.entries();
+    if let Some(key) = compiled_entries
+        .keys()
+        .chain(interpreted_entries.keys())
+        .find(|key| compiled_entries.get(*key) != interpreted_entries.get(*key))
+    {
+        panic!(
+            "Stored entry {key} diverges\ncompiled: {:?}\ninterpreted: {:?}",
+            compiled_entries.get(key),
+            interpreted_entries.get(key)
+        );
+    }
+
+    std::mem::swap(&mut compiled_env.datastore, &mut interpreted_env.datastore);
+    for getter in getters {
+        let compiled = compiled_env.call_function("contract", getter, &[]);
+        let interpreted = interpreted_env.interpret_function("contract", getter, &[]);
+        assert_eq!(
+            compiled, interpreted,
+            "Runtimes read diverging values with {getter}\ncompiled: {compiled:?}\ninterpreted: {interpreted:?}"
+        );
+    }
+}
+
 // TODO: This function is a temporary solution until issue #421 is addressed.
 // Tests that call this function will need to be adjusted.
 //
@@ -743,12 +1686,6 @@ fn compare_events(events_a: &[EventBatch], events_b: &[EventBatch]) {
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum Network {
-    Mainnet,
-    Testnet,
-}
-
 pub fn crosscheck_with_network(
     network: Network,
     snippet: &str,
@@ -757,7 +1694,7 @@ pub fn crosscheck_with_network(
     let eval = match crosseval(
         snippet,
         TestEnvironment::new_with_network(
-            TestConfig::latest_epoch(),
+            TestConfig::epoch(),
             TestConfig::clarity_version(),
             network,
         ),
@@ -780,17 +1717,799 @@ pub fn crosscheck_with_network(
 #[cfg(test)]
 mod tests {
 
+    use std::time::Duration;
+
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::database::{BurnStateDB, MemoryBackingStore};
+    use clarity::vm::errors::RuntimeErrorType;
+    use clarity::vm::events::FTEventType;
+
     use super::*;
+    use crate::datastore::NodeStore;
+    use crate::linker::{FixedHeights, NonDeterministicHostCall};
+    use crate::wasm_utils::{call_function_deterministic, ReadOnlyContext};
+    use crate::HostFunctionError;
 
     #[test]
     fn test_evaluate_snippet() {
         assert_eq!(evaluate("(+ 1 2)"), Ok(Some(Value::Int(3))));
     }
 
+    #[test]
+    fn test_top_level_events() {
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        env.evaluate("(define-fungible-token foo) (print 1) (ft-mint? foo u10 tx-sender)")
+            .unwrap();
+
+        let events = &env.get_events().last().unwrap().events;
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[0],
+            StacksTransactionEvent::SmartContractEvent(_)
+        ));
+        assert!(matches!(
+            events[1],
+            StacksTransactionEvent::FTEvent(FTEventType::FTMintEvent(_))
+        ));
+    }
+
+    #[test]
+    fn test_default_network_is_testnet() {
+        let default = TestEnvironment::default();
+        let testnet = TestEnvironment::new_with_network(
+            TestConfig::epoch(),
+            TestConfig::clarity_version(),
+            Network::Testnet,
+        );
+        for env in [&default, &testnet] {
+            assert_eq!(env.burn_datastore.get_burn_start_height(), 2_000_000);
+            assert_eq!(env.burn_datastore.get_pox_reward_cycle_length(), 1050);
+        }
+    }
+
+    #[test]
+    fn test_network_profiles_are_distinct() {
+        let networks = [
+            Network::Mainnet,
+            Network::Testnet,
+            Network::Regtest,
+            Network::Mocknet,
+        ];
+        for (i, a) in networks.iter().enumerate() {
+            for b in &networks[i + 1..] {
+                let (ca, cb) = (
+                    StacksConstants::for_network(*a),
+                    StacksConstants::for_network(*b),
+                );
+                assert_ne!(a.chain_id(), b.chain_id(), "{a:?} and {b:?}");
+                assert!(
+                    (
+                        ca.burn_start_height,
+                        ca.pox_prepare_length,
+                        ca.pox_reward_cycle_length
+                    ) != (
+                        cb.burn_start_height,
+                        cb.pox_prepare_length,
+                        cb.pox_reward_cycle_length
+                    ),
+                    "{a:?} and {b:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_host_call_counts() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var v int 1) (var-set v 2) (+ (var-get v) (var-get v))",
+        )
+        .unwrap();
+
+        let host_calls = env.get_host_calls("counter").unwrap();
+        assert_eq!(host_calls.get("define_variable"), Some(&1));
+        assert_eq!(host_calls.get("set_variable"), Some(&1));
+        assert_eq!(host_calls.get("get_variable"), Some(&2));
+        assert_eq!(host_calls.get("print"), None);
+        assert!(env.get_host_calls("other").is_none());
+    }
+
+    #[test]
+    fn test_host_call_counts_of_calls() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var v int 1)
+            (define-public (incr) (ok (var-set v (+ (var-get v) 1))))",
+        )
+        .unwrap();
+        assert_eq!(
+            env.get_host_calls("counter").unwrap().get("get_variable"),
+            None
+        );
+
+        env.call_function("counter", "incr", &[]).unwrap();
+        env.call_function("counter", "incr", &[]).unwrap();
+
+        let host_calls = env.get_host_calls("counter").unwrap();
+        assert_eq!(host_calls.get("define_variable"), Some(&1));
+        assert_eq!(host_calls.get("get_variable"), Some(&2));
+        assert_eq!(host_calls.get("set_variable"), Some(&2));
+    }
+
+    #[test]
+    fn test_instrumentation_flags_are_independent() {
+        let contract_id = QualifiedContractIdentifier::local("counter").unwrap();
+        let mut datastore = MemoryBackingStore::new();
+        let result = compile(
+            "(define-data-var v int 1) (var-get v)",
+            &contract_id,
+            LimitedCostTracker::new_free(),
+            TestConfig::clarity_version(),
+            TestConfig::epoch(),
+            &mut datastore.as_analysis_db(),
+            CompileOptions::default(),
+        )
+        .unwrap();
+
+        let mut initialize = |instrumentation: Instrumentation| {
+            let mut global_context = GlobalContext::new(
+                false,
+                Network::Testnet.chain_id(),
+                datastore.as_clarity_db(),
+                LimitedCostTracker::new_free(),
+                TestConfig::epoch(),
+            );
+            let mut contract_context =
+                ContractContext::new(contract_id.clone(), TestConfig::clarity_version());
+            contract_context.set_wasm_module(result.module.emit_wasm());
+            global_context.begin();
+            let initialization = initialize_contract_with(
+                &mut global_context,
+                &mut contract_context,
+                None,
+                &result.contract_analysis,
+                instrumentation,
+            )
+            .unwrap();
+            global_context.roll_back().unwrap();
+            initialization
+        };
+
+        let initialization = initialize(Instrumentation {
+            timings: true,
+            ..Default::default()
+        });
+        assert!(initialization.timings.is_some());
+        assert!(initialization.host_calls.is_none());
+        assert!(initialization.host_call_trace.is_none());
+
+        let initialization = initialize(Instrumentation {
+            count_host_calls: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            initialization.host_calls.unwrap().get("get_variable"),
+            Some(&1)
+        );
+        assert!(initialization.timings.is_none());
+        assert!(initialization.host_call_trace.is_none());
+
+        let mut snapshot = MemorySnapshot::default();
+        let initialization = initialize(Instrumentation {
+            memory_snapshot: Some(&mut snapshot),
+            ..Default::default()
+        });
+        assert!(initialization.host_calls.is_none());
+        assert!(initialization.host_call_trace.is_none());
+        assert!(initialization.timings.is_none());
+        assert!(!snapshot.memory.is_empty());
+    }
+
+    #[test]
+    fn test_host_call_trace() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var v int 1)
+            (define-public (set (n int)) (ok (var-set v n)))
+            (var-set v 2)
+            (var-get v)",
+        )
+        .unwrap();
+
+        let trace = env.get_host_call_trace("counter").unwrap();
+        let variable_calls: Vec<_> = trace
+            .calls
+            .iter()
+            .filter(|call| call.function.ends_with("_variable"))
+            .collect();
+        assert_eq!(
+            variable_calls
+                .iter()
+                .map(|call| call.function)
+                .collect::<Vec<_>>(),
+            ["define_variable", "set_variable", "get_variable"]
+        );
+        assert_ne!(variable_calls[0].args_digest, variable_calls[1].args_digest);
+        assert!(trace.logs.is_empty());
+        assert!(env.get_host_call_trace("other").is_none());
+
+        let tracer = HostCallTracer::new();
+        env.call_function_traced("counter", "set", &[Value::Int(3)], &tracer)
+            .unwrap();
+        env.call_function("counter", "set", &[Value::Int(4)])
+            .unwrap();
+        let trace = tracer.take();
+        assert_eq!(
+            trace
+                .calls
+                .iter()
+                .filter(|call| call.function == "set_variable")
+                .count(),
+            1
+        );
+        assert_eq!(tracer.take(), HostCallTrace::default());
+    }
+
+    #[test]
+    fn test_initialization_timings() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet("counter", "(define-data-var v int 1) (var-get v)")
+            .unwrap();
+
+        let timings = env.get_timings("counter").unwrap();
+        assert!(timings.compilation > Duration::ZERO);
+        assert!(env.get_timings("other").is_none());
+    }
+
+    #[test]
+    fn test_crosscheck_timings() {
+        let timings = crosscheck_compare_only_with_epoch_and_version(
+            "(+ 1 2)",
+            TestConfig::epoch(),
+            TestConfig::clarity_version(),
+        )
+        .unwrap();
+        assert!(timings.compilation > Duration::ZERO);
+        assert!(timings.to_string().starts_with("compilation "));
+    }
+
+    #[test]
+    fn test_deterministic_host_functions() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "chain",
+            "(define-read-only (height) burn-block-height)
+             (define-read-only (pox-addrs) (get-burn-block-info? pox-addrs u1))",
+        )
+        .unwrap();
+        env.advance_chain_tip(5);
+
+        let heights = FixedHeights {
+            stacks_block_height: 42,
+            tenure_height: 42,
+            burn_block_height: 7,
+        };
+        let mut call = |function_name: &str| {
+            env.transaction("chain", |global_context, contract_context, sender| {
+                call_function_deterministic(
+                    function_name,
+                    &[],
+                    global_context,
+                    contract_context,
+                    &mut CallStack::new(),
+                    Some(sender.clone()),
+                    Some(sender),
+                    None,
+                    heights,
+                )
+            })
+        };
+
+        assert_eq!(call("height"), Ok(Value::UInt(7)));
+        match call("pox-addrs") {
+            Err(Error::Wasm(WasmError::Runtime(e))) => {
+                assert_eq!(
+                    e.downcast_ref::<HostFunctionError>(),
+                    Some(&HostFunctionError {
+                        name: "get_burn_block_info"
+                    })
+                );
+                let Some(Error::Wasm(WasmError::Runtime(cause))) =
+                    e.root_cause().downcast_ref::<Error>()
+                else {
+                    panic!("unexpected error: {e:?}");
+                };
+                assert_eq!(
+                    cause.downcast_ref::<NonDeterministicHostCall>(),
+                    Some(&NonDeterministicHostCall("get_burn_block_info"))
+                );
+            }
+            result => panic!("expected a rejected host call, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_deterministic_contract_call() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "callee",
+            "(define-read-only (height) (ok burn-block-height))",
+        )
+        .unwrap();
+        env.init_contract_with_snippet(
+            "caller",
+            "(define-read-only (height) (contract-call? .callee height))",
+        )
+        .unwrap();
+        env.advance_chain_tip(5);
+
+        // The callee would be executed with the regular host functions, and
+        // read the height of the chain tip instead of the fixed one.
+        let result = env.transaction("caller", |global_context, contract_context, sender| {
+            call_function_deterministic(
+                "height",
+                &[],
+                global_context,
+                contract_context,
+                &mut CallStack::new(),
+                Some(sender.clone()),
+                Some(sender),
+                None,
+                FixedHeights::default(),
+            )
+        });
+        match result {
+            Err(Error::Wasm(WasmError::Runtime(e))) => {
+                let Some(Error::Wasm(WasmError::Runtime(cause))) =
+                    e.root_cause().downcast_ref::<Error>()
+                else {
+                    panic!("unexpected error: {e:?}");
+                };
+                assert_eq!(
+                    cause.downcast_ref::<NonDeterministicHostCall>(),
+                    Some(&NonDeterministicHostCall("contract_call"))
+                );
+            }
+            result => panic!("expected a rejected host call, got {result:?}"),
+        }
+
+        // Outside of the deterministic mode, the call reads the chain tip.
+        let height = env.call_function("callee", "height", &[]);
+        assert!(height.is_ok());
+        assert_eq!(env.call_function("caller", "height", &[]), height);
+    }
+
+    #[test]
+    fn test_deploy_precompiled() {
+        let contract_id = QualifiedContractIdentifier::local("precompiled").unwrap();
+        let mut compile_result = compile(
+            "(define-data-var v int 1) (var-set v 5) (var-get v)",
+            &contract_id,
+            LimitedCostTracker::new_free(),
+            TestConfig::clarity_version(),
+            TestConfig::epoch(),
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .unwrap();
+        let wasm_bytes = compile_result.module.emit_wasm();
+
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        assert_eq!(
+            env.deploy_precompiled(&contract_id, &wasm_bytes, &compile_result.contract_analysis),
+            Ok(Some(Value::Int(5)))
+        );
+        assert!(env.get_contract_context("precompiled").is_some());
+        assert_eq!(
+            env.get_host_calls("precompiled")
+                .unwrap()
+                .get("set_variable"),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn test_call_function() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var count int 0)
+            (define-public (increment (n int))
+                (begin
+                    (var-set count (+ (var-get count) n))
+                    (asserts! (> n 0) (err u1))
+                    (ok (var-get count))))
+            (define-read-only (get-count) (var-get count))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            env.call_function("counter", "increment", &[Value::Int(5)]),
+            Ok(Value::okay(Value::Int(5)).unwrap())
+        );
+        // The changes of a call returning an `err` are rolled back.
+        assert_eq!(
+            env.call_function("counter", "increment", &[Value::Int(-2)]),
+            Ok(Value::error(Value::UInt(1)).unwrap())
+        );
+        assert_eq!(
+            env.call_function("counter", "get-count", &[]),
+            Ok(Value::Int(5))
+        );
+        assert!(env.call_function("other", "get-count", &[]).is_err());
+    }
+
+    #[test]
+    fn test_environment_with_node_store() {
+        let mut env = TestEnvironment::with_store(
+            NodeStore::new(Datastore::new()),
+            BurnDatastore::new(StacksConstants::for_network(Network::Mainnet)),
+            TestConfig::epoch(),
+            TestConfig::clarity_version(),
+        );
+        // The burnchain state is the one given with the store.
+        assert_eq!(env.burn_datastore.get_pox_reward_cycle_length(), 2100);
+
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var count int 0)
+            (define-public (increment (n int))
+                (begin
+                    (var-set count (+ (var-get count) n))
+                    (ok (var-get count))))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            env.call_function("counter", "increment", &[Value::Int(3)]),
+            Ok(Value::okay(Value::Int(3)).unwrap())
+        );
+        assert_eq!(
+            env.call_function("counter", "increment", &[Value::Int(2)]),
+            Ok(Value::okay(Value::Int(5)).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_environment_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TestEnvironment>();
+
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var count int 0)
+            (define-public (increment (n int))
+                (begin
+                    (var-set count (+ (var-get count) n))
+                    (ok (var-get count))))
+            (define-read-only (get-count) (var-get count))",
+        )
+        .unwrap();
+
+        let handles: Vec<_> = (1..=4)
+            .map(|n| {
+                let mut env = env.clone();
+                std::thread::spawn(move || {
+                    env.call_function("counter", "increment", &[Value::Int(n)])
+                })
+            })
+            .collect();
+        for (n, handle) in (1..=4).zip(handles) {
+            assert_eq!(
+                handle.join().unwrap(),
+                Ok(Value::okay(Value::Int(n)).unwrap())
+            );
+        }
+
+        // Each clone has its own chain state.
+        assert_eq!(
+            env.call_function("counter", "get-count", &[]),
+            Ok(Value::Int(0))
+        );
+    }
+
+    #[test]
+    fn test_call_function_prepared() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var count int 0)
+            (define-public (increment)
+                (begin
+                    (var-set count (+ (var-get count) 1))
+                    (ok (var-get count))))",
+        )
+        .unwrap();
+        let prepared = env.prepared_contracts["counter"].clone();
+        assert_eq!(prepared.link_count(), 0);
+
+        // Each call links the module prepared at deployment, instead of
+        // preparing a new one.
+        for count in 1..=3 {
+            assert_eq!(
+                env.call_function("counter", "increment", &[]),
+                Ok(Value::okay(Value::Int(count)).unwrap())
+            );
+            assert_eq!(prepared.link_count(), count as usize);
+        }
+    }
+
+    #[test]
+    fn test_interpreted_redeploy_drops_prepared_contract() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet("contract", "(define-read-only (get) 1)")
+            .unwrap();
+        assert!(env.prepared_contracts.contains_key("contract"));
+
+        // The compiled module of the previous deployment must not be called
+        // with the context of the interpreted contract.
+        env.interpret_contract_with_snippet("contract", "(define-read-only (get) 2)")
+            .unwrap();
+        assert!(!env.prepared_contracts.contains_key("contract"));
+        assert_eq!(
+            env.interpret_function("contract", "get", &[]),
+            Ok(Value::Int(2))
+        );
+    }
+
+    #[test]
+    fn test_call_read_only() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var count int 3)
+            (define-public (increment)
+                (begin
+                    (var-set count (+ (var-get count) 1))
+                    (ok (var-get count))))
+            (define-private (double) (* 2 (var-get count)))
+            (define-read-only (get-double) (double))",
+        )
+        .unwrap();
+
+        assert_eq!(
+            env.call_read_only("counter", "get-double", &[]),
+            Ok(Value::Int(6))
+        );
+        assert!(matches!(
+            env.call_read_only("counter", "increment", &[]),
+            Err(Error::Unchecked(CheckErrors::PublicFunctionNotReadOnly(..)))
+        ));
+        assert!(matches!(
+            env.call_read_only("counter", "double", &[]),
+            Err(Error::Unchecked(CheckErrors::NoSuchPublicFunction(..)))
+        ));
+        assert!(env.call_read_only("other", "get-double", &[]).is_err());
+    }
+
+    #[test]
+    fn test_read_only_context() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet(
+            "counter",
+            "(define-data-var count int 3)
+            (define-read-only (get-double) (* 2 (var-get count)))
+            (define-read-only (get-quotient (n int)) (/ (var-get count) n))",
+        )
+        .unwrap();
+        let contract_context = env.contract_contexts["counter"].clone();
+        let prepared = env.prepared_contracts["counter"].clone();
+        let sender = PrincipalData::Standard(StandardPrincipalData::transient());
+
+        // The same context runs all the calls, including after a failure.
+        let database =
+            ClarityDatabase::new(&mut env.datastore, &env.burn_datastore, &env.burn_datastore);
+        let mut context = ReadOnlyContext::new(
+            database,
+            env.network.is_mainnet(),
+            env.network.chain_id(),
+            env.epoch,
+        );
+        for n in 1..=3 {
+            assert_eq!(
+                context.call(
+                    &prepared,
+                    &contract_context,
+                    "get-quotient",
+                    &[Value::Int(n)],
+                    sender.clone(),
+                ),
+                Ok(Value::Int(3 / n))
+            );
+            assert!(context
+                .call(
+                    &prepared,
+                    &contract_context,
+                    "get-quotient",
+                    &[Value::Int(0)],
+                    sender.clone(),
+                )
+                .is_err());
+        }
+        assert_eq!(
+            context.call(&prepared, &contract_context, "get-double", &[], sender),
+            Ok(Value::Int(6))
+        );
+    }
+
+    #[test]
+    fn test_runtime_costs_match_interpreter() {
+        const BANK: &str = "
+            (define-data-var total uint u0)
+            (define-map balances principal uint)
+            (define-public (deposit (amount uint))
+                (let ((balance (default-to u0 (map-get? balances tx-sender))))
+                    (map-set balances tx-sender (+ balance amount))
+                    (var-set total (+ (var-get total) amount))
+                    (print { total: (var-get total), balance: (+ balance amount) })
+                    (ok (var-get total))))";
+
+        let mut compiled = TestEnvironment::default();
+        compiled.track_costs().unwrap();
+        compiled.init_contract_with_snippet("bank", BANK).unwrap();
+
+        let mut interpreted = TestEnvironment::default();
+        interpreted.track_costs().unwrap();
+        interpreted
+            .interpret_contract_with_snippet("bank", BANK)
+            .unwrap();
+
+        // The first deposit creates the entry of the map, the second one
+        // updates it.
+        for total in [100, 200] {
+            let args = [Value::UInt(100)];
+
+            let before = compiled.get_total_cost();
+            let value = compiled.call_function("bank", "deposit", &args);
+            let mut compiled_cost = compiled.get_total_cost();
+            compiled_cost.sub(&before).unwrap();
+
+            let before = interpreted.get_total_cost();
+            let expected = interpreted.interpret_function("bank", "deposit", &args);
+            let mut interpreted_cost = interpreted.get_total_cost();
+            interpreted_cost.sub(&before).unwrap();
+
+            assert_eq!(value, Ok(Value::okay(Value::UInt(total)).unwrap()));
+            assert_eq!(value, expected);
+
+            // The host functions charge the reads and writes of the data like
+            // the interpreter.
+            assert_eq!(compiled_cost.read_count, interpreted_cost.read_count);
+            assert_eq!(compiled_cost.read_length, interpreted_cost.read_length);
+            assert_eq!(compiled_cost.write_count, interpreted_cost.write_count);
+            assert_eq!(compiled_cost.write_length, interpreted_cost.write_length);
+            assert!(compiled_cost.write_length > 0);
+
+            // The compiled code only charges the runtime of the host
+            // functions, not the one of the other expressions.
+            assert!(compiled_cost.runtime > 0);
+            assert!(compiled_cost.runtime < interpreted_cost.runtime);
+        }
+    }
+
+    #[test]
+    fn test_memory_snapshot() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet("literal", r#"(concat "hello " "world")"#)
+            .unwrap();
+
+        let snapshot = env.get_memory_snapshot("literal").unwrap();
+        assert!(snapshot.literal_memory_end > END_OF_STANDARD_DATA as i32);
+        let literals =
+            &snapshot.memory[END_OF_STANDARD_DATA as usize..snapshot.literal_memory_end as usize];
+        assert!(literals.windows(5).any(|w| w == b"world"));
+    }
+
+    #[test]
+    fn test_format_memory_dump() {
+        let literal_memory_end = END_OF_STANDARD_DATA as usize + 8;
+        let mut memory = vec![0; 65536];
+        memory[literal_memory_end - 8..literal_memory_end].copy_from_slice(b"clarity!");
+        memory[literal_memory_end + 4] = 0x2a;
+        let snapshot = MemorySnapshot {
+            memory,
+            literal_memory_end: literal_memory_end as i32,
+            stack_pointer: literal_memory_end as i32 + 16,
+        };
+
+        let timings = InitializationTimings {
+            compilation: Duration::from_millis(12),
+            execution: Duration::from_millis(3),
+        };
+
+        let dump = format_memory_dump(
+            "(+ 1 2)",
+            &Ok(Some(Value::Int(4))),
+            &Ok(Some(Value::Int(3))),
+            &snapshot,
+            Some(&timings),
+        );
+
+        assert!(dump.starts_with(";; (+ 1 2)\n"));
+        assert!(dump.contains(";; compiled: Ok(Some(Int(4)))"));
+        assert!(dump.contains(";; expected: Ok(Some(Int(3)))"));
+        assert!(dump.contains(";; compilation 12ms, execution 3ms"));
+        assert!(dump.contains(&format!(
+            ";; literal memory [{END_OF_STANDARD_DATA}, {literal_memory_end})"
+        )));
+        assert!(dump.contains("|clarity!|"));
+        assert!(dump.contains(&format!(
+            ";; call stack [{literal_memory_end}, {})",
+            literal_memory_end + 16
+        )));
+        assert!(dump.contains(&format!("{literal_memory_end:08x}  00 00 00 00 2a 00")));
+        // The unused memory is not dumped, and the zeroed rows are elided.
+        assert!(dump.contains("\n*\n"));
+        assert!(dump.len() < 16 * 1024);
+    }
+
+    #[test]
+    fn test_crosscheck_error_message() {
+        crosscheck_error_message("(/ 1 0)", "DivisionByZero");
+        crosscheck_error_message(
+            "(+ 170141183460469231731687303715884105727 1)",
+            "ArithmeticOverflow",
+        );
+        crosscheck_error_message("(pow 2 -1)", "must be a u32 integer");
+        crosscheck_error_message("(unwrap-panic none)", "UnwrapFailure");
+        crosscheck_error_message("(unwrap-err-panic (ok 1))", "UnwrapFailure");
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected both snippets to fail")]
+    fn test_crosscheck_error_message_success() {
+        crosscheck_error_message("(+ 1 2)", "");
+    }
+
+    #[test]
+    fn test_evaluate_word() {
+        assert_eq!(
+            evaluate_word("+", &[Value::Int(1), Value::Int(2)], TypeSignature::IntType),
+            Ok(Some(Value::Int(3)))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_word_with_composite_arguments() {
+        let list = |items: Vec<i128>| {
+            Value::cons_list_unsanitized(items.into_iter().map(Value::Int).collect()).unwrap()
+        };
+        let result_ty = TypeSignature::type_of(&list(vec![1, 2, 3])).unwrap();
+        assert_eq!(
+            evaluate_word("concat", &[list(vec![1]), list(vec![2, 3])], result_ty),
+            Ok(Some(list(vec![1, 2, 3])))
+        );
+
+        assert_eq!(
+            evaluate_word(
+                "default-to",
+                &[Value::UInt(1), Value::some(Value::UInt(2)).unwrap()],
+                TypeSignature::UIntType,
+            ),
+            Ok(Some(Value::UInt(2)))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_word_runtime_error() {
+        assert!(matches!(
+            evaluate_word(
+                "+",
+                &[Value::Int(i128::MAX), Value::Int(1)],
+                TypeSignature::IntType
+            ),
+            Err(Error::Runtime(RuntimeErrorType::ArithmeticOverflow, _))
+        ));
+    }
+
     #[cfg(not(feature = "test-clarity-v1"))]
     #[test]
     fn test_compare_events() {
-        let env = TestEnvironment::new(TestConfig::latest_epoch(), TestConfig::clarity_version());
+        let env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
 
         let mut env_interpreted = env.clone();
         let interpreted = env_interpreted.interpret("(stx-transfer-memo? u1 'S1G2081040G2081040G2081040G208105NK8PE5 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM 0x010203)");
@@ -811,7 +2530,7 @@ mod tests {
     #[test]
     #[should_panic(expected = "events mismatch")]
     fn test_compare_events_mismatch() {
-        let env = TestEnvironment::new(TestConfig::latest_epoch(), TestConfig::clarity_version());
+        let env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
 
         let mut env_interpreted = env.clone();
         let interpreted = env_interpreted.interpret("(stx-transfer-memo? u1 'S1G2081040G2081040G2081040G208105NK8PE5 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM 0x010203)");
@@ -0,0 +1,268 @@
+//! Conformance of the compiled functions of a contract to the traits it
+//! implements.
+//!
+//! The analysis accepts a function implementing a trait function if its
+//! argument types admit the types of the trait, so a function can accept
+//! larger values than the trait declares. The check runs after the
+//! generation: a trait function which is not exported by the module is an
+//! error, and an argument type which is not exactly the type of the trait is
+//! reported as a warning. The return types are inferred from the bodies of
+//! the functions, and are usually narrower than the ones of the trait, they
+//! are left to the analysis.
+
+use std::collections::BTreeMap;
+
+use clarity::types::StacksEpochId;
+use clarity::vm::analysis::{AnalysisDatabase, ContractAnalysis};
+use clarity::vm::diagnostic::{Diagnostic, Level};
+use clarity::vm::representations::Span;
+use clarity::vm::types::{FunctionSignature, FunctionType, TraitIdentifier, TypeSignature};
+use clarity::vm::{ClarityName, SymbolicExpression};
+
+use crate::wasm_generator::GeneratorError;
+use crate::ContractFunction;
+
+/// Checks the functions of the contract analyzed in `contract_analysis`,
+/// found in the module as `functions`, against the traits it implements.
+/// Returns the warnings about the inexact signatures.
+pub(crate) fn check_trait_conformance(
+    analysis_db: &mut AnalysisDatabase,
+    contract_analysis: &ContractAnalysis,
+    functions: &BTreeMap<ClarityName, ContractFunction>,
+    epoch: StacksEpochId,
+) -> Result<Vec<Diagnostic>, GeneratorError> {
+    let mut warnings = Vec::new();
+    for trait_identifier in &contract_analysis.implemented_traits {
+        let impl_span = impl_trait_span(&contract_analysis.expressions, trait_identifier);
+        let signatures = trait_functions(analysis_db, contract_analysis, trait_identifier, epoch)?;
+
+        for (name, signature) in &signatures {
+            let spanned = |error: GeneratorError| match &impl_span {
+                Some(span) => error.at(span),
+                None => error,
+            };
+            let not_exported = || {
+                spanned(GeneratorError::TraitFunctionNotExported {
+                    trait_identifier: trait_identifier.to_string(),
+                    function: name.to_string(),
+                })
+            };
+            functions
+                .get(name)
+                .and_then(|function| function.export)
+                .ok_or_else(not_exported)?;
+            let Some(FunctionType::Fixed(function)) = contract_analysis
+                .get_public_function_type(name)
+                .or_else(|| contract_analysis.get_read_only_function_type(name))
+            else {
+                return Err(not_exported());
+            };
+
+            let args: Vec<_> = function.args.iter().map(|arg| &arg.signature).collect();
+            if !args.iter().copied().eq(signature.args.iter()) {
+                warnings.push(Diagnostic {
+                    level: Level::Warning,
+                    message: format!(
+                        "{name} implements {trait_identifier} with the argument types ({}), \
+                         which are not exactly the types of the trait ({})",
+                        join(args.into_iter()),
+                        join(signature.args.iter()),
+                    ),
+                    spans: definition_span(&contract_analysis.expressions, name)
+                        .into_iter()
+                        .collect(),
+                    suggestion: Some(format!(
+                        "declare the arguments of {name} with the types of the trait"
+                    )),
+                });
+            }
+        }
+    }
+    Ok(warnings)
+}
+
+/// Returns the signatures of the functions of the trait `trait_identifier`,
+/// defined by the contract itself or by a contract of `analysis_db`.
+fn trait_functions(
+    analysis_db: &mut AnalysisDatabase,
+    contract_analysis: &ContractAnalysis,
+    trait_identifier: &TraitIdentifier,
+    epoch: StacksEpochId,
+) -> Result<BTreeMap<ClarityName, FunctionSignature>, GeneratorError> {
+    let functions = if trait_identifier.contract_identifier == contract_analysis.contract_identifier
+    {
+        contract_analysis
+            .defined_traits
+            .get(&trait_identifier.name)
+            .cloned()
+    } else {
+        analysis_db
+            .get_defined_trait(
+                &trait_identifier.contract_identifier,
+                &trait_identifier.name,
+                &epoch,
+            )
+            .map_err(|e| {
+                GeneratorError::InternalError(format!(
+                    "failed to load the trait {trait_identifier}: {e}"
+                ))
+            })?
+    };
+    functions.ok_or_else(|| {
+        GeneratorError::InternalError(format!("trait not found: {trait_identifier}"))
+    })
+}
+
+/// Formats `types` like the arguments of a signature.
+fn join<'a>(types: impl Iterator<Item = &'a TypeSignature>) -> String {
+    types.map(|ty| ty.to_string()).collect::<Vec<_>>().join(" ")
+}
+
+/// Returns the span of the `(impl-trait trait_identifier)` expression.
+fn impl_trait_span(
+    expressions: &[SymbolicExpression],
+    trait_identifier: &TraitIdentifier,
+) -> Option<Span> {
+    expressions
+        .iter()
+        .find(|expr| match expr.match_list() {
+            Some([keyword, trait_expr]) => {
+                keyword.match_atom().map(|atom| atom.as_str()) == Some("impl-trait")
+                    && trait_expr.match_field() == Some(trait_identifier)
+            }
+            _ => false,
+        })
+        .map(|expr| expr.span.clone())
+}
+
+/// Returns the span of the definition of the function `name`.
+fn definition_span(expressions: &[SymbolicExpression], name: &ClarityName) -> Option<Span> {
+    expressions
+        .iter()
+        .find(|expr| match expr.match_list() {
+            Some([keyword, signature, ..]) => {
+                keyword
+                    .match_atom()
+                    .is_some_and(|atom| atom.starts_with("define-"))
+                    && signature
+                        .match_list()
+                        .and_then(|signature| signature.first())
+                        .and_then(|function| function.match_atom())
+                        == Some(name)
+            }
+            _ => false,
+        })
+        .map(|expr| expr.span.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::types::StacksEpochId;
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
+    use clarity::vm::diagnostic::{Diagnostic, Level};
+    use clarity::vm::types::QualifiedContractIdentifier;
+    use clarity::vm::{ClarityName, ClarityVersion};
+
+    use super::check_trait_conformance;
+    use crate::{compile, CompileOptions, CompileResult};
+
+    const TRAIT: &str =
+        "(define-trait named ((get-name ((buff 8)) (response (string-ascii 16) uint))))";
+
+    fn compile_with_trait(store: &mut MemoryBackingStore, source: &str) -> CompileResult {
+        let mut analysis_db = AnalysisDatabase::new(store);
+        let trait_id = QualifiedContractIdentifier::local("trait").unwrap();
+        let trait_result = compile(
+            TRAIT,
+            &trait_id,
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut analysis_db,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile trait");
+        analysis_db
+            .execute(|db| db.insert_contract(&trait_id, &trait_result.contract_analysis))
+            .unwrap();
+
+        compile(
+            source,
+            &QualifiedContractIdentifier::local("impl").unwrap(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut analysis_db,
+            CompileOptions::default(),
+        )
+        .expect("failed to compile contract")
+    }
+
+    fn warnings(result: &CompileResult) -> Vec<&Diagnostic> {
+        result
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.level == Level::Warning)
+            .collect()
+    }
+
+    #[test]
+    fn exact_implementation() {
+        let result = compile_with_trait(
+            &mut MemoryBackingStore::new(),
+            r#"(impl-trait .trait.named)
+               (define-public (get-name (id (buff 8))) (ok "name"))"#,
+        );
+        assert!(warnings(&result).is_empty(), "{:?}", result.diagnostics);
+    }
+
+    #[test]
+    fn inexact_argument_types() {
+        let result = compile_with_trait(
+            &mut MemoryBackingStore::new(),
+            r#"(impl-trait .trait.named)
+               (define-public (get-name (id (buff 32))) (ok "name"))"#,
+        );
+        let warnings = warnings(&result);
+        assert_eq!(warnings.len(), 1, "{:?}", result.diagnostics);
+        assert!(
+            warnings[0].message.contains("(buff 32)") && warnings[0].message.contains("(buff 8)"),
+            "{}",
+            warnings[0].message
+        );
+        assert_eq!(warnings[0].spans[0].start_line, 2);
+    }
+
+    #[test]
+    fn trait_function_not_exported() {
+        let mut store = MemoryBackingStore::new();
+        let mut result = compile_with_trait(
+            &mut store,
+            r#"(impl-trait .trait.named)
+               (define-public (get-name (id (buff 8))) (ok "name"))"#,
+        );
+
+        // The analysis only accepts public and read-only functions, which
+        // are always exported: drop the export to check the module.
+        result
+            .functions
+            .get_mut(&ClarityName::from("get-name"))
+            .unwrap()
+            .export = None;
+        let error = check_trait_conformance(
+            &mut AnalysisDatabase::new(&mut store),
+            &result.contract_analysis,
+            &result.functions,
+            StacksEpochId::Epoch25,
+        )
+        .unwrap_err();
+        assert_eq!(error.code(), 7);
+        assert_eq!(error.span().map(|span| span.start_line), Some(1));
+        assert!(
+            error.to_string().contains("get-name of the trait"),
+            "{error}"
+        );
+    }
+}
@@ -2,43 +2,59 @@ use core::panic;
 use std::borrow::BorrowMut;
 use std::cell::RefCell;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Deref;
 use std::rc::Rc;
 
+use clarity::types::StacksEpochId;
 use clarity::vm::analysis::ContractAnalysis;
-use clarity::vm::diagnostic::DiagnosableError;
+use clarity::vm::diagnostic::{DiagnosableError, Diagnostic, Level};
+use clarity::vm::representations::Span;
 use clarity::vm::types::signatures::{CallableSubtype, StringUTF8Length, BUFF_1};
 use clarity::vm::types::{
     ASCIIData, CharType, FixedFunction, FunctionType, ListTypeData, PrincipalData, SequenceData,
     SequenceSubtype, StringSubtype, TupleTypeSignature, TypeSignature,
 };
 use clarity::vm::variables::NativeVariables;
-use clarity::vm::{functions, variables, ClarityName, SymbolicExpression, SymbolicExpressionType};
+use clarity::vm::{
+    functions, variables, ClarityName, ClarityVersion, SymbolicExpression, SymbolicExpressionType,
+};
 use walrus::ir::{
-    BinaryOp, IfElse, InstrSeqId, InstrSeqType, LoadKind, MemArg, StoreKind, UnaryOp,
+    BinaryOp, ExtendedLoad, IfElse, InstrLocId, InstrSeqId, InstrSeqType, LoadKind, MemArg,
+    StoreKind, UnaryOp,
 };
 use walrus::{
-    ActiveData, DataKind, FunctionBuilder, FunctionId, GlobalId, InstrSeqBuilder, LocalId,
+    ActiveData, DataId, DataKind, FunctionBuilder, FunctionId, GlobalId, InstrSeqBuilder, LocalId,
     MemoryId, Module, ValType,
 };
 
+use crate::debug_print::LogLevel;
 use crate::error_mapping::ErrorMap;
+use crate::sections::{ModuleError, ABI_VERSION_SECTION};
+use crate::target_features::{Feature, TargetFeatures};
 use crate::wasm_utils::{
-    check_argument_count, get_type_in_memory_size, get_type_size, is_in_memory_type,
-    signature_from_string, ArgumentCountCheck,
+    check_argument_count, contains_in_memory_type, get_type_in_memory_size, get_type_size,
+    is_in_memory_type, signature_from_string, ArgumentCountCheck,
 };
-use crate::{check_args, debug_msg, words};
+use crate::{check_args, debug_msg, sections, words, DEFAULT_HOST_MODULE};
 
 // First free position after data directly defined in standard.wat
 pub const END_OF_STANDARD_DATA: u32 = 1352;
 
+const WASM_PAGE_SIZE: u64 = 64 * 1024;
+// Maximum number of pages of a 32-bit memory.
+const MAX_MEMORY_PAGES: u64 = 1 << 16;
+
 /// WasmGenerator is a Clarity AST visitor that generates a WebAssembly module
 /// as it traverses the AST.
 pub struct WasmGenerator {
     /// The contract analysis, which contains the expressions and type
     /// information for the contract.
     pub(crate) contract_analysis: ContractAnalysis,
+    /// The epoch the contract is compiled for.
+    epoch: StacksEpochId,
+    /// The Clarity version of the contract.
+    clarity_version: ClarityVersion,
     /// The WebAssembly module that is being generated.
     pub(crate) module: Module,
     /// Offset of the end of the literal memory.
@@ -49,8 +65,13 @@ pub struct WasmGenerator {
     pub(crate) literal_memory_offset: HashMap<LiteralMemoryEntry, u32>,
     /// Map constants to an offset in the literal memory.
     pub(crate) constants: HashMap<String, u32>,
+    /// Names of the functions defined so far.
+    defined_functions: HashSet<ClarityName>,
     /// The current function body block, used for early exit
     early_return_block_id: Option<InstrSeqId>,
+    /// Number of `as-contract` expressions enclosing the expression being
+    /// traversed, which must be exited before an early exit.
+    pub(crate) as_contract_depth: usize,
     /// The type of the current function.
     pub(crate) current_function_type: Option<FixedFunction>,
     /// The types of defined data-vars
@@ -62,12 +83,39 @@ pub struct WasmGenerator {
 
     /// The locals for the current function.
     pub(crate) bindings: Bindings,
-    /// Size of the current function's stack frame.
-    frame_size: i32,
+    /// The stack frame of the current function.
+    frame: Frame,
+    /// The stack frames of the functions generated so far.
+    frames: HashMap<ClarityName, Frame>,
     /// Size of the maximum extra work space required by the stdlib functions
     /// to be available on the stack.
     max_work_space: u32,
     local_pool: Rc<RefCell<HashMap<ValType, Vec<LocalId>>>>,
+    /// Ids of the `debug-print` expressions to compile, with their level.
+    pub(crate) debug_prints: HashMap<u64, LogLevel>,
+    /// Check the values read from and written to the memory, see
+    /// [`crate::CompileOptions::memory_checks`].
+    pub(crate) memory_checks: bool,
+    /// Compile the map lookups over a list to one host call, see
+    /// [`crate::CompileOptions::batch_map_reads`].
+    pub(crate) batch_map_reads: bool,
+    /// The WebAssembly features the generated code may use, see
+    /// [`crate::CompileOptions::target_features`].
+    pub(crate) target_features: TargetFeatures,
+    /// Span of the expression being traversed, named by the runtime checks.
+    current_span: Span,
+    /// Warnings about the contract found during generation.
+    diagnostics: Vec<Diagnostic>,
+    /// Globals caching the results of the [`CACHED_HEIGHT_FUNCTIONS`], added
+    /// to the module when a height is first read.
+    height_caches: BTreeMap<&'static str, GlobalId>,
+    /// Ids of the [`STDLIB_FUNCTIONS`] and of the [`STDLIB_SIMD_FUNCTIONS`]
+    /// defined by the standard library, looked up once when the generator is
+    /// created.
+    stdlib_functions: HashMap<&'static str, FunctionId>,
+    /// Module from which the host functions are imported, see
+    /// [`WasmGenerator::set_host_module`].
+    host_module: String,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -111,14 +159,116 @@ pub enum LiteralMemoryEntry {
     Bytes(Box<[u8]>),
 }
 
-#[derive(Debug)]
+/// Errors raised while generating the Wasm module of a contract.
+///
+/// Each kind of error has a stable numeric code, see [`GeneratorError::code`],
+/// which prefixes the message of its diagnostic.
+#[derive(Debug, thiserror::Error)]
 pub enum GeneratorError {
+    #[error("Not implemented")]
     NotImplemented,
+    #[error("Internal error: {0}")]
     InternalError(String),
+    #[error("Type error: {0}")]
     TypeError(String),
+    #[error("Argument count mismatch")]
     ArgumentCountMismatch,
+    /// A call to a name which is neither a builtin nor a function of the contract.
+    #[error("Unsupported word: {name}")]
+    UnsupportedWord { name: String },
+    /// The memory of the module cannot hold the contract.
+    #[error("Memory layout error: {0}")]
+    MemoryLayout(String),
+    /// A function of a trait implemented by the contract is not exported by
+    /// the module.
+    #[error("{function} of the trait {trait_identifier} is not exported")]
+    TraitFunctionNotExported {
+        trait_identifier: String,
+        function: String,
+    },
+    /// A definition or a binding of a name reserved by Clarity, with the
+    /// message of the analysis.
+    #[error("defining '{0}' conflicts with previous value")]
+    NameAlreadyUsed(String),
+    /// The module uses a WebAssembly feature which is not in the target
+    /// features, see [`crate::CompileOptions::target_features`].
+    #[error("the module uses the WebAssembly feature {feature}, which is not a target feature: {message}")]
+    UnsupportedFeature { feature: Feature, message: String },
+    /// An error raised while traversing the expression at `span`.
+    #[error("{error}")]
+    Spanned {
+        error: Box<GeneratorError>,
+        span: Span,
+    },
+}
+
+impl GeneratorError {
+    /// The stable numeric code of the error. Codes are never reused.
+    pub fn code(&self) -> u32 {
+        match self {
+            GeneratorError::NotImplemented => 1,
+            GeneratorError::InternalError(_) => 2,
+            GeneratorError::TypeError(_) => 3,
+            GeneratorError::ArgumentCountMismatch => 4,
+            GeneratorError::UnsupportedWord { .. } => 5,
+            GeneratorError::MemoryLayout(_) => 6,
+            GeneratorError::TraitFunctionNotExported { .. } => 7,
+            GeneratorError::NameAlreadyUsed(_) => 8,
+            GeneratorError::UnsupportedFeature { .. } => 9,
+            GeneratorError::Spanned { error, .. } => error.code(),
+        }
+    }
+
+    /// The location in the source of the expression which raised the error.
+    pub fn span(&self) -> Option<&Span> {
+        match self {
+            GeneratorError::Spanned { span, .. } => Some(span),
+            _ => None,
+        }
+    }
+
+    /// Attaches `span` to the error, unless it already has a more precise one.
+    pub(crate) fn at(self, span: &Span) -> Self {
+        match self {
+            GeneratorError::Spanned { .. } => self,
+            error => GeneratorError::Spanned {
+                error: Box::new(error),
+                span: span.clone(),
+            },
+        }
+    }
+}
+
+/// Whether a function returning a value of type `ty` leaves the value in
+/// memory to its caller, instead of the caller copying it.
+///
+/// A sequence, whose elements don't refer to other values in memory, is copied
+/// by the caller to its own frame. Values containing several in-memory values,
+/// like a response wrapping a tuple of lists, need a copy of each of them, and
+/// their offsets to be rewritten: the callee compacts them at the start of its
+/// frame before returning (see [`WasmGenerator::compact_value`]), and pops the
+/// rest of its frame.
+pub(crate) fn returns_in_frame(ty: &TypeSignature) -> bool {
+    match ty {
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list)) => {
+            contains_in_memory_type(list.get_list_item_type())
+        }
+        ty => !is_in_memory_type(ty) && contains_in_memory_type(ty),
+    }
+}
+
+/// The number of bytes of the values in memory referred to by a value of type
+/// `ty`, which a function returning it leaves to its caller.
+pub(crate) fn referenced_size(ty: &TypeSignature) -> i32 {
+    if contains_in_memory_type(ty) {
+        get_type_in_memory_size(ty, true) - get_type_size(ty)
+    } else {
+        0
+    }
 }
 
+/// The kind of a function of the contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FunctionKind {
     Public,
     Private,
@@ -127,12 +277,7 @@ pub enum FunctionKind {
 
 impl DiagnosableError for GeneratorError {
     fn message(&self) -> String {
-        match self {
-            GeneratorError::NotImplemented => "Not implemented".to_string(),
-            GeneratorError::InternalError(msg) => format!("Internal error: {}", msg),
-            GeneratorError::TypeError(msg) => format!("Type error: {}", msg),
-            GeneratorError::ArgumentCountMismatch => "Argument count mismatch".to_string(),
-        }
+        format!("[G{:03}] {self}", self.code())
     }
 
     fn suggestion(&self) -> Option<String> {
@@ -262,6 +407,350 @@ pub fn type_from_sequence_element(se: &SequenceElementType) -> TypeSignature {
     }
 }
 
+/// Host functions returning a height of the chain. The heights can't change
+/// while a contract runs, except when entering or exiting `at-block`, so their
+/// results are cached in globals: reading a height in a loop, or in a function
+/// called for each element of a sequence, calls the host once.
+const CACHED_HEIGHT_FUNCTIONS: [&str; 4] = [
+    "block_height",
+    "stacks_block_height",
+    "tenure_height",
+    "burn_block_height",
+];
+
+/// Name of the routine copying memory on the targets without the bulk memory
+/// operations, see [`WasmGenerator::copy_memory`].
+const COPY_MEMORY_ROUTINE: &str = ".copy-memory";
+
+/// How the generated code copies memory, with the destination, the source
+/// and the length on top of the stack, see [`WasmGenerator::copy_memory`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum CopyMemory {
+    /// The `memory.copy` instruction of the bulk memory operations.
+    Instruction(MemoryId),
+    /// A call to the [`COPY_MEMORY_ROUTINE`].
+    Routine(FunctionId),
+}
+
+impl CopyMemory {
+    /// Emits the copy into `builder`.
+    pub(crate) fn emit<'a, 'b>(
+        self,
+        builder: &'a mut InstrSeqBuilder<'b>,
+    ) -> &'a mut InstrSeqBuilder<'b> {
+        match self {
+            CopyMemory::Instruction(memory) => builder.memory_copy(memory, memory),
+            CopyMemory::Routine(routine) => builder.call(routine),
+        }
+    }
+}
+
+/// Builds the routine `(destination, source, length)` copying `length` bytes
+/// like `memory.copy`, one byte at a time. The bytes are copied from the end
+/// when the destination is after the source, so that overlapping ranges are
+/// copied correctly. Unlike `memory.copy`, a copy out of the memory traps
+/// after copying the bytes which are in the memory.
+fn build_copy_memory(module: &mut Module, memory: MemoryId) -> FunctionId {
+    let destination = module.locals.add(ValType::I32);
+    let source = module.locals.add(ValType::I32);
+    let length = module.locals.add(ValType::I32);
+    let byte = MemArg {
+        align: 1,
+        offset: 0,
+    };
+    let load = LoadKind::I32_8 {
+        kind: ExtendedLoad::ZeroExtend,
+    };
+    let store = StoreKind::I32_8 { atomic: false };
+
+    let mut func = FunctionBuilder::new(&mut module.types, &[ValType::I32; 3], &[]);
+    func.func_body()
+        .local_get(destination)
+        .local_get(source)
+        .binop(BinaryOp::I32GtU)
+        .if_else(
+            None,
+            |backward| {
+                backward.block(None, |done| {
+                    let done_id = done.id();
+                    done.loop_(None, |loop_| {
+                        let loop_id = loop_.id();
+                        loop_
+                            .local_get(length)
+                            .unop(UnaryOp::I32Eqz)
+                            .br_if(done_id)
+                            .local_get(length)
+                            .i32_const(1)
+                            .binop(BinaryOp::I32Sub)
+                            .local_set(length);
+                        loop_
+                            .local_get(destination)
+                            .local_get(length)
+                            .binop(BinaryOp::I32Add)
+                            .local_get(source)
+                            .local_get(length)
+                            .binop(BinaryOp::I32Add)
+                            .load(memory, load, byte)
+                            .store(memory, store, byte)
+                            .br(loop_id);
+                    });
+                });
+            },
+            |forward| {
+                forward.block(None, |done| {
+                    let done_id = done.id();
+                    done.loop_(None, |loop_| {
+                        let loop_id = loop_.id();
+                        loop_
+                            .local_get(length)
+                            .unop(UnaryOp::I32Eqz)
+                            .br_if(done_id)
+                            .local_get(destination)
+                            .local_get(source)
+                            .load(memory, load, byte)
+                            .store(memory, store, byte);
+                        for (local, step) in [(destination, 1), (source, 1), (length, -1)] {
+                            loop_
+                                .local_get(local)
+                                .i32_const(step)
+                                .binop(BinaryOp::I32Add)
+                                .local_set(local);
+                        }
+                        loop_.br(loop_id);
+                    });
+                });
+            },
+        );
+    func.finish(vec![destination, source, length], &mut module.funcs)
+}
+
+/// Functions of the standard library called by the generated code, which
+/// must all be in `standard.wasm`. The words build some of these names from
+/// their types, e.g. `stdlib.add-int` and `stdlib.add-uint`; a test of the words
+/// compiles every word with all the argument types to check that none is
+/// missing.
+const STDLIB_FUNCTIONS: &[&str] = &[
+    // Host functions
+    "stdlib.define_function",
+    "stdlib.define_variable",
+    "stdlib.define_ft",
+    "stdlib.define_nft",
+    "stdlib.define_map",
+    "stdlib.define_trait",
+    "stdlib.impl_trait",
+    "stdlib.get_variable",
+    "stdlib.set_variable",
+    "stdlib.print",
+    "stdlib.enter_as_contract",
+    "stdlib.exit_as_contract",
+    "stdlib.enter_at_block",
+    "stdlib.exit_at_block",
+    "stdlib.stx_get_balance",
+    "stdlib.stx_account",
+    "stdlib.stx_burn",
+    "stdlib.stx_transfer",
+    "stdlib.ft_get_supply",
+    "stdlib.ft_get_balance",
+    "stdlib.ft_burn",
+    "stdlib.ft_mint",
+    "stdlib.ft_transfer",
+    "stdlib.nft_get_owner",
+    "stdlib.nft_burn",
+    "stdlib.nft_mint",
+    "stdlib.nft_transfer",
+    "stdlib.map_get",
+    "stdlib.map_set",
+    "stdlib.map_insert",
+    "stdlib.map_delete",
+    "stdlib.get_block_info",
+    "stdlib.get_burn_block_info",
+    "stdlib.get_stacks_block_info",
+    "stdlib.get_tenure_info",
+    "stdlib.contract_call",
+    "stdlib.begin_public_call",
+    "stdlib.begin_read_only_call",
+    "stdlib.commit_call",
+    "stdlib.roll_back_call",
+    "stdlib.keccak256",
+    "stdlib.sha512_256",
+    "stdlib.secp256k1_recover",
+    "stdlib.secp256k1_verify",
+    "stdlib.principal_of",
+    "stdlib.tx_sender",
+    "stdlib.contract_caller",
+    "stdlib.tx_sponsor",
+    "stdlib.block_height",
+    "stdlib.stacks_block_height",
+    "stdlib.tenure_height",
+    "stdlib.burn_block_height",
+    "stdlib.stx_liquid_supply",
+    "stdlib.save_constant",
+    "stdlib.load_constant",
+    "stdlib.is_in_regtest",
+    "stdlib.is_in_mainnet",
+    "stdlib.chain_id",
+    // Arithmetic
+    "stdlib.add-int",
+    "stdlib.add-uint",
+    "stdlib.sub-int",
+    "stdlib.sub-uint",
+    "stdlib.mul-int",
+    "stdlib.mul-uint",
+    "stdlib.div-int",
+    "stdlib.div-uint",
+    "stdlib.mod-int",
+    "stdlib.mod-uint",
+    "stdlib.pow-int",
+    "stdlib.pow-uint",
+    "stdlib.sqrti-int",
+    "stdlib.sqrti-uint",
+    "stdlib.log2-int",
+    "stdlib.log2-uint",
+    // Comparison
+    "stdlib.lt-int",
+    "stdlib.lt-uint",
+    "stdlib.lt-buff",
+    "stdlib.gt-int",
+    "stdlib.gt-uint",
+    "stdlib.gt-buff",
+    "stdlib.le-int",
+    "stdlib.le-uint",
+    "stdlib.le-buff",
+    "stdlib.ge-int",
+    "stdlib.ge-uint",
+    "stdlib.ge-buff",
+    "stdlib.is-eq-int",
+    "stdlib.is-eq-bytes",
+    "stdlib.not",
+    // Bitwise
+    "stdlib.bit-and",
+    "stdlib.bit-or",
+    "stdlib.bit-xor",
+    "stdlib.bit-not",
+    "stdlib.bit-shift-left",
+    "stdlib.bit-shift-right-int",
+    "stdlib.bit-shift-right-uint",
+    // Hashing
+    "stdlib.sha256-buf",
+    "stdlib.sha256-int",
+    "stdlib.sha512-buf",
+    "stdlib.sha512-int",
+    "stdlib.hash160-buf",
+    "stdlib.hash160-int",
+    // Conversion
+    "stdlib.to-int",
+    "stdlib.to-uint",
+    "stdlib.string-to-int",
+    "stdlib.string-to-uint",
+    "stdlib.utf8-to-int",
+    "stdlib.utf8-to-uint",
+    "stdlib.int-to-string",
+    "stdlib.uint-to-string",
+    "stdlib.int-to-utf8",
+    "stdlib.uint-to-utf8",
+    "stdlib.convert-scalars-to-utf8",
+    "stdlib.utf8-to-string-utf8",
+    "stdlib.is-valid-string-ascii",
+    "stdlib.buff-to-uint-be",
+    "stdlib.buff-to-uint-le",
+    // Principals and serialization
+    "stdlib.principal-construct",
+    "stdlib.is-version-valid",
+    "stdlib.store-i32-be",
+    "stdlib.store-i64-be",
+    "stdlib.load-i32-be",
+    "stdlib.bsearch-clarity-name",
+    "stdlib.check-clarity-name",
+    "stdlib.skip-unknown-value",
+    "stdlib.runtime-error",
+];
+
+/// SIMD variants of some [`STDLIB_FUNCTIONS`], called instead of them when
+/// the target allows SIMD, see [`WasmGenerator::target_func_by_name`]. A
+/// standard library built for the targets without SIMD doesn't need to
+/// define them.
+const STDLIB_SIMD_FUNCTIONS: &[&str] = &[
+    "stdlib.is-eq-bytes-simd",
+    "stdlib.sha256-buf-simd",
+    "stdlib.sha256-int-simd",
+];
+
+/// Looks up the [`STDLIB_FUNCTIONS`] in `module`, with a single error naming
+/// all the functions which are missing, and the [`STDLIB_SIMD_FUNCTIONS`] it
+/// defines.
+fn stdlib_functions(module: &Module) -> Result<HashMap<&'static str, FunctionId>, GeneratorError> {
+    let mut functions =
+        HashMap::with_capacity(STDLIB_FUNCTIONS.len() + STDLIB_SIMD_FUNCTIONS.len());
+    let mut missing = Vec::new();
+    for &name in STDLIB_FUNCTIONS {
+        match module.funcs.by_name(name) {
+            Some(id) => {
+                functions.insert(name, id);
+            }
+            None => missing.push(name),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(GeneratorError::InternalError(format!(
+            "standard library is missing: {}",
+            missing.join(", ")
+        )));
+    }
+    for &name in STDLIB_SIMD_FUNCTIONS {
+        if let Some(id) = module.funcs.by_name(name) {
+            functions.insert(name, id);
+        }
+    }
+    Ok(functions)
+}
+
+/// The standard library bundled with clar2wasm, built from `standard.wat`,
+/// or copied from the file named by `CLAR2WASM_STDLIB` at build time.
+pub const STANDARD_LIBRARY: &[u8] = include_bytes!("standard/standard.wasm");
+
+/// Parses the standard library `wasm`. An external standard library must
+/// record the ABI version it was built for, the bundled one is only checked
+/// if it records one. The section is removed, the generated module gets its
+/// own.
+fn load_standard_library(wasm: &[u8], external: bool) -> Result<Module, GeneratorError> {
+    let mut module = Module::from_buffer(wasm).map_err(|_err| {
+        GeneratorError::InternalError("failed to load standard library".to_owned())
+    })?;
+    let has_abi_version = !matches!(
+        sections::abi_version(&module),
+        Err(ModuleError::MissingSection(_))
+    );
+    if external || has_abi_version {
+        sections::validate(&module)
+            .map_err(|e| GeneratorError::InternalError(format!("invalid standard library: {e}")))?;
+    }
+    module.customs.remove_raw(ABI_VERSION_SECTION);
+    Ok(module)
+}
+
+/// Value of a height cache holding no height, which are `u32`s.
+const EMPTY_HEIGHT_CACHE: i64 = -1;
+
+/// Sets the location of the instructions added to `builder` since `start`,
+/// and not already set by a nested expression, to the id of `expr`. The
+/// locations don't change the emitted module, they are read by
+/// [`crate::disassemble`] to name the expression which generated each
+/// instruction.
+fn tag_instructions(builder: &mut InstrSeqBuilder, start: usize, expr: &SymbolicExpression) {
+    let Some(loc) = u32::try_from(expr.id)
+        .ok()
+        .filter(|id| *id != 0 && *id != u32::MAX)
+        .map(InstrLocId::new)
+    else {
+        return;
+    };
+    for (_, instr_loc) in builder.instrs_mut().iter_mut().skip(start) {
+        if instr_loc.is_default() {
+            *instr_loc = loc;
+        }
+    }
+}
+
 fn get_global(module: &Module, name: &str) -> Result<GlobalId, GeneratorError> {
     module
         .globals
@@ -278,6 +767,18 @@ fn get_global(module: &Module, name: &str) -> Result<GlobalId, GeneratorError> {
         })
 }
 
+/// The part of the call stack used by a function, see
+/// [`WasmGenerator::call_stack_size`].
+#[derive(Debug, Default)]
+struct Frame {
+    /// Size of the locals currently reserved in the frame.
+    size: i32,
+    /// Largest size the frame reaches.
+    max_size: i32,
+    /// The functions of the contract called from the frame.
+    callees: HashSet<ClarityName>,
+}
+
 pub(crate) struct BorrowedLocal {
     id: LocalId,
     ty: ValType,
@@ -303,53 +804,142 @@ impl Deref for BorrowedLocal {
 }
 
 impl WasmGenerator {
-    pub fn new(contract_analysis: ContractAnalysis) -> Result<WasmGenerator, GeneratorError> {
-        let standard_lib_wasm: &[u8] = include_bytes!("standard/standard.wasm");
+    /// Creates a generator for the contract `contract_analysis`, compiled for
+    /// `epoch` with the Clarity version `clarity_version`. They are recorded in
+    /// the generated module, see [`crate::sections::target`].
+    pub fn new(
+        contract_analysis: ContractAnalysis,
+        epoch: StacksEpochId,
+        clarity_version: ClarityVersion,
+    ) -> Result<WasmGenerator, GeneratorError> {
+        let module = load_standard_library(STANDARD_LIBRARY, false)?;
+        Self::with_module(contract_analysis, epoch, clarity_version, module)
+    }
 
-        let module = Module::from_buffer(standard_lib_wasm).map_err(|_err| {
-            GeneratorError::InternalError("failed to load standard library".to_owned())
-        })?;
+    /// Creates a generator like [`WasmGenerator::new`], which builds the
+    /// module from the precompiled standard library `standard_lib_wasm`
+    /// instead of the bundled one. It must record the ABI version of this
+    /// version of clar2wasm in its [`ABI_VERSION_SECTION`], and define all
+    /// the functions of `standard.wat` called by the generated code.
+    pub fn with_standard_library(
+        contract_analysis: ContractAnalysis,
+        epoch: StacksEpochId,
+        clarity_version: ClarityVersion,
+        standard_lib_wasm: &[u8],
+    ) -> Result<WasmGenerator, GeneratorError> {
+        let module = load_standard_library(standard_lib_wasm, true)?;
+        Self::with_module(contract_analysis, epoch, clarity_version, module)
+    }
+
+    fn with_module(
+        contract_analysis: ContractAnalysis,
+        epoch: StacksEpochId,
+        clarity_version: ClarityVersion,
+        module: Module,
+    ) -> Result<WasmGenerator, GeneratorError> {
         // Get the stack-pointer global ID
         let global_id = get_global(&module, "stack-pointer")?;
+        let stdlib_functions = stdlib_functions(&module)?;
 
         Ok(WasmGenerator {
             contract_analysis,
+            epoch,
+            clarity_version,
             module,
             literal_memory_end: END_OF_STANDARD_DATA,
             stack_pointer: global_id,
             literal_memory_offset: HashMap::new(),
             constants: HashMap::new(),
+            defined_functions: HashSet::new(),
             bindings: Bindings::new(),
             early_return_block_id: None,
+            as_contract_depth: 0,
             current_function_type: None,
-            frame_size: 0,
+            frame: Frame::default(),
+            frames: HashMap::new(),
             max_work_space: 0,
             datavars_types: HashMap::new(),
             maps_types: HashMap::new(),
             local_pool: Rc::new(RefCell::new(HashMap::new())),
             nft_types: HashMap::new(),
+            debug_prints: HashMap::new(),
+            memory_checks: false,
+            batch_map_reads: false,
+            target_features: TargetFeatures::ALL,
+            current_span: Span::zero(),
+            diagnostics: Vec::new(),
+            height_caches: BTreeMap::new(),
+            stdlib_functions,
+            host_module: DEFAULT_HOST_MODULE.to_owned(),
         })
     }
 
+    /// Imports the host functions from `host_module` instead of
+    /// [`DEFAULT_HOST_MODULE`]. The imports of the standard library are moved
+    /// to it, and the host functions imported by the generated code are
+    /// imported from it.
+    pub fn set_host_module(&mut self, host_module: &str) {
+        for import in self.module.imports.iter_mut() {
+            if import.module == self.host_module {
+                import.module = host_module.to_owned();
+            }
+        }
+        self.host_module = host_module.to_owned();
+    }
+
+    /// The epoch the contract is being compiled for. A contract can be called
+    /// in a later epoch than the one it was deployed in, so behavior which
+    /// depends on the epoch of the call is decided by the host functions.
+    pub(crate) fn epoch(&self) -> StacksEpochId {
+        self.epoch
+    }
+
+    /// The Clarity version of the contract, which selects the words and
+    /// keywords available to it.
+    pub(crate) fn clarity_version(&self) -> ClarityVersion {
+        self.clarity_version
+    }
+
+    /// Sets the initial size of the memory so that it holds the literal
+    /// memory, the call stack and the work space of the standard library,
+    /// which are all known at compile time. A module whose memory cannot be
+    /// that large is rejected here, instead of failing at instantiation.
     pub fn set_memory_pages(&mut self) -> Result<(), GeneratorError> {
         let memory = self
             .module
             .memories
             .iter_mut()
             .next()
-            .ok_or_else(|| GeneratorError::InternalError("No Memory found".to_owned()))?;
-
-        let total_memory_bytes =
-            self.literal_memory_end + (self.frame_size as u32) + self.max_work_space;
-        let pages_required = total_memory_bytes / (64 * 1024);
-        let remainder = total_memory_bytes % (64 * 1024);
+            .ok_or_else(|| GeneratorError::MemoryLayout("No Memory found".to_owned()))?;
+
+        let total_memory_bytes = self.literal_memory_end as u64
+            + self.call_stack_size(&self.frame)
+            + self.max_work_space as u64;
+        let pages_required = total_memory_bytes.div_ceil(WASM_PAGE_SIZE);
+
+        let max_pages = memory
+            .maximum
+            .map_or(MAX_MEMORY_PAGES, |max| MAX_MEMORY_PAGES.min(max as u64));
+        if pages_required > max_pages {
+            return Err(GeneratorError::MemoryLayout(format!(
+                "contract requires {pages_required} pages of memory, but at most {max_pages} are available"
+            )));
+        }
 
-        memory.initial = pages_required + (remainder > 0) as u32;
+        memory.initial = pages_required as u32;
 
         Ok(())
     }
 
-    pub fn generate(mut self) -> Result<Module, GeneratorError> {
+    pub fn generate(self) -> Result<Module, GeneratorError> {
+        self.generate_with_diagnostics().map(|(module, _)| module)
+    }
+
+    /// Like [`WasmGenerator::generate`], also returning the warnings found
+    /// during generation.
+    pub fn generate_with_diagnostics(
+        mut self,
+    ) -> Result<(Module, Vec<Diagnostic>), GeneratorError> {
         let expressions = std::mem::take(&mut self.contract_analysis.expressions);
 
         // Get the type of the last top-level expression with a return value
@@ -370,6 +960,7 @@ impl WasmGenerator {
 
         let top_level = current_function.finish(vec![], &mut self.module.funcs);
         self.module.exports.add(".top-level", top_level);
+        sections::add_target(&mut self.module, self.epoch, self.clarity_version);
 
         self.set_memory_pages()?;
 
@@ -379,7 +970,207 @@ impl WasmGenerator {
             walrus::InitExpr::Value(walrus::ir::Value::I32(self.literal_memory_end as i32)),
         );
 
-        Ok(self.module)
+        Ok((self.module, self.diagnostics))
+    }
+
+    /// Regenerates the function `name` of `module`, a module generated for a
+    /// previous version of the contract, from the analysis of the new version
+    /// given to [`WasmGenerator::new`].
+    /// The other functions and the literal memory of the module are kept as
+    /// they are, and the literals of the new function are appended to the
+    /// literal memory.
+    ///
+    /// The signature of the function must not have changed, and the module
+    /// must have kept the names of its functions and globals. On failure, the
+    /// functions and the memory of `module` are left unchanged.
+    pub fn regenerate_function(
+        mut self,
+        module: &mut Module,
+        name: &str,
+    ) -> Result<(), GeneratorError> {
+        self.module = std::mem::take(module);
+        let data_segments: HashSet<DataId> =
+            self.module.data.iter().map(|data| data.id()).collect();
+        let result = self.regenerate(name);
+        if result.is_err() {
+            let added: Vec<_> = self
+                .module
+                .data
+                .iter()
+                .map(|data| data.id())
+                .filter(|id| !data_segments.contains(id))
+                .collect();
+            for id in added {
+                self.module.data.delete(id);
+            }
+        }
+        *module = self.module;
+        result
+    }
+
+    fn regenerate(&mut self, name: &str) -> Result<(), GeneratorError> {
+        let stack_pointer = get_global(&self.module, "stack-pointer")?;
+        let walrus::GlobalKind::Local(walrus::InitExpr::Value(walrus::ir::Value::I32(
+            literal_memory_end,
+        ))) = self.module.globals.get(stack_pointer).kind
+        else {
+            return Err(GeneratorError::InternalError(
+                "unexpected initial value of the stack pointer".to_owned(),
+            ));
+        };
+        let old_function = self.module.funcs.by_name(name).ok_or_else(|| {
+            GeneratorError::InternalError(format!("function {name} not found in the module"))
+        })?;
+
+        self.stack_pointer = stack_pointer;
+        self.literal_memory_end = literal_memory_end as u32;
+        if get_global(&self.module, "block_height-cache").is_ok() {
+            for cache in CACHED_HEIGHT_FUNCTIONS {
+                let global = get_global(&self.module, &format!("{cache}-cache"))?;
+                self.height_caches.insert(cache, global);
+            }
+        }
+
+        // Restore what the generator knew about the contract when it reached
+        // the definition of the function.
+        let expressions = std::mem::take(&mut self.contract_analysis.expressions);
+        let mut definition = None;
+        for expr in &expressions {
+            let Some((keyword, args)) = expr.match_list().and_then(|list| list.split_first())
+            else {
+                continue;
+            };
+            let epoch = self.epoch;
+            let parse_type = |n: usize| {
+                TypeSignature::parse_type_repr(epoch, args.get_expr(n)?, &mut ())
+                    .map_err(|e| GeneratorError::TypeError(e.to_string()))
+            };
+            match keyword.match_atom().map(|keyword| keyword.as_str()) {
+                Some(keyword @ ("define-public" | "define-read-only" | "define-private")) => {
+                    let function_name = args.get_list(0)?.get_name(0)?;
+                    if function_name.as_str() == name {
+                        let kind = match keyword {
+                            "define-public" => FunctionKind::Public,
+                            "define-read-only" => FunctionKind::ReadOnly,
+                            _ => FunctionKind::Private,
+                        };
+                        definition = Some((function_name.clone(), args.get_expr(1)?.clone(), kind));
+                        break;
+                    }
+                    self.defined_functions.insert(function_name.clone());
+                }
+                Some("define-constant") => {
+                    self.constants.insert(args.get_name(0)?.to_string(), 0);
+                }
+                Some("define-data-var") => {
+                    self.datavars_types
+                        .insert(args.get_name(0)?.clone(), parse_type(1)?);
+                }
+                Some("define-map") => {
+                    self.maps_types
+                        .insert(args.get_name(0)?.clone(), (parse_type(1)?, parse_type(2)?));
+                }
+                Some("define-non-fungible-token") => {
+                    self.nft_types
+                        .insert(args.get_name(0)?.clone(), parse_type(1)?);
+                }
+                _ => {}
+            }
+        }
+        self.contract_analysis.expressions = expressions;
+        let (name, body, kind) = definition.ok_or_else(|| {
+            GeneratorError::InternalError(format!("function {name} not found in the contract"))
+        })?;
+
+        // The call to `define_function` is generated for the top-level
+        // function, which is not regenerated.
+        let mut top_level = FunctionBuilder::new(&mut self.module.types, &[], &[]);
+        let new_function =
+            self.traverse_define_function(&mut top_level.func_body(), &name, &body, kind)?;
+
+        let ty = self.module.funcs.get(new_function).ty();
+        let pages = if ty == self.module.funcs.get(old_function).ty() {
+            self.regenerated_memory_pages(literal_memory_end as u32)
+        } else {
+            Err(GeneratorError::TypeError(format!(
+                "the signature of {name} changed, the contract must be recompiled"
+            )))
+        };
+        let pages = match pages {
+            Ok(pages) => pages,
+            Err(e) => {
+                self.module.funcs.delete(new_function);
+                return Err(e);
+            }
+        };
+
+        let new_kind = std::mem::replace(
+            &mut self.module.funcs.get_mut(new_function).kind,
+            walrus::FunctionKind::Uninitialized(ty),
+        );
+        self.module.funcs.get_mut(old_function).kind = new_kind;
+        self.module.funcs.delete(new_function);
+
+        let memory = self.get_memory()?;
+        self.module.memories.get_mut(memory).initial = pages;
+        self.module.globals.get_mut(stack_pointer).kind = walrus::GlobalKind::Local(
+            walrus::InitExpr::Value(walrus::ir::Value::I32(self.literal_memory_end as i32)),
+        );
+        Ok(())
+    }
+
+    /// Returns the number of pages of memory needed by a regenerated module,
+    /// whose literal memory ended at `literal_memory_end`. The memory must
+    /// still hold the call stack and the work space of the other functions,
+    /// which are not known anymore, so it only grows.
+    fn regenerated_memory_pages(&self, literal_memory_end: u32) -> Result<u32, GeneratorError> {
+        let memory = self.module.memories.get(self.get_memory()?);
+        let appended = self.literal_memory_end - literal_memory_end;
+        let call_stack_size = self
+            .frames
+            .values()
+            .map(|frame| self.call_stack_size(frame))
+            .max()
+            .unwrap_or_default();
+        let pages_required = (memory.initial as u64 * WASM_PAGE_SIZE
+            + appended as u64
+            + call_stack_size
+            + self.max_work_space as u64)
+            .div_ceil(WASM_PAGE_SIZE);
+
+        let max_pages = memory
+            .maximum
+            .map_or(MAX_MEMORY_PAGES, |max| MAX_MEMORY_PAGES.min(max as u64));
+        if pages_required > max_pages {
+            return Err(GeneratorError::MemoryLayout(format!(
+                "contract requires {pages_required} pages of memory, but at most {max_pages} are available"
+            )));
+        }
+        Ok(pages_required as u32)
+    }
+
+    /// Returns the largest size of the call stack from `frame`: the largest
+    /// size of the frame, with the deepest stack of the functions it calls.
+    /// A contract cannot call itself recursively, so the calls end.
+    fn call_stack_size(&self, frame: &Frame) -> u64 {
+        let callees = frame
+            .callees
+            .iter()
+            .filter_map(|callee| self.frames.get(callee))
+            .map(|callee| self.call_stack_size(callee))
+            .max()
+            .unwrap_or_default();
+        frame.max_size as u64 + callees
+    }
+
+    /// Records a warning about `expr`.
+    pub(crate) fn warn(&mut self, expr: &SymbolicExpression, message: String) {
+        self.diagnostics.push(Diagnostic {
+            level: Level::Warning,
+            message,
+            spans: vec![expr.span.clone()],
+            suggestion: None,
+        });
     }
 
     pub fn get_memory(&self) -> Result<MemoryId, GeneratorError> {
@@ -388,7 +1179,7 @@ impl WasmGenerator {
             .memories
             .iter()
             .next()
-            .ok_or(GeneratorError::InternalError("No memory found".to_owned()))?
+            .ok_or(GeneratorError::MemoryLayout("No memory found".to_owned()))?
             .id())
     }
 
@@ -397,7 +1188,9 @@ impl WasmGenerator {
         builder: &mut InstrSeqBuilder,
         expr: &SymbolicExpression,
     ) -> Result<(), GeneratorError> {
-        match &expr.expr {
+        let outer_span = std::mem::replace(&mut self.current_span, expr.span.clone());
+        let start = builder.instrs().len();
+        let result = match &expr.expr {
             SymbolicExpressionType::Atom(name) => self.visit_atom(builder, expr, name),
             SymbolicExpressionType::List(exprs) => self.traverse_list(builder, expr, exprs),
             SymbolicExpressionType::LiteralValue(value) => {
@@ -405,6 +1198,10 @@ impl WasmGenerator {
             }
             _ => Ok(()),
         }
+        .map_err(|e| e.at(&expr.span));
+        self.current_span = outer_span;
+        tag_instructions(builder, start, expr);
+        result
     }
 
     fn traverse_list(
@@ -440,12 +1237,24 @@ impl WasmGenerator {
                     Ok((arg_types?, return_type?))
                 };
 
+                if let Some(level) = self.debug_prints.get(&expr.id).copied() {
+                    return words::print::traverse_debug_print(self, builder, level, args);
+                }
+
                 // Complex words handle their own argument traversal, and have priority
                 // since we need to have a slight overlap for the words `and` and `or`
                 // which exist in both complex and simple forms
-                if let Some(word) = words::lookup_complex(function_name) {
+                let version = self.clarity_version;
+                if let Some(word) = words::lookup_complex(function_name, version) {
                     word.traverse(self, builder, expr, args)?;
-                } else if let Some(simpleword) = words::lookup_simple(function_name) {
+                } else if let Some(simpleword) = words::lookup_simple(function_name, version) {
+                    let (expected, check) = simpleword.arity();
+                    check_args!(self, builder, expected, args.len(), check);
+
+                    if let Some(message) = simpleword.check_literal_args(args) {
+                        self.warn(expr, message);
+                    }
+
                     let (arg_types, return_type) = get_types()?;
 
                     // traverse arguments
@@ -454,7 +1263,11 @@ impl WasmGenerator {
                     }
 
                     simpleword.visit(self, builder, &arg_types, &return_type)?;
-                } else if let Some(variadic) = words::lookup_variadic_simple(function_name) {
+                } else if let Some(variadic) = words::lookup_variadic_simple(function_name, version)
+                {
+                    let (expected, check) = variadic.arity();
+                    check_args!(self, builder, expected, args.len(), check);
+
                     let (arg_types, return_type) = get_types()?;
 
                     let mut args_enumerated = args.iter().enumerate();
@@ -480,6 +1293,10 @@ impl WasmGenerator {
                     }
 
                     // first argument is traversed outside loop
+                } else if let Some(word) = words::lookup_experimental(function_name)
+                    .filter(|_| self.get_function_type(function_name).is_none())
+                {
+                    word.traverse(self, builder, expr, args)?;
                 } else {
                     self.traverse_call_user_defined(builder, expr, function_name, args)?;
                 }
@@ -496,21 +1313,21 @@ impl WasmGenerator {
         body: &SymbolicExpression,
         kind: FunctionKind,
     ) -> Result<FunctionId, GeneratorError> {
-        let opt_function_type = match kind {
-            FunctionKind::ReadOnly => {
-                builder.i32_const(0);
+        let (kind_id, opt_function_type) = match kind {
+            FunctionKind::ReadOnly => (
+                0,
                 self.contract_analysis
-                    .get_read_only_function_type(name.as_str())
-            }
-            FunctionKind::Public => {
-                builder.i32_const(1);
+                    .get_read_only_function_type(name.as_str()),
+            ),
+            FunctionKind::Public => (
+                1,
                 self.contract_analysis
-                    .get_public_function_type(name.as_str())
-            }
-            FunctionKind::Private => {
-                builder.i32_const(2);
-                self.contract_analysis.get_private_function(name.as_str())
-            }
+                    .get_public_function_type(name.as_str()),
+            ),
+            FunctionKind::Private => (
+                2,
+                self.contract_analysis.get_private_function(name.as_str()),
+            ),
         };
         let function_type = if let Some(FunctionType::Fixed(fixed)) = opt_function_type {
             fixed.clone()
@@ -521,6 +1338,20 @@ impl WasmGenerator {
             }));
         };
 
+        // The interpreter rejects the definition, at runtime, if an argument is
+        // named after a reserved word, a constant or a function defined before.
+        let illegal_arg = function_type
+            .args
+            .iter()
+            .map(|arg| &arg.name)
+            .find(|arg_name| self.is_name_used(arg_name))
+            .cloned();
+        if let Some(arg_name) = illegal_arg {
+            self.name_already_used(builder, &arg_name)?;
+        }
+
+        builder.i32_const(kind_id);
+
         self.current_function_type = Some(function_type.clone());
 
         // Call the host interface to save this function
@@ -531,7 +1362,7 @@ impl WasmGenerator {
             .i32_const(id_length as i32);
 
         // Call the host interface function, `define_function`
-        builder.call(self.func_by_name("stdlib.define_function"));
+        builder.call(self.func_by_name("stdlib.define_function")?);
 
         let mut bindings = Bindings::new();
 
@@ -575,6 +1406,7 @@ impl WasmGenerator {
         // Setup the locals map for this function, saving the top-level map to
         // restore after.
         let top_level_locals = std::mem::replace(&mut self.bindings, bindings);
+        let top_level_frame = std::mem::take(&mut self.frame);
 
         let mut block = func_body.dangling_instr_seq(InstrSeqType::new(
             &mut self.module.types,
@@ -592,41 +1424,36 @@ impl WasmGenerator {
         // If the same arg name is used multiple times, the interpreter throws an
         // `Unchecked` error at runtime, so we do the same here
         if let Some(arg_name) = reused_arg {
-            let (arg_name_offset, arg_name_len) =
-                self.add_clarity_string_literal(&CharType::ASCII(ASCIIData {
-                    data: arg_name.as_bytes().to_vec(),
-                }))?;
-
             // Clear function body
             block.instrs_mut().clear();
 
-            block
-                .i32_const(arg_name_offset as i32)
-                .global_set(get_global(&self.module, "runtime-error-arg-offset")?)
-                .i32_const(arg_name_len as i32)
-                .global_set(get_global(&self.module, "runtime-error-arg-len")?)
-                .i32_const(ErrorMap::NameAlreadyUsed as i32)
-                .call(self.func_by_name("stdlib.runtime-error"))
-                // To avoid having to generate correct return values
-                .unreachable();
+            self.name_already_used(&mut block, &arg_name)?;
         }
 
         // Insert the function body block into the function
         func_body.instr(walrus::ir::Block { seq: block_id });
 
         // Function postlude
-        // Restore the initial stack pointer.
-        func_body
-            .local_get(frame_pointer)
-            .global_set(self.stack_pointer);
+        // Restore the initial stack pointer, or move the values returned to
+        // the start of the frame for the caller.
+        if returns_in_frame(&function_type.returns) {
+            self.compact_value(&mut func_body, &function_type.returns, frame_pointer)?;
+        } else {
+            func_body
+                .local_get(frame_pointer)
+                .global_set(self.stack_pointer);
+        }
 
         // Restore the top-level locals map.
         self.bindings = top_level_locals;
+        let frame = std::mem::replace(&mut self.frame, top_level_frame);
+        self.frames.insert(name.clone(), frame);
 
         // Reset the return type and early block to None
         self.current_function_type = None;
         self.early_return_block_id = None;
 
+        self.defined_functions.insert(name.clone());
         Ok(func_builder.finish(param_locals, &mut self.module.funcs))
     }
 
@@ -652,7 +1479,8 @@ impl WasmGenerator {
     ///
     /// # Behavior
     ///
-    /// - If `early_return_block_id` is set, it will generate a branch instruction to that block.
+    /// - If `early_return_block_id` is set, it will generate a branch instruction to that block,
+    ///   after exiting the enclosing `as-contract` expressions.
     /// - For `ShortReturnAssertionFailure`, `ShortReturnExpectedValue`, and `ShortReturnExpectedValueResponse`:
     ///   - It generates code to create a local variable, write the value to memory,
     ///     serialize the type, and set up global variables for the runtime error.
@@ -666,6 +1494,11 @@ impl WasmGenerator {
         runtime_error: ErrorMap,
     ) -> Result<(), GeneratorError> {
         if let Some(block_id) = self.early_return_block_id {
+            // Restore the sender and caller changed by the enclosing
+            // `as-contract` expressions, which are exited by the branch.
+            for _ in 0..self.as_contract_depth {
+                builder.call(self.func_by_name("stdlib.exit_as_contract")?);
+            }
             builder.instr(walrus::ir::Br { block: block_id });
             return Ok(());
         }
@@ -687,14 +1520,9 @@ impl WasmGenerator {
                 let serialized_ty = self.type_for_serialization(&ty).to_string();
 
                 // Validate serialized type
-                signature_from_string(
-                    &serialized_ty,
-                    self.contract_analysis.clarity_version,
-                    self.contract_analysis.epoch,
-                )
-                .map_err(|e| {
-                    GeneratorError::TypeError(format!("type cannot be deserialized: {e:?}"))
-                })?;
+                signature_from_string(&serialized_ty, self.clarity_version, self.epoch).map_err(
+                    |e| GeneratorError::TypeError(format!("type cannot be deserialized: {e:?}")),
+                )?;
 
                 let (type_ser_offset, type_ser_len) =
                     self.add_clarity_string_literal(&CharType::ASCII(ASCIIData {
@@ -710,13 +1538,13 @@ impl WasmGenerator {
                     .i32_const(type_ser_len as i32)
                     .global_set(get_global(&self.module, "runtime-error-type-ser-len")?)
                     .i32_const(runtime_error as i32)
-                    .call(self.func_by_name("stdlib.runtime-error"));
+                    .call(self.func_by_name("stdlib.runtime-error")?);
             }
             ErrorMap::ShortReturnExpectedValueOptional => {
                 // Simple case: just call runtime error
                 builder
                     .i32_const(runtime_error as i32)
-                    .call(self.func_by_name("stdlib.runtime-error"));
+                    .call(self.func_by_name("stdlib.runtime-error")?);
             }
             _ => {
                 return Err(GeneratorError::InternalError(
@@ -1038,11 +1866,194 @@ impl WasmGenerator {
             // [ new_stack_ptr ]
             .global_set(self.stack_pointer);
         // [  ]
-        self.frame_size += size;
+        self.grow_frame(size);
 
         (offset, size)
     }
 
+    /// Records that `size` more bytes of the frame are used, without
+    /// reserving them, e.g. for the values left in memory by a call.
+    pub(crate) fn grow_frame(&mut self, size: i32) {
+        self.frame.size += size;
+        self.frame.max_size = self.frame.max_size.max(self.frame.size);
+    }
+
+    /// Moves the values in memory referred to by the value of type `ty` on
+    /// top of the data stack to `start`, one after the other, and points the
+    /// stack pointer after them, so that the memory after `start` used for
+    /// the value, and freed since, can be reused. The offsets in the value
+    /// are rewritten.
+    ///
+    /// The values are first copied after the stack pointer, so that the
+    /// copies don't overwrite the values still to copy, then moved down.
+    pub(crate) fn compact_value(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        ty: &TypeSignature,
+        start: LocalId,
+    ) -> Result<(), GeneratorError> {
+        let locals = self.save_to_locals(builder, ty, true);
+        let copies = self.module.locals.add(ValType::I32);
+        let shift = self.module.locals.add(ValType::I32);
+        builder
+            .global_get(self.stack_pointer)
+            .local_tee(copies)
+            .local_get(start)
+            .binop(BinaryOp::I32Sub)
+            .local_set(shift);
+
+        self.copy_referenced_values(builder, ty, &locals, shift)?;
+
+        builder
+            .local_get(start)
+            .local_get(copies)
+            .global_get(self.stack_pointer)
+            .local_get(copies)
+            .binop(BinaryOp::I32Sub);
+        self.copy_memory()?.emit(builder);
+        builder
+            .global_get(self.stack_pointer)
+            .local_get(shift)
+            .binop(BinaryOp::I32Sub)
+            .global_set(self.stack_pointer);
+        self.grow_frame(referenced_size(ty));
+
+        for local in locals {
+            builder.local_get(local);
+        }
+        Ok(())
+    }
+
+    /// Copies the values in memory referred to by the value of type `ty` in
+    /// `locals`, recursively, after the stack pointer, and points `locals` to
+    /// the copies, minus `shift`.
+    fn copy_referenced_values(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        ty: &TypeSignature,
+        locals: &[LocalId],
+        shift: LocalId,
+    ) -> Result<(), GeneratorError> {
+        match ty {
+            TypeSignature::OptionalType(inner) => {
+                self.copy_referenced_values(builder, inner, &locals[1..], shift)
+            }
+            TypeSignature::ResponseType(inner) => {
+                let ok_locals = clar2wasm_ty(&inner.0).len();
+                let (ok, err) = locals[1..].split_at(ok_locals);
+                self.copy_referenced_values(builder, &inner.0, ok, shift)?;
+                self.copy_referenced_values(builder, &inner.1, err, shift)
+            }
+            TypeSignature::TupleType(tuple_ty) => {
+                let mut locals = locals;
+                for ty in tuple_ty.get_type_map().values() {
+                    let (field, rest) = locals.split_at(clar2wasm_ty(ty).len());
+                    self.copy_referenced_values(builder, ty, field, shift)?;
+                    locals = rest;
+                }
+                Ok(())
+            }
+            TypeSignature::SequenceType(_)
+            | TypeSignature::PrincipalType
+            | TypeSignature::CallableType(_)
+            | TypeSignature::TraitReferenceType(_) => {
+                let &[offset, length] = locals else {
+                    return Err(GeneratorError::InternalError(
+                        "expected the offset and length of a value in memory".to_owned(),
+                    ));
+                };
+                let copy = self.module.locals.add(ValType::I32);
+                builder
+                    .global_get(self.stack_pointer)
+                    .local_tee(copy)
+                    .local_get(length)
+                    .binop(BinaryOp::I32Add)
+                    .global_set(self.stack_pointer);
+                builder.local_get(copy).local_get(offset).local_get(length);
+                self.copy_memory()?.emit(builder);
+
+                // The elements of a list may refer to other values, which
+                // are copied too.
+                if let TypeSignature::SequenceType(SequenceSubtype::ListType(list_ty)) = ty {
+                    let elem_ty = list_ty.get_list_item_type();
+                    if contains_in_memory_type(elem_ty) {
+                        self.copy_referenced_elements(builder, elem_ty, copy, length, shift)?;
+                    }
+                }
+
+                builder
+                    .local_get(copy)
+                    .local_get(shift)
+                    .binop(BinaryOp::I32Sub)
+                    .local_set(offset);
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Copies the values referred to by the elements of type `elem_ty` of
+    /// the list at `offset`, of `length` bytes, like
+    /// [`Self::copy_referenced_values`], rewriting the elements in place.
+    fn copy_referenced_elements(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        elem_ty: &TypeSignature,
+        offset: LocalId,
+        length: LocalId,
+        shift: LocalId,
+    ) -> Result<(), GeneratorError> {
+        let element = self.module.locals.add(ValType::I32);
+        let end = self.module.locals.add(ValType::I32);
+        builder
+            .local_get(offset)
+            .local_tee(element)
+            .local_get(length)
+            .binop(BinaryOp::I32Add)
+            .local_set(end);
+
+        let mut loop_exit = builder.dangling_instr_seq(None);
+        let loop_exit_id = loop_exit.id();
+        let mut loop_ = loop_exit.dangling_instr_seq(None);
+        let loop_id = loop_.id();
+
+        loop_
+            .local_get(element)
+            .local_get(end)
+            .binop(BinaryOp::I32GeU)
+            .br_if(loop_exit_id);
+        self.read_from_memory(&mut loop_, element, 0, elem_ty)?;
+        let elem_locals = self.save_to_locals(&mut loop_, elem_ty, true);
+        self.copy_referenced_values(&mut loop_, elem_ty, &elem_locals, shift)?;
+        for local in &elem_locals {
+            loop_.local_get(*local);
+        }
+        self.write_to_memory(&mut loop_, element, 0, elem_ty)?;
+        loop_
+            .local_get(element)
+            .i32_const(get_type_size(elem_ty))
+            .binop(BinaryOp::I32Add)
+            .local_set(element)
+            .br(loop_id);
+
+        loop_exit.instr(walrus::ir::Loop { seq: loop_id });
+        builder.instr(walrus::ir::Block { seq: loop_exit_id });
+        Ok(())
+    }
+
+    /// Releases the local at `offset` of `size` bytes, the last one created
+    /// with [`Self::create_call_stack_local`], so that its space is reused by
+    /// the next locals.
+    pub(crate) fn release_call_stack_local(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        offset: LocalId,
+        size: i32,
+    ) {
+        builder.local_get(offset).global_set(self.stack_pointer);
+        self.frame.size -= size;
+    }
+
     pub(crate) fn borrow_local(&mut self, ty: ValType) -> BorrowedLocal {
         let reuse = (*self.local_pool)
             .borrow_mut()
@@ -1066,6 +2077,7 @@ impl WasmGenerator {
         offset: u32,
         ty: &TypeSignature,
     ) -> Result<u32, GeneratorError> {
+        self.check_memory_bounds(builder, offset_local, offset, ty)?;
         let memory = self.get_memory()?;
         match ty {
             TypeSignature::IntType | TypeSignature::UIntType => {
@@ -1120,6 +2132,7 @@ impl WasmGenerator {
             TypeSignature::BoolType => {
                 // Data stack: TOP | Value | ...
                 // Save the value to a local.
+                self.check_memory_value(builder, 1)?;
                 let bool_val = self.module.locals.add(ValType::I32);
                 builder.local_set(bool_val);
 
@@ -1149,6 +2162,7 @@ impl WasmGenerator {
                     self.write_to_memory(builder, offset_local, offset + 4, some_ty)?;
 
                 // Save the variant to a local and store it to memory
+                self.check_memory_value(builder, 1)?;
                 let variant_val = self.module.locals.add(ValType::I32);
                 builder
                     .local_set(variant_val)
@@ -1179,6 +2193,7 @@ impl WasmGenerator {
                 bytes_written +=
                     self.write_to_memory(builder, offset_local, offset + 4, &ok_err_ty.0)?;
 
+                self.check_memory_value(builder, 1)?;
                 let variant_val = self.module.locals.add(ValType::I32);
                 builder
                     .local_set(variant_val)
@@ -1233,12 +2248,13 @@ impl WasmGenerator {
         literal_offset: u32,
         ty: &TypeSignature,
     ) -> Result<i32, GeneratorError> {
+        self.check_memory_bounds(builder, offset, literal_offset, ty)?;
         let memory = self
             .module
             .memories
             .iter()
             .next()
-            .ok_or_else(|| GeneratorError::InternalError("No memory found".to_owned()))?;
+            .ok_or_else(|| GeneratorError::MemoryLayout("No memory found".to_owned()))?;
         match ty {
             TypeSignature::IntType | TypeSignature::UIntType => {
                 // Memory: Offset -> | Low | High |
@@ -1270,6 +2286,7 @@ impl WasmGenerator {
                         offset: literal_offset,
                     },
                 );
+                self.check_value_at_most(builder, 1, ErrorMap::InvalidHostData)?;
                 Ok(4 + self.read_from_memory(builder, offset, literal_offset + 4, inner)?)
             }
             TypeSignature::ResponseType(inner) => {
@@ -1282,6 +2299,7 @@ impl WasmGenerator {
                         offset: literal_offset,
                     },
                 );
+                self.check_value_at_most(builder, 1, ErrorMap::InvalidHostData)?;
                 let mut offset_adjust = 4;
                 offset_adjust += self.read_from_memory(
                     builder,
@@ -1320,6 +2338,11 @@ impl WasmGenerator {
                         offset: literal_offset + 4,
                     },
                 );
+                self.check_value_at_most(
+                    builder,
+                    get_type_in_memory_size(ty, false),
+                    ErrorMap::SequenceOutOfBounds,
+                )?;
                 Ok(8)
             }
             TypeSignature::TupleType(tuple) => {
@@ -1346,6 +2369,7 @@ impl WasmGenerator {
                         offset: literal_offset,
                     },
                 );
+                self.check_value_at_most(builder, 1, ErrorMap::InvalidHostData)?;
                 Ok(4)
             }
             TypeSignature::ListUnionType(_) => Err(GeneratorError::TypeError(
@@ -1354,45 +2378,146 @@ impl WasmGenerator {
         }
     }
 
-    pub(crate) fn traverse_statement_list(
+    /// With [`crate::CompileOptions::memory_checks`], checks that a value of
+    /// type `ty` at the offset in local `offset` plus `literal_offset` is
+    /// within the memory.
+    fn check_memory_bounds(
         &mut self,
         builder: &mut InstrSeqBuilder,
-        statements: &[SymbolicExpression],
+        offset: LocalId,
+        literal_offset: u32,
+        ty: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        if statements.is_empty() {
-            return Err(GeneratorError::InternalError(
-                "statement list must have at least one statement".to_owned(),
-            ));
-        }
-
-        let mut last_ty = None;
-        // Traverse the statements, saving the last non-none value.
-        for stmt in statements {
-            // If stmt has a type, save that type. If there was a previous type
-            // saved, then drop that value.
-            if let Some(ty) = self.get_expr_type(stmt) {
-                if let Some(last_ty) = &last_ty {
-                    drop_value(builder.borrow_mut(), last_ty);
-                }
-                last_ty = Some(ty.clone());
-            }
-            self.traverse_expr(builder, stmt)?;
+        if !self.memory_checks || matches!(ty, TypeSignature::ListUnionType(_)) {
+            return Ok(());
         }
+        let memory = self.get_memory()?;
 
-        Ok(())
+        // offset + literal_offset + size > memory.size * 64KiB
+        builder
+            .local_get(offset)
+            .unop(UnaryOp::I64ExtendUI32)
+            .i64_const(literal_offset as i64 + get_type_size(ty) as i64)
+            .binop(BinaryOp::I64Add)
+            .memory_size(memory)
+            .unop(UnaryOp::I64ExtendUI32)
+            .i64_const(16)
+            .binop(BinaryOp::I64Shl)
+            .binop(BinaryOp::I64GtU);
+        self.runtime_error_if(builder, ErrorMap::MemoryCheckFailed)
     }
 
-    /// If `name` is a reserved variable, push its value onto the data stack.
-    pub fn lookup_reserved_variable(
+    /// With [`crate::CompileOptions::memory_checks`], checks that the `i32`
+    /// on top of the stack, a variant indicator or a bool written to the
+    /// memory, is at most `max`. The value is left on the stack.
+    fn check_memory_value(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        max: i32,
+    ) -> Result<(), GeneratorError> {
+        if !self.memory_checks {
+            return Ok(());
+        }
+        self.check_value_at_most(builder, max, ErrorMap::MemoryCheckFailed)
+    }
+
+    /// Checks that the `i32` on top of the stack, a variant indicator, a bool
+    /// or the length of an in-memory value, is at most `max`, or traps with
+    /// `error`. The value is left on the stack.
+    ///
+    /// Unlike the memory checks, the checks of the values read from the
+    /// memory are always emitted: these values may have been written by the
+    /// host, and an invalid one must fail with an error instead of being used.
+    fn check_value_at_most(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        max: i32,
+        error: ErrorMap,
+    ) -> Result<(), GeneratorError> {
+        let value = self.module.locals.add(ValType::I32);
+
+        builder
+            .local_tee(value)
+            .i32_const(max)
+            .binop(BinaryOp::I32GtU);
+        self.runtime_error_if(builder, error)?;
+        builder.local_get(value);
+        Ok(())
+    }
+
+    /// Traps with the runtime error `error`, with the expression being
+    /// traversed as argument, if the condition on top of the stack is true.
+    fn runtime_error_if(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        error: ErrorMap,
+    ) -> Result<(), GeneratorError> {
+        let expression = format!(
+            "expression at line {}, column {}",
+            self.current_span.start_line, self.current_span.start_column
+        );
+        let (expression_offset, expression_len) =
+            self.add_clarity_string_literal(&CharType::ASCII(ASCIIData {
+                data: expression.into_bytes(),
+            }))?;
+        let arg_offset = get_global(&self.module, "runtime-error-arg-offset")?;
+        let arg_len = get_global(&self.module, "runtime-error-arg-len")?;
+        let runtime_error = self.func_by_name("stdlib.runtime-error")?;
+
+        builder.if_else(
+            None,
+            |then| {
+                then.i32_const(expression_offset as i32)
+                    .global_set(arg_offset)
+                    .i32_const(expression_len as i32)
+                    .global_set(arg_len)
+                    .i32_const(error as i32)
+                    .call(runtime_error)
+                    .unreachable();
+            },
+            |_| {},
+        );
+        Ok(())
+    }
+
+    pub(crate) fn traverse_statement_list(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        statements: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        if statements.is_empty() {
+            return Err(GeneratorError::InternalError(
+                "statement list must have at least one statement".to_owned(),
+            ));
+        }
+
+        let mut last_ty = None;
+        // Traverse the statements, saving the last non-none value.
+        for stmt in statements {
+            // If stmt has a type, save that type. If there was a previous type
+            // saved, then drop that value.
+            if let Some(ty) = self.get_expr_type(stmt) {
+                if let Some(last_ty) = &last_ty {
+                    drop_value(builder.borrow_mut(), last_ty);
+                }
+                last_ty = Some(ty.clone());
+            }
+            self.traverse_expr(builder, stmt)?;
+        }
+
+        Ok(())
+    }
+
+    /// If `name` is a reserved variable, push its value onto the data stack.
+    pub fn lookup_reserved_variable(
         &mut self,
         builder: &mut InstrSeqBuilder,
         name: &str,
         expr: &SymbolicExpression,
     ) -> Result<bool, GeneratorError> {
-        if let Some(variable) = NativeVariables::lookup_by_name_at_version(
-            name,
-            &self.contract_analysis.clarity_version,
-        ) {
+        if let Some(variable) =
+            NativeVariables::lookup_by_name_at_version(name, &self.clarity_version)
+        {
             match variable {
                 NativeVariables::TxSender => {
                     // Create a new local to hold the result on the call stack
@@ -1407,7 +2532,7 @@ impl WasmGenerator {
                     // Push the offset and size to the data stack
                     builder.local_get(offset).i32_const(size);
 
-                    builder.call(self.func_by_name("stdlib.tx_sender"));
+                    builder.call(self.func_by_name("stdlib.tx_sender")?);
 
                     Ok(true)
                 }
@@ -1425,7 +2550,7 @@ impl WasmGenerator {
                     builder.local_get(offset).i32_const(size);
 
                     // Call the host interface function, `contract_caller`
-                    builder.call(self.func_by_name("stdlib.contract_caller"));
+                    builder.call(self.func_by_name("stdlib.contract_caller")?);
                     Ok(true)
                 }
                 NativeVariables::TxSponsor => {
@@ -1443,27 +2568,29 @@ impl WasmGenerator {
 
                     // Call the host interface function, `tx_sponsor`
 
-                    builder.call(self.func_by_name("stdlib.tx_sponsor"));
+                    builder.call(self.func_by_name("stdlib.tx_sponsor")?);
                     Ok(true)
                 }
                 NativeVariables::BlockHeight => {
-                    // Call the host interface function, `block_height`
-                    builder.call(self.func_by_name("stdlib.block_height"));
+                    // Call the host interface function, `block_height`, which
+                    // returns the tenure height when called in epoch 3.0 or
+                    // later.
+                    self.cached_height(builder, "block_height")?;
                     Ok(true)
                 }
                 NativeVariables::StacksBlockHeight => {
                     // Call the host interface function, `stacks_block_height`
-                    builder.call(self.func_by_name("stdlib.stacks_block_height"));
+                    self.cached_height(builder, "stacks_block_height")?;
                     Ok(true)
                 }
                 NativeVariables::TenureHeight => {
                     // Call the host interface function, `tenure_height`
-                    builder.call(self.func_by_name("stdlib.tenure_height"));
+                    self.cached_height(builder, "tenure_height")?;
                     Ok(true)
                 }
                 NativeVariables::BurnBlockHeight => {
                     // Call the host interface function, `burn_block_height`
-                    builder.call(self.func_by_name("stdlib.burn_block_height"));
+                    self.cached_height(builder, "burn_block_height")?;
                     Ok(true)
                 }
                 NativeVariables::NativeNone => {
@@ -1483,22 +2610,22 @@ impl WasmGenerator {
                 }
                 NativeVariables::TotalLiquidMicroSTX => {
                     // Call the host interface function, `stx_liquid_supply`
-                    builder.call(self.func_by_name("stdlib.stx_liquid_supply"));
+                    builder.call(self.func_by_name("stdlib.stx_liquid_supply")?);
                     Ok(true)
                 }
                 NativeVariables::Regtest => {
                     // Call the host interface function, `is_in_regtest`
-                    builder.call(self.func_by_name("stdlib.is_in_regtest"));
+                    builder.call(self.func_by_name("stdlib.is_in_regtest")?);
                     Ok(true)
                 }
                 NativeVariables::Mainnet => {
                     // Call the host interface function, `is_in_mainnet`
-                    builder.call(self.func_by_name("stdlib.is_in_mainnet"));
+                    builder.call(self.func_by_name("stdlib.is_in_mainnet")?);
                     Ok(true)
                 }
                 NativeVariables::ChainId => {
                     // Call the host interface function, `chain_id`
-                    builder.call(self.func_by_name("stdlib.chain_id"));
+                    builder.call(self.func_by_name("stdlib.chain_id")?);
                     Ok(true)
                 }
             }
@@ -1507,6 +2634,75 @@ impl WasmGenerator {
         }
     }
 
+    /// Returns how to copy memory on the target: with `memory.copy`, or with a
+    /// routine added to the module the first time it is needed, when the
+    /// target doesn't support the bulk memory operations.
+    pub(crate) fn copy_memory(&mut self) -> Result<CopyMemory, GeneratorError> {
+        let memory = self.get_memory()?;
+        if self.target_features.allows(Feature::BulkMemory) {
+            return Ok(CopyMemory::Instruction(memory));
+        }
+        if let Some(routine) = self.module.funcs.by_name(COPY_MEMORY_ROUTINE) {
+            return Ok(CopyMemory::Routine(routine));
+        }
+        let routine = build_copy_memory(&mut self.module, memory);
+        self.module.funcs.get_mut(routine).name = Some(COPY_MEMORY_ROUTINE.to_owned());
+        Ok(CopyMemory::Routine(routine))
+    }
+
+    /// Returns the globals caching the results of the height host functions,
+    /// adding them to the module on first use.
+    fn height_caches(&mut self) -> &BTreeMap<&'static str, GlobalId> {
+        if self.height_caches.is_empty() {
+            for name in CACHED_HEIGHT_FUNCTIONS {
+                let global = self.module.globals.add_local(
+                    ValType::I64,
+                    true,
+                    walrus::InitExpr::Value(walrus::ir::Value::I64(EMPTY_HEIGHT_CACHE)),
+                );
+                self.module.globals.get_mut(global).name = Some(format!("{name}-cache"));
+                self.height_caches.insert(name, global);
+            }
+        }
+        &self.height_caches
+    }
+
+    /// Pushes the height returned by the host function `name` onto the data
+    /// stack, only calling the host if the height is not cached yet.
+    fn cached_height(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        name: &'static str,
+    ) -> Result<(), GeneratorError> {
+        let cache = *self.height_caches().get(name).ok_or_else(|| {
+            GeneratorError::InternalError(format!("{name} is not a cached height"))
+        })?;
+        let host_function = self.func_by_name(&format!("stdlib.{name}"))?;
+
+        builder
+            .global_get(cache)
+            .i64_const(EMPTY_HEIGHT_CACHE)
+            .binop(BinaryOp::I64Eq)
+            .if_else(
+                None,
+                |then| {
+                    // The high part of the `uint` is always 0.
+                    then.call(host_function).drop().global_set(cache);
+                },
+                |_| {},
+            );
+        builder.global_get(cache).i64_const(0);
+        Ok(())
+    }
+
+    /// Empties the height caches, when the heights change on entering or
+    /// exiting `at-block`.
+    pub(crate) fn clear_height_caches(&mut self, builder: &mut InstrSeqBuilder) {
+        for cache in self.height_caches().values() {
+            builder.i64_const(EMPTY_HEIGHT_CACHE).global_set(*cache);
+        }
+    }
+
     /// If `name` is a constant, push its value onto the data stack.
     pub fn lookup_constant_variable(
         &mut self,
@@ -1535,7 +2731,7 @@ impl WasmGenerator {
 
             // Call a host interface function to load
             // constant attributes from a data structure.
-            builder.call(self.func_by_name("stdlib.load_constant"));
+            builder.call(self.func_by_name("stdlib.load_constant")?);
 
             self.read_from_memory(builder, result_local, 0, &ty)?;
 
@@ -1573,11 +2769,67 @@ impl WasmGenerator {
         locals
     }
 
-    pub fn func_by_name(&self, name: &str) -> FunctionId {
-        self.module
-            .funcs
-            .by_name(name)
-            .unwrap_or_else(|| panic!("function not found: {name}"))
+    /// Returns the function `name`, which is either one of the
+    /// [`STDLIB_FUNCTIONS`], found when the generator was created, or a
+    /// function added to the module since.
+    ///
+    /// Standard library functions are only looked up in [`STDLIB_FUNCTIONS`],
+    /// so that a name missing from the list is an error even if the standard
+    /// library happens to define it.
+    pub fn func_by_name(&self, name: &str) -> Result<FunctionId, GeneratorError> {
+        let id = if name.starts_with("stdlib.") {
+            self.stdlib_functions.get(name).copied()
+        } else {
+            self.module.funcs.by_name(name)
+        };
+        id.ok_or_else(|| GeneratorError::InternalError(format!("function not found: {name}")))
+    }
+
+    /// Returns the standard library function `name` like
+    /// [`WasmGenerator::func_by_name`], or its variant of the
+    /// [`STDLIB_SIMD_FUNCTIONS`] if the target allows SIMD and the standard
+    /// library defines it.
+    pub fn target_func_by_name(&self, name: &str) -> Result<FunctionId, GeneratorError> {
+        if self.target_features.allows(Feature::Simd) {
+            if let Some(id) = self.stdlib_functions.get(format!("{name}-simd").as_str()) {
+                return Ok(*id);
+            }
+        }
+        self.func_by_name(name)
+    }
+
+    /// Returns the host function `name`, adding the import to the module if it
+    /// is not there yet. This is used for host functions which are not
+    /// imported by the standard library, because only some modules need them.
+    pub(crate) fn import_host_function(
+        &mut self,
+        name: &str,
+        func_name: &str,
+        params: &[ValType],
+        results: &[ValType],
+    ) -> FunctionId {
+        let module = self.host_module.clone();
+        self.import_function(&module, name, func_name, params, results)
+    }
+
+    /// Returns the function `name` from `module`, adding the import to the
+    /// module if it is not there yet.
+    pub(crate) fn import_function(
+        &mut self,
+        module: &str,
+        name: &str,
+        func_name: &str,
+        params: &[ValType],
+        results: &[ValType],
+    ) -> FunctionId {
+        if let Some(id) = self.module.funcs.by_name(func_name) {
+            return id;
+        }
+
+        let ty = self.module.types.add(params, results);
+        let (id, _) = self.module.add_import_func(module, name, ty);
+        self.module.funcs.get_mut(id).name = Some(func_name.to_owned());
+        id
     }
 
     pub fn get_function_type(&self, name: &str) -> Option<&FunctionType> {
@@ -1707,6 +2959,7 @@ impl WasmGenerator {
         return_ty: &TypeSignature,
         name: &ClarityName,
     ) -> Result<(), GeneratorError> {
+        self.frame.callees.insert(name.clone());
         if self
             .contract_analysis
             .get_public_function_type(name.as_str())
@@ -1726,15 +2979,26 @@ impl WasmGenerator {
         {
             self.local_call(builder, name)?;
         } else {
-            return Err(GeneratorError::TypeError(format!(
-                "function not found: {name}",
-                name = name.as_str()
-            )));
+            return Err(GeneratorError::UnsupportedWord {
+                name: name.to_string(),
+            });
         }
 
+        // The callee decides how its values are returned from its declared
+        // type, which can differ from the type of the call.
+        let returns = match self.get_function_type(name.as_str()) {
+            Some(FunctionType::Fixed(function)) => function.returns.clone(),
+            _ => return_ty.clone(),
+        };
+        let in_callee_frame = returns_in_frame(&returns);
+        // The values in memory returned stay in the frame, either copied
+        // below or left by the callee.
+        self.grow_frame(referenced_size(&returns));
+
         // If an in-memory value is returned from a function, we need to copy
-        // it to our frame, from the callee's frame.
-        if is_in_memory_type(return_ty) {
+        // it to our frame, from the callee's frame, unless the callee left
+        // its frame to us.
+        if is_in_memory_type(return_ty) && !in_callee_frame {
             // The result may be in the callee's call frame, can be overwritten
             // after returning, so we need to copy it to our frame.
             let result_offset = self.module.locals.add(ValType::I32);
@@ -1749,14 +3013,12 @@ impl WasmGenerator {
                 .binop(BinaryOp::I32Add)
                 .global_set(self.stack_pointer);
 
-            let memory = self.get_memory()?;
-
             // Copy the result to our frame.
             builder
                 .local_get(offset)
                 .local_get(result_offset)
-                .local_get(result_length)
-                .memory_copy(memory, memory);
+                .local_get(result_length);
+            self.copy_memory()?.emit(builder);
 
             // Push the copied offset and length to the stack
             builder.local_get(offset).local_get(result_length);
@@ -1771,7 +3033,7 @@ impl WasmGenerator {
         builder: &mut InstrSeqBuilder,
         name: &ClarityName,
     ) -> Result<(), GeneratorError> {
-        builder.call(self.func_by_name(name.as_str()));
+        builder.call(self.func_by_name(name.as_str())?);
 
         Ok(())
     }
@@ -1785,7 +3047,7 @@ impl WasmGenerator {
         name: &ClarityName,
     ) -> Result<(), GeneratorError> {
         // Call the host interface function, `begin_public_call`
-        builder.call(self.func_by_name("stdlib.begin_public_call"));
+        builder.call(self.func_by_name("stdlib.begin_public_call")?);
 
         self.local_call(builder, name)?;
 
@@ -1797,13 +3059,13 @@ impl WasmGenerator {
         // response indicator (all public functions return a response).
         let if_id = {
             let mut if_case: InstrSeqBuilder<'_> = builder.dangling_instr_seq(None);
-            if_case.call(self.func_by_name("stdlib.commit_call"));
+            if_case.call(self.func_by_name("stdlib.commit_call")?);
             if_case.id()
         };
 
         let else_id = {
             let mut else_case: InstrSeqBuilder<'_> = builder.dangling_instr_seq(None);
-            else_case.call(self.func_by_name("stdlib.roll_back_call"));
+            else_case.call(self.func_by_name("stdlib.roll_back_call")?);
             else_case.id()
         };
 
@@ -1827,12 +3089,12 @@ impl WasmGenerator {
         name: &ClarityName,
     ) -> Result<(), GeneratorError> {
         // Call the host interface function, `begin_readonly_call`
-        builder.call(self.func_by_name("stdlib.begin_read_only_call"));
+        builder.call(self.func_by_name("stdlib.begin_read_only_call")?);
 
         self.local_call(builder, name)?;
 
         // Call the host interface function, `roll_back_call`
-        builder.call(self.func_by_name("stdlib.roll_back_call"));
+        builder.call(self.func_by_name("stdlib.roll_back_call")?);
 
         Ok(())
     }
@@ -1848,10 +3110,15 @@ impl WasmGenerator {
         Ok(())
     }
 
-    pub fn debug_msg<M: Into<String>>(&mut self, builder: &mut InstrSeqBuilder, message: M) {
+    pub fn debug_msg<M: Into<String>>(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        message: M,
+    ) -> Result<(), GeneratorError> {
         let id = debug_msg::register(message.into());
         builder.i32_const(id);
-        builder.call(self.func_by_name("debug_msg"));
+        builder.call(self.func_by_name("debug_msg")?);
+        Ok(())
     }
 
     /// Dump the top of the stack to debug messages
@@ -1860,9 +3127,9 @@ impl WasmGenerator {
         builder: &mut InstrSeqBuilder,
         message: M,
         expected_types: &[ValType],
-    ) {
-        self.debug_msg(builder, message);
-        self.debug_msg(builder, "<stack dump start>");
+    ) -> Result<(), GeneratorError> {
+        self.debug_msg(builder, message)?;
+        self.debug_msg(builder, "<stack dump start>")?;
         let mut locals = vec![];
 
         for t in expected_types {
@@ -1870,8 +3137,8 @@ impl WasmGenerator {
             builder.local_tee(*l);
             locals.push(l);
             match t {
-                ValType::I32 => self.debug_log_i32(builder),
-                ValType::I64 => self.debug_log_i64(builder),
+                ValType::I32 => self.debug_log_i32(builder)?,
+                ValType::I64 => self.debug_log_i64(builder)?,
                 _ => {
                     // allow unimplemented in debug code
                     #[allow(clippy::unimplemented)]
@@ -1881,12 +3148,13 @@ impl WasmGenerator {
                 }
             }
         }
-        self.debug_msg(builder, "<stack dump end>");
+        self.debug_msg(builder, "<stack dump end>")?;
 
         // restore the stack
         while let Some(l) = locals.pop() {
             builder.local_get(*l);
         }
+        Ok(())
     }
 
     pub fn debug_log_local_i32<M: Into<String>>(
@@ -1894,8 +3162,8 @@ impl WasmGenerator {
         builder: &mut InstrSeqBuilder,
         message: M,
         local_id: &LocalId,
-    ) {
-        self.debug_msg(builder, message);
+    ) -> Result<(), GeneratorError> {
+        self.debug_msg(builder, message)?;
         builder.local_get(*local_id);
         self.debug_log_i32(builder)
     }
@@ -1905,33 +3173,77 @@ impl WasmGenerator {
         builder: &mut InstrSeqBuilder,
         message: M,
         local_id: &LocalId,
-    ) {
-        self.debug_msg(builder, message);
+    ) -> Result<(), GeneratorError> {
+        self.debug_msg(builder, message)?;
         builder.local_get(*local_id);
         self.debug_log_i64(builder)
     }
 
     #[allow(dead_code)]
     /// Log an i64 that is on top of the stack.
-    pub fn debug_log_i64(&self, builder: &mut InstrSeqBuilder) {
-        builder.call(self.func_by_name("log"));
+    pub fn debug_log_i64(&self, builder: &mut InstrSeqBuilder) -> Result<(), GeneratorError> {
+        builder.call(self.func_by_name("log")?);
+        Ok(())
     }
 
     #[allow(dead_code)]
     /// Log an i32 that is on top of the stack.
-    pub fn debug_log_i32(&self, builder: &mut InstrSeqBuilder) {
+    pub fn debug_log_i32(&self, builder: &mut InstrSeqBuilder) -> Result<(), GeneratorError> {
         builder
             .unop(UnaryOp::I64ExtendUI32)
-            .call(self.func_by_name("log"));
+            .call(self.func_by_name("log")?);
+        Ok(())
     }
 
+    /// Whether `name` is a builtin function or variable in the Clarity version
+    /// of the contract.
     pub(crate) fn is_reserved_name(&self, name: &ClarityName) -> bool {
-        let version = self.contract_analysis.clarity_version;
+        let version = self.clarity_version;
 
         functions::lookup_reserved_functions(name.as_str(), &version).is_some()
             || variables::is_reserved_name(name, &version)
     }
 
+    /// Checks that `name`, defined or bound by the contract, is not reserved,
+    /// see [`Self::is_reserved_name`]. The error is the one of the analysis.
+    pub(crate) fn check_reserved_name(&self, name: &ClarityName) -> Result<(), GeneratorError> {
+        if self.is_reserved_name(name) {
+            return Err(GeneratorError::NameAlreadyUsed(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Whether `name` is reserved, or already used by a constant or a function
+    /// defined earlier in the contract.
+    fn is_name_used(&self, name: &ClarityName) -> bool {
+        self.is_reserved_name(name)
+            || self.constants.contains_key(name.as_str())
+            || self.defined_functions.contains(name)
+    }
+
+    /// Emits a `NameAlreadyUsed` runtime error for `name`.
+    fn name_already_used(
+        &mut self,
+        builder: &mut InstrSeqBuilder,
+        name: &ClarityName,
+    ) -> Result<(), GeneratorError> {
+        let (name_offset, name_len) =
+            self.add_clarity_string_literal(&CharType::ASCII(ASCIIData {
+                data: name.as_bytes().to_vec(),
+            }))?;
+
+        builder
+            .i32_const(name_offset as i32)
+            .global_set(get_global(&self.module, "runtime-error-arg-offset")?)
+            .i32_const(name_len as i32)
+            .global_set(get_global(&self.module, "runtime-error-arg-len")?)
+            .i32_const(ErrorMap::NameAlreadyUsed as i32)
+            .call(self.func_by_name("stdlib.runtime-error")?)
+            // To avoid having to generate correct return values
+            .unreachable();
+        Ok(())
+    }
+
     pub fn get_sequence_element_type(
         &self,
         sequence: &SymbolicExpression,
@@ -1992,7 +3304,7 @@ mod tests {
     use clarity::vm::database::MemoryBackingStore;
     use clarity::vm::errors::{CheckErrors, Error};
     use clarity::vm::types::{QualifiedContractIdentifier, StandardPrincipalData};
-    use clarity::vm::ClarityVersion;
+    use clarity::vm::{ClarityName, ClarityVersion};
     use walrus::Module;
 
     // Tests that don't relate to specific words
@@ -2000,6 +3312,7 @@ mod tests {
         compile,
         tools::{crosscheck, evaluate},
         wasm_generator::END_OF_STANDARD_DATA,
+        CompileOptions,
     };
 
     #[test]
@@ -2027,6 +3340,762 @@ mod tests {
         crosscheck(&snippet, Ok(Some(clarity::vm::Value::Bool(false))));
     }
 
+    fn large_constant_table(count: usize, size: usize) -> String {
+        (0..count)
+            .map(|i| {
+                let c = char::from(b'a' + (i % 26) as u8);
+                format!(
+                    "(define-constant c{i} \"{}\")\n",
+                    c.to_string().repeat(size)
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn large_constant_table_fits_in_memory() {
+        let mut snippet = large_constant_table(64, 4096);
+        snippet.push_str("(define-constant big (list c0 c1 c2 c3 c4 c5 c6 c7))\n");
+        snippet.push_str("(+ (len (concat c0 c63)) (len (unwrap-panic (element-at? big u7))))");
+        crosscheck(&snippet, Ok(Some(clarity::vm::Value::UInt(3 * 4096))));
+
+        let module = compile_with_options(&snippet, CompileOptions::default());
+        let initial_bytes =
+            module.memories.iter().next().unwrap().initial as u64 * super::WASM_PAGE_SIZE;
+        assert!(initial_bytes > super::WASM_PAGE_SIZE * 4);
+        for data in module.data.iter() {
+            if let walrus::DataKind::Active(walrus::ActiveData {
+                location: walrus::ActiveDataLocation::Absolute(offset),
+                ..
+            }) = data.kind
+            {
+                assert!(offset as u64 + data.value.len() as u64 <= initial_bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn memory_too_large_is_rejected() {
+        let contract_analysis = compile(
+            "(+ 1 2)",
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .unwrap()
+        .contract_analysis;
+
+        // Beyond the size of a 32-bit memory.
+        let mut generator = super::WasmGenerator::new(
+            contract_analysis.clone(),
+            StacksEpochId::Epoch25,
+            ClarityVersion::Clarity2,
+        )
+        .unwrap();
+        generator.literal_memory_end = u32::MAX;
+        generator.frame.max_size = 1024;
+        assert!(matches!(
+            generator.set_memory_pages(),
+            Err(super::GeneratorError::MemoryLayout(_))
+        ));
+
+        // Beyond the maximum size of the memory.
+        let mut generator = super::WasmGenerator::new(
+            contract_analysis,
+            StacksEpochId::Epoch25,
+            ClarityVersion::Clarity2,
+        )
+        .unwrap();
+        generator.module.memories.iter_mut().next().unwrap().maximum = Some(1);
+        generator.literal_memory_end = 2 * super::WASM_PAGE_SIZE as u32;
+        assert!(matches!(
+            generator.set_memory_pages(),
+            Err(super::GeneratorError::MemoryLayout(_))
+        ));
+        generator.literal_memory_end = super::WASM_PAGE_SIZE as u32 / 2;
+        assert!(generator.set_memory_pages().is_ok());
+    }
+
+    #[test]
+    fn simd_variants_follow_the_target() {
+        use crate::target_features::{Feature, TargetFeatures};
+
+        let contract_analysis = compile(
+            "(+ 1 2)",
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .unwrap()
+        .contract_analysis;
+
+        let mut generator = super::WasmGenerator::new(
+            contract_analysis.clone(),
+            StacksEpochId::Epoch25,
+            ClarityVersion::Clarity2,
+        )
+        .unwrap();
+        for name in [
+            "stdlib.is-eq-bytes",
+            "stdlib.sha256-buf",
+            "stdlib.sha256-int",
+        ] {
+            let simd = generator.func_by_name(&format!("{name}-simd")).unwrap();
+            let scalar = generator.func_by_name(name).unwrap();
+            assert_eq!(generator.target_func_by_name(name).unwrap(), simd);
+
+            generator.target_features = TargetFeatures::ALL.without(Feature::Simd);
+            assert_eq!(generator.target_func_by_name(name).unwrap(), scalar);
+            generator.target_features = TargetFeatures::ALL;
+        }
+        // The functions without a variant.
+        assert_eq!(
+            generator.target_func_by_name("stdlib.sha512-buf").unwrap(),
+            generator.func_by_name("stdlib.sha512-buf").unwrap()
+        );
+
+        // A standard library without the variants falls back to the scalar
+        // functions.
+        let mut module = Module::from_buffer(super::STANDARD_LIBRARY).unwrap();
+        for func in module.funcs.iter_mut() {
+            if func
+                .name
+                .as_ref()
+                .is_some_and(|name| name.ends_with("-simd"))
+            {
+                func.name = None;
+            }
+        }
+        crate::sections::add_abi_version(&mut module);
+        let generator = super::WasmGenerator::with_standard_library(
+            contract_analysis,
+            StacksEpochId::Epoch25,
+            ClarityVersion::Clarity2,
+            &module.emit_wasm(),
+        )
+        .unwrap();
+        assert_eq!(
+            generator.target_func_by_name("stdlib.is-eq-bytes").unwrap(),
+            generator.func_by_name("stdlib.is-eq-bytes").unwrap()
+        );
+    }
+
+    #[test]
+    fn reserved_names_by_version() {
+        let contract_analysis = compile(
+            "(+ 1 2)",
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity1,
+            StacksEpochId::Epoch21,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .unwrap()
+        .contract_analysis;
+
+        let generator = |version| {
+            super::WasmGenerator::new(contract_analysis.clone(), StacksEpochId::Epoch21, version)
+                .unwrap()
+        };
+        let (clarity1, clarity2) = (
+            generator(ClarityVersion::Clarity1),
+            generator(ClarityVersion::Clarity2),
+        );
+
+        for name in ["map", "tx-sender", "element-at"] {
+            for generator in [&clarity1, &clarity2] {
+                assert!(matches!(
+                    generator.check_reserved_name(&name.into()),
+                    Err(super::GeneratorError::NameAlreadyUsed(used)) if used == name
+                ));
+            }
+        }
+
+        // Introduced in Clarity 2.
+        assert!(clarity1.check_reserved_name(&"stx-account".into()).is_ok());
+        let error = clarity2
+            .check_reserved_name(&"stx-account".into())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "defining 'stx-account' conflicts with previous value"
+        );
+
+        assert!(clarity2.check_reserved_name(&"foo".into()).is_ok());
+    }
+
+    fn compile_with_options(snippet: &str, options: CompileOptions) -> Module {
+        compile(
+            snippet,
+            &QualifiedContractIdentifier::new(StandardPrincipalData::transient(), ("tmp").into()),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            options,
+        )
+        .unwrap()
+        .module
+    }
+
+    #[test]
+    fn compile_options_emit_names() {
+        let snippet = "(define-private (foo) 42) (foo)";
+
+        let module = compile_with_options(snippet, CompileOptions::default());
+        assert!(module
+            .funcs
+            .iter()
+            .any(|f| f.name.as_deref() == Some("foo")));
+
+        let module = compile_with_options(snippet, CompileOptions::default().emit_names(false));
+        assert!(module.funcs.iter().all(|f| f.name.is_none()));
+    }
+
+    #[test]
+    fn compile_options_deterministic() {
+        let snippet = "(define-data-var foo int 42) (var-get foo)";
+        let options = CompileOptions::default().deterministic(true);
+
+        let mut first = compile_with_options(snippet, options.clone());
+        let mut second = compile_with_options(snippet, options);
+        assert_eq!(first.emit_wasm(), second.emit_wasm());
+    }
+
+    #[test]
+    fn compile_options_host_module() {
+        let snippet = "(define-data-var foo int 42) (var-get foo)";
+        let host_imports = |module: &Module| {
+            module
+                .imports
+                .iter()
+                .filter(|import| matches!(import.kind, walrus::ImportKind::Function(_)))
+                .filter(|import| !import.module.is_empty())
+                .map(|import| import.module.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let module = compile_with_options(snippet, CompileOptions::default());
+        let imports = host_imports(&module);
+        assert!(!imports.is_empty());
+        assert!(imports.iter().all(|m| m == crate::DEFAULT_HOST_MODULE));
+
+        let module = compile_with_options(
+            snippet,
+            CompileOptions::default().host_module("stacks_host"),
+        );
+        let imports = host_imports(&module);
+        assert!(!imports.is_empty());
+        assert!(imports.iter().all(|m| m == "stacks_host"));
+        assert!(module.imports.find("stacks_host", "get_variable").is_some());
+    }
+
+    #[test]
+    fn compile_options_memory_checks() {
+        use wasmtime::{Engine, Instance, Store, Val};
+
+        let snippet = "(define-read-only (foo) (default-to 0 (unwrap-panic (element-at? (list (some 1) none) u0))))";
+        let options = CompileOptions::default().standalone(true);
+
+        let unchecked = compile_with_options(snippet, options.clone()).emit_wasm();
+        let mut module = compile_with_options(snippet, options.memory_checks(true));
+        let checked = module.emit_wasm();
+        assert!(checked.len() > unchecked.len());
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = wasmtime::Module::from_binary(&engine, &checked).unwrap();
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+
+        let foo = instance.get_func(&mut store, "foo").unwrap();
+        let mut results = [Val::I64(0), Val::I64(0)];
+        foo.call(&mut store, &[], &mut results).unwrap();
+        assert_eq!(results[0].unwrap_i64(), 1);
+        assert_eq!(results[1].unwrap_i64(), 0);
+    }
+
+    #[test]
+    fn invalid_values_read_from_memory_fail_without_memory_checks() {
+        use wasmtime::{Engine, Instance, Store, Val};
+
+        use crate::error_mapping::ErrorMap;
+
+        let snippet =
+            "(define-read-only (foo) (unwrap-panic (element-at? (list true false true true false) u0)))";
+        let wasm =
+            compile_with_options(snippet, CompileOptions::default().standalone(true)).emit_wasm();
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = wasmtime::Module::from_binary(&engine, &wasm).unwrap();
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+
+        // The list is a literal in the memory, make its first bool invalid,
+        // like a value written by a faulty host.
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let list: Vec<u8> = [1i32, 0, 1, 1, 0]
+            .iter()
+            .flat_map(|b| b.to_le_bytes())
+            .collect();
+        let offset = memory
+            .data(&store)
+            .windows(list.len())
+            .position(|w| w == list)
+            .unwrap();
+        memory.data_mut(&mut store)[offset] = 2;
+
+        let foo = instance.get_func(&mut store, "foo").unwrap();
+        let mut results = [Val::I32(0)];
+        assert!(foo.call(&mut store, &[], &mut results).is_err());
+        let error_code = instance
+            .get_global(&mut store, "runtime-error-code")
+            .unwrap()
+            .get(&mut store)
+            .unwrap_i32();
+        assert_eq!(error_code, ErrorMap::InvalidHostData as i32);
+    }
+
+    #[test]
+    fn memory_checks_catch_frame_overflow() {
+        use clarity::vm::errors::WasmError;
+        use walrus::{ir::Value, GlobalKind, InitExpr};
+
+        use crate::tools::{TestConfig, TestEnvironment};
+
+        let contract_id = QualifiedContractIdentifier::local("overflow").unwrap();
+        let mut result = compile(
+            "(let ((a 1)) (list a 2))",
+            &contract_id,
+            LimitedCostTracker::new_free(),
+            TestConfig::clarity_version(),
+            TestConfig::epoch(),
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default().memory_checks(true),
+        )
+        .unwrap();
+
+        // Start the call stack 16 bytes before the end of the memory, so that
+        // the second element of the list is written past it.
+        let memory_end = result.module.memories.iter().next().unwrap().initial as i64 * 65536;
+        let stack_pointer = super::get_global(&result.module, "stack-pointer").unwrap();
+        result.module.globals.get_mut(stack_pointer).kind =
+            GlobalKind::Local(InitExpr::Value(Value::I32((memory_end - 16) as i32)));
+
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        let err = env
+            .deploy_precompiled(
+                &contract_id,
+                &result.module.emit_wasm(),
+                &result.contract_analysis,
+            )
+            .unwrap_err();
+        assert!(
+            matches!(
+                &err,
+                Error::Wasm(WasmError::WasmGeneratorError(message))
+                    if message.starts_with("memory check failed for expression at line 1, column ")
+            ),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn call_stack_is_the_deepest_frame() {
+        // Each function reserves 32KiB in its frame for the list.
+        let function = |name: &str, call: &str| {
+            format!(
+                "(define-private ({name} (x int)) (+ {call} (to-int (len (list {})))))",
+                "x ".repeat(2048)
+            )
+        };
+        let pages = |snippet: String| {
+            compile_with_options(&snippet, CompileOptions::default())
+                .memories
+                .iter()
+                .next()
+                .unwrap()
+                .initial
+        };
+
+        let one = pages(function("f", "x"));
+        // Functions which don't call each other share the space of the call
+        // stack.
+        let siblings = pages(format!("{} {}", function("f", "x"), function("g", "x")));
+        assert_eq!(siblings, one);
+        // A function calling another one needs both frames.
+        let nested = pages(format!("{} {}", function("g", "x"), function("f", "(g x)")));
+        assert!(nested > one);
+    }
+
+    #[test]
+    fn copy_memory_routine() {
+        use wasmtime::{Engine, Instance, Store, Val};
+
+        let mut module = Module::default();
+        let memory = module.memories.add_local(false, 1, None);
+        module.exports.add("memory", memory);
+        let copy = super::build_copy_memory(&mut module, memory);
+        module.exports.add("copy", copy);
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = wasmtime::Module::from_binary(&engine, &module.emit_wasm()).unwrap();
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let copy = instance.get_func(&mut store, "copy").unwrap();
+
+        let mut expected: Vec<u8> = (0..32).collect();
+        memory.write(&mut store, 0, &expected).unwrap();
+        // Overlapping ranges, in both directions, and an empty copy.
+        for (destination, source, length) in [(4, 0, 12), (1, 10, 8), (20, 3, 0), (16, 0, 16)] {
+            copy.call(
+                &mut store,
+                &[Val::I32(destination), Val::I32(source), Val::I32(length)],
+                &mut [],
+            )
+            .unwrap();
+            let (destination, source, length) =
+                (destination as usize, source as usize, length as usize);
+            expected.copy_within(source..source + length, destination);
+            assert_eq!(&memory.data(&store)[..32], &expected[..]);
+        }
+    }
+
+    #[test]
+    fn compile_options_target_features() {
+        use crate::target_features::{Feature, TargetFeatures};
+        use crate::CompileError;
+
+        let snippet = r#"(define-read-only (foo) (concat "hello, " "world"))"#;
+        compile_with_options(
+            snippet,
+            CompileOptions::default().target_features(TargetFeatures::ALL),
+        );
+
+        // The bundled standard library uses `memory.copy`.
+        let Err(CompileError::Generic { diagnostics, .. }) = compile(
+            snippet,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default()
+                .target_features(TargetFeatures::ALL.without(Feature::BulkMemory)),
+        ) else {
+            panic!("compiled without the bulk memory operations");
+        };
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("WebAssembly feature bulk-memory")));
+    }
+
+    /// Builds the routine `(destination, value, length)` filling `length`
+    /// bytes like `memory.fill`, from the end.
+    fn build_fill_memory(module: &mut Module, memory: walrus::MemoryId) -> walrus::FunctionId {
+        use walrus::ir::{BinaryOp, MemArg, StoreKind, UnaryOp};
+        use walrus::{FunctionBuilder, ValType};
+
+        let destination = module.locals.add(ValType::I32);
+        let value = module.locals.add(ValType::I32);
+        let length = module.locals.add(ValType::I32);
+        let mut func = FunctionBuilder::new(&mut module.types, &[ValType::I32; 3], &[]);
+        func.func_body().block(None, |done| {
+            let done_id = done.id();
+            done.loop_(None, |loop_| {
+                let loop_id = loop_.id();
+                loop_
+                    .local_get(length)
+                    .unop(UnaryOp::I32Eqz)
+                    .br_if(done_id)
+                    .local_get(length)
+                    .i32_const(1)
+                    .binop(BinaryOp::I32Sub)
+                    .local_tee(length)
+                    .local_get(destination)
+                    .binop(BinaryOp::I32Add)
+                    .local_get(value)
+                    .store(
+                        memory,
+                        StoreKind::I32_8 { atomic: false },
+                        MemArg {
+                            align: 1,
+                            offset: 0,
+                        },
+                    )
+                    .br(loop_id);
+            });
+        });
+        func.finish(vec![destination, value, length], &mut module.funcs)
+    }
+
+    /// The bundled standard library, with calls to routines instead of the
+    /// bulk memory operations.
+    fn standard_library_without_bulk_memory() -> Vec<u8> {
+        use walrus::ir::{Block, Call, IfElse, Instr, Loop};
+
+        let mut module = Module::from_buffer(super::STANDARD_LIBRARY).unwrap();
+        let memory = module.memories.iter().next().unwrap().id();
+        let copy = super::build_copy_memory(&mut module, memory);
+        let fill = build_fill_memory(&mut module, memory);
+        for (_, func) in module.funcs.iter_local_mut() {
+            let mut seqs = vec![func.entry_block()];
+            while let Some(seq) = seqs.pop() {
+                for (instr, _) in func.block_mut(seq).instrs.iter_mut() {
+                    match instr {
+                        Instr::MemoryCopy(_) => *instr = Instr::Call(Call { func: copy }),
+                        Instr::MemoryFill(_) => *instr = Instr::Call(Call { func: fill }),
+                        Instr::Block(Block { seq }) | Instr::Loop(Loop { seq }) => seqs.push(*seq),
+                        Instr::IfElse(IfElse {
+                            consequent,
+                            alternative,
+                        }) => seqs.extend([*consequent, *alternative]),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        crate::sections::add_abi_version(&mut module);
+        module.emit_wasm()
+    }
+
+    #[test]
+    fn target_without_bulk_memory() {
+        use clarity::vm::Value;
+
+        use crate::target_features::{Feature, TargetFeatures};
+        use crate::tools::{TestConfig, TestEnvironment};
+
+        // Compiled and validated against the target, so the module doesn't
+        // use `memory.copy` nor `memory.fill`.
+        let options = CompileOptions::default()
+            .standard_library(standard_library_without_bulk_memory())
+            .target_features(TargetFeatures::ALL.without(Feature::BulkMemory));
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        let value = env
+            .init_contract_with_options(
+                "copies",
+                r#"
+(define-read-only (greet (name (string-ascii 16)))
+  (concat "hello, " name))
+(define-read-only (rotate (items (list 4 int)))
+  (concat (unwrap-panic (slice? items u1 (len items))) (list (unwrap-panic (element-at? items u0)))))
+(concat (list 1 2) (list 3))
+"#,
+                options,
+            )
+            .unwrap();
+        assert_eq!(
+            value,
+            Some(
+                Value::cons_list_unsanitized(vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+                    .unwrap()
+            )
+        );
+
+        assert_eq!(
+            env.call_function(
+                "copies",
+                "greet",
+                &[Value::string_ascii_from_bytes(b"world".to_vec()).unwrap()]
+            ),
+            Ok(Value::string_ascii_from_bytes(b"hello, world".to_vec()).unwrap())
+        );
+        assert_eq!(
+            env.call_function(
+                "copies",
+                "rotate",
+                &[
+                    Value::cons_list_unsanitized(vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+                        .unwrap()
+                ]
+            ),
+            Ok(
+                Value::cons_list_unsanitized(vec![Value::Int(2), Value::Int(3), Value::Int(1)])
+                    .unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn target_without_multi_value() {
+        use clarity::vm::Value;
+
+        use crate::target_features::{Feature, TargetFeatures};
+        use crate::tools::{TestConfig, TestEnvironment};
+
+        // The module is lowered like with `memory_returns`, and validated
+        // against the target.
+        let options = CompileOptions::default()
+            .target_features(TargetFeatures::ALL.without(Feature::MultiValue));
+        let module = compile_with_options(
+            r#"(define-read-only (greet (name (string-ascii 16))) (concat "hi " name))"#,
+            options.clone(),
+        );
+        for func in module.funcs.iter() {
+            let results = module.types.get(func.ty()).results().len();
+            assert!(results <= 1, "{:?} returns {results} values", func.name);
+        }
+
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        env.init_contract_with_options(
+            "greeter",
+            r#"(define-read-only (greet (name (string-ascii 16))) (concat "hi " name))"#,
+            options,
+        )
+        .unwrap();
+        assert_eq!(
+            env.call_function(
+                "greeter",
+                "greet",
+                &[Value::string_ascii_from_bytes(b"bob".to_vec()).unwrap()]
+            ),
+            Ok(Value::string_ascii_from_bytes(b"hi bob".to_vec()).unwrap())
+        );
+    }
+
+    #[test]
+    fn compile_result_functions() {
+        let result = compile(
+            "(define-private (helper) 1) (define-public (pay) (ok (helper))) (define-read-only (get) u1)",
+            &QualifiedContractIdentifier::new(StandardPrincipalData::transient(), ("tmp").into()),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default().emit_names(false),
+        )
+        .unwrap();
+
+        assert_eq!(result.functions.len(), 3);
+        let helper = result.functions[&ClarityName::from("helper")];
+        assert_eq!(helper.kind, super::FunctionKind::Private);
+        assert_eq!(helper.export, None);
+        for (name, kind) in [
+            ("pay", super::FunctionKind::Public),
+            ("get", super::FunctionKind::ReadOnly),
+        ] {
+            let function = result.functions[&ClarityName::from(name)];
+            assert_eq!(function.kind, kind);
+            let export = result.module.exports.get(function.export.unwrap());
+            assert_eq!(export.name, name);
+            assert!(matches!(export.item, walrus::ExportItem::Function(id) if id == function.id));
+        }
+    }
+
+    #[test]
+    fn recompile_function_patches_module() {
+        use wasmtime::{Engine, Instance, Store, Val};
+
+        let contract_id =
+            QualifiedContractIdentifier::new(StandardPrincipalData::transient(), ("tmp").into());
+        let options = CompileOptions::default().standalone(true);
+        let recompile = |result: &mut crate::CompileResult, source: &str| {
+            crate::recompile_function(
+                result,
+                "foo",
+                source,
+                &contract_id,
+                LimitedCostTracker::new_free(),
+                ClarityVersion::Clarity2,
+                StacksEpochId::Epoch25,
+                &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+                options.clone(),
+            )
+        };
+
+        let mut result = compile(
+            r#"(define-read-only (foo) (+ 1 2)) (define-read-only (name) "hello")"#,
+            &contract_id,
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            options.clone(),
+        )
+        .unwrap();
+        let functions = result.module.funcs.iter().count();
+
+        // A new signature requires a full compilation.
+        let before = result.module.emit_wasm();
+        assert!(recompile(
+            &mut result,
+            r#"(define-read-only (foo) u3) (define-read-only (name) "hello")"#
+        )
+        .is_err());
+        assert_eq!(result.module.emit_wasm(), before);
+
+        recompile(
+            &mut result,
+            r#"(define-read-only (foo) (begin (print "patched") (+ 1 41))) (define-read-only (name) "hello")"#,
+        )
+        .unwrap();
+        assert_eq!(result.module.funcs.iter().count(), functions);
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = wasmtime::Module::from_binary(&engine, &result.module.emit_wasm()).unwrap();
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+
+        let foo = instance.get_func(&mut store, "foo").unwrap();
+        let mut results = [Val::I64(0), Val::I64(0)];
+        foo.call(&mut store, &[], &mut results).unwrap();
+        assert_eq!(results[0].unwrap_i64(), 42);
+
+        let name = instance.get_func(&mut store, "name").unwrap();
+        let mut results = [Val::I32(0), Val::I32(0)];
+        name.call(&mut store, &[], &mut results).unwrap();
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let offset = results[0].unwrap_i32() as usize;
+        assert_eq!(&memory.data(&store)[offset..offset + 5], b"hello");
+    }
+
+    #[test]
+    fn recompile_function_checks_target_features() {
+        use crate::target_features::{Feature, TargetFeatures};
+        use crate::CompileError;
+
+        let contract_id =
+            QualifiedContractIdentifier::new(StandardPrincipalData::transient(), ("tmp").into());
+        let mut result = compile(
+            "(define-read-only (foo) (+ 1 2))",
+            &contract_id,
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default(),
+        )
+        .unwrap();
+
+        // The patched module is validated like a compiled one: it still
+        // contains the `memory.copy` of the bundled standard library.
+        let Err(CompileError::Generic { diagnostics, .. }) = crate::recompile_function(
+            &mut result,
+            "foo",
+            "(define-read-only (foo) (+ 1 41))",
+            &contract_id,
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            CompileOptions::default()
+                .target_features(TargetFeatures::ALL.without(Feature::BulkMemory)),
+        ) else {
+            panic!("recompiled without the bulk memory operations");
+        };
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("WebAssembly feature bulk-memory")));
+    }
+
     #[test]
     fn test_work_space() {
         let buff_len = 1048576;
@@ -2043,6 +4112,7 @@ mod tests {
                 ClarityVersion::Clarity2,
                 StacksEpochId::Epoch25,
                 &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+                CompileOptions::default(),
             )
             .unwrap()
             .module;
@@ -2155,6 +4225,120 @@ mod tests {
         );
     }
 
+    #[test]
+    fn stdlib_functions_are_found() {
+        let standard_lib = Module::from_buffer(include_bytes!("standard/standard.wasm")).unwrap();
+        let functions = super::stdlib_functions(&standard_lib).unwrap();
+        assert_eq!(
+            functions.len(),
+            super::STDLIB_FUNCTIONS.len() + super::STDLIB_SIMD_FUNCTIONS.len()
+        );
+
+        // All the missing functions are named in one error.
+        let err = super::stdlib_functions(&Module::default()).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("stdlib.define_function, stdlib.define_variable"));
+        assert!(message.contains("stdlib.runtime-error"));
+    }
+
+    fn standard_library_with_abi_version(version: Option<u32>) -> Vec<u8> {
+        use walrus::RawCustomSection;
+
+        use crate::sections::ABI_VERSION_SECTION;
+
+        let mut module = Module::from_buffer(super::STANDARD_LIBRARY).unwrap();
+        if let Some(version) = version {
+            module.customs.add(RawCustomSection {
+                name: ABI_VERSION_SECTION.to_owned(),
+                data: version.to_le_bytes().to_vec(),
+            });
+        }
+        module.emit_wasm()
+    }
+
+    #[test]
+    fn external_standard_library() {
+        use crate::sections::{abi_version, ABI_VERSION, ABI_VERSION_SECTION};
+        use crate::CompileError;
+
+        let snippet = "(define-read-only (foo) (+ 1 2))";
+        let module = compile_with_options(
+            snippet,
+            CompileOptions::default()
+                .standard_library(standard_library_with_abi_version(Some(ABI_VERSION))),
+        );
+        assert_eq!(abi_version(&module).unwrap(), ABI_VERSION);
+        assert_eq!(
+            module
+                .customs
+                .iter()
+                .filter(|(_, section)| section.name() == ABI_VERSION_SECTION)
+                .count(),
+            1
+        );
+
+        for version in [None, Some(ABI_VERSION + 1)] {
+            let Err(CompileError::Generic { diagnostics, .. }) = compile(
+                snippet,
+                &QualifiedContractIdentifier::transient(),
+                LimitedCostTracker::new_free(),
+                ClarityVersion::Clarity2,
+                StacksEpochId::Epoch25,
+                &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+                CompileOptions::default()
+                    .standard_library(standard_library_with_abi_version(version)),
+            ) else {
+                panic!("compiled with a standard library of ABI version {version:?}");
+            };
+            assert!(diagnostics
+                .iter()
+                .any(|d| d.message.contains("invalid standard library")));
+        }
+    }
+
+    #[test]
+    fn generator_error_codes() {
+        use clarity::vm::representations::Span;
+
+        use crate::wasm_generator::GeneratorError;
+
+        let errors = [
+            GeneratorError::NotImplemented,
+            GeneratorError::InternalError("internal".to_owned()),
+            GeneratorError::TypeError("type".to_owned()),
+            GeneratorError::ArgumentCountMismatch,
+            GeneratorError::UnsupportedWord {
+                name: "foo".to_owned(),
+            },
+            GeneratorError::MemoryLayout("memory".to_owned()),
+            GeneratorError::TraitFunctionNotExported {
+                trait_identifier: "S1G2081040G2081040G2081040G208105NK8PE5.trait.named".to_owned(),
+                function: "get-name".to_owned(),
+            },
+            GeneratorError::NameAlreadyUsed("map".to_owned()),
+            GeneratorError::UnsupportedFeature {
+                feature: crate::target_features::Feature::BulkMemory,
+                message: "memory.copy".to_owned(),
+            },
+        ];
+        for (code, error) in (1..).zip(errors) {
+            assert_eq!(error.code(), code);
+            assert!(error.span().is_none());
+
+            let message = error.to_string();
+            let inner_span = Span {
+                start_line: 2,
+                start_column: 3,
+                end_line: 2,
+                end_column: 8,
+            };
+            let spanned = error.at(&inner_span).at(&Span::zero());
+            assert_eq!(spanned.code(), code);
+            assert_eq!(spanned.span(), Some(&inner_span));
+            assert_eq!(spanned.to_string(), message);
+        }
+    }
+
     //
     // Module with tests that should only be executed
     // when running Clarity::V2 or Clarity::v3.
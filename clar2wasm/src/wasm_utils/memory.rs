@@ -0,0 +1,175 @@
+//! Inspection of the memory of an instance, for the tools and the tests
+//! reading or writing its regions, and for the error dumps.
+
+use std::fmt::{self, Write};
+use std::ops::Deref;
+
+use clarity::vm::errors::{Error, WasmError};
+use wasmtime::{AsContext, AsContextMut, Memory};
+
+/// A region of the memory of an instance: its bytes, and the offset where
+/// they were read or where they are written.
+#[derive(Clone, PartialEq, Eq)]
+pub struct MemoryRegion {
+    bytes: Vec<u8>,
+    offset: usize,
+}
+
+impl fmt::Debug for MemoryRegion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryRegion")
+            .field("bytes", &format_args!("0x{}", to_hex(&self.bytes)))
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+impl MemoryRegion {
+    /// Creates the region of `bytes` at `offset`.
+    pub fn new(bytes: Vec<u8>, offset: usize) -> Self {
+        Self { bytes, offset }
+    }
+
+    /// Reads the region of `length` bytes at `offset` of `memory`.
+    pub fn read(
+        memory: Memory,
+        store: impl AsContext,
+        offset: usize,
+        length: usize,
+    ) -> Result<Self, Error> {
+        let mut bytes = vec![0u8; length];
+        memory
+            .read(store, offset, &mut bytes)
+            .map_err(|e| Error::Wasm(WasmError::Runtime(e.into())))?;
+        Ok(Self { bytes, offset })
+    }
+
+    /// Writes the region to `memory`, and returns its offset and length, as
+    /// passed to the functions of a module.
+    pub fn write(&self, memory: Memory, store: impl AsContextMut) -> Result<(i32, i32), Error> {
+        memory
+            .write(store, self.offset, &self.bytes)
+            .map_err(|e| Error::Wasm(WasmError::Runtime(e.into())))?;
+        Ok((self.offset as i32, self.bytes.len() as i32))
+    }
+
+    /// Offset of the region in the memory.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Formats the region as a hex dump, see [`hex_dump`].
+    pub fn hex_dump(&self) -> String {
+        hex_dump(&self.bytes, self.offset)
+    }
+}
+
+impl From<MemoryRegion> for Vec<u8> {
+    fn from(value: MemoryRegion) -> Self {
+        value.bytes
+    }
+}
+
+impl Deref for MemoryRegion {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.bytes
+    }
+}
+
+impl AsRef<[u8]> for MemoryRegion {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+/// Formats `bytes` as lowercase hexadecimal digits.
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+/// Formats `bytes`, found at `offset` of the memory, as `hexdump -C` does:
+/// rows of 16 bytes with their address, in hexadecimal and in ASCII. The
+/// repeated rows are elided.
+pub fn hex_dump(bytes: &[u8], offset: usize) -> String {
+    let mut dump = String::new();
+    let mut previous_row: Option<&[u8]> = None;
+    let mut skipping = false;
+    for (index, row) in bytes.chunks(16).enumerate() {
+        if previous_row == Some(row) {
+            if !skipping {
+                let _ = writeln!(dump, "*");
+                skipping = true;
+            }
+            continue;
+        }
+        previous_row = Some(row);
+        skipping = false;
+
+        let hex: Vec<_> = row.iter().map(|b| format!("{b:02x}")).collect();
+        let ascii: String = row
+            .iter()
+            .map(|&b| if b.is_ascii_graphic() { b as char } else { '.' })
+            .collect();
+        let _ = writeln!(
+            dump,
+            "{:08x}  {:<47}  |{ascii}|",
+            offset + index * 16,
+            hex.join(" ")
+        );
+    }
+    dump
+}
+
+#[cfg(test)]
+mod tests {
+    use wasmtime::{Engine, MemoryType, Store};
+
+    use super::*;
+
+    #[test]
+    fn read_and_write_a_region() {
+        let mut store = Store::new(&Engine::default(), ());
+        let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+
+        let region = MemoryRegion::new(b"clarity".to_vec(), 100);
+        assert_eq!(region.write(memory, &mut store).unwrap(), (100, 7));
+        assert_eq!(MemoryRegion::read(memory, &store, 100, 7).unwrap(), region);
+        assert_eq!(
+            &*MemoryRegion::read(memory, &store, 102, 3).unwrap(),
+            b"ari"
+        );
+
+        assert!(MemoryRegion::new(vec![0; 2], 65535)
+            .write(memory, &mut store)
+            .is_err());
+        assert!(MemoryRegion::read(memory, &store, 65535, 2).is_err());
+    }
+
+    #[test]
+    fn debug_format() {
+        assert_eq!(
+            format!("{:?}", MemoryRegion::new(vec![0x01, 0xab], 8)),
+            "MemoryRegion { bytes: 0x01ab, offset: 8 }"
+        );
+    }
+
+    #[test]
+    fn hex_dump_of_a_region() {
+        let mut bytes = b"hello, world!\0\0\0".to_vec();
+        bytes.extend([0; 40]);
+        bytes.extend([0xff, b'x']);
+
+        assert_eq!(
+            MemoryRegion::new(bytes, 0x20).hex_dump(),
+            "00000020  68 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 00 00 00  |hello,.world!...|\n\
+             00000030  00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00  |................|\n\
+             *\n\
+             00000050  00 00 00 00 00 00 00 00 ff 78                    |.........x|\n"
+        );
+    }
+}
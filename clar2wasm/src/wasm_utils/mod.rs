@@ -1,8 +1,15 @@
 #![allow(non_camel_case_types)]
 
+pub mod memory;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
 use clarity::vm::analysis::CheckErrors;
 use clarity::vm::ast::{build_ast_with_rules, ASTRules};
 use clarity::vm::contexts::GlobalContext;
+use clarity::vm::costs::LimitedCostTracker;
+use clarity::vm::database::ClarityDatabase;
 use clarity::vm::errors::{Error, WasmError};
 use clarity::vm::types::signatures::CallableSubtype;
 use clarity::vm::types::{
@@ -13,11 +20,15 @@ use clarity::vm::types::{
 use clarity::vm::{CallStack, ClarityVersion, ContractContext, ContractName, Value};
 use stacks_common::types::StacksEpochId;
 use walrus::{GlobalId, InstrSeqBuilder};
-use wasmtime::{AsContextMut, Linker, Memory, Module, Store, Val, ValType};
+use wasmtime::{AsContextMut, Engine, Instance, Linker, Memory, Module, Store, Val, ValType};
 
 use crate::error_mapping::{self, ErrorMap};
-use crate::initialize::ClarityWasmContext;
-use crate::linker::link_host_functions;
+use crate::initialize::{ClarityWasmContext, HostCallCounts, HostCallTracer};
+use crate::layout::LayoutType;
+use crate::linker::{
+    link_deterministic_host_functions, link_host_functions, link_memory_returns_host_functions,
+    FixedHeights,
+};
 use crate::wasm_generator::{GeneratorError, WasmGenerator};
 
 #[allow(non_snake_case)]
@@ -61,20 +72,10 @@ pub enum StxErrorCodes {
     SENDER_IS_NOT_TX_SENDER = 4,
 }
 
-// Bytes for principal version
-pub const PRINCIPAL_VERSION_BYTES: usize = 1;
-// Number of bytes in principal hash
-pub const PRINCIPAL_HASH_BYTES: usize = 20;
-// Standard principal version + hash
-pub const PRINCIPAL_BYTES: usize = PRINCIPAL_VERSION_BYTES + PRINCIPAL_HASH_BYTES;
-// Number of bytes used to store the length of the contract name
-pub const CONTRACT_NAME_LENGTH_BYTES: usize = 1;
-// 1 byte for version, 20 bytes for hash, 4 bytes for contract name length (0)
-pub const STANDARD_PRINCIPAL_BYTES: usize = PRINCIPAL_BYTES + CONTRACT_NAME_LENGTH_BYTES;
-// Max length of a contract name
-pub const CONTRACT_NAME_MAX_LENGTH: usize = 128;
-// Standard principal, but at most 128 character function name
-pub const PRINCIPAL_BYTES_MAX: usize = STANDARD_PRINCIPAL_BYTES + CONTRACT_NAME_MAX_LENGTH;
+pub use crate::layout::{
+    CONTRACT_NAME_LENGTH_BYTES, CONTRACT_NAME_MAX_LENGTH, PRINCIPAL_BYTES, PRINCIPAL_BYTES_MAX,
+    PRINCIPAL_HASH_BYTES, PRINCIPAL_VERSION_BYTES, STANDARD_PRINCIPAL_BYTES,
+};
 
 /// Convert a Wasm value into a Clarity `Value`. Depending on the type, the
 /// values may be directly passed in the Wasm `Val`s or may be read from the
@@ -617,95 +618,60 @@ pub fn read_indirect_offset_and_length(
 /// For in-memory types, this is just the size of the offset and
 /// length. For non-in-memory types, this is the size of the value itself.
 pub fn get_type_size(ty: &TypeSignature) -> i32 {
-    match ty {
-        TypeSignature::IntType | TypeSignature::UIntType => 16, // low: i64, high: i64
-        TypeSignature::BoolType => 4,                           // i32
-        TypeSignature::PrincipalType
-        | TypeSignature::SequenceType(_)
-        | TypeSignature::CallableType(_)
-        | TypeSignature::TraitReferenceType(_) => 8, // offset: i32, length: i32
-        TypeSignature::OptionalType(inner) => 4 + get_type_size(inner), // indicator: i32, value: inner
-        TypeSignature::TupleType(tuple_ty) => {
-            let mut size = 0;
-            for inner_type in tuple_ty.get_type_map().values() {
-                size += get_type_size(inner_type);
-            }
-            size
-        }
-        TypeSignature::ResponseType(inner_types) => {
-            // indicator: i32, ok_val: inner_types.0, err_val: inner_types.1
-            4 + get_type_size(&inner_types.0) + get_type_size(&inner_types.1)
-        }
-        TypeSignature::NoType => 4, // i32
-        TypeSignature::ListUnionType(_) => {
-            unreachable!("not a value type")
-        }
-    }
+    value_layout_type(ty).size()
 }
 
 /// Return the number of bytes required to store a value of the type `ty`.
 pub fn get_type_in_memory_size(ty: &TypeSignature, include_repr: bool) -> i32 {
-    match ty {
-        TypeSignature::IntType | TypeSignature::UIntType => 16, // i64_low + i64_high
-        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(length))) => {
-            let mut size = u32::from(length) as i32;
-            if include_repr {
-                size += 8; // offset + length
-            }
-            size
-        }
+    value_layout_type(ty).in_memory_size(include_repr)
+}
+
+/// Return the [`LayoutType`] of the values of type `ty`, to decode them with
+/// the `no_std` [`crate::layout`] crate. The sizes of the types are computed
+/// from their layout.
+pub fn layout_type(ty: &TypeSignature) -> Result<LayoutType, Error> {
+    Ok(match ty {
+        TypeSignature::IntType => LayoutType::Int,
+        TypeSignature::UIntType => LayoutType::UInt,
+        TypeSignature::BoolType => LayoutType::Bool,
+        TypeSignature::NoType => LayoutType::NoType,
         TypeSignature::PrincipalType
         | TypeSignature::CallableType(_)
-        | TypeSignature::TraitReferenceType(_) => {
-            // Standard principal is a 1 byte version and a 20 byte Hash160.
-            // Then there is an int32 for the contract name length, followed by
-            // the contract name, which has a max length of 128.
-            let mut size = PRINCIPAL_BYTES_MAX as i32;
-            if include_repr {
-                size += 8; // offset + length
-            }
-            size
-        }
-        TypeSignature::OptionalType(inner) => 4 + get_type_in_memory_size(inner, include_repr),
-        TypeSignature::SequenceType(SequenceSubtype::ListType(list_data)) => {
-            if include_repr {
-                8 // offset + length
-                 + list_data.get_max_len() as i32
-                    * get_type_in_memory_size(list_data.get_list_item_type(), true)
-            } else {
-                list_data.get_max_len() as i32 * get_type_size(list_data.get_list_item_type())
-            }
-        }
+        | TypeSignature::TraitReferenceType(_) => LayoutType::Principal,
         TypeSignature::SequenceType(SequenceSubtype::BufferType(length)) => {
-            let mut size = u32::from(length) as i32;
-            if include_repr {
-                size += 8; // offset + length
-            }
-            size
+            LayoutType::Buffer(u32::from(length))
         }
-        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(length))) => {
-            let mut size = u32::from(length) as i32 * 4;
-            if include_repr {
-                size += 8; // offset + length
-            }
-            size
-        }
-        TypeSignature::NoType => 4,   // i32
-        TypeSignature::BoolType => 4, // i32
-        TypeSignature::TupleType(tuple_ty) => {
-            let mut size = 0;
-            for inner_type in tuple_ty.get_type_map().values() {
-                size += get_type_in_memory_size(inner_type, include_repr);
-            }
-            size
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(length))) => {
+            LayoutType::StringAscii(u32::from(length))
         }
-        TypeSignature::ResponseType(res_types) => {
-            // indicator: i32, ok_val: inner_types.0, err_val: inner_types.1
-            4 + get_type_in_memory_size(&res_types.0, include_repr)
-                + get_type_in_memory_size(&res_types.1, include_repr)
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(length))) => {
+            LayoutType::StringUtf8(u32::from(length))
+        }
+        TypeSignature::SequenceType(SequenceSubtype::ListType(list_data)) => LayoutType::List(
+            Box::new(layout_type(list_data.get_list_item_type())?),
+            list_data.get_max_len(),
+        ),
+        TypeSignature::OptionalType(inner) => LayoutType::Optional(Box::new(layout_type(inner)?)),
+        TypeSignature::ResponseType(inner_types) => LayoutType::Response(
+            Box::new(layout_type(&inner_types.0)?),
+            Box::new(layout_type(&inner_types.1)?),
+        ),
+        TypeSignature::TupleType(tuple_ty) => LayoutType::Tuple(
+            tuple_ty
+                .get_type_map()
+                .values()
+                .map(layout_type)
+                .collect::<Result<_, _>>()?,
+        ),
+        TypeSignature::ListUnionType(_) => {
+            return Err(Error::Wasm(WasmError::InvalidListUnionTypeInValue))
         }
-        TypeSignature::ListUnionType(_) => unreachable!("not a value type"),
-    }
+    })
+}
+
+/// The layout of `ty`, which the callers know to be the type of a value.
+fn value_layout_type(ty: &TypeSignature) -> LayoutType {
+    layout_type(ty).unwrap_or_else(|_| unreachable!("not a value type"))
 }
 
 /// Push a placeholder value for Wasm type `ty` onto the data stack.
@@ -1166,6 +1132,37 @@ pub fn read_bytes_from_wasm(
     Ok(buffer)
 }
 
+/// Read the results of a call to an export returning them in memory, at
+/// `offset`, as values of `types` (see
+/// [`CompileOptions::memory_returns`](crate::CompileOptions::memory_returns)).
+pub fn read_memory_returns(
+    memory: Memory,
+    store: &mut impl AsContextMut,
+    offset: i32,
+    types: &[ValType],
+) -> Result<Vec<Val>, Error> {
+    let mut results = Vec::with_capacity(types.len());
+    let mut offset = offset;
+    for ty in types {
+        let (result, size) = match ty {
+            ValType::I32 => {
+                let mut bytes = [0; 4];
+                bytes.copy_from_slice(&read_bytes_from_wasm(memory, store, offset, 4)?);
+                (Val::I32(i32::from_le_bytes(bytes)), 4)
+            }
+            ValType::I64 => {
+                let mut bytes = [0; 8];
+                bytes.copy_from_slice(&read_bytes_from_wasm(memory, store, offset, 8)?);
+                (Val::I64(i64::from_le_bytes(bytes)), 8)
+            }
+            _ => return Err(Error::Wasm(WasmError::ValueTypeMismatch)),
+        };
+        results.push(result);
+        offset += size;
+    }
+    Ok(results)
+}
+
 /// Read an identifier (string) from the WASM memory at `offset` with `length`.
 pub fn read_identifier_from_wasm(
     memory: Memory,
@@ -1177,6 +1174,22 @@ pub fn read_identifier_from_wasm(
     String::from_utf8(buffer).map_err(|e| Error::Wasm(WasmError::UnableToReadIdentifier(e)))
 }
 
+/// Return true if a value of the given type has a part which stays in memory,
+/// the value itself or one of the values it contains.
+pub fn contains_in_memory_type(ty: &TypeSignature) -> bool {
+    match ty {
+        TypeSignature::TupleType(tuple_ty) => tuple_ty
+            .get_type_map()
+            .values()
+            .any(contains_in_memory_type),
+        TypeSignature::OptionalType(inner) => contains_in_memory_type(inner),
+        TypeSignature::ResponseType(inner) => {
+            contains_in_memory_type(&inner.0) || contains_in_memory_type(&inner.1)
+        }
+        ty => is_in_memory_type(ty),
+    }
+}
+
 /// Return true if the value of the given type stays in memory, and false if
 /// it is stored on the data stack.
 pub fn is_in_memory_type(ty: &TypeSignature) -> bool {
@@ -1197,7 +1210,7 @@ pub fn is_in_memory_type(ty: &TypeSignature) -> bool {
 }
 
 #[allow(clippy::unimplemented)]
-fn clar2wasm_ty(ty: &TypeSignature) -> Vec<ValType> {
+pub(crate) fn clar2wasm_ty(ty: &TypeSignature) -> Vec<ValType> {
     match ty {
         TypeSignature::NoType => vec![ValType::I32], // TODO: issue #445. Can this just be empty?
         TypeSignature::IntType => vec![ValType::I64, ValType::I64],
@@ -1244,10 +1257,364 @@ pub fn call_function<'a>(
     sender: Option<PrincipalData>,
     caller: Option<PrincipalData>,
     sponsor: Option<PrincipalData>,
+) -> Result<Value, Error> {
+    call_function_linked(
+        function_name,
+        args,
+        global_context,
+        contract_context,
+        call_stack,
+        sender,
+        caller,
+        sponsor,
+        link_host_functions,
+        None,
+    )
+}
+
+/// Call a function in the contract like [`call_function`], recording the
+/// calls to the host functions made by the contract in `tracer`, see
+/// [`ClarityWasmContext::trace_host_calls`]. The calls made by the contracts
+/// it calls are not recorded, they are part of the duration of its
+/// `contract_call`.
+#[allow(clippy::too_many_arguments)]
+pub fn call_function_traced<'a>(
+    function_name: &str,
+    args: &[Value],
+    global_context: &'a mut GlobalContext,
+    contract_context: &'a ContractContext,
+    call_stack: &'a mut CallStack,
+    sender: Option<PrincipalData>,
+    caller: Option<PrincipalData>,
+    sponsor: Option<PrincipalData>,
+    tracer: &HostCallTracer,
+) -> Result<Value, Error> {
+    call_function_linked(
+        function_name,
+        args,
+        global_context,
+        contract_context,
+        call_stack,
+        sender,
+        caller,
+        sponsor,
+        link_host_functions,
+        Some(tracer),
+    )
+}
+
+/// Call a function in the contract, with the host functions which don't
+/// depend on the state of the chain (see [`link_deterministic_host_functions`]).
+#[allow(clippy::too_many_arguments)]
+pub fn call_function_deterministic<'a>(
+    function_name: &str,
+    args: &[Value],
+    global_context: &'a mut GlobalContext,
+    contract_context: &'a ContractContext,
+    call_stack: &'a mut CallStack,
+    sender: Option<PrincipalData>,
+    caller: Option<PrincipalData>,
+    sponsor: Option<PrincipalData>,
+    heights: FixedHeights,
+) -> Result<Value, Error> {
+    call_function_linked(
+        function_name,
+        args,
+        global_context,
+        contract_context,
+        call_stack,
+        sender,
+        caller,
+        sponsor,
+        |linker| link_deterministic_host_functions(linker, heights),
+        None,
+    )
+}
+
+/// The module of a contract, deserialized once, so that it can be
+/// instantiated for many calls without deserializing it again, see
+/// [`call_function_prepared`].
+///
+/// The host functions are linked for each call: the context of the store
+/// borrows the global context of the call, so a linker can't outlive it.
+/// Linking is cheap compared to deserializing the module.
+///
+/// The module is deserialized for an engine, which is used for the calls
+/// instead of the engine of their global context.
+#[derive(Clone)]
+pub struct PreparedContract {
+    module: Module,
+    /// The number of times the module was linked, shared by the clones.
+    links: Arc<AtomicUsize>,
+}
+
+impl PreparedContract {
+    /// Prepares the module of `contract_context` for `engine`.
+    pub fn new(engine: &Engine, contract_context: &ContractContext) -> Result<Self, Error> {
+        let module = contract_context.with_wasm_module(|wasm_module| unsafe {
+            Module::deserialize(engine, wasm_module)
+                .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))
+        })?;
+        Ok(Self {
+            module,
+            links: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// The engine the module was deserialized for.
+    pub fn engine(&self) -> &Engine {
+        self.module.engine()
+    }
+
+    /// The number of times the module was linked with the host functions,
+    /// by this value and its clones.
+    pub fn link_count(&self) -> usize {
+        self.links.load(Ordering::Relaxed)
+    }
+
+    fn instantiate<'a, 'b>(
+        &self,
+        store: &mut Store<ClarityWasmContext<'a, 'b>>,
+    ) -> Result<Instance, Error> {
+        let mut linker = Linker::new(self.engine());
+        link_host_functions(&mut linker)?;
+        link_memory_returns_host_functions(&mut linker, store, &self.module)?;
+        self.links.fetch_add(1, Ordering::Relaxed);
+        linker
+            .instantiate(store, &self.module)
+            .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))
+    }
+}
+
+/// Call a function in the contract, instantiating the module prepared in
+/// `prepared`, instead of deserializing it for this call.
+#[allow(clippy::too_many_arguments)]
+pub fn call_function_prepared<'a>(
+    prepared: &PreparedContract,
+    function_name: &str,
+    args: &[Value],
+    global_context: &'a mut GlobalContext,
+    contract_context: &'a ContractContext,
+    call_stack: &'a mut CallStack,
+    sender: Option<PrincipalData>,
+    caller: Option<PrincipalData>,
+    sponsor: Option<PrincipalData>,
+) -> Result<Value, Error> {
+    call_function_instantiated(
+        function_name,
+        args,
+        global_context,
+        contract_context,
+        call_stack,
+        sender,
+        caller,
+        sponsor,
+        prepared.engine(),
+        |store| prepared.instantiate(store),
+        None,
+    )
+}
+
+/// Call a function in the contract like [`call_function_prepared`], adding
+/// the number of calls to each host function made by the contract to
+/// `host_calls`, even if the call fails. The calls made by the contracts it
+/// calls are not counted.
+#[allow(clippy::too_many_arguments)]
+pub fn call_function_prepared_counted<'a>(
+    prepared: &PreparedContract,
+    function_name: &str,
+    args: &[Value],
+    global_context: &'a mut GlobalContext,
+    contract_context: &'a ContractContext,
+    call_stack: &'a mut CallStack,
+    sender: Option<PrincipalData>,
+    caller: Option<PrincipalData>,
+    sponsor: Option<PrincipalData>,
+    host_calls: &mut HostCallCounts,
+) -> Result<Value, Error> {
+    call_function_instantiated(
+        function_name,
+        args,
+        global_context,
+        contract_context,
+        call_stack,
+        sender,
+        caller,
+        sponsor,
+        prepared.engine(),
+        |store| prepared.instantiate(store),
+        Some(host_calls),
+    )
+}
+
+/// A context for view calls on the chain state of a database: the calls run
+/// in a read-only context, without costs, and are rolled back. Indexers can
+/// create it once and run many calls with it, without the machinery of a
+/// transaction.
+pub struct ReadOnlyContext<'a> {
+    global_context: GlobalContext<'a>,
+}
+
+impl<'a> ReadOnlyContext<'a> {
+    /// Creates a context for view calls on the chain state of `database`.
+    pub fn new(
+        database: ClarityDatabase<'a>,
+        mainnet: bool,
+        chain_id: u32,
+        epoch: StacksEpochId,
+    ) -> Self {
+        Self {
+            global_context: GlobalContext::new(
+                mainnet,
+                chain_id,
+                database,
+                LimitedCostTracker::new_free(),
+                epoch,
+            ),
+        }
+    }
+
+    /// Call the read-only function `function_name` of the contract prepared
+    /// in `prepared`, as `sender`.
+    pub fn call(
+        &mut self,
+        prepared: &PreparedContract,
+        contract_context: &ContractContext,
+        function_name: &str,
+        args: &[Value],
+        sender: PrincipalData,
+    ) -> Result<Value, Error> {
+        let function = contract_context
+            .lookup_function(function_name)
+            .ok_or_else(|| CheckErrors::UndefinedFunction(function_name.to_string()))?;
+        if !function.is_public() {
+            return Err(CheckErrors::NoSuchPublicFunction(
+                contract_context.contract_identifier.to_string(),
+                function_name.to_string(),
+            )
+            .into());
+        }
+        if !function.is_read_only() {
+            return Err(CheckErrors::PublicFunctionNotReadOnly(
+                contract_context.contract_identifier.to_string(),
+                function_name.to_string(),
+            )
+            .into());
+        }
+
+        self.global_context.begin_read_only();
+        let result = call_function_prepared(
+            prepared,
+            function_name,
+            args,
+            &mut self.global_context,
+            contract_context,
+            &mut CallStack::new(),
+            Some(sender.clone()),
+            Some(sender),
+            None,
+        );
+        self.global_context.roll_back()?;
+        result
+    }
+}
+
+/// Call the read-only function `function_name` of the contract prepared in
+/// `prepared`, as a single view call on the chain state of `database`, see
+/// [`ReadOnlyContext`]. The context is created for this call, use a
+/// [`ReadOnlyContext`] to run many calls.
+#[allow(clippy::too_many_arguments)]
+pub fn call_read_only(
+    prepared: &PreparedContract,
+    contract_context: &ContractContext,
+    database: ClarityDatabase,
+    mainnet: bool,
+    chain_id: u32,
+    epoch: StacksEpochId,
+    function_name: &str,
+    args: &[Value],
+    sender: PrincipalData,
+) -> Result<Value, Error> {
+    ReadOnlyContext::new(database, mainnet, chain_id, epoch).call(
+        prepared,
+        contract_context,
+        function_name,
+        args,
+        sender,
+    )
+}
+
+/// Call a function in the contract, with the host functions linked by `link`,
+/// recording their calls in `tracer` if any.
+#[allow(clippy::too_many_arguments)]
+fn call_function_linked<'a>(
+    function_name: &str,
+    args: &[Value],
+    global_context: &'a mut GlobalContext,
+    contract_context: &'a ContractContext,
+    call_stack: &'a mut CallStack,
+    sender: Option<PrincipalData>,
+    caller: Option<PrincipalData>,
+    sponsor: Option<PrincipalData>,
+    link: impl FnOnce(&mut Linker<ClarityWasmContext>) -> Result<(), Error>,
+    tracer: Option<&HostCallTracer>,
+) -> Result<Value, Error> {
+    let engine = global_context.engine.clone();
+    call_function_instantiated(
+        function_name,
+        args,
+        global_context,
+        contract_context,
+        call_stack,
+        sender,
+        caller,
+        sponsor,
+        &engine,
+        |store| {
+            if let Some(tracer) = tracer {
+                store.data_mut().trace_host_calls(tracer.clone());
+            }
+            let module =
+                store
+                    .data()
+                    .contract_context()
+                    .with_wasm_module(|wasm_module| unsafe {
+                        Module::deserialize(&engine, wasm_module)
+                            .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))
+                    })?;
+            let mut linker = Linker::new(&engine);
+
+            // Link in the host interface functions.
+            link(&mut linker)?;
+            link_memory_returns_host_functions(&mut linker, store, &module)?;
+
+            linker
+                .instantiate(store, &module)
+                .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))
+        },
+        None,
+    )
+}
+
+/// Call a function in the contract, in a store of `engine`, with the
+/// instance created by `instantiate`, adding the number of calls to each host
+/// function to `host_calls` if any.
+#[allow(clippy::too_many_arguments)]
+fn call_function_instantiated<'a, 'b>(
+    function_name: &str,
+    args: &[Value],
+    global_context: &'a mut GlobalContext<'b>,
+    contract_context: &'a ContractContext,
+    call_stack: &'a mut CallStack,
+    sender: Option<PrincipalData>,
+    caller: Option<PrincipalData>,
+    sponsor: Option<PrincipalData>,
+    engine: &Engine,
+    instantiate: impl FnOnce(&mut Store<ClarityWasmContext<'a, 'b>>) -> Result<Instance, Error>,
+    host_calls: Option<&mut HostCallCounts>,
 ) -> Result<Value, Error> {
     let epoch = global_context.epoch_id;
     let clarity_version = *contract_context.get_clarity_version();
-    let engine = global_context.engine.clone();
     let context = ClarityWasmContext::new_run(
         global_context,
         contract_context,
@@ -1262,21 +1629,11 @@ pub fn call_function<'a>(
         .contract_context()
         .lookup_function(function_name)
         .ok_or(CheckErrors::UndefinedFunction(function_name.to_string()))?;
-    let module = context
-        .contract_context()
-        .with_wasm_module(|wasm_module| unsafe {
-            Module::deserialize(&engine, wasm_module)
-                .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))
-        })?;
-    let mut store = Store::new(&engine, context);
-    let mut linker = Linker::new(&engine);
-
-    // Link in the host interface functions.
-    link_host_functions(&mut linker)?;
-
-    let instance = linker
-        .instantiate(&mut store, &module)
-        .map_err(|e| Error::Wasm(WasmError::UnableToLoadModule(e)))?;
+    let mut store = Store::new(engine, context);
+    if host_calls.is_some() {
+        store.data_mut().count_host_calls();
+    }
+    let instance = instantiate(&mut store)?;
 
     // Call the specified function
     let func = instance
@@ -1329,14 +1686,44 @@ pub fn call_function<'a>(
         .set(&mut store, Val::I32(offset))
         .map_err(|e| Error::Wasm(WasmError::Runtime(e)))?;
 
+    // An export compiled with memory returns returns the offset of the
+    // results instead.
+    let memory_returns = results.len() > 1 && func.ty(&store).results().len() == 1;
+    let mut returned = if memory_returns {
+        vec![Val::I32(0)]
+    } else {
+        std::mem::take(&mut results)
+    };
+
     // Call the function
-    func.call(&mut store, &wasm_args, &mut results)
-        .map_err(|e| {
-            error_mapping::resolve_error(e, instance, &mut store, &epoch, &clarity_version)
-        })?;
+    let call_result = func.call(&mut store, &wasm_args, &mut returned);
+    if let Some(host_calls) = host_calls {
+        for (name, count) in store.data_mut().take_host_calls().unwrap_or_default() {
+            *host_calls.entry(name).or_default() += count;
+        }
+    }
+    call_result.map_err(|e| {
+        let host_function = store.data().last_host_call();
+        error_mapping::resolve_error(
+            e,
+            instance,
+            &mut store,
+            &epoch,
+            &clarity_version,
+            host_function,
+        )
+    })?;
+
+    if memory_returns {
+        let types: Vec<_> = results.iter().map(Val::ty).collect();
+        let offset = returned[0]
+            .i32()
+            .ok_or(Error::Wasm(WasmError::ValueTypeMismatch))?;
+        returned = read_memory_returns(memory, &mut store, offset, &types)?;
+    }
 
     // If the function returns a value, translate it into a Clarity `Value`
-    wasm_to_clarity_value(&return_type, 0, &results, memory, &mut &mut store, epoch)
+    wasm_to_clarity_value(&return_type, 0, &returned, memory, &mut &mut store, epoch)
         .map(|(val, _offset)| val)
         .and_then(|option_value| {
             option_value.ok_or_else(|| Error::Wasm(WasmError::ExpectedReturnValue))
@@ -1661,6 +2048,7 @@ pub fn get_global(module: &walrus::Module, name: &str) -> Result<GlobalId, Gener
         })
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ArgumentCountCheck {
     Exact,
     AtLeast,
@@ -1674,19 +2062,17 @@ pub fn check_argument_count(
     actual: usize,
     check: ArgumentCountCheck,
 ) -> Result<(), GeneratorError> {
-    let expected = expected as u32;
-    let actual = actual as u32;
     let mut handle_mismatch = |error_map: ErrorMap| -> Result<(), GeneratorError> {
-        let (arg_name_offset_start, arg_name_len_expected) =
-            generator.add_bytes_literal(&expected.to_le_bytes())?;
-        let (_, arg_name_len_got) = generator.add_bytes_literal(&actual.to_le_bytes())?;
         builder
-            .i32_const(arg_name_offset_start as i32)
-            .global_set(get_global(&generator.module, "runtime-error-arg-offset")?)
-            .i32_const((arg_name_len_expected + arg_name_len_got) as i32)
-            .global_set(get_global(&generator.module, "runtime-error-arg-len")?)
+            .i32_const(expected as i32)
+            .global_set(get_global(
+                &generator.module,
+                "runtime-error-expected-args",
+            )?)
+            .i32_const(actual as i32)
+            .global_set(get_global(&generator.module, "runtime-error-actual-args")?)
             .i32_const(error_map as i32)
-            .call(generator.func_by_name("stdlib.runtime-error"));
+            .call(generator.func_by_name("stdlib.runtime-error")?);
         Ok(())
     };
 
@@ -1723,3 +2109,170 @@ macro_rules! check_args {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use clarity::vm::types::UTF8Data;
+    use wasmtime::MemoryType;
+
+    use super::*;
+    use crate::layout::{read_value_indirect, LayoutValue};
+    use crate::tools::evaluate;
+
+    /// The value decoded by the [`crate::layout`] crate for `value`.
+    fn to_layout_value(value: &Value) -> LayoutValue {
+        match value {
+            Value::Int(i) => LayoutValue::Int(*i),
+            Value::UInt(u) => LayoutValue::UInt(*u),
+            Value::Bool(b) => LayoutValue::Bool(*b),
+            Value::Principal(PrincipalData::Standard(StandardPrincipalData(version, hash))) => {
+                LayoutValue::Principal {
+                    version: *version,
+                    hash: *hash,
+                    contract_name: None,
+                }
+            }
+            Value::Principal(PrincipalData::Contract(contract)) => LayoutValue::Principal {
+                version: contract.issuer.0,
+                hash: contract.issuer.1,
+                contract_name: Some(contract.name.to_string()),
+            },
+            Value::Sequence(SequenceData::Buffer(buff)) => LayoutValue::Buffer(buff.data.clone()),
+            Value::Sequence(SequenceData::String(CharType::ASCII(s))) => {
+                LayoutValue::StringAscii(s.data.clone())
+            }
+            Value::Sequence(SequenceData::String(CharType::UTF8(UTF8Data { data }))) => {
+                LayoutValue::StringUtf8(
+                    data.iter()
+                        .flat_map(|c| std::str::from_utf8(c).unwrap().chars())
+                        .map(u32::from)
+                        .collect(),
+                )
+            }
+            Value::Sequence(SequenceData::List(list)) => {
+                LayoutValue::List(list.data.iter().map(to_layout_value).collect())
+            }
+            Value::Optional(optional) => LayoutValue::Optional(
+                optional
+                    .data
+                    .as_deref()
+                    .map(|v| Box::new(to_layout_value(v))),
+            ),
+            Value::Response(response) => {
+                let data = Box::new(to_layout_value(&response.data));
+                LayoutValue::Response(if response.committed {
+                    Ok(data)
+                } else {
+                    Err(data)
+                })
+            }
+            Value::Tuple(tuple) => {
+                LayoutValue::Tuple(tuple.data_map.values().map(to_layout_value).collect())
+            }
+            value => panic!("no layout value for {value}"),
+        }
+    }
+
+    #[test]
+    fn layout_decoder_matches_wasm_to_clarity_value() {
+        let epoch = StacksEpochId::latest();
+        for (ty, snippet) in [
+            ("int", "-42"),
+            ("uint", "u340282366920938463463374607431768211455"),
+            ("bool", "true"),
+            ("principal", "'S1G2081040G2081040G2081040G208105NK8PE5"),
+            (
+                "principal",
+                "'S1G2081040G2081040G2081040G208105NK8PE5.a-contract",
+            ),
+            ("(buff 4)", "0x0102"),
+            ("(string-ascii 5)", r#""hello""#),
+            ("(string-utf8 3)", r#"u"a\u{e9}\u{1F600}""#),
+            ("(list 3 (optional int))", "(list (some 1) none (some -1))"),
+            (
+                "(response (tuple (a uint) (b (string-ascii 3))) principal)",
+                r#"(ok { a: u1, b: "abc" })"#,
+            ),
+            (
+                "(response (tuple (a uint) (b (string-ascii 3))) principal)",
+                "(err tx-sender)",
+            ),
+            (
+                "(list 2 (list 2 (buff 2)))",
+                "(list (list 0x01 0x0203) (list 0x04))",
+            ),
+        ] {
+            let ty =
+                signature_from_string(ty, ClarityVersion::latest(), epoch).expect("invalid type");
+            let value = evaluate(snippet).unwrap().unwrap();
+
+            let mut store = Store::<()>::default();
+            let memory = Memory::new(&mut store, MemoryType::new(1, None)).unwrap();
+            write_to_wasm(&mut store, memory, &ty, 0, 1024, &value, true).unwrap();
+
+            // The representation at offset 0 holds the values passed to or
+            // returned by the functions of a contract, in order.
+            let mut offset = 0;
+            let vals: Vec<Val> = clar2wasm_ty(&ty)
+                .into_iter()
+                .map(|val_ty| {
+                    let data = &memory.data(&store)[offset..];
+                    match val_ty {
+                        ValType::I32 => {
+                            offset += 4;
+                            Val::I32(i32::from_le_bytes(data[..4].try_into().unwrap()))
+                        }
+                        ValType::I64 => {
+                            offset += 8;
+                            Val::I64(i64::from_le_bytes(data[..8].try_into().unwrap()))
+                        }
+                        val_ty => panic!("unexpected {val_ty:?}"),
+                    }
+                })
+                .collect();
+            let (expected, _) =
+                wasm_to_clarity_value(&ty, 0, &vals, memory, &mut store, epoch).unwrap();
+            assert_eq!(expected.as_ref(), Some(&value), "{snippet}");
+
+            let decoded =
+                read_value_indirect(memory.data(&store), &layout_type(&ty).unwrap(), 0).unwrap();
+            assert_eq!(decoded, to_layout_value(&value), "{snippet}");
+        }
+    }
+
+    #[test]
+    fn layout_type_of_list_union_type() {
+        assert!(matches!(
+            layout_type(&TypeSignature::ListUnionType(HashSet::new())),
+            Err(Error::Wasm(WasmError::InvalidListUnionTypeInValue))
+        ));
+    }
+
+    #[test]
+    fn layout_type_matches_type_sizes() {
+        for ty in [
+            "int",
+            "bool",
+            "principal",
+            "(string-ascii 5)",
+            "(optional (tuple (a uint) (b (buff 10))))",
+            "(response (list 3 (string-utf8 2)) principal)",
+            "(list 2 (tuple (x (optional int)) (y (string-ascii 3))))",
+        ] {
+            let ty = signature_from_string(ty, ClarityVersion::latest(), StacksEpochId::latest())
+                .expect("invalid type");
+            let layout = layout_type(&ty).unwrap();
+            assert_eq!(layout.size(), get_type_size(&ty), "{ty}");
+            assert_eq!(layout.is_in_memory(), is_in_memory_type(&ty), "{ty}");
+            for include_repr in [false, true] {
+                assert_eq!(
+                    layout.in_memory_size(include_repr),
+                    get_type_in_memory_size(&ty, include_repr),
+                    "{ty}"
+                );
+            }
+        }
+    }
+}
@@ -1,10 +1,11 @@
 use clarity::vm::types::TypeSignature;
-use clarity::vm::ClarityName;
+use clarity::vm::{ClarityName, SymbolicExpression, Value};
 use walrus::ValType;
 
 use super::SimpleWord;
 use crate::error_mapping::ErrorMap;
 use crate::wasm_generator::{GeneratorError, WasmGenerator};
+use crate::wasm_utils::ArgumentCountCheck;
 
 fn simple_typed_one_call(
     generator: &mut WasmGenerator,
@@ -23,7 +24,7 @@ fn simple_typed_one_call(
         }
     };
 
-    let func = generator.func_by_name(&format!("stdlib.{name}-{type_suffix}"));
+    let func = generator.func_by_name(&format!("stdlib.{name}-{type_suffix}"))?;
     builder.call(func);
 
     Ok(())
@@ -37,6 +38,10 @@ impl SimpleWord for Add {
         "+".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -54,7 +59,7 @@ impl SimpleWord for Add {
                     ));
                 }
             };
-            let func = generator.func_by_name(&format!("stdlib.add-{type_suffix}"));
+            let func = generator.func_by_name(&format!("stdlib.add-{type_suffix}"))?;
             builder.call(func);
         }
         Ok(())
@@ -69,6 +74,10 @@ impl SimpleWord for Sub {
         "-".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -91,7 +100,7 @@ impl SimpleWord for Sub {
                     builder.local_get(op_lo).local_get(op_hi);
                 }
 
-                generator.func_by_name(&format!("stdlib.sub-{type_suffix}"))
+                generator.func_by_name(&format!("stdlib.sub-{type_suffix}"))?
             }
             TypeSignature::UIntType => {
                 let type_suffix = "uint";
@@ -99,9 +108,9 @@ impl SimpleWord for Sub {
                     // unary 'uint' subtraction:
                     // throws an underflow runtime error.
                     builder.i32_const(ErrorMap::ArithmeticUnderflow as i32);
-                    generator.func_by_name("stdlib.runtime-error")
+                    generator.func_by_name("stdlib.runtime-error")?
                 } else {
-                    generator.func_by_name(&format!("stdlib.sub-{type_suffix}"))
+                    generator.func_by_name(&format!("stdlib.sub-{type_suffix}"))?
                 }
             }
             _ => {
@@ -125,6 +134,10 @@ impl SimpleWord for Mul {
         "*".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -142,7 +155,7 @@ impl SimpleWord for Mul {
                     ));
                 }
             };
-            let func = generator.func_by_name(&format!("stdlib.mul-{type_suffix}"));
+            let func = generator.func_by_name(&format!("stdlib.mul-{type_suffix}"))?;
             builder.call(func);
         }
         Ok(())
@@ -157,6 +170,10 @@ impl SimpleWord for Div {
         "/".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -174,7 +191,7 @@ impl SimpleWord for Div {
                     ));
                 }
             };
-            let func = generator.func_by_name(&format!("stdlib.div-{type_suffix}"));
+            let func = generator.func_by_name(&format!("stdlib.div-{type_suffix}"))?;
             builder.call(func);
         }
         Ok(())
@@ -189,6 +206,10 @@ impl SimpleWord for Modulo {
         "mod".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -208,6 +229,10 @@ impl SimpleWord for Log2 {
         "log2".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit<'b>(
         &self,
         generator: &mut WasmGenerator,
@@ -227,6 +252,21 @@ impl SimpleWord for Power {
         "pow".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
+    fn check_literal_args(&self, args: &[SymbolicExpression]) -> Option<String> {
+        let exponent = match args.get(1)?.match_literal_value()? {
+            Value::Int(i) => *i,
+            Value::UInt(u) => i128::try_from(*u).unwrap_or(i128::MAX),
+            _ => return None,
+        };
+        (!(0..=u32::MAX as i128).contains(&exponent)).then(|| {
+            format!("(pow ...) will always fail: the exponent {exponent} is not a u32 integer")
+        })
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -246,6 +286,10 @@ impl SimpleWord for Sqrti {
         "sqrti".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -264,6 +308,36 @@ mod tests {
 
     use crate::tools::{crosscheck, crosscheck_expect_failure, evaluate};
 
+    #[test]
+    fn add_less_than_one_arg() {
+        let result = evaluate("(+)");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("expecting >= 1 arguments, got 0"));
+    }
+
+    #[test]
+    fn sqrti_more_than_one_arg() {
+        let result = evaluate("(sqrti 4 9)");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("expecting 1 arguments, got 2"));
+    }
+
+    #[test]
+    fn pow_less_than_two_args() {
+        let result = evaluate("(pow 2)");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("expecting 2 arguments, got 1"));
+    }
+
     #[test]
     fn test_overflow() {
         crosscheck(
@@ -377,6 +451,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pow_exponent_too_large() {
+        crosscheck(
+            "(pow u2 u4294967296)",
+            Err(Error::Runtime(
+                RuntimeErrorType::Arithmetic(
+                    "Power argument to (pow ...) must be a u32 integer".to_string(),
+                ),
+                Some(Vec::new()),
+            )),
+        );
+    }
+
+    #[test]
+    fn test_pow_invalid_exponent_in_untaken_branch() {
+        crosscheck("(if false (pow 2 -3) 1)", Ok(Some(Value::Int(1))));
+    }
+
+    fn pow_warnings(snippet: &str) -> Vec<String> {
+        use clarity::types::StacksEpochId;
+        use clarity::vm::analysis::AnalysisDatabase;
+        use clarity::vm::costs::LimitedCostTracker;
+        use clarity::vm::database::MemoryBackingStore;
+        use clarity::vm::diagnostic::Level;
+        use clarity::vm::types::QualifiedContractIdentifier;
+        use clarity::vm::ClarityVersion;
+
+        crate::compile(
+            snippet,
+            &QualifiedContractIdentifier::transient(),
+            LimitedCostTracker::new_free(),
+            ClarityVersion::Clarity2,
+            StacksEpochId::Epoch25,
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            crate::CompileOptions::default(),
+        )
+        .expect("failed to compile contract")
+        .diagnostics
+        .into_iter()
+        .filter(|d| d.level == Level::Warning)
+        .map(|d| d.message)
+        .collect()
+    }
+
+    #[test]
+    fn test_pow_invalid_exponent_warning() {
+        assert_eq!(
+            pow_warnings("(define-read-only (foo) (pow 2 -3))"),
+            vec!["(pow ...) will always fail: the exponent -3 is not a u32 integer"]
+        );
+        assert_eq!(
+            pow_warnings("(define-read-only (foo) (pow u2 u4294967296))"),
+            vec!["(pow ...) will always fail: the exponent 4294967296 is not a u32 integer"]
+        );
+        assert!(
+            pow_warnings("(define-read-only (foo (n int)) (+ (pow 2 3) (pow 2 n)))").is_empty()
+        );
+    }
+
     #[test]
     fn test_sqrti() {
         crosscheck("(sqrti 8)", Ok(Some(Value::Int(2))));
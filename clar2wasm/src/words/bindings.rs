@@ -38,13 +38,7 @@ impl ComplexWord for Let {
             let pair = bindings.get_list(i)?;
             let name = pair.get_name(0)?;
             let value = pair.get_expr(1)?;
-            // make sure name does not collide with builtin symbols
-            if generator.is_reserved_name(name) {
-                return Err(GeneratorError::InternalError(format!(
-                    "Name already used {:?}",
-                    name
-                )));
-            }
+            generator.check_reserved_name(name)?;
 
             // Traverse the value
             generator.traverse_expr(builder, value)?;
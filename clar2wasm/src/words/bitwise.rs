@@ -4,6 +4,7 @@ use walrus::InstrSeqBuilder;
 
 use super::SimpleWord;
 use crate::wasm_generator::{GeneratorError, WasmGenerator};
+use crate::wasm_utils::ArgumentCountCheck;
 
 #[derive(Debug)]
 pub struct BitwiseNot;
@@ -13,6 +14,10 @@ impl SimpleWord for BitwiseNot {
         "bit-not".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -20,7 +25,7 @@ impl SimpleWord for BitwiseNot {
         _arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        let helper_func = generator.func_by_name("stdlib.bit-not");
+        let helper_func = generator.func_by_name("stdlib.bit-not")?;
         builder.call(helper_func);
         Ok(())
     }
@@ -34,7 +39,7 @@ fn traverse_bitwise(
     builder: &mut InstrSeqBuilder,
     arg_types: &[TypeSignature],
 ) -> Result<(), GeneratorError> {
-    let helper_func = generator.func_by_name(&format!("stdlib.{name}"));
+    let helper_func = generator.func_by_name(&format!("stdlib.{name}"))?;
     // Run this once for every arg except first
     for _ in arg_types.iter().skip(1) {
         builder.call(helper_func);
@@ -50,6 +55,10 @@ impl SimpleWord for BitwiseOr {
         "bit-or".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -69,6 +78,10 @@ impl SimpleWord for BitwiseAnd {
         "bit-and".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -88,6 +101,10 @@ impl SimpleWord for BitwiseXor {
         "bit-xor".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -107,6 +124,10 @@ impl SimpleWord for BitwiseLShift {
         "bit-shift-left".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -114,7 +135,7 @@ impl SimpleWord for BitwiseLShift {
         _arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        let func = generator.func_by_name("stdlib.bit-shift-left");
+        let func = generator.func_by_name("stdlib.bit-shift-left")?;
         builder.call(func);
         Ok(())
     }
@@ -128,6 +149,10 @@ impl SimpleWord for BitwiseRShift {
         "bit-shift-right".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -145,7 +170,7 @@ impl SimpleWord for BitwiseRShift {
             }
         };
 
-        let helper = generator.func_by_name(&format!("stdlib.bit-shift-right-{type_suffix}"));
+        let helper = generator.func_by_name(&format!("stdlib.bit-shift-right-{type_suffix}"))?;
 
         builder.call(helper);
 
@@ -161,6 +186,10 @@ impl SimpleWord for Xor {
         "xor".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -173,10 +202,42 @@ impl SimpleWord for Xor {
     }
 }
 
-#[cfg(not(feature = "test-clarity-v1"))]
 #[cfg(test)]
 mod tests {
-    #[cfg(test)]
+    //
+    // Module with tests that should only be executed
+    // when running Clarity::V1.
+    //
+    #[cfg(feature = "test-clarity-v1")]
+    mod clarity_v1 {
+        use clarity::types::StacksEpochId;
+        use clarity::vm::Value;
+
+        use crate::tools::crosscheck_with_epoch;
+
+        #[test]
+        fn bitwise_words_are_not_builtins() {
+            // The `bit-*` words were introduced in Clarity 2, before that they
+            // are valid names for user-defined functions.
+            crosscheck_with_epoch(
+                "(define-private (bit-and (a int) (b int)) (+ a b)) (bit-and 1 2)",
+                Ok(Some(Value::Int(3))),
+                StacksEpochId::Epoch20,
+            );
+
+            crosscheck_with_epoch(
+                "(define-private (bit-not (a int)) (- a)) (bit-not 3)",
+                Ok(Some(Value::Int(-3))),
+                StacksEpochId::Epoch20,
+            );
+        }
+    }
+
+    //
+    // Module with tests that should only be executed
+    // when running Clarity::V2 or Clarity::v3.
+    //
+    #[cfg(not(feature = "test-clarity-v1"))]
     mod clarity_v2_v3 {
         use crate::tools::{crosscheck, evaluate};
 
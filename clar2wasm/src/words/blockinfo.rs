@@ -49,7 +49,7 @@ impl ComplexWord for GetBlockInfo {
         builder.local_get(return_offset).i32_const(return_size);
 
         // Call the host interface function, `get_block_info`
-        builder.call(generator.func_by_name("stdlib.get_block_info"));
+        builder.call(generator.func_by_name("stdlib.get_block_info")?);
 
         // Host interface fills the result into the specified memory. Read it
         // back out, and place the value on the data stack.
@@ -105,7 +105,117 @@ impl ComplexWord for GetBurnBlockInfo {
         builder.local_get(return_offset).i32_const(return_size);
 
         // Call the host interface function, `get_burn_block_info`
-        builder.call(generator.func_by_name("stdlib.get_burn_block_info"));
+        builder.call(generator.func_by_name("stdlib.get_burn_block_info")?);
+
+        // Host interface fills the result into the specified memory. Read it
+        // back out, and place the value on the data stack.
+        generator.read_from_memory(builder, return_offset, 0, &return_ty)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct GetStacksBlockInfo;
+
+impl ComplexWord for GetStacksBlockInfo {
+    fn name(&self) -> ClarityName {
+        "get-stacks-block-info?".into()
+    }
+
+    fn traverse(
+        &self,
+        generator: &mut WasmGenerator,
+        builder: &mut walrus::InstrSeqBuilder,
+        expr: &SymbolicExpression,
+        args: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        check_args!(generator, builder, 2, args.len(), ArgumentCountCheck::Exact);
+
+        let prop_name = args.get_name(0)?;
+        let block = args.get_expr(1)?;
+
+        // Push the property name onto the stack
+        let (id_offset, id_length) = generator.add_string_literal(prop_name)?;
+        builder
+            .i32_const(id_offset as i32)
+            .i32_const(id_length as i32);
+
+        // Push the block number onto the stack
+        generator.traverse_expr(builder, block)?;
+
+        // Reserve space on the stack for the return value
+        let return_ty = generator
+            .get_expr_type(expr)
+            .ok_or_else(|| {
+                GeneratorError::TypeError(
+                    "get-stacks-block-info? expression must be typed".to_owned(),
+                )
+            })?
+            .clone();
+
+        let (return_offset, return_size) =
+            generator.create_call_stack_local(builder, &return_ty, true, true);
+
+        // Push the offset and size to the data stack
+        builder.local_get(return_offset).i32_const(return_size);
+
+        // Call the host interface function, `get_stacks_block_info`
+        builder.call(generator.func_by_name("stdlib.get_stacks_block_info")?);
+
+        // Host interface fills the result into the specified memory. Read it
+        // back out, and place the value on the data stack.
+        generator.read_from_memory(builder, return_offset, 0, &return_ty)?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct GetTenureInfo;
+
+impl ComplexWord for GetTenureInfo {
+    fn name(&self) -> ClarityName {
+        "get-tenure-info?".into()
+    }
+
+    fn traverse(
+        &self,
+        generator: &mut WasmGenerator,
+        builder: &mut walrus::InstrSeqBuilder,
+        expr: &SymbolicExpression,
+        args: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        check_args!(generator, builder, 2, args.len(), ArgumentCountCheck::Exact);
+
+        let prop_name = args.get_name(0)?;
+        let block = args.get_expr(1)?;
+
+        // Push the property name onto the stack
+        let (id_offset, id_length) = generator.add_string_literal(prop_name)?;
+        builder
+            .i32_const(id_offset as i32)
+            .i32_const(id_length as i32);
+
+        // Push the block number onto the stack
+        generator.traverse_expr(builder, block)?;
+
+        // Reserve space on the stack for the return value
+        let return_ty = generator
+            .get_expr_type(expr)
+            .ok_or_else(|| {
+                GeneratorError::TypeError("get-tenure-info? expression must be typed".to_owned())
+            })?
+            .clone();
+
+        let (return_offset, return_size) =
+            generator.create_call_stack_local(builder, &return_ty, true, true);
+
+        // Push the offset and size to the data stack
+        builder.local_get(return_offset).i32_const(return_size);
+
+        // Call the host interface function, `get_tenure_info`
+        builder.call(generator.func_by_name("stdlib.get_tenure_info")?);
 
         // Host interface fills the result into the specified memory. Read it
         // back out, and place the value on the data stack.
@@ -139,13 +249,15 @@ impl ComplexWord for AtBlock {
         generator.traverse_expr(builder, block_hash)?;
 
         // Call the host interface function, `enter_at_block`
-        builder.call(generator.func_by_name("stdlib.enter_at_block"));
+        builder.call(generator.func_by_name("stdlib.enter_at_block")?);
+        generator.clear_height_caches(builder);
 
         // Traverse the inner expression
         generator.traverse_expr(builder, e)?;
 
         // Call the host interface function, `exit_at_block`
-        builder.call(generator.func_by_name("stdlib.exit_at_block"));
+        builder.call(generator.func_by_name("stdlib.exit_at_block")?);
+        generator.clear_height_caches(builder);
 
         Ok(())
     }
@@ -157,7 +269,7 @@ mod tests {
     use clarity::vm::types::{OptionalData, PrincipalData, TupleData};
     use clarity::vm::Value;
 
-    use crate::tools::{evaluate, TestEnvironment};
+    use crate::tools::{crosscheck_compare_only_advancing_tip, evaluate, TestEnvironment};
 
     //
     // Module with tests that should only be executed
@@ -167,9 +279,10 @@ mod tests {
     #[cfg(test)]
     mod clarity_v1_v2 {
         use clarity::types::StacksEpochId;
+        use clarity::vm::ClarityVersion;
 
         use super::*;
-        use crate::tools::crosscheck_with_epoch;
+        use crate::tools::{crosscheck_compare_only_with_epoch, crosscheck_with_epoch};
 
         #[test]
         fn get_block_info_non_existent() {
@@ -202,6 +315,44 @@ mod tests {
             );
         }
 
+        #[test]
+        fn block_height_across_epochs() {
+            for epoch in [
+                StacksEpochId::Epoch21,
+                StacksEpochId::Epoch25,
+                StacksEpochId::Epoch30,
+            ] {
+                crosscheck_compare_only_with_epoch("block-height", epoch);
+            }
+        }
+
+        #[test]
+        fn block_height_follows_the_epoch_of_the_call() {
+            let snippet = "(define-read-only (get-height) block-height)";
+            let mut compiled =
+                TestEnvironment::new(StacksEpochId::Epoch25, ClarityVersion::Clarity2);
+            let mut interpreted = compiled.clone();
+            compiled
+                .evaluate(snippet)
+                .expect("failed to deploy contract");
+            interpreted
+                .interpret(snippet)
+                .expect("failed to deploy contract");
+
+            // Deployed in 2.5, then called in 3.0 with a tenure height
+            // different from the block height.
+            for env in [&mut compiled, &mut interpreted] {
+                env.advance_chain_tip(5);
+                env.set_epoch(StacksEpochId::Epoch30);
+                env.set_tenure_height(2);
+            }
+
+            let compiled = compiled.call_function("snippet", "get-height", &[]);
+            let interpreted = interpreted.interpret_function("snippet", "get-height", &[]);
+            assert_eq!(compiled, interpreted);
+            assert_eq!(compiled.unwrap(), Value::UInt(2));
+        }
+
         #[test]
         fn at_block() {
             crosscheck_with_epoch(
@@ -246,7 +397,7 @@ mod tests {
         use clarity::types::StacksEpochId;
 
         use super::*;
-        use crate::tools::crosscheck_with_epoch;
+        use crate::tools::{crosscheck_compare_only_advancing_tip, crosscheck_with_epoch};
 
         //- At Block
         #[test]
@@ -276,6 +427,87 @@ mod tests {
                 .to_string()
                 .contains("expecting 2 arguments, got 3"));
         }
+
+        //- Stacks Block Info
+
+        #[test]
+        fn get_stacks_block_info_id_header_hash() {
+            crosscheck_compare_only_advancing_tip("(get-stacks-block-info? id-header-hash u0)", 1);
+        }
+
+        #[test]
+        fn get_stacks_block_info_header_hash() {
+            crosscheck_compare_only_advancing_tip("(get-stacks-block-info? header-hash u0)", 1);
+        }
+
+        #[test]
+        fn get_stacks_block_info_time() {
+            crosscheck_compare_only_advancing_tip("(get-stacks-block-info? time u0)", 1);
+        }
+
+        #[test]
+        fn get_stacks_block_info_future_block() {
+            crosscheck_with_epoch(
+                "(get-stacks-block-info? id-header-hash u9999)",
+                Ok(Some(Value::none())),
+                StacksEpochId::Epoch30,
+            );
+        }
+
+        #[test]
+        fn get_stacks_block_info_height_out_of_u32_range() {
+            crosscheck_with_epoch(
+                "(get-stacks-block-info? time u4294967296)",
+                Ok(Some(Value::none())),
+                StacksEpochId::Epoch30,
+            );
+        }
+
+        //- Tenure Info
+
+        #[test]
+        fn get_tenure_info_burnchain_header_hash() {
+            crosscheck_compare_only_advancing_tip("(get-tenure-info? burnchain-header-hash u0)", 1);
+        }
+
+        #[test]
+        fn get_tenure_info_miner_address() {
+            crosscheck_compare_only_advancing_tip("(get-tenure-info? miner-address u0)", 1);
+        }
+
+        #[test]
+        fn get_tenure_info_time() {
+            crosscheck_compare_only_advancing_tip("(get-tenure-info? time u0)", 1);
+        }
+
+        #[test]
+        fn get_tenure_info_vrf_seed() {
+            crosscheck_compare_only_advancing_tip("(get-tenure-info? vrf-seed u0)", 1);
+        }
+
+        #[test]
+        fn get_tenure_info_block_reward() {
+            crosscheck_compare_only_advancing_tip("(get-tenure-info? block-reward u0)", 1);
+        }
+
+        #[test]
+        fn get_tenure_info_miner_spend_total() {
+            crosscheck_compare_only_advancing_tip("(get-tenure-info? miner-spend-total u0)", 1);
+        }
+
+        #[test]
+        fn get_tenure_info_miner_spend_winner() {
+            crosscheck_compare_only_advancing_tip("(get-tenure-info? miner-spend-winner u0)", 1);
+        }
+
+        #[test]
+        fn get_tenure_info_future_block() {
+            crosscheck_with_epoch(
+                "(get-tenure-info? vrf-seed u9999)",
+                Ok(Some(Value::none())),
+                StacksEpochId::Epoch30,
+            );
+        }
     }
 
     //- Block Info
@@ -490,6 +722,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn heights_are_read_once_in_loops() {
+        let snippet = "
+(define-private (add-height (x uint) (acc uint)) (+ acc x burn-block-height))
+(fold add-height (list u1 u2 u3 u4) burn-block-height)
+";
+        crosscheck_compare_only_advancing_tip(snippet, 3);
+
+        let mut env = TestEnvironment::default();
+        env.advance_chain_tip(3);
+        env.init_contract_with_snippet("heights", snippet).unwrap();
+        let host_calls = env.get_host_calls("heights").unwrap();
+        assert_eq!(host_calls.get("burn_block_height"), Some(&1));
+    }
+
+    #[test]
+    fn heights_are_read_again_around_at_block() {
+        let snippet = "
+(list
+    burn-block-height
+    (at-block 0xb5e076ab7609c7f8c763b5c571d07aea80b06b41452231b1437370f4964ed66e burn-block-height) ;; block 0
+    burn-block-height)
+";
+        crosscheck_compare_only_advancing_tip(snippet, 3);
+
+        let mut env = TestEnvironment::default();
+        env.advance_chain_tip(3);
+        env.init_contract_with_snippet("heights", snippet).unwrap();
+        let host_calls = env.get_host_calls("heights").unwrap();
+        assert_eq!(host_calls.get("burn_block_height"), Some(&3));
+    }
+
     //
     // Module with tests that should only be executed
     // when running Clarity::V2 or Clarity::v3.
@@ -498,7 +762,7 @@ mod tests {
     #[cfg(test)]
     mod clarity_v2_v3 {
         use super::*;
-        use crate::tools::crosscheck;
+        use crate::tools::{crosscheck, crosscheck_with_network, Network};
 
         #[test]
         fn get_burn_block_info_non_existent() {
@@ -532,5 +796,30 @@ mod tests {
                 evaluate("(ok u2147483648)"),
             );
         }
+
+        #[test]
+        fn network_profiles() {
+            let snippet = "{chain-id: chain-id, mainnet: is-in-mainnet, regtest: is-in-regtest}";
+            for (network, expected) in [
+                (
+                    Network::Mainnet,
+                    "{chain-id: u1, mainnet: true, regtest: false}",
+                ),
+                (
+                    Network::Testnet,
+                    "{chain-id: u2147483648, mainnet: false, regtest: false}",
+                ),
+                (
+                    Network::Regtest,
+                    "{chain-id: u2147483649, mainnet: false, regtest: false}",
+                ),
+                (
+                    Network::Mocknet,
+                    "{chain-id: u2147483650, mainnet: false, regtest: false}",
+                ),
+            ] {
+                crosscheck_with_network(network, snippet, evaluate(expected));
+            }
+        }
     }
 }
@@ -1,6 +1,7 @@
 use clarity::vm::types::TypeSignature;
 
 use crate::wasm_generator::{GeneratorError, WasmGenerator};
+use crate::wasm_utils::ArgumentCountCheck;
 use crate::words::SimpleWord;
 
 fn traverse_buffer_to_integer(
@@ -8,11 +9,7 @@ fn traverse_buffer_to_integer(
     generator: &mut WasmGenerator,
     builder: &mut walrus::InstrSeqBuilder,
 ) -> Result<(), GeneratorError> {
-    let func = generator
-        .module
-        .funcs
-        .by_name(name)
-        .ok_or_else(|| GeneratorError::InternalError(format!("function not found: {name}")))?;
+    let func = generator.func_by_name(name)?;
     builder.call(func);
     Ok(())
 }
@@ -25,6 +22,10 @@ impl SimpleWord for BuffToUintBe {
         "buff-to-uint-be".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut crate::wasm_generator::WasmGenerator,
@@ -44,6 +45,10 @@ impl SimpleWord for BuffToIntBe {
         "buff-to-int-be".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut crate::wasm_generator::WasmGenerator,
@@ -65,6 +70,10 @@ impl SimpleWord for BuffToUintLe {
         "buff-to-uint-le".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut crate::wasm_generator::WasmGenerator,
@@ -84,6 +93,10 @@ impl SimpleWord for BuffToIntLe {
         "buff-to-int-le".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut crate::wasm_generator::WasmGenerator,
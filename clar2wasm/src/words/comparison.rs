@@ -3,6 +3,7 @@ use clarity::vm::ClarityName;
 
 use super::SimpleWord;
 use crate::wasm_generator::{GeneratorError, WasmGenerator};
+use crate::wasm_utils::ArgumentCountCheck;
 
 fn traverse_comparison(
     name: &str,
@@ -32,13 +33,7 @@ fn traverse_comparison(
         }
     };
 
-    let func = generator
-        .module
-        .funcs
-        .by_name(&format!("stdlib.{name}-{type_suffix}"))
-        .ok_or_else(|| {
-            GeneratorError::InternalError(format!("function not found: {name}-{type_suffix}"))
-        })?;
+    let func = generator.func_by_name(&format!("stdlib.{name}-{type_suffix}"))?;
 
     builder.call(func);
 
@@ -53,6 +48,10 @@ impl SimpleWord for CmpLess {
         "<".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -72,6 +71,10 @@ impl SimpleWord for CmpLeq {
         "<=".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -91,6 +94,10 @@ impl SimpleWord for CmpGreater {
         ">".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -110,6 +117,10 @@ impl SimpleWord for CmpGeq {
         ">=".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -82,12 +82,7 @@ impl ComplexWord for Match {
         let match_on = args.get_expr(0)?;
         let success_binding = args.get_name(1)?;
 
-        if generator.is_reserved_name(success_binding) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                success_binding
-            )));
-        }
+        generator.check_reserved_name(success_binding)?;
 
         let success_body = args.get_expr(2)?;
         // WORKAROND: type set on some/ok body
@@ -134,12 +129,7 @@ impl ComplexWord for Match {
 
                 let err_binding = args.get_name(3)?;
 
-                if generator.is_reserved_name(err_binding) {
-                    return Err(GeneratorError::InternalError(format!(
-                        "Name already used {:?}",
-                        err_binding
-                    )));
-                }
+                generator.check_reserved_name(err_binding)?;
 
                 let err_body = args.get_expr(4)?;
                 // Workaround: set type on err body
@@ -239,7 +229,7 @@ impl ComplexWord for Filter {
         // reserve space for the length of the output list
         let (output_offset, _) = generator.create_call_stack_local(builder, &ty, false, true);
 
-        let memory = generator.get_memory()?;
+        let copy_memory = generator.copy_memory()?;
 
         let mut loop_result = Ok(());
 
@@ -272,7 +262,7 @@ impl ComplexWord for Filter {
 
         // call the discriminator
 
-        if let Some(simple) = words::lookup_simple(discriminator) {
+        if let Some(simple) = words::lookup_simple(discriminator, generator.clarity_version()) {
             // Call simple builtin
             loop_result = simple.visit(
                 generator,
@@ -282,7 +272,7 @@ impl ComplexWord for Filter {
             );
         } else {
             // user defined
-            loop_.call(generator.func_by_name(discriminator.as_str()));
+            loop_.call(generator.func_by_name(discriminator.as_str())?);
         }
         // [ Discriminator result (bool) ]
 
@@ -303,9 +293,10 @@ impl ComplexWord for Filter {
             // [ output_write_pos ]
             .local_get(input_offset)
             // [ output_write_pos, input_offset ]
-            .i32_const(elem_size)
-            // [ output_write_pos, input_offset, element_size ]
-            .memory_copy(memory, memory)
+            .i32_const(elem_size);
+        // [ output_write_pos, input_offset, element_size ]
+        copy_memory
+            .emit(&mut success_branch)
             // [  ]
             .local_get(output_len)
             // [ output_len ]
@@ -438,6 +429,10 @@ impl SimpleWord for SimpleAnd {
         "and".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         _generator: &mut WasmGenerator,
@@ -487,6 +482,10 @@ impl SimpleWord for SimpleOr {
         "or".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::AtLeast)
+    }
+
     fn visit(
         &self,
         _generator: &mut WasmGenerator,
@@ -899,7 +898,7 @@ mod tests {
     use clarity::vm::types::ResponseData;
     use clarity::vm::Value;
 
-    use crate::tools::{crosscheck, crosscheck_expect_failure, evaluate};
+    use crate::tools::{crosscheck, crosscheck_expect_failure, evaluate, TestEnvironment};
 
     #[test]
     fn trivial() {
@@ -1101,6 +1100,109 @@ mod tests {
         );
     }
 
+    const SHORT_CIRCUITS: &str = r#"
+(define-map m int int)
+(define-data-var count int 0)
+(define-private (bump) (begin (var-set count (+ (var-get count) 1)) true))
+(list
+  (and false (bump) (map-insert m 1 1))
+  (or true (bump) (map-insert m 2 2))
+  (and true (bump) (map-insert m 3 3))
+  (or false (bump) (map-insert m 4 4))
+  (is-some (map-get? m 1))
+  (is-some (map-get? m 3))
+  (is-eq (var-get count) 2))
+"#;
+
+    #[test]
+    fn and_or_short_circuit() {
+        crosscheck(
+            SHORT_CIRCUITS,
+            evaluate("(list false true true true false true true)"),
+        );
+    }
+
+    #[test]
+    fn and_or_branches_not_taken_are_not_charged() {
+        // The costs are charged by the host functions, a branch which is not
+        // evaluated must not call any of them.
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet("short-circuits", SHORT_CIRCUITS)
+            .unwrap();
+        let host_calls = env.get_host_calls("short-circuits").unwrap();
+        assert_eq!(host_calls.get("map_insert"), Some(&1));
+        assert_eq!(host_calls.get("map_get"), Some(&2));
+        assert_eq!(host_calls.get("set_variable"), Some(&2));
+        assert_eq!(host_calls.get("get_variable"), Some(&3));
+    }
+
+    #[test]
+    fn and_or_costs_match_interpreter() {
+        const CONTRACT: &str = r#"
+(define-map m int int)
+(define-data-var count int 0)
+(define-private (bump) (begin (var-set count (+ (var-get count) 1)) true))
+(define-public (short-circuits)
+  (ok (list
+    (and false (bump) (map-insert m 1 1))
+    (or true (bump) (map-insert m 2 2))
+    (and true (bump) (map-insert m 3 3))
+    (or false (bump) (map-insert m 4 4))
+    (is-some (map-get? m 1))
+    (is-some (map-get? m 3))
+    (is-eq (var-get count) 2))))
+"#;
+
+        let mut compiled = TestEnvironment::default();
+        compiled.track_costs().unwrap();
+        compiled
+            .init_contract_with_snippet("short-circuits", CONTRACT)
+            .unwrap();
+        let mut interpreted = TestEnvironment::default();
+        interpreted.track_costs().unwrap();
+        interpreted
+            .interpret_contract_with_snippet("short-circuits", CONTRACT)
+            .unwrap();
+
+        let before = compiled.get_total_cost();
+        let value = compiled.call_function("short-circuits", "short-circuits", &[]);
+        let mut compiled_cost = compiled.get_total_cost();
+        compiled_cost.sub(&before).unwrap();
+
+        let before = interpreted.get_total_cost();
+        let expected = interpreted.interpret_function("short-circuits", "short-circuits", &[]);
+        let mut interpreted_cost = interpreted.get_total_cost();
+        interpreted_cost.sub(&before).unwrap();
+
+        assert_eq!(
+            value,
+            Ok(Value::okay(
+                Value::cons_list_unsanitized(
+                    [false, true, true, true, false, true, true]
+                        .into_iter()
+                        .map(Value::Bool)
+                        .collect()
+                )
+                .unwrap()
+            )
+            .unwrap())
+        );
+        assert_eq!(value, expected);
+
+        // A branch which is not evaluated reads and writes nothing, on both
+        // sides.
+        assert_eq!(compiled_cost.read_count, interpreted_cost.read_count);
+        assert_eq!(compiled_cost.read_length, interpreted_cost.read_length);
+        assert_eq!(compiled_cost.write_count, interpreted_cost.write_count);
+        assert_eq!(compiled_cost.write_length, interpreted_cost.write_length);
+
+        // The interpreter also charges the runtime of `and`, `or` and the
+        // applications of `bump`, the compiled code only the one of the host
+        // functions.
+        assert!(compiled_cost.runtime > 0);
+        assert!(compiled_cost.runtime < interpreted_cost.runtime);
+    }
+
     #[test]
     fn match_less_than_two_args() {
         crosscheck_expect_failure(
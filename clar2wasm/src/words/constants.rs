@@ -28,13 +28,7 @@ impl ComplexWord for DefineConstant {
         // Constant name
         let name = args.get_name(0)?;
 
-        // Making sure if name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         // Constant value
         let value = args.get_expr(1)?;
@@ -105,7 +99,7 @@ impl ComplexWord for DefineConstant {
 
         // Call a host interface function to add the constant name
         // and evaluated value to a persistent data structure.
-        builder.call(generator.func_by_name("stdlib.save_constant"));
+        builder.call(generator.func_by_name("stdlib.save_constant")?);
 
         generator.constants.insert(name.to_string(), offset);
 
@@ -349,6 +343,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn constant_referencing_constants() {
+        crosscheck(
+            "(define-constant a 2) (define-constant b (* a 3)) (define-constant c (list a b)) c",
+            evaluate("(list 2 6)"),
+        );
+    }
+
+    #[test]
+    fn constant_referencing_later_constant() {
+        crosscheck(
+            "(define-constant b (* a 3)) (define-constant a 2) b",
+            Ok(Some(Value::Int(6))),
+        );
+    }
+
+    #[test]
+    fn constant_calling_private_function() {
+        crosscheck(
+            "(define-constant cst (double 21)) (define-private (double (x int)) (* x 2)) cst",
+            Ok(Some(Value::Int(42))),
+        );
+    }
+
+    #[test]
+    fn constant_calling_function_reading_constant() {
+        crosscheck(
+            r#"
+(define-constant greeting (greet "world"))
+(define-private (greet (name (string-ascii 8))) (concat prefix name))
+(define-constant prefix "hello ")
+(define-read-only (get-greeting) greeting)
+(get-greeting)
+"#,
+            evaluate(r#""hello world""#),
+        );
+    }
+
+    #[test]
+    fn constant_cycles() {
+        for snippet in [
+            "(define-constant a (+ b 1)) (define-constant b (+ a 1))",
+            "(define-constant a (f)) (define-private (f) (+ a 1))",
+        ] {
+            crosscheck_expect_failure(snippet);
+
+            let error = evaluate(snippet).unwrap_err();
+            assert!(
+                error.to_string().contains("interdependent"),
+                "unexpected error for {snippet}: {error}"
+            );
+        }
+    }
+
     #[test]
     fn test_concretize() {
         crosscheck(
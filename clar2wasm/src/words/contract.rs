@@ -30,13 +30,16 @@ impl ComplexWord for AsContract {
         let inner = args.get_expr(0)?;
 
         // Call the host interface function, `enter_as_contract`
-        builder.call(generator.func_by_name("stdlib.enter_as_contract"));
+        builder.call(generator.func_by_name("stdlib.enter_as_contract")?);
 
         // Traverse the inner expression
-        generator.traverse_expr(builder, inner)?;
+        generator.as_contract_depth += 1;
+        let result = generator.traverse_expr(builder, inner);
+        generator.as_contract_depth -= 1;
+        result?;
 
         // Call the host interface function, `exit_as_contract`
-        builder.call(generator.func_by_name("stdlib.exit_as_contract"));
+        builder.call(generator.func_by_name("stdlib.exit_as_contract")?);
 
         Ok(())
     }
@@ -175,7 +178,7 @@ impl ComplexWord for ContractCall {
         builder.local_get(return_offset).i32_const(return_size);
 
         // Call the host interface function, `contract_call`
-        builder.call(generator.func_by_name("stdlib.contract_call"));
+        builder.call(generator.func_by_name("stdlib.contract_call")?);
 
         // Host interface fills the result into the specified memory. Read it
         // back out, and place the value on the data stack.
@@ -189,7 +192,7 @@ impl ComplexWord for ContractCall {
 mod tests {
     use clarity::vm::Value;
 
-    use crate::tools::{evaluate, TestEnvironment};
+    use crate::tools::{crosscheck, evaluate, TestEnvironment};
 
     #[test]
     fn as_contract_less_than_one_arg() {
@@ -211,6 +214,52 @@ mod tests {
             .contains("expecting 1 arguments, got 2"));
     }
 
+    #[test]
+    fn as_contract_restores_sender_after_unwrap() {
+        crosscheck(
+            "
+(define-private (inner)
+  (as-contract (begin (unwrap! none (err u1)) (ok tx-sender))))
+(define-private (outer)
+  (begin (unwrap-err-panic (inner)) {sender: tx-sender, caller: contract-caller}))
+(is-eq (outer) {sender: tx-sender, caller: contract-caller})
+",
+            Ok(Some(Value::Bool(true))),
+        );
+    }
+
+    #[test]
+    fn nested_as_contract_restores_sender_after_short_return() {
+        crosscheck(
+            "
+(define-private (inner (fail bool))
+  (as-contract
+    (begin
+      (asserts! (not fail) (err u1))
+      (as-contract (begin (try! (if fail (ok u0) (err u2))) (ok tx-sender))))))
+(define-private (outer (fail bool))
+  (begin (unwrap-err-panic (inner fail)) tx-sender))
+(list (is-eq (outer true) tx-sender) (is-eq (outer false) tx-sender))
+",
+            Ok(Some(
+                Value::cons_list_unsanitized(vec![Value::Bool(true), Value::Bool(true)]).unwrap(),
+            )),
+        );
+    }
+
+    #[test]
+    fn as_contract_restores_sender_across_public_functions() {
+        crosscheck(
+            "
+(define-public (foo)
+  (as-contract (begin (unwrap! (some u1) (err u1)) (unwrap! none (err u2)) (ok tx-sender))))
+(define-read-only (whoami) tx-sender)
+(match (foo) sender (err sender) code (ok (is-eq (whoami) tx-sender)))
+",
+            Ok(Some(Value::okay(Value::Bool(true)).unwrap())),
+        );
+    }
+
     #[test]
     fn contract_call_less_than_two_args() {
         let mut env = TestEnvironment::default();
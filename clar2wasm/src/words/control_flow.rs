@@ -92,17 +92,9 @@ impl ComplexWord for UnwrapPanic {
                 // If the indicator is 0, throw a runtime error
                 let if_id = {
                     let mut if_case = builder.dangling_instr_seq(None);
-                    if_case.i32_const(ErrorMap::Panic as i32).call(
-                        generator
-                            .module
-                            .funcs
-                            .by_name("stdlib.runtime-error")
-                            .ok_or_else(|| {
-                                GeneratorError::InternalError(
-                                    "stdlib.runtime-error not found".to_owned(),
-                                )
-                            })?,
-                    );
+                    if_case
+                        .i32_const(ErrorMap::Panic as i32)
+                        .call(generator.func_by_name("stdlib.runtime-error")?);
                     if_case.id()
                 };
 
@@ -146,17 +138,9 @@ impl ComplexWord for UnwrapPanic {
                 // If the indicator is 0, throw a runtime error
                 let if_id = {
                     let mut if_case = builder.dangling_instr_seq(None);
-                    if_case.i32_const(ErrorMap::Panic as i32).call(
-                        generator
-                            .module
-                            .funcs
-                            .by_name("stdlib.runtime-error")
-                            .ok_or_else(|| {
-                                GeneratorError::InternalError(
-                                    "stdlib.runtime-error not found".to_owned(),
-                                )
-                            })?,
-                    );
+                    if_case
+                        .i32_const(ErrorMap::Panic as i32)
+                        .call(generator.func_by_name("stdlib.runtime-error")?);
                     if_case.id()
                 };
 
@@ -244,17 +228,9 @@ impl ComplexWord for UnwrapErrPanic {
 
                 let else_id = {
                     let mut else_case = builder.dangling_instr_seq(None);
-                    else_case.i32_const(ErrorMap::Panic as i32).call(
-                        generator
-                            .module
-                            .funcs
-                            .by_name("stdlib.runtime-error")
-                            .ok_or_else(|| {
-                                GeneratorError::InternalError(
-                                    "stdlib.runtime-error not found".to_owned(),
-                                )
-                            })?,
-                    );
+                    else_case
+                        .i32_const(ErrorMap::Panic as i32)
+                        .call(generator.func_by_name("stdlib.runtime-error")?);
                     else_case.id()
                 };
 
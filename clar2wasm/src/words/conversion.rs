@@ -2,6 +2,7 @@ use clarity::vm::types::{SequenceSubtype, StringSubtype, TypeSignature};
 
 use super::SimpleWord;
 use crate::wasm_generator::GeneratorError;
+use crate::wasm_utils::ArgumentCountCheck;
 
 #[derive(Debug)]
 pub struct StringToInt;
@@ -11,6 +12,10 @@ impl SimpleWord for StringToInt {
         "string-to-int?".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut crate::wasm_generator::WasmGenerator,
@@ -32,7 +37,7 @@ impl SimpleWord for StringToInt {
             }
         };
 
-        let func = generator.func_by_name(&format!("stdlib.{func_prefix}-to-int"));
+        let func = generator.func_by_name(&format!("stdlib.{func_prefix}-to-int"))?;
         builder.call(func);
 
         Ok(())
@@ -47,6 +52,10 @@ impl SimpleWord for StringToUint {
         "string-to-uint?".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut crate::wasm_generator::WasmGenerator,
@@ -68,7 +77,7 @@ impl SimpleWord for StringToUint {
             }
         };
 
-        let func = generator.func_by_name(&format!("stdlib.{func_prefix}-to-uint"));
+        let func = generator.func_by_name(&format!("stdlib.{func_prefix}-to-uint"))?;
 
         builder.call(func);
 
@@ -84,6 +93,10 @@ impl SimpleWord for IntToAscii {
         "int-to-ascii".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut crate::wasm_generator::WasmGenerator,
@@ -101,7 +114,7 @@ impl SimpleWord for IntToAscii {
             }
         };
 
-        let func = generator.func_by_name(&format!("stdlib.{type_prefix}-to-string"));
+        let func = generator.func_by_name(&format!("stdlib.{type_prefix}-to-string"))?;
 
         builder.call(func);
 
@@ -117,6 +130,10 @@ impl SimpleWord for IntToUtf8 {
         "int-to-utf8".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut crate::wasm_generator::WasmGenerator,
@@ -134,7 +151,7 @@ impl SimpleWord for IntToUtf8 {
             }
         };
 
-        let func = generator.func_by_name(&format!("stdlib.{type_prefix}-to-utf8"));
+        let func = generator.func_by_name(&format!("stdlib.{type_prefix}-to-utf8"))?;
 
         builder.call(func);
 
@@ -25,18 +25,11 @@ impl ComplexWord for DefineDataVar {
         check_args!(generator, builder, 3, args.len(), ArgumentCountCheck::Exact);
 
         let name = args.get_name(0)?;
-        // Making sure if name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         let data_type = args.get_expr(1)?;
-        let ty =
-            TypeSignature::parse_type_repr(generator.contract_analysis.epoch, data_type, &mut ())
-                .map_err(|e| GeneratorError::TypeError(e.to_string()))?;
+        let ty = TypeSignature::parse_type_repr(generator.epoch(), data_type, &mut ())
+            .map_err(|e| GeneratorError::TypeError(e.to_string()))?;
 
         let initial = args.get_expr(2)?;
         generator.set_expr_type(initial, ty.clone())?;
@@ -48,21 +41,11 @@ impl ComplexWord for DefineDataVar {
         // data stack)
         generator.traverse_expr(builder, initial)?;
 
-        // The initial value can be placed on the top of the memory, since at
-        // the top-level, we have not set up the call stack yet.
-        let offset = generator.module.locals.add(ValType::I32);
-        builder
-            .i32_const(generator.literal_memory_end as i32)
-            .local_set(offset);
-
-        // Write the initial value to the memory, to be read by the host.
-        let size = generator.write_to_memory(builder, offset, 0, &ty)?;
-
-        // Increment the literal memory end
-        // FIXME: These initial values do not need to be saved in the literal
-        //        memory forever... we just need them once, when .top-level
-        //        is called.
-        generator.literal_memory_end += size;
+        // The initial value is only read by the host when defining the
+        // variable, so it is written to the call stack, and released once the
+        // variable is defined.
+        let (offset, size) = generator.create_call_stack_local(builder, &ty, true, false);
+        generator.write_to_memory(builder, offset, 0, &ty)?;
 
         // Push the name onto the data stack
         builder
@@ -73,18 +56,13 @@ impl ComplexWord for DefineDataVar {
         builder.local_get(offset);
 
         // Push the size onto the data stack
-        builder.i32_const(size as i32);
+        builder.i32_const(size);
 
         // Call the host interface function, `define_variable`
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.define_variable")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.define_variable not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.define_variable")?);
+
+        // Release the initial value
+        generator.release_call_stack_local(builder, offset, size);
 
         // Add type to the datavars_types (for var-set workaround)
         if generator.datavars_types.insert(name.clone(), ty).is_some() {
@@ -153,15 +131,7 @@ impl ComplexWord for SetDataVar {
         builder.local_get(offset).i32_const(size);
 
         // Call the host interface function, `set_variable`
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.set_variable")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.set_variable not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.set_variable")?);
 
         // `var-set` always returns `true`
         builder.i32_const(1);
@@ -214,15 +184,7 @@ impl ComplexWord for GetDataVar {
         builder.local_get(offset).i32_const(size);
 
         // Call the host interface function, `get_variable`
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.get_variable")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.get_variable not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.get_variable")?);
 
         // Host interface fills the result into the specified memory. Read it
         // back out, and place the value on the data stack.
@@ -234,12 +196,18 @@ impl ComplexWord for GetDataVar {
 
 #[cfg(test)]
 mod tests {
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
     use clarity::vm::errors::{CheckErrors, Error};
+    use clarity::vm::types::QualifiedContractIdentifier;
     use clarity::vm::Value;
 
     use crate::tools::{
         crosscheck, crosscheck_expect_failure, crosscheck_with_clarity_version, evaluate,
+        TestConfig, TestEnvironment,
     };
+    use crate::{compile, CompileOptions};
 
     //
     // Module with tests that should only be executed
@@ -368,6 +336,66 @@ mod tests {
         crosscheck_expect_failure("(define-data-var a int 0) (define-data-var a int 0)");
     }
 
+    #[test]
+    fn define_data_var_initial_value_not_in_literal_memory() {
+        let literal_memory_end = |snippet: &str| {
+            let mut env = TestEnvironment::default();
+            env.init_contract_with_snippet("vars", snippet).unwrap();
+            env.get_memory_snapshot("vars").unwrap().literal_memory_end
+        };
+
+        // Only the name of the second variable is added to the literal memory.
+        let one_var = literal_memory_end("(define-data-var a int 1)");
+        let two_vars = literal_memory_end("(define-data-var a int 1) (define-data-var b int 2)");
+        assert_eq!(two_vars, one_var + 1);
+
+        crosscheck(
+            "(define-data-var a int 1) (define-data-var b (optional uint) (some u2)) (var-set a 3) (list (var-get a) (to-int (unwrap-panic (var-get b))))",
+            Ok(Some(
+                Value::cons_list_unsanitized(vec![Value::Int(3), Value::Int(2)]).unwrap(),
+            )),
+        );
+    }
+
+    #[test]
+    fn define_data_var_initial_values_share_the_call_stack() {
+        // Each initial value takes 3200 bytes on the call stack.
+        let fields = |value: &dyn Fn(usize) -> String| {
+            (0..200)
+                .map(|i| format!("f{i}: {}", value(i)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let ty = fields(&|_| "int".to_owned());
+        let value = fields(&|i| i.to_string());
+        let pages = |count: usize| {
+            let snippet = (0..count)
+                .map(|i| format!("(define-data-var v{i} {{{ty}}} {{{value}}})"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            compile(
+                &snippet,
+                &QualifiedContractIdentifier::transient(),
+                LimitedCostTracker::new_free(),
+                TestConfig::clarity_version(),
+                TestConfig::epoch(),
+                &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+                CompileOptions::default(),
+            )
+            .unwrap()
+            .module
+            .memories
+            .iter()
+            .next()
+            .unwrap()
+            .initial
+        };
+
+        // The 64 values would take more than 3 pages if they were all kept,
+        // only the names of the variables are added to the literal memory.
+        assert!(pages(64) <= pages(1) + 1);
+    }
+
     #[test]
     fn define_data_var_has_correct_type_with_clarity1() {
         // https://github.com/stacks-network/clarity-wasm/issues/497
@@ -405,17 +405,25 @@ fn wasm_equal(
     };
 
     match ty {
-        // we should never compare NoType
+        // A NoType on either side means that the operand can never hold a value
+        // at this position, e.g. the `ok` side of `(err none)`. The branch is dead
+        // at runtime, so we push a constant instead of trapping, and we must not
+        // read the placeholder locals, which are never assigned.
         TypeSignature::NoType => {
-            builder.unreachable();
+            builder.i32_const(matches!(nth_ty, TypeSignature::NoType) as i32);
             Ok(())
         }
+        _ if matches!(nth_ty, TypeSignature::NoType) => no_type_match(),
         TypeSignature::BoolType => {
-            builder
-                .local_get(first_op[0])
-                .local_get(nth_op[0])
-                .binop(BinaryOp::I32Eq);
-            Ok(())
+            if ty == nth_ty {
+                builder
+                    .local_get(first_op[0])
+                    .local_get(nth_op[0])
+                    .binop(BinaryOp::I32Eq);
+                Ok(())
+            } else {
+                no_type_match()
+            }
         }
         // is-eq-int function can be reused to both int and uint types.
         TypeSignature::IntType | TypeSignature::UIntType => {
@@ -427,7 +435,7 @@ fn wasm_equal(
         }
         // is-eq-bytes function can be used for types with (offset, length)
         TypeSignature::SequenceType(SequenceSubtype::BufferType(_))
-        | TypeSignature::SequenceType(SequenceSubtype::StringType(_)) => {
+        | TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(_))) => {
             if matches!(
                 (ty, nth_ty),
                 (
@@ -440,13 +448,6 @@ fn wasm_equal(
                     TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(
                         _
                     ))),
-                ) | (
-                    TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(
-                        _
-                    ))),
-                    TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(
-                        _
-                    ))),
                 )
             ) {
                 wasm_equal_bytes(generator, builder, first_op, nth_op)
@@ -454,6 +455,18 @@ fn wasm_equal(
                 no_type_match()
             }
         }
+        // utf8 strings are sequences of 4-byte unicode scalars, so comparing
+        // their bytes compares them scalar by scalar
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(_))) => {
+            if matches!(
+                nth_ty,
+                TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(_)))
+            ) {
+                wasm_equal_bytes(generator, builder, first_op, nth_op)
+            } else {
+                no_type_match()
+            }
+        }
         TypeSignature::PrincipalType
         | TypeSignature::CallableType(CallableSubtype::Principal(_)) => {
             if ty == nth_ty {
@@ -517,7 +530,7 @@ fn wasm_equal_int128(
     }
 
     // Call the function with the operands on the stack.
-    let func = generator.func_by_name("stdlib.is-eq-int");
+    let func = generator.func_by_name("stdlib.is-eq-int")?;
     builder.call(func);
 
     Ok(())
@@ -540,7 +553,7 @@ fn wasm_equal_bytes(
     }
 
     // Call the function with the operands on the stack.
-    let func = generator.func_by_name("stdlib.is-eq-bytes");
+    let func = generator.target_func_by_name("stdlib.is-eq-bytes")?;
     builder.call(func);
 
     Ok(())
@@ -884,6 +897,17 @@ fn wasm_equal_list(
         ));
     };
 
+    // Elements of those types are stored inline with a canonical representation,
+    // so two lists are equal iff their memory regions are identical.
+    if list_ty == nth_list_ty
+        && matches!(
+            list_ty,
+            TypeSignature::IntType | TypeSignature::UIntType | TypeSignature::BoolType
+        )
+    {
+        return wasm_equal_bytes(generator, builder, first_op, nth_op);
+    }
+
     let first_wasm_types = clar2wasm_ty(list_ty);
     let first_locals: Vec<_> = first_wasm_types
         .iter()
@@ -1272,6 +1296,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn is_eq_err_none() {
+        crosscheck("(is-eq (err none) (err none))", Ok(Some(Value::Bool(true))));
+    }
+
+    #[test]
+    fn is_eq_ok_with_err_none() {
+        crosscheck("(is-eq (ok true) (err none))", Ok(Some(Value::Bool(false))));
+        crosscheck("(is-eq (err none) (ok true))", Ok(Some(Value::Bool(false))));
+    }
+
+    #[test]
+    fn is_eq_nested_no_type() {
+        crosscheck(
+            "(is-eq (some (err none)) (some (ok (some u1))) (some (err none)))",
+            Ok(Some(Value::Bool(false))),
+        );
+        crosscheck(
+            "(is-eq (ok (err none)) (ok (err none)) (ok (err (some 1))))",
+            Ok(Some(Value::Bool(false))),
+        );
+    }
+
+    #[test]
+    fn is_eq_list_of_no_type_responses() {
+        crosscheck(
+            "(is-eq (list (ok u1) (err none)) (list (ok u1) (err none)))",
+            Ok(Some(Value::Bool(true))),
+        );
+    }
+
     #[test]
     fn is_eq_equal_buffers_with_different_max_len() {
         let snippet = "
@@ -1299,6 +1354,54 @@ mod tests {
         crosscheck(snippet, Ok(Some(clarity::vm::Value::Bool(true))));
     }
 
+    #[test]
+    fn is_eq_utf8_non_bmp() {
+        crosscheck(
+            r#"(is-eq u"\u{1F600}\u{10348}" u"\u{1F600}\u{10348}")"#,
+            Ok(Some(Value::Bool(true))),
+        );
+        crosscheck(
+            r#"(is-eq u"\u{1F600}\u{10348}" u"\u{1F600}\u{10349}")"#,
+            Ok(Some(Value::Bool(false))),
+        );
+    }
+
+    #[test]
+    fn is_eq_utf8_different_lengths() {
+        crosscheck(
+            r#"(is-eq u"\u{1F600}" u"\u{1F600}\u{1F600}")"#,
+            Ok(Some(Value::Bool(false))),
+        );
+    }
+
+    #[test]
+    fn is_eq_utf8_built_via_concat() {
+        crosscheck(
+            r#"(is-eq (concat u"a\u{1F600}" u"b") (concat u"a" u"\u{1F600}b") u"a\u{1F600}b")"#,
+            Ok(Some(Value::Bool(true))),
+        );
+    }
+
+    #[test]
+    fn is_eq_primitive_lists() {
+        crosscheck(
+            "(is-eq (list 1 2 -3) (list 1 2 -3) (list 1 2 -3))",
+            Ok(Some(Value::Bool(true))),
+        );
+        crosscheck(
+            "(is-eq (list u1 u2 u3) (list u1 u2 u4))",
+            Ok(Some(Value::Bool(false))),
+        );
+        crosscheck(
+            "(is-eq (list true false) (list true false true))",
+            Ok(Some(Value::Bool(false))),
+        );
+        crosscheck(
+            "(is-eq (list true false) (list true false))",
+            Ok(Some(Value::Bool(true))),
+        );
+    }
+
     #[test]
     fn is_eq_equal_lists_with_different_max_len() {
         let snippet = "
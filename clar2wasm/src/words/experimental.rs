@@ -0,0 +1,1383 @@
+//! Proposed Clarity builtins which are not part of any released Clarity version.
+//!
+//! These words are only compiled with the `experimental-words` feature, so that
+//! new natives can be prototyped on the Wasm backend without affecting
+//! consensus builds. They are never reserved names: a user-defined function
+//! with the same name always takes precedence.
+//!
+//! Their host functions and routines are not part of the standard library,
+//! the import or the routine is added to the module the first time a word
+//! needs it.
+//!
+//! The analysis doesn't know these words, so their calls are rewritten before
+//! it runs into builtins with the same argument and result types (see
+//! [`rewrite_for_analysis`]), and restored once it is done. The arguments no
+//! stand-in can restrict are checked afterwards (see [`check_argument_types`]).
+//! The generator then compiles the words themselves, with the types the
+//! analysis found.
+
+use std::collections::HashSet;
+
+use clarity::vm::analysis::{CheckError, CheckErrors, ContractAnalysis};
+use clarity::vm::functions::define::DefineFunctions;
+use clarity::vm::types::{
+    BufferLength, SequenceSubtype, StringSubtype, StringUTF8Length, TypeSignature, BUFF_32,
+    MAX_VALUE_SIZE,
+};
+use clarity::vm::{ClarityName, SymbolicExpression, SymbolicExpressionType, Value};
+use walrus::ir::{BinaryOp, ExtendedLoad, LoadKind, MemArg, StoreKind, UnaryOp};
+use walrus::{FunctionBuilder, FunctionId, InstrSeqBuilder, LocalId, MemoryId, Module, ValType};
+
+use super::{lookup_experimental, ComplexWord};
+use crate::check_args;
+use crate::error_mapping::ErrorMap;
+use crate::wasm_generator::{ArgumentsExt, CopyMemory, GeneratorError, WasmGenerator};
+use crate::wasm_utils::{check_argument_count, ArgumentCountCheck};
+
+/// `(secp256r1-verify message-hash signature public-key)`, which verifies a
+/// P-256 signature of a 32-byte message hash.
+#[derive(Debug)]
+pub struct Secp256r1Verify;
+
+impl ComplexWord for Secp256r1Verify {
+    fn name(&self) -> ClarityName {
+        "secp256r1-verify".into()
+    }
+
+    fn traverse(
+        &self,
+        generator: &mut WasmGenerator,
+        builder: &mut walrus::InstrSeqBuilder,
+        _expr: &SymbolicExpression,
+        args: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        check_args!(generator, builder, 3, args.len(), ArgumentCountCheck::Exact);
+
+        generator.traverse_expr(builder, args.get_expr(0)?)?;
+        generator.traverse_expr(builder, args.get_expr(1)?)?;
+        generator.traverse_expr(builder, args.get_expr(2)?)?;
+
+        // Call the host interface function, `secp256r1_verify`
+        let func = generator.import_host_function(
+            "secp256r1_verify",
+            "experimental.secp256r1_verify",
+            &[ValType::I32; 6],
+            &[ValType::I32],
+        );
+        builder.call(func);
+
+        Ok(())
+    }
+}
+
+/// `(contract-source-hash? contract)`, which returns the SHA-512/256 hash of
+/// the source of a deployed contract, or `none` if the principal is not a
+/// deployed contract.
+#[derive(Debug)]
+pub struct ContractSourceHash;
+
+impl ComplexWord for ContractSourceHash {
+    fn name(&self) -> ClarityName {
+        "contract-source-hash?".into()
+    }
+
+    fn traverse(
+        &self,
+        generator: &mut WasmGenerator,
+        builder: &mut walrus::InstrSeqBuilder,
+        _expr: &SymbolicExpression,
+        args: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        check_args!(generator, builder, 1, args.len(), ArgumentCountCheck::Exact);
+
+        generator.traverse_expr(builder, args.get_expr(0)?)?;
+
+        // Reserve space on the call stack for the hash
+        let (hash_local, hash_size) =
+            generator.create_call_stack_local(builder, &BUFF_32, false, true);
+        builder.local_get(hash_local);
+
+        // Call the host interface function, `contract_source_hash`, which
+        // pushes the indicator of the optional
+        let func = generator.import_host_function(
+            "contract_source_hash",
+            "experimental.contract_source_hash",
+            &[ValType::I32; 3],
+            &[ValType::I32],
+        );
+        builder.call(func);
+
+        // Push the offset and length of the hash
+        builder.local_get(hash_local).i32_const(hash_size);
+
+        Ok(())
+    }
+}
+
+/// Encoding of a string argument, `string-ascii` strings have 1-byte
+/// characters, `string-utf8` strings 4-byte big-endian unicode scalars.
+#[derive(Debug, Clone, Copy)]
+enum Encoding {
+    Ascii,
+    Utf8,
+}
+
+impl Encoding {
+    fn of(generator: &WasmGenerator, expr: &SymbolicExpression) -> Result<Self, GeneratorError> {
+        match generator.get_expr_type(expr) {
+            Some(TypeSignature::SequenceType(SequenceSubtype::StringType(
+                StringSubtype::ASCII(_),
+            ))) => Ok(Encoding::Ascii),
+            Some(TypeSignature::SequenceType(SequenceSubtype::StringType(
+                StringSubtype::UTF8(_),
+            ))) => Ok(Encoding::Utf8),
+            _ => Err(GeneratorError::TypeError(
+                "expected a string argument".to_owned(),
+            )),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Ascii => "ascii",
+            Encoding::Utf8 => "utf8",
+        }
+    }
+
+    fn char_size(self) -> i32 {
+        match self {
+            Encoding::Ascii => 1,
+            Encoding::Utf8 => 4,
+        }
+    }
+
+    /// Pushes the character at `offset` if it is an ASCII character, and 0
+    /// otherwise.
+    fn load_ascii(self, builder: &mut InstrSeqBuilder, memory: MemoryId, offset: LocalId) {
+        let load_byte = |builder: &mut InstrSeqBuilder, at: u32| {
+            builder.local_get(offset).load(
+                memory,
+                LoadKind::I32_8 {
+                    kind: ExtendedLoad::ZeroExtend,
+                },
+                MemArg {
+                    align: 1,
+                    offset: at,
+                },
+            );
+        };
+        match self {
+            Encoding::Ascii => load_byte(builder, 0),
+            Encoding::Utf8 => {
+                // The scalar is ASCII if its 3 high bytes, stored first, are 0.
+                load_byte(builder, 3);
+                builder.i32_const(0);
+                builder
+                    .local_get(offset)
+                    .load(
+                        memory,
+                        LoadKind::I32 { atomic: false },
+                        MemArg {
+                            align: 1,
+                            offset: 0,
+                        },
+                    )
+                    .i32_const(0x00ff_ffff)
+                    .binop(BinaryOp::I32And)
+                    .unop(UnaryOp::I32Eqz)
+                    .select(Some(ValType::I32));
+            }
+        }
+    }
+}
+
+/// Returns the routine named `name`, adding it to the module with `build`
+/// the first time it is needed.
+fn routine(
+    generator: &mut WasmGenerator,
+    name: &str,
+    build: impl FnOnce(&mut Module, MemoryId) -> FunctionId,
+) -> Result<FunctionId, GeneratorError> {
+    if let Some(id) = generator.module.funcs.by_name(name) {
+        return Ok(id);
+    }
+    let memory = generator.get_memory()?;
+    let id = build(&mut generator.module, memory);
+    generator.module.funcs.get_mut(id).name = Some(name.to_owned());
+    Ok(id)
+}
+
+/// Builds a routine `(offset, length, result)` copying the string at `offset`
+/// to `result`, converting the ASCII letters from `from` to the other case.
+/// The other characters are copied unchanged, with `copy_memory`.
+fn build_case_conversion(
+    module: &mut Module,
+    memory: MemoryId,
+    copy_memory: CopyMemory,
+    encoding: Encoding,
+    from: u8,
+) -> FunctionId {
+    let offset = module.locals.add(ValType::I32);
+    let length = module.locals.add(ValType::I32);
+    let result = module.locals.add(ValType::I32);
+    let end = module.locals.add(ValType::I32);
+    let character = module.locals.add(ValType::I32);
+    let char_size = encoding.char_size();
+
+    let mut func = FunctionBuilder::new(&mut module.types, &[ValType::I32; 3], &[]);
+    let mut body = func.func_body();
+
+    // Copy the string, then fix the letters in place.
+    body.local_get(result).local_get(offset).local_get(length);
+    copy_memory.emit(&mut body);
+    body.local_get(result)
+        .local_get(length)
+        .binop(BinaryOp::I32Add)
+        .local_set(end);
+
+    body.block(None, |done| {
+        let done_id = done.id();
+        done.loop_(None, |loop_| {
+            let loop_id = loop_.id();
+            loop_
+                .local_get(result)
+                .local_get(end)
+                .binop(BinaryOp::I32GeU)
+                .br_if(done_id);
+
+            encoding.load_ascii(loop_, memory, result);
+            loop_.local_tee(character);
+            // Letters differ by the 0x20 bit between cases.
+            loop_
+                .i32_const(from as i32)
+                .binop(BinaryOp::I32Sub)
+                .i32_const(26)
+                .binop(BinaryOp::I32LtU)
+                .if_else(
+                    None,
+                    |then| {
+                        then.local_get(result)
+                            .local_get(character)
+                            .i32_const(0x20)
+                            .binop(BinaryOp::I32Xor)
+                            .store(
+                                memory,
+                                StoreKind::I32_8 { atomic: false },
+                                MemArg {
+                                    align: 1,
+                                    offset: char_size as u32 - 1,
+                                },
+                            );
+                    },
+                    |_| {},
+                );
+
+            loop_
+                .local_get(result)
+                .i32_const(char_size)
+                .binop(BinaryOp::I32Add)
+                .local_set(result)
+                .br(loop_id);
+        });
+    });
+
+    func.finish(vec![offset, length, result], &mut module.funcs)
+}
+
+/// Builds a routine `(offset, length) -> (offset, length)` removing the
+/// leading and trailing ASCII whitespaces (space, `\t`, `\n`, `\x0b`,
+/// `\x0c` and `\r`) of the string at `offset`, without copying it.
+fn build_trim(module: &mut Module, memory: MemoryId, encoding: Encoding) -> FunctionId {
+    let offset = module.locals.add(ValType::I32);
+    let length = module.locals.add(ValType::I32);
+    let last = module.locals.add(ValType::I32);
+    let character = module.locals.add(ValType::I32);
+    let char_size = encoding.char_size();
+
+    let is_whitespace = |builder: &mut InstrSeqBuilder, at: LocalId| {
+        encoding.load_ascii(builder, memory, at);
+        builder
+            .local_tee(character)
+            .i32_const(b' ' as i32)
+            .binop(BinaryOp::I32Eq)
+            .local_get(character)
+            .i32_const(b'\t' as i32)
+            .binop(BinaryOp::I32Sub)
+            .i32_const(5)
+            .binop(BinaryOp::I32LtU)
+            .binop(BinaryOp::I32Or);
+    };
+
+    let mut func = FunctionBuilder::new(&mut module.types, &[ValType::I32; 2], &[ValType::I32; 2]);
+    let mut body = func.func_body();
+
+    // Leading whitespaces move the start of the string...
+    body.block(None, |done| {
+        let done_id = done.id();
+        done.loop_(None, |loop_| {
+            let loop_id = loop_.id();
+            loop_.local_get(length).unop(UnaryOp::I32Eqz).br_if(done_id);
+            is_whitespace(loop_, offset);
+            loop_.unop(UnaryOp::I32Eqz).br_if(done_id);
+            loop_
+                .local_get(offset)
+                .i32_const(char_size)
+                .binop(BinaryOp::I32Add)
+                .local_set(offset)
+                .local_get(length)
+                .i32_const(char_size)
+                .binop(BinaryOp::I32Sub)
+                .local_set(length)
+                .br(loop_id);
+        });
+    });
+
+    // ...and trailing whitespaces shorten it.
+    body.block(None, |done| {
+        let done_id = done.id();
+        done.loop_(None, |loop_| {
+            let loop_id = loop_.id();
+            loop_.local_get(length).unop(UnaryOp::I32Eqz).br_if(done_id);
+            loop_
+                .local_get(offset)
+                .local_get(length)
+                .binop(BinaryOp::I32Add)
+                .i32_const(char_size)
+                .binop(BinaryOp::I32Sub)
+                .local_set(last);
+            is_whitespace(loop_, last);
+            loop_.unop(UnaryOp::I32Eqz).br_if(done_id);
+            loop_
+                .local_get(length)
+                .i32_const(char_size)
+                .binop(BinaryOp::I32Sub)
+                .local_set(length)
+                .br(loop_id);
+        });
+    });
+
+    body.local_get(offset).local_get(length);
+
+    func.finish(vec![offset, length], &mut module.funcs)
+}
+
+fn traverse_case_conversion(
+    name: &str,
+    from: u8,
+    generator: &mut WasmGenerator,
+    builder: &mut walrus::InstrSeqBuilder,
+    args: &[SymbolicExpression],
+) -> Result<(), GeneratorError> {
+    check_args!(generator, builder, 1, args.len(), ArgumentCountCheck::Exact);
+
+    let string = args.get_expr(0)?;
+    let encoding = Encoding::of(generator, string)?;
+    let ty = generator
+        .get_expr_type(string)
+        .cloned()
+        .ok_or_else(|| GeneratorError::TypeError("expected a string argument".to_owned()))?;
+    generator.traverse_expr(builder, string)?;
+
+    // The result has the length of the argument.
+    let length = generator.module.locals.add(ValType::I32);
+    builder.local_tee(length);
+    let (result, _) = generator.create_call_stack_local(builder, &ty, false, true);
+    builder.local_get(result);
+
+    let copy_memory = generator.copy_memory()?;
+    let func = routine(
+        generator,
+        &format!("experimental.{name}-{}", encoding.name()),
+        |module, memory| build_case_conversion(module, memory, copy_memory, encoding, from),
+    )?;
+    builder.call(func).local_get(result).local_get(length);
+
+    Ok(())
+}
+
+/// `(to-upper string)`, which converts the ASCII letters of a `string-ascii`
+/// or `string-utf8` to upper case.
+#[derive(Debug)]
+pub struct ToUpper;
+
+impl ComplexWord for ToUpper {
+    fn name(&self) -> ClarityName {
+        "to-upper".into()
+    }
+
+    fn traverse(
+        &self,
+        generator: &mut WasmGenerator,
+        builder: &mut walrus::InstrSeqBuilder,
+        _expr: &SymbolicExpression,
+        args: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        traverse_case_conversion("to-upper", b'a', generator, builder, args)
+    }
+}
+
+/// `(to-lower string)`, which converts the ASCII letters of a `string-ascii`
+/// or `string-utf8` to lower case.
+#[derive(Debug)]
+pub struct ToLower;
+
+impl ComplexWord for ToLower {
+    fn name(&self) -> ClarityName {
+        "to-lower".into()
+    }
+
+    fn traverse(
+        &self,
+        generator: &mut WasmGenerator,
+        builder: &mut walrus::InstrSeqBuilder,
+        _expr: &SymbolicExpression,
+        args: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        traverse_case_conversion("to-lower", b'A', generator, builder, args)
+    }
+}
+
+/// `(trim string)`, which removes the leading and trailing ASCII whitespaces
+/// of a `string-ascii` or `string-utf8`.
+#[derive(Debug)]
+pub struct Trim;
+
+impl ComplexWord for Trim {
+    fn name(&self) -> ClarityName {
+        "trim".into()
+    }
+
+    fn traverse(
+        &self,
+        generator: &mut WasmGenerator,
+        builder: &mut walrus::InstrSeqBuilder,
+        _expr: &SymbolicExpression,
+        args: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        check_args!(generator, builder, 1, args.len(), ArgumentCountCheck::Exact);
+
+        let string = args.get_expr(0)?;
+        let encoding = Encoding::of(generator, string)?;
+        generator.traverse_expr(builder, string)?;
+
+        let func = routine(
+            generator,
+            &format!("experimental.trim-{}", encoding.name()),
+            |module, memory| build_trim(module, memory, encoding),
+        )?;
+        builder.call(func);
+
+        Ok(())
+    }
+}
+
+const LOW_32_BITS: i64 = 0xffff_ffff;
+
+/// Sets `lo` and `hi` to the 128-bit product of the 64-bit unsigned values
+/// `x` and `y`, using the temporary locals `t` and `k` (Hacker's Delight,
+/// chapter 8).
+fn mul_wide(
+    builder: &mut InstrSeqBuilder,
+    x: LocalId,
+    y: LocalId,
+    t: LocalId,
+    k: LocalId,
+    lo: LocalId,
+    hi: LocalId,
+) {
+    let half = |builder: &mut InstrSeqBuilder, value: LocalId, high: bool| {
+        builder.local_get(value);
+        if high {
+            builder.i64_const(32).binop(BinaryOp::I64ShrU);
+        } else {
+            builder.i64_const(LOW_32_BITS).binop(BinaryOp::I64And);
+        }
+    };
+
+    builder
+        .local_get(x)
+        .local_get(y)
+        .binop(BinaryOp::I64Mul)
+        .local_set(lo);
+
+    // k <- (x0 * y0) >> 32
+    half(builder, x, false);
+    half(builder, y, false);
+    builder
+        .binop(BinaryOp::I64Mul)
+        .i64_const(32)
+        .binop(BinaryOp::I64ShrU)
+        .local_set(k);
+    // t <- x1 * y0 + k
+    half(builder, x, true);
+    half(builder, y, false);
+    builder
+        .binop(BinaryOp::I64Mul)
+        .local_get(k)
+        .binop(BinaryOp::I64Add)
+        .local_set(t);
+    // k <- (x0 * y1 + (t & 0xffffffff)) >> 32
+    half(builder, x, false);
+    half(builder, y, true);
+    builder.binop(BinaryOp::I64Mul);
+    half(builder, t, false);
+    builder
+        .binop(BinaryOp::I64Add)
+        .i64_const(32)
+        .binop(BinaryOp::I64ShrU)
+        .local_set(k);
+    // hi <- x1 * y1 + (t >> 32) + k
+    half(builder, x, true);
+    half(builder, y, true);
+    builder.binop(BinaryOp::I64Mul);
+    half(builder, t, true);
+    builder
+        .binop(BinaryOp::I64Add)
+        .local_get(k)
+        .binop(BinaryOp::I64Add)
+        .local_set(hi);
+}
+
+/// Adds `value` to `acc`, and the carry to `carry`.
+fn add_with_carry(builder: &mut InstrSeqBuilder, acc: LocalId, value: LocalId, carry: LocalId) {
+    builder
+        .local_get(acc)
+        .local_get(value)
+        .binop(BinaryOp::I64Add)
+        .local_tee(acc)
+        .local_get(value)
+        .binop(BinaryOp::I64LtU)
+        .unop(UnaryOp::I64ExtendUI32)
+        .local_get(carry)
+        .binop(BinaryOp::I64Add)
+        .local_set(carry);
+}
+
+/// Pushes whether the unsigned 128-bit `a` is greater than or equal to `b`.
+fn ge_u128(builder: &mut InstrSeqBuilder, a: (LocalId, LocalId), b: (LocalId, LocalId)) {
+    builder
+        .local_get(a.1)
+        .local_get(b.1)
+        .binop(BinaryOp::I64GtU)
+        .local_get(a.1)
+        .local_get(b.1)
+        .binop(BinaryOp::I64Eq)
+        .local_get(a.0)
+        .local_get(b.0)
+        .binop(BinaryOp::I64GeU)
+        .binop(BinaryOp::I32And)
+        .binop(BinaryOp::I32Or);
+}
+
+/// Sets `hi` to `(hi << 1) | (lo >> 63)`.
+fn shift_in(builder: &mut InstrSeqBuilder, hi: LocalId, lo: LocalId) {
+    builder
+        .local_get(hi)
+        .i64_const(1)
+        .binop(BinaryOp::I64Shl)
+        .local_get(lo)
+        .i64_const(63)
+        .binop(BinaryOp::I64ShrU)
+        .binop(BinaryOp::I64Or)
+        .local_set(hi);
+}
+
+/// Negates the 128-bit value `(lo, hi)` in place.
+fn negate_128(builder: &mut InstrSeqBuilder, lo: LocalId, hi: LocalId) {
+    builder
+        .i64_const(0)
+        .local_get(hi)
+        .binop(BinaryOp::I64Sub)
+        .local_get(lo)
+        .i64_const(0)
+        .binop(BinaryOp::I64Ne)
+        .unop(UnaryOp::I64ExtendUI32)
+        .binop(BinaryOp::I64Sub)
+        .local_set(hi);
+    builder
+        .i64_const(0)
+        .local_get(lo)
+        .binop(BinaryOp::I64Sub)
+        .local_set(lo);
+}
+
+fn runtime_error_if(builder: &mut InstrSeqBuilder, runtime_error: FunctionId, error: ErrorMap) {
+    builder.if_else(
+        None,
+        |then| {
+            then.i32_const(error as i32).call(runtime_error);
+        },
+        |_| {},
+    );
+}
+
+/// Builds a routine `(a_lo, a_hi, b_lo, b_hi, c_lo, c_hi) -> (lo, hi)`
+/// computing `a * b / c` on unsigned 128-bit integers, with a 256-bit
+/// intermediate product. It fails with `runtime_error` if `c` is 0 or if the
+/// quotient does not fit in 128 bits.
+fn build_mul_div_uint(module: &mut Module, runtime_error: FunctionId) -> FunctionId {
+    let mut local = || module.locals.add(ValType::I64);
+    let [a_lo, a_hi, b_lo, b_hi, c_lo, c_hi] = [(); 6].map(|_| local());
+    let [l01, h01, l10, h10, l11, t, k, carry, top, q_lo, q_hi] = [(); 11].map(|_| local());
+    // The product is computed in place in the dividend `(n_lo, n_hi)` and
+    // the initial remainder `(r_lo, r_hi)`.
+    let [n_lo, n_hi, r_lo, r_hi] = [(); 4].map(|_| local());
+    let count = module.locals.add(ValType::I32);
+
+    let mut func = FunctionBuilder::new(&mut module.types, &[ValType::I64; 6], &[ValType::I64; 2]);
+    let mut body = func.func_body();
+
+    body.local_get(c_lo)
+        .local_get(c_hi)
+        .binop(BinaryOp::I64Or)
+        .unop(UnaryOp::I64Eqz);
+    runtime_error_if(&mut body, runtime_error, ErrorMap::DivisionByZero);
+
+    // The 256-bit product, from the four 128-bit partial products.
+    mul_wide(&mut body, a_lo, b_lo, t, k, n_lo, n_hi);
+    mul_wide(&mut body, a_lo, b_hi, t, k, l01, h01);
+    mul_wide(&mut body, a_hi, b_lo, t, k, l10, h10);
+    mul_wide(&mut body, a_hi, b_hi, t, k, l11, r_hi);
+
+    body.i64_const(0).local_set(carry);
+    add_with_carry(&mut body, n_hi, l01, carry);
+    add_with_carry(&mut body, n_hi, l10, carry);
+    body.local_get(h01).local_set(r_lo);
+    // `top` holds the carry of the third limb.
+    body.i64_const(0).local_set(top);
+    add_with_carry(&mut body, r_lo, carry, top);
+    add_with_carry(&mut body, r_lo, h10, top);
+    add_with_carry(&mut body, r_lo, l11, top);
+    // The product fits in 256 bits, this can't overflow.
+    body.local_get(r_hi)
+        .local_get(top)
+        .binop(BinaryOp::I64Add)
+        .local_set(r_hi);
+
+    // The quotient fits in 128 bits only if the high half of the product is
+    // lower than the divisor.
+    ge_u128(&mut body, (r_lo, r_hi), (c_lo, c_hi));
+    runtime_error_if(&mut body, runtime_error, ErrorMap::ArithmeticOverflow);
+
+    // Long division of the low half, one bit at a time. The remainder is
+    // always lower than the divisor, but can overflow 128 bits when shifted,
+    // in which case it is greater than the divisor and the subtraction
+    // wraps to the right value.
+    body.i64_const(0)
+        .local_set(q_lo)
+        .i64_const(0)
+        .local_set(q_hi)
+        .i32_const(128)
+        .local_set(count);
+    body.loop_(None, |loop_| {
+        let loop_id = loop_.id();
+
+        loop_
+            .local_get(r_hi)
+            .i64_const(63)
+            .binop(BinaryOp::I64ShrU)
+            .local_set(top);
+        shift_in(loop_, r_hi, r_lo);
+        shift_in(loop_, r_lo, n_hi);
+        shift_in(loop_, n_hi, n_lo);
+        shift_in(loop_, q_hi, q_lo);
+        for low in [n_lo, q_lo] {
+            loop_
+                .local_get(low)
+                .i64_const(1)
+                .binop(BinaryOp::I64Shl)
+                .local_set(low);
+        }
+
+        loop_.local_get(top).unop(UnaryOp::I32WrapI64);
+        ge_u128(loop_, (r_lo, r_hi), (c_lo, c_hi));
+        loop_.binop(BinaryOp::I32Or).if_else(
+            None,
+            |then| {
+                then.local_get(r_hi)
+                    .local_get(c_hi)
+                    .binop(BinaryOp::I64Sub)
+                    .local_get(r_lo)
+                    .local_get(c_lo)
+                    .binop(BinaryOp::I64LtU)
+                    .unop(UnaryOp::I64ExtendUI32)
+                    .binop(BinaryOp::I64Sub)
+                    .local_set(r_hi);
+                then.local_get(r_lo)
+                    .local_get(c_lo)
+                    .binop(BinaryOp::I64Sub)
+                    .local_set(r_lo);
+                then.local_get(q_lo)
+                    .i64_const(1)
+                    .binop(BinaryOp::I64Or)
+                    .local_set(q_lo);
+            },
+            |_| {},
+        );
+
+        loop_
+            .local_get(count)
+            .i32_const(1)
+            .binop(BinaryOp::I32Sub)
+            .local_tee(count)
+            .br_if(loop_id);
+    });
+
+    body.local_get(q_lo).local_get(q_hi);
+
+    func.finish(vec![a_lo, a_hi, b_lo, b_hi, c_lo, c_hi], &mut module.funcs)
+}
+
+/// Builds a routine `(a_lo, a_hi, b_lo, b_hi, c_lo, c_hi) -> (lo, hi)`
+/// computing `a * b / c` on signed 128-bit integers, rounding towards zero
+/// like `/`, by calling `mul_div_uint` on the absolute values.
+fn build_mul_div_int(
+    module: &mut Module,
+    runtime_error: FunctionId,
+    mul_div_uint: FunctionId,
+) -> FunctionId {
+    let mut local = || module.locals.add(ValType::I64);
+    let args = [(); 6].map(|_| local());
+    let [sign, q_lo, q_hi] = [(); 3].map(|_| local());
+
+    let mut func = FunctionBuilder::new(&mut module.types, &[ValType::I64; 6], &[ValType::I64; 2]);
+    let mut body = func.func_body();
+
+    // The result is negative if an odd number of arguments are.
+    body.local_get(args[1])
+        .local_get(args[3])
+        .binop(BinaryOp::I64Xor)
+        .local_get(args[5])
+        .binop(BinaryOp::I64Xor)
+        .i64_const(63)
+        .binop(BinaryOp::I64ShrU)
+        .local_set(sign);
+
+    for arg in args.chunks(2) {
+        body.local_get(arg[1])
+            .i64_const(0)
+            .binop(BinaryOp::I64LtS)
+            .if_else(None, |then| negate_128(then, arg[0], arg[1]), |_| {});
+        body.local_get(arg[0]).local_get(arg[1]);
+    }
+    body.call(mul_div_uint).local_set(q_hi).local_set(q_lo);
+
+    // The quotient must be lower than 2^127, or equal to it for a negative
+    // result.
+    body.local_get(q_hi)
+        .i64_const(0)
+        .binop(BinaryOp::I64LtS)
+        .local_get(sign)
+        .unop(UnaryOp::I64Eqz)
+        .local_get(q_hi)
+        .i64_const(i64::MIN)
+        .binop(BinaryOp::I64Ne)
+        .binop(BinaryOp::I32Or)
+        .local_get(q_lo)
+        .i64_const(0)
+        .binop(BinaryOp::I64Ne)
+        .binop(BinaryOp::I32Or)
+        .binop(BinaryOp::I32And);
+    runtime_error_if(&mut body, runtime_error, ErrorMap::ArithmeticOverflow);
+
+    body.local_get(sign).unop(UnaryOp::I32WrapI64).if_else(
+        None,
+        |then| negate_128(then, q_lo, q_hi),
+        |_| {},
+    );
+    body.local_get(q_lo).local_get(q_hi);
+
+    func.finish(args.to_vec(), &mut module.funcs)
+}
+
+/// `(mul-div a b c)`, which computes `(/ (* a b) c)` on `int` or `uint`
+/// values without overflowing on the intermediate product, which has 256
+/// bits.
+#[derive(Debug)]
+pub struct MulDiv;
+
+impl ComplexWord for MulDiv {
+    fn name(&self) -> ClarityName {
+        "mul-div".into()
+    }
+
+    fn traverse(
+        &self,
+        generator: &mut WasmGenerator,
+        builder: &mut walrus::InstrSeqBuilder,
+        _expr: &SymbolicExpression,
+        args: &[SymbolicExpression],
+    ) -> Result<(), GeneratorError> {
+        check_args!(generator, builder, 3, args.len(), ArgumentCountCheck::Exact);
+
+        let signed = match generator.get_expr_type(args.get_expr(0)?) {
+            Some(TypeSignature::IntType) => true,
+            Some(TypeSignature::UIntType) => false,
+            _ => {
+                return Err(GeneratorError::TypeError(
+                    "invalid type for arithmetic".to_owned(),
+                ))
+            }
+        };
+
+        for arg in args {
+            generator.traverse_expr(builder, arg)?;
+        }
+
+        let runtime_error = generator.func_by_name("stdlib.runtime-error")?;
+        let mut func = routine(generator, "experimental.mul-div-uint", |module, _| {
+            build_mul_div_uint(module, runtime_error)
+        })?;
+        if signed {
+            let mul_div_uint = func;
+            func = routine(generator, "experimental.mul-div-int", |module, _| {
+                build_mul_div_int(module, runtime_error, mul_div_uint)
+            })?;
+        }
+        builder.call(func);
+
+        Ok(())
+    }
+}
+
+/// Rewrites the calls to the experimental words of a contract into calls to
+/// builtins the analysis can type-check:
+/// - `(to-upper s)`, `(to-lower s)` and `(trim s)` into `(begin s)`,
+/// - `(mul-div a b c)` into `(+ a b c)`,
+/// - `(secp256r1-verify hash sig key)` into `(secp256k1-verify hash sig key)`,
+/// - `(contract-source-hash? p)` into
+///   `(begin (stx-get-balance p) (some 0x00...00))`.
+///
+/// The rewritten calls keep their id, and their arguments are left as-is, so
+/// the type map of the analysis has the types of both. The nodes added for
+/// the rewrite get fresh ids. Calls to a function defined by the contract, or
+/// with a wrong number of arguments, are left untouched.
+///
+/// `begin` accepts an argument of any type, so the argument of the string
+/// words must be checked with [`check_argument_types`] once the analysis is
+/// done.
+///
+/// Returns the original expressions, to restore once the analysis is done, or
+/// `None` if the contract calls no experimental word.
+pub(crate) fn rewrite_for_analysis(
+    expressions: &mut [SymbolicExpression],
+) -> Option<Vec<SymbolicExpression>> {
+    let defined = defined_functions(expressions);
+    let mut next_id = expressions.iter().map(max_id).max().unwrap_or(0) + 1;
+
+    let original = expressions.to_vec();
+    let mut rewritten = false;
+    for expr in expressions.iter_mut() {
+        rewritten |= rewrite(expr, &defined, &mut next_id);
+    }
+    rewritten.then_some(original)
+}
+
+/// Checks that the argument of the calls to `to-upper`, `to-lower` and `trim`
+/// in the original `expressions` is a string, with the types found by the
+/// analysis of their rewritten calls.
+pub(crate) fn check_argument_types(
+    expressions: &[SymbolicExpression],
+    analysis: &ContractAnalysis,
+) -> Result<(), CheckError> {
+    let defined = defined_functions(expressions);
+    expressions
+        .iter()
+        .try_for_each(|expr| check_strings(expr, &defined, analysis))
+}
+
+fn check_strings(
+    expr: &SymbolicExpression,
+    defined: &HashSet<ClarityName>,
+    analysis: &ContractAnalysis,
+) -> Result<(), CheckError> {
+    let Some(list) = expr.match_list() else {
+        return Ok(());
+    };
+    for inner in list {
+        check_strings(inner, defined, analysis)?;
+    }
+
+    let Some((word, [string])) = list.split_first() else {
+        return Ok(());
+    };
+    match word.match_atom() {
+        Some(name)
+            if matches!(name.as_str(), "to-upper" | "to-lower" | "trim")
+                && !defined.contains(name) => {}
+        _ => return Ok(()),
+    }
+    let Some(ty) = analysis
+        .type_map
+        .as_ref()
+        .and_then(|type_map| type_map.get_type(string))
+    else {
+        return Ok(());
+    };
+    if matches!(
+        ty,
+        TypeSignature::SequenceType(SequenceSubtype::StringType(_))
+    ) {
+        return Ok(());
+    }
+
+    let ascii = BufferLength::try_from(MAX_VALUE_SIZE)?;
+    let utf8 = StringUTF8Length::try_from(MAX_VALUE_SIZE / 4)?;
+    let string_types = vec![
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::ASCII(ascii))),
+        TypeSignature::SequenceType(SequenceSubtype::StringType(StringSubtype::UTF8(utf8))),
+    ];
+    let mut error = CheckError::new(CheckErrors::UnionTypeError(string_types, ty.clone()));
+    error.set_expression(string);
+    Err(error)
+}
+
+/// The names of the functions defined by the contract.
+fn defined_functions(expressions: &[SymbolicExpression]) -> HashSet<ClarityName> {
+    expressions
+        .iter()
+        .filter_map(|expr| {
+            let [define, signature, ..] = expr.match_list()? else {
+                return None;
+            };
+            DefineFunctions::lookup_by_name(define.match_atom()?)?;
+            signature.match_list()?.first()?.match_atom().cloned()
+        })
+        .collect()
+}
+
+fn max_id(expr: &SymbolicExpression) -> u64 {
+    expr.match_list()
+        .into_iter()
+        .flatten()
+        .map(max_id)
+        .fold(expr.id, u64::max)
+}
+
+fn rewrite(
+    expr: &mut SymbolicExpression,
+    defined: &HashSet<ClarityName>,
+    next_id: &mut u64,
+) -> bool {
+    let SymbolicExpressionType::List(list) = &mut expr.expr else {
+        return false;
+    };
+
+    let mut rewritten = false;
+    for inner in list.iter_mut() {
+        rewritten |= rewrite(inner, defined, next_id);
+    }
+
+    let Some(name) = list.first().and_then(|first| first.match_atom()) else {
+        return rewritten;
+    };
+    if defined.contains(name) || lookup_experimental(name.as_str()).is_none() {
+        return rewritten;
+    }
+    match stand_in(list, next_id) {
+        Some(stand_in) => {
+            *list = stand_in;
+            true
+        }
+        None => rewritten,
+    }
+}
+
+/// Returns the builtin call replacing the call to an experimental word `list`.
+fn stand_in(list: &[SymbolicExpression], next_id: &mut u64) -> Option<Vec<SymbolicExpression>> {
+    let (word, args) = list.split_first()?;
+    // A new node, with the span of the name of the word.
+    let mut node = |expr: SymbolicExpressionType| {
+        let mut node = word.clone();
+        node.expr = expr;
+        node.id = *next_id;
+        *next_id += 1;
+        node
+    };
+    let atom = |name: &str| SymbolicExpressionType::Atom(name.into());
+
+    match (word.match_atom()?.as_str(), args) {
+        ("to-upper" | "to-lower" | "trim", [string]) => {
+            Some(vec![node(atom("begin")), string.clone()])
+        }
+        ("mul-div", [a, b, c]) => Some(vec![node(atom("+")), a.clone(), b.clone(), c.clone()]),
+        ("secp256r1-verify", [hash, signature, key]) => Some(vec![
+            node(atom("secp256k1-verify")),
+            hash.clone(),
+            signature.clone(),
+            key.clone(),
+        ]),
+        ("contract-source-hash?", [contract]) => {
+            let get_balance = node(atom("stx-get-balance"));
+            let balance = node(SymbolicExpressionType::List(vec![
+                get_balance,
+                contract.clone(),
+            ]));
+            let some = node(atom("some"));
+            let hash = node(SymbolicExpressionType::LiteralValue(
+                Value::buff_from(vec![0; 32]).ok()?,
+            ));
+            let optional = node(SymbolicExpressionType::List(vec![some, hash]));
+            Some(vec![node(atom("begin")), balance, optional])
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clarity::util::hash::Sha512Trunc256Sum;
+    use wasmtime::{Engine, Instance, Store, Val};
+
+    use super::*;
+    use crate::tools::{evaluate, TestEnvironment};
+
+    /// Runs a routine on `input` in a module containing only the routine and
+    /// a memory, and returns the resulting bytes.
+    fn run_routine(
+        build: impl FnOnce(&mut Module, MemoryId) -> FunctionId,
+        input: &[u8],
+        returns_string: bool,
+    ) -> Vec<u8> {
+        let mut module = Module::default();
+        let memory = module.memories.add_local(false, 1, None);
+        module.exports.add("memory", memory);
+        let func = build(&mut module, memory);
+        module.exports.add("routine", func);
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = wasmtime::Module::from_binary(&engine, &module.emit_wasm()).unwrap();
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+        let memory = instance.get_memory(&mut store, "memory").unwrap();
+        let routine = instance.get_func(&mut store, "routine").unwrap();
+
+        let (offset, result) = (100, 1000);
+        memory.write(&mut store, offset, input).unwrap();
+        let len = input.len() as i32;
+
+        if returns_string {
+            let mut results = [Val::I32(0), Val::I32(0)];
+            routine
+                .call(
+                    &mut store,
+                    &[Val::I32(offset as i32), Val::I32(len)],
+                    &mut results,
+                )
+                .unwrap();
+            let (start, len) = (results[0].unwrap_i32(), results[1].unwrap_i32());
+            memory.data(&store)[start as usize..(start + len) as usize].to_vec()
+        } else {
+            routine
+                .call(
+                    &mut store,
+                    &[
+                        Val::I32(offset as i32),
+                        Val::I32(len),
+                        Val::I32(result as i32),
+                    ],
+                    &mut [],
+                )
+                .unwrap();
+            memory.data(&store)[result..result + input.len()].to_vec()
+        }
+    }
+
+    fn utf8(s: &str) -> Vec<u8> {
+        s.chars().flat_map(|c| (c as u32).to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn case_conversion_ascii() {
+        let to_upper = |s: &str| {
+            run_routine(
+                |module, memory| {
+                    build_case_conversion(
+                        module,
+                        memory,
+                        CopyMemory::Instruction(memory),
+                        Encoding::Ascii,
+                        b'a',
+                    )
+                },
+                s.as_bytes(),
+                false,
+            )
+        };
+        let to_lower = |s: &str| {
+            run_routine(
+                |module, memory| {
+                    build_case_conversion(
+                        module,
+                        memory,
+                        CopyMemory::Instruction(memory),
+                        Encoding::Ascii,
+                        b'A',
+                    )
+                },
+                s.as_bytes(),
+                false,
+            )
+        };
+
+        assert_eq!(to_upper("Hello, World! az{`@"), b"HELLO, WORLD! AZ{`@");
+        assert_eq!(to_lower("Hello, World! AZ[@`"), b"hello, world! az[@`");
+        assert_eq!(to_upper(""), b"");
+    }
+
+    #[test]
+    fn case_conversion_utf8() {
+        let to_upper = |s: &str| {
+            run_routine(
+                |module, memory| {
+                    build_case_conversion(
+                        module,
+                        memory,
+                        CopyMemory::Instruction(memory),
+                        Encoding::Utf8,
+                        b'a',
+                    )
+                },
+                &utf8(s),
+                false,
+            )
+        };
+
+        assert_eq!(to_upper("stacks"), utf8("STACKS"));
+        // Only ASCII letters are converted, other scalars ending with the byte
+        // of an ASCII letter are not.
+        assert_eq!(to_upper("café \u{0161}"), utf8("CAFé \u{0161}"));
+    }
+
+    #[test]
+    fn trim() {
+        let trim_ascii = |s: &str| {
+            run_routine(
+                |module, memory| build_trim(module, memory, Encoding::Ascii),
+                s.as_bytes(),
+                true,
+            )
+        };
+        let trim_utf8 = |s: &str| {
+            run_routine(
+                |module, memory| build_trim(module, memory, Encoding::Utf8),
+                &utf8(s),
+                true,
+            )
+        };
+
+        assert_eq!(trim_ascii(" \t hello world \r\n"), b"hello world");
+        assert_eq!(trim_ascii("hello"), b"hello");
+        assert_eq!(trim_ascii(" \n "), b"");
+        assert_eq!(trim_ascii(""), b"");
+        assert_eq!(trim_utf8("  héllo\t"), utf8("héllo"));
+        // U+2020 is not a whitespace, even if its low byte is a space.
+        assert_eq!(trim_utf8("\u{2020} "), utf8("\u{2020}"));
+    }
+
+    /// Runs `mul-div` on `a`, `b` and `c` given as 128-bit integers, in a
+    /// module containing only the routines, and returns `None` if it fails.
+    fn mul_div(signed: bool, a: u128, b: u128, c: u128) -> Option<u128> {
+        let mut module = Module::default();
+        let mut runtime_error = FunctionBuilder::new(&mut module.types, &[ValType::I32], &[]);
+        runtime_error.func_body().unreachable();
+        let param = module.locals.add(ValType::I32);
+        let runtime_error = runtime_error.finish(vec![param], &mut module.funcs);
+        let mut func = build_mul_div_uint(&mut module, runtime_error);
+        if signed {
+            func = build_mul_div_int(&mut module, runtime_error, func);
+        }
+        module.exports.add("routine", func);
+
+        let engine = Engine::default();
+        let mut store = Store::new(&engine, ());
+        let module = wasmtime::Module::from_binary(&engine, &module.emit_wasm()).unwrap();
+        let instance = Instance::new(&mut store, &module, &[]).unwrap();
+        let routine = instance.get_func(&mut store, "routine").unwrap();
+
+        let args: Vec<_> = [a, b, c]
+            .iter()
+            .flat_map(|v| [Val::I64(*v as i64), Val::I64((*v >> 64) as i64)])
+            .collect();
+        let mut results = [Val::I64(0), Val::I64(0)];
+        routine.call(&mut store, &args, &mut results).ok()?;
+        Some((results[0].unwrap_i64() as u64 as u128) | ((results[1].unwrap_i64() as u128) << 64))
+    }
+
+    #[test]
+    fn mul_div_uint() {
+        let mul_div = |a, b, c| mul_div(false, a, b, c);
+
+        assert_eq!(mul_div(7, 3, 2), Some(10));
+        assert_eq!(mul_div(0, 3, 2), Some(0));
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(
+            mul_div(10u128.pow(30), 10u128.pow(30), 10u128.pow(40)),
+            Some(10u128.pow(20))
+        );
+        assert_eq!(mul_div(u128::MAX, 3, 4), Some(u128::MAX / 4 * 3 + 2));
+        assert_eq!(mul_div(1 << 127, 1 << 64, 1 << 100), Some(1 << 91));
+        // Overflow of the result and division by zero
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+        assert_eq!(mul_div(1 << 64, 1 << 64, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+    }
+
+    #[test]
+    fn mul_div_int() {
+        let mul_div = |a: i128, b: i128, c: i128| {
+            mul_div(true, a as u128, b as u128, c as u128).map(|r| r as i128)
+        };
+
+        assert_eq!(mul_div(7, 3, 2), Some(10));
+        assert_eq!(mul_div(-7, 3, 2), Some(-10));
+        assert_eq!(mul_div(-7, -3, -2), Some(-10));
+        assert_eq!(mul_div(7, -3, -2), Some(10));
+        assert_eq!(mul_div(i128::MAX, i128::MAX, i128::MAX), Some(i128::MAX));
+        assert_eq!(mul_div(i128::MIN, i128::MIN, i128::MIN), Some(i128::MIN));
+        assert_eq!(mul_div(i128::MIN, 3, 3), Some(i128::MIN));
+        assert_eq!(mul_div(i128::MIN, -1, -1), Some(i128::MIN));
+        // Overflow of the result and division by zero
+        assert_eq!(mul_div(i128::MIN, -1, 1), None);
+        assert_eq!(mul_div(i128::MAX, 2, 1), None);
+        assert_eq!(mul_div(1, 1, 0), None);
+    }
+
+    #[test]
+    fn to_upper_and_to_lower_compile() {
+        assert_eq!(
+            evaluate(r#"(to-upper "Hello, World!")"#),
+            evaluate(r#""HELLO, WORLD!""#)
+        );
+        assert_eq!(
+            evaluate(r#"(to-lower u"Stacks \u{1F600}")"#),
+            evaluate(r#"u"stacks \u{1F600}""#)
+        );
+        assert_eq!(
+            evaluate(
+                r#"
+(define-read-only (shout (s (string-ascii 16))) (to-upper s))
+(shout "quiet")
+"#
+            ),
+            evaluate(r#""QUIET""#)
+        );
+    }
+
+    #[test]
+    fn trim_compiles() {
+        assert_eq!(evaluate(r#"(trim "  hello  ")"#), evaluate(r#""hello""#));
+        assert_eq!(
+            evaluate(r#"(trim u" \u{2020} ")"#),
+            evaluate(r#"u"\u{2020}""#)
+        );
+    }
+
+    #[test]
+    fn mul_div_compiles() {
+        assert_eq!(evaluate("(mul-div -7 3 2)"), evaluate("-10"));
+        assert_eq!(
+            evaluate("(mul-div u340282366920938463463374607431768211455 u3 u4)"),
+            evaluate("u255211775190703847597530955573826158591")
+        );
+        assert!(evaluate("(mul-div u1 u1 u0)").is_err());
+        // The arguments must have the same type, like for `+`.
+        assert!(evaluate("(mul-div u1 1 u1)").is_err());
+    }
+
+    #[test]
+    fn secp256r1_verify_compiles() {
+        use p256::ecdsa::signature::hazmat::PrehashSigner;
+        use p256::ecdsa::{Signature, SigningKey};
+
+        let key = SigningKey::from_slice(&[0x11; 32]).unwrap();
+        let hash = [0x42; 32];
+        let signature: Signature = key.sign_prehash(&hash).unwrap();
+        let public_key = key.verifying_key().to_encoded_point(true);
+
+        let verify = |hash: &[u8]| {
+            evaluate(&format!(
+                "(secp256r1-verify 0x{} 0x{} 0x{})",
+                hex::encode(hash),
+                hex::encode(signature.to_bytes()),
+                hex::encode(public_key.as_bytes()),
+            ))
+        };
+        assert_eq!(verify(&hash), Ok(Some(Value::Bool(true))));
+        assert_eq!(verify(&[0x43; 32]), Ok(Some(Value::Bool(false))));
+    }
+
+    #[test]
+    fn contract_source_hash_compiles() {
+        let source = "(define-read-only (foo) u1)";
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet("other", source)
+            .expect("failed to deploy contract");
+
+        let hash = Sha512Trunc256Sum::from_data(source.as_bytes());
+        assert_eq!(
+            env.evaluate("(contract-source-hash? .other)"),
+            Ok(Some(
+                Value::some(Value::buff_from(hash.as_bytes().to_vec()).unwrap()).unwrap()
+            ))
+        );
+        assert_eq!(
+            env.evaluate(
+                "(list (contract-source-hash? .missing) (contract-source-hash? tx-sender))"
+            ),
+            evaluate("(list none none)")
+        );
+    }
+
+    #[test]
+    fn defined_function_shadows_experimental_word() {
+        assert_eq!(
+            evaluate("(define-private (trim (s int)) (+ s 1)) (trim 1)"),
+            evaluate("2")
+        );
+    }
+
+    #[test]
+    fn string_words_reject_other_types() {
+        for snippet in [
+            "(to-upper u5)",
+            "(to-lower 1)",
+            "(trim 0x00)",
+            "(to-upper (list \"a\"))",
+            "(trim (trim 0x00))",
+        ] {
+            assert!(
+                TestEnvironment::default()
+                    .init_contract_with_snippet("snippet", snippet)
+                    .is_err(),
+                "{snippet} should not compile"
+            );
+        }
+    }
+
+    #[test]
+    fn rewrite_for_analysis_keeps_ids() {
+        let mut expressions = vec![SymbolicExpression::list(vec![
+            SymbolicExpression::atom("trim".into()),
+            SymbolicExpression::atom("s".into()),
+        ])];
+        expressions[0].id = 1;
+        let original = rewrite_for_analysis(&mut expressions).expect("nothing rewritten");
+
+        let names = |expr: &SymbolicExpression| -> Vec<String> {
+            expr.match_list()
+                .unwrap()
+                .iter()
+                .map(|e| e.match_atom().unwrap().to_string())
+                .collect()
+        };
+        assert_eq!(names(&original[0]), ["trim", "s"]);
+        assert_eq!(names(&expressions[0]), ["begin", "s"]);
+        assert_eq!(expressions[0].id, 1);
+        assert_eq!(expressions[0].match_list().unwrap()[0].id, 2);
+
+        // A contract without experimental words is left untouched.
+        let mut expressions = vec![SymbolicExpression::atom("trim".into())];
+        assert!(rewrite_for_analysis(&mut expressions).is_none());
+    }
+}
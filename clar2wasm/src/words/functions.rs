@@ -26,13 +26,7 @@ impl ComplexWord for DefinePrivateFunction {
             return Err(GeneratorError::NotImplemented);
         };
         let name = signature.get_name(0)?;
-        // Making sure name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         let body = args.get_expr(1)?;
 
@@ -62,13 +56,7 @@ impl ComplexWord for DefineReadonlyFunction {
             return Err(GeneratorError::NotImplemented);
         };
         let name = signature.get_name(0)?;
-        // Making sure name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         let body = args.get_expr(1)?;
 
@@ -100,13 +88,7 @@ impl ComplexWord for DefinePublicFunction {
             return Err(GeneratorError::NotImplemented);
         };
         let name = signature.get_name(0)?;
-        // Making sure name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         let body = args.get_expr(1)?;
 
@@ -423,6 +405,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn reuse_arg_name_read_only_and_public() {
+        crosscheck(
+            "(define-read-only (foo (a int) (b int) (a int)) 1) (foo 1 2 3)",
+            Err(Error::Unchecked(CheckErrors::NameAlreadyUsed(
+                "a".to_string(),
+            ))),
+        );
+        crosscheck(
+            "(define-public (foo (a int) (b int) (b int)) (ok 1)) (foo 1 2 3)",
+            Err(Error::Unchecked(CheckErrors::NameAlreadyUsed(
+                "b".to_string(),
+            ))),
+        );
+    }
+
+    #[test]
+    fn reserved_arg_name() {
+        for snippet in [
+            "(define-private (foo (tx-sender int)) 1)",
+            "(define-read-only (foo (tx-sender int)) 1)",
+            "(define-public (foo (tx-sender int)) (ok 1))",
+        ] {
+            crosscheck(
+                snippet,
+                Err(Error::Unchecked(CheckErrors::NameAlreadyUsed(
+                    "tx-sender".to_string(),
+                ))),
+            );
+        }
+
+        crosscheck(
+            "(define-private (foo (a int) (map int)) 1)",
+            Err(Error::Unchecked(CheckErrors::NameAlreadyUsed(
+                "map".to_string(),
+            ))),
+        );
+    }
+
+    #[test]
+    fn arg_name_used_by_definition() {
+        crosscheck(
+            "(define-constant a 1) (define-read-only (foo (a int)) 2)",
+            Err(Error::Unchecked(CheckErrors::NameAlreadyUsed(
+                "a".to_string(),
+            ))),
+        );
+        crosscheck(
+            "(define-private (a) 1) (define-public (foo (a int)) (ok 2))",
+            Err(Error::Unchecked(CheckErrors::NameAlreadyUsed(
+                "a".to_string(),
+            ))),
+        );
+    }
+
+    #[test]
+    fn arg_name_used_by_data_var() {
+        // Only constants and functions are checked by the interpreter.
+        crosscheck(
+            "(define-data-var a int 1) (define-private (foo (a int)) a) (foo 2)",
+            Ok(Some(Value::Int(2))),
+        );
+    }
+
     #[test]
     fn reuse_arg_name_contrac_call() {
         let first_contract_name = "callee".into();
@@ -443,4 +489,106 @@ mod tests {
             ))),
         );
     }
+    #[test]
+    fn return_response_of_tuple_of_lists() {
+        // Both results must survive the second call.
+        crosscheck(
+            r#"
+            (define-private (wrap (n int))
+                (if (> n 0)
+                    (ok (tuple (items (map + (list n n) (list 1 2))) (name (concat "n-" "ame"))))
+                    (err u1)))
+            (let ((a (unwrap-panic (wrap 1))) (b (unwrap-panic (wrap 10))))
+                (tuple (a (get items a)) (b (get items b)) (name (get name a))))
+            "#,
+            evaluate(r#"(tuple (a (list 2 3)) (b (list 11 12)) (name "n-ame"))"#),
+        );
+    }
+
+    #[test]
+    fn return_nested_response_from_public_function() {
+        crosscheck(
+            r#"
+            (define-public (squares (l (list 8 uint)))
+                (if (> (len l) u0)
+                    (ok (ok (tuple (squares (map * l l)) (label (some (concat "sq" "uares"))))))
+                    (err u0)))
+            (define-private (labels (a (list 8 uint)) (b (list 8 uint)))
+                (let ((x (unwrap-panic (unwrap-panic (squares a))))
+                      (y (unwrap-panic (unwrap-panic (squares b)))))
+                    (tuple (x (get squares x)) (y (get squares y)) (label (get label y)))))
+            (labels (list u1 u2 u3) (list u4 u5))
+            "#,
+            evaluate(r#"(tuple (x (list u1 u4 u9)) (y (list u16 u25)) (label (some "squares")))"#),
+        );
+    }
+
+    #[test]
+    fn return_list_of_lists() {
+        crosscheck(
+            r#"
+            (define-private (nested (n int)) (list (list n) (concat (list n) (list n))))
+            (let ((a (nested 1)) (b (nested 2))) (concat a b))
+            "#,
+            evaluate("(list (list 1) (list 1 1) (list 2) (list 2 2))"),
+        );
+    }
+
+    #[test]
+    fn return_in_frame_in_a_loop() {
+        crosscheck(
+            r#"
+            (define-private (pair (n int)) (some (tuple (n n) (l (list n n)))))
+            (define-private (add-pair (n int) (acc int))
+                (let ((p (unwrap-panic (pair n)))) (+ acc (fold + (get l p) 0))))
+            (fold add-pair (list 1 2 3 4 5 6 7 8) 0)
+            "#,
+            Ok(Some(Value::Int(72))),
+        );
+    }
+
+    /// The elements `1` to `count`, to write a list literal.
+    fn elements(count: i32) -> String {
+        (1..=count)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn return_in_frame_mapped_over_a_long_list() {
+        // The temporary list of each call is freed, only the list returned
+        // stays in memory.
+        crosscheck(
+            &format!(
+                r#"
+                (define-private (pair (n int))
+                    (let ((many (list n n n n n n n n n n n n n n n n)))
+                        (some (tuple (n n) (l (list n (fold + many 0)))))))
+                (define-private (add-pair (p (optional (tuple (n int) (l (list 2 int))))) (acc int))
+                    (+ acc (fold + (get l (unwrap-panic p)) 0)))
+                (fold add-pair (map pair (list {})) 0)
+                "#,
+                elements(4000)
+            ),
+            Ok(Some(Value::Int(17 * 4000 * 4001 / 2))),
+        );
+    }
+
+    #[test]
+    fn return_in_frame_folded_over_a_long_list() {
+        // Only the last accumulator stays in memory.
+        crosscheck(
+            &format!(
+                r#"
+                (define-private (push (n int) (acc (tuple (last (list 2 int)) (count int))))
+                    (let ((many (list n n n n n n n n n n n n n n n n)))
+                        (tuple (last (list n (fold + many 0))) (count (+ (get count acc) 1)))))
+                (fold push (list {}) (tuple (last (list 0 0)) (count 0)))
+                "#,
+                elements(4000)
+            ),
+            evaluate("(tuple (last (list 4000 64000)) (count 4000))"),
+        );
+    }
 }
@@ -3,6 +3,7 @@ use clarity::vm::ClarityName;
 
 use super::SimpleWord;
 use crate::wasm_generator::{GeneratorError, WasmGenerator};
+use crate::wasm_utils::ArgumentCountCheck;
 
 pub fn traverse_hash(
     name: &'static str,
@@ -30,13 +31,7 @@ pub fn traverse_hash(
             return Err(GeneratorError::NotImplemented);
         }
     };
-    let hash_func = generator
-        .module
-        .funcs
-        .by_name(&format!("stdlib.{name}-{hash_type}"))
-        .ok_or_else(|| {
-            GeneratorError::InternalError(format!("function not found: {name}-{hash_type}"))
-        })?;
+    let hash_func = generator.target_func_by_name(&format!("stdlib.{name}-{hash_type}"))?;
 
     builder
         .i32_const(offset_res as i32) // result offset
@@ -45,6 +40,46 @@ pub fn traverse_hash(
     Ok(())
 }
 
+/// Hashes the argument with the host function `host_function`, for the hashes
+/// which are not implemented in the standard library. Integers are hashed as
+/// their 16 bytes little-endian representation, like in the interpreter.
+fn traverse_host_hash(
+    word: &str,
+    host_function: &str,
+    generator: &mut WasmGenerator,
+    builder: &mut walrus::InstrSeqBuilder,
+    arg_types: &[TypeSignature],
+) -> Result<(), GeneratorError> {
+    let ty = &arg_types[0];
+    match ty {
+        TypeSignature::IntType | TypeSignature::UIntType => {
+            // Convert integers to buffers by storing them to memory
+            let (buffer_local, size) = generator.create_call_stack_local(builder, ty, false, true);
+            generator.write_to_memory(builder, buffer_local, 0, ty)?;
+
+            // Then load the offset and length onto the stack
+            builder.local_get(buffer_local).i32_const(size);
+        }
+        TypeSignature::SequenceType(SequenceSubtype::BufferType(_)) => {}
+        _ => {
+            return Err(GeneratorError::TypeError(format!(
+                "invalid type for {word}"
+            )))
+        }
+    }
+
+    // Reserve stack space for the host-function to write the result
+    let ret_ty = BUFF_32.clone();
+    let (result_local, result_size) =
+        generator.create_call_stack_local(builder, &ret_ty, false, true);
+    builder.local_get(result_local).i32_const(result_size);
+
+    // Call the host interface function
+    builder.call(generator.func_by_name(&format!("stdlib.{host_function}"))?);
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct Hash160;
 
@@ -53,6 +88,10 @@ impl SimpleWord for Hash160 {
         "hash160".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -73,6 +112,10 @@ impl SimpleWord for Sha256 {
         "sha256".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -93,6 +136,10 @@ impl SimpleWord for Keccak256 {
         "keccak256".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -100,43 +147,7 @@ impl SimpleWord for Keccak256 {
         arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        let ty = &arg_types[0];
-        match ty {
-            TypeSignature::IntType | TypeSignature::UIntType => {
-                // Convert integers to buffers by storing them to memory
-                let (buffer_local, size) =
-                    generator.create_call_stack_local(builder, ty, false, true);
-                generator.write_to_memory(builder, buffer_local, 0, ty)?;
-
-                // Then load the offset and length onto the stack
-                builder.local_get(buffer_local).i32_const(size);
-            }
-            TypeSignature::SequenceType(SequenceSubtype::BufferType(_)) => {}
-            _ => {
-                return Err(GeneratorError::TypeError(
-                    "invalid type for keccak256".to_string(),
-                ))
-            }
-        }
-
-        // Reserve stack space for the host-function to write the result
-        let ret_ty = BUFF_32.clone();
-        let (result_local, result_size) =
-            generator.create_call_stack_local(builder, &ret_ty, false, true);
-        builder.local_get(result_local).i32_const(result_size);
-
-        // Call the host interface function, `keccak256`
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.keccak256")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.keccak256 not found".to_owned())
-                })?,
-        );
-
-        Ok(())
+        traverse_host_hash("keccak256", "keccak256", generator, builder, arg_types)
     }
 }
 
@@ -148,6 +159,10 @@ impl SimpleWord for Sha512 {
         "sha512".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -168,6 +183,10 @@ impl SimpleWord for Sha512_256 {
         "sha512/256".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -175,43 +194,7 @@ impl SimpleWord for Sha512_256 {
         arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        let ty = &arg_types[0];
-        match ty {
-            TypeSignature::IntType | TypeSignature::UIntType => {
-                // Convert integers to buffers by storing them to memory
-                let (buffer_local, size) =
-                    generator.create_call_stack_local(builder, ty, false, true);
-                generator.write_to_memory(builder, buffer_local, 0, ty)?;
-
-                // Then load the offset and length onto the stack
-                builder.local_get(buffer_local).i32_const(size);
-            }
-            TypeSignature::SequenceType(SequenceSubtype::BufferType(_)) => {}
-            _ => {
-                return Err(GeneratorError::TypeError(
-                    "invalid type for sha512/256".to_string(),
-                ))
-            }
-        }
-
-        // Reserve stack space for the host-function to write the result
-        let ret_ty = BUFF_32.clone();
-        let (result_local, result_size) =
-            generator.create_call_stack_local(builder, &ret_ty, false, true);
-        builder.local_get(result_local).i32_const(result_size);
-
-        // Call the host interface function, `sha512_256`
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.sha512_256")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.sha512_256 not found".to_owned())
-                })?,
-        );
-
-        Ok(())
+        traverse_host_hash("sha512/256", "sha512_256", generator, builder, arg_types)
     }
 }
 
@@ -219,7 +202,17 @@ impl SimpleWord for Sha512_256 {
 mod tests {
     use clarity::vm::Value;
 
-    use crate::tools::crosscheck;
+    use crate::tools::{crosscheck, evaluate};
+
+    #[test]
+    fn sha256_more_than_one_arg() {
+        let result = evaluate("(sha256 0x00 0x01)");
+        assert!(result.is_err());
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("expecting 1 arguments, got 2"));
+    }
 
     #[test]
     fn test_keccak256() {
@@ -3,6 +3,7 @@ use clarity::vm::ClarityName;
 
 use super::SimpleWord;
 use crate::wasm_generator::{GeneratorError, WasmGenerator};
+use crate::wasm_utils::ArgumentCountCheck;
 
 #[derive(Debug)]
 pub struct Not;
@@ -12,6 +13,10 @@ impl SimpleWord for Not {
         "not".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -19,7 +24,7 @@ impl SimpleWord for Not {
         _arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        builder.call(generator.func_by_name("stdlib.not"));
+        builder.call(generator.func_by_name("stdlib.not")?);
         Ok(())
     }
 }
@@ -1,5 +1,6 @@
-use clarity::vm::types::TypeSignature;
+use clarity::vm::types::{FunctionType, TypeSignature};
 use clarity::vm::{ClarityName, SymbolicExpression};
+use walrus::ValType;
 
 use super::ComplexWord;
 use crate::check_args;
@@ -24,20 +25,14 @@ impl ComplexWord for MapDefinition {
         check_args!(generator, builder, 3, args.len(), ArgumentCountCheck::Exact);
 
         let name = args.get_name(0)?;
-        // Making sure if name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         let key_type = args.get_expr(1).and_then(|sym_ty| {
-            TypeSignature::parse_type_repr(generator.contract_analysis.epoch, sym_ty, &mut ())
+            TypeSignature::parse_type_repr(generator.epoch(), sym_ty, &mut ())
                 .map_err(|e| GeneratorError::TypeError(format!("invalid type for map key: {e}")))
         })?;
         let value_type = args.get_expr(2).and_then(|sym_ty| {
-            TypeSignature::parse_type_repr(generator.contract_analysis.epoch, sym_ty, &mut ())
+            TypeSignature::parse_type_repr(generator.epoch(), sym_ty, &mut ())
                 .map_err(|e| GeneratorError::TypeError(format!("invalid type for map value: {e}")))
         })?;
 
@@ -49,15 +44,7 @@ impl ComplexWord for MapDefinition {
             .i32_const(name_offset as i32)
             .i32_const(name_length as i32);
 
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.define_map")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.define_map not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.define_map")?);
 
         // Add the map types to generator
         generator
@@ -137,7 +124,7 @@ impl ComplexWord for MapGet {
         builder.local_get(return_offset).i32_const(return_size);
 
         // Call the host-interface function, `map_get`
-        builder.call(generator.func_by_name("stdlib.map_get"));
+        builder.call(generator.func_by_name("stdlib.map_get")?);
 
         // Host interface fills the result into the specified memory. Read it
         // back out, and place the value on the data stack.
@@ -147,6 +134,103 @@ impl ComplexWord for MapGet {
     }
 }
 
+/// Returns the map read by the user-defined function `name`, if it is a
+/// private or read-only function whose body is only `(map-get? map key)`,
+/// where `key` is its single argument, of the same type as the keys of the
+/// map.
+pub(crate) fn map_lookup_function(
+    generator: &WasmGenerator,
+    name: &ClarityName,
+) -> Option<ClarityName> {
+    generator
+        .contract_analysis
+        .expressions
+        .iter()
+        .find_map(|expr| {
+            let [define, signature, body] = expr.match_list()? else {
+                return None;
+            };
+            if !matches!(
+                define.match_atom()?.as_str(),
+                "define-private" | "define-read-only"
+            ) {
+                return None;
+            }
+            let [function_name, argument] = signature.match_list()? else {
+                return None;
+            };
+            if function_name.match_atom()? != name {
+                return None;
+            }
+            let [get, map_name, key] = body.match_list()? else {
+                return None;
+            };
+            let argument_name = argument.match_list()?.first()?.match_atom()?;
+            (get.match_atom()?.as_str() == "map-get?" && key.match_atom()? == argument_name)
+                .then(|| map_name.match_atom().cloned())
+                .flatten()
+        })
+        .filter(|map_name| {
+            let key_type = generator.maps_types.get(map_name).map(|(key, _)| key);
+            let argument_type = match generator.get_function_type(name) {
+                Some(FunctionType::Fixed(fixed)) => fixed.args.first().map(|arg| &arg.signature),
+                _ => None,
+            };
+            key_type.is_some() && key_type == argument_type
+        })
+}
+
+/// Traverses `(map f keys)`, where `f` only reads `map_name` (see
+/// [`map_lookup_function`]), as a single call to the `map_get_batch` host
+/// function, which writes the list of the optional values of the `keys`.
+/// `ty` is the type of this list.
+pub(crate) fn traverse_map_get_batch(
+    generator: &mut WasmGenerator,
+    builder: &mut walrus::InstrSeqBuilder,
+    map_name: &ClarityName,
+    keys: &SymbolicExpression,
+    ty: &TypeSignature,
+) -> Result<(), GeneratorError> {
+    // Get the offset and length for this identifier in the literal memory
+    let id_offset = *generator
+        .literal_memory_offset
+        .get(&LiteralMemoryEntry::Ascii(map_name.as_str().into()))
+        .ok_or_else(|| GeneratorError::InternalError(format!("map not found: {map_name}")))?;
+    let id_length = map_name.len();
+
+    // Push the identifier offset and length onto the data stack
+    builder
+        .i32_const(id_offset as i32)
+        .i32_const(id_length as i32);
+
+    // Push the offset and length of the list of keys
+    generator.traverse_expr(builder, keys)?;
+
+    // Create space on the call stack for the list of values, with their
+    // in-memory data
+    let (return_offset, return_size) = generator.create_call_stack_local(builder, ty, true, true);
+    builder.local_get(return_offset).i32_const(return_size);
+
+    // Call the host-interface function, `map_get_batch`, which returns the
+    // length of the list of values
+    let func = generator.import_host_function(
+        "map_get_batch",
+        "stdlib.map_get_batch",
+        &[ValType::I32; 6],
+        &[ValType::I32],
+    );
+    builder.call(func);
+
+    // Place the list of values on the data stack
+    let length = generator.module.locals.add(ValType::I32);
+    builder
+        .local_set(length)
+        .local_get(return_offset)
+        .local_get(length);
+
+    Ok(())
+}
+
 #[derive(Debug)]
 pub struct MapSet;
 
@@ -223,7 +307,7 @@ impl ComplexWord for MapSet {
         builder.local_get(val_offset).i32_const(val_size);
 
         // Call the host interface function, `map_set`
-        builder.call(generator.func_by_name("stdlib.map_set"));
+        builder.call(generator.func_by_name("stdlib.map_set")?);
 
         Ok(())
     }
@@ -305,7 +389,7 @@ impl ComplexWord for MapInsert {
         builder.local_get(val_offset).i32_const(val_size);
 
         // Call the host interface function, `map_insert`
-        builder.call(generator.func_by_name("stdlib.map_insert"));
+        builder.call(generator.func_by_name("stdlib.map_insert")?);
 
         Ok(())
     }
@@ -368,15 +452,7 @@ impl ComplexWord for MapDelete {
         builder.local_get(key_offset).i32_const(key_size);
 
         // Call the host interface function, `map_delete`
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.map_delete")
-                .ok_or_else(|| {
-                    GeneratorError::TypeError("stdlib.map_delete not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.map_delete")?);
 
         Ok(())
     }
@@ -386,10 +462,17 @@ impl ComplexWord for MapDelete {
 mod tests {
     // use clarity::vm::errors::{CheckErrors, Error};
 
+    use clarity::vm::analysis::AnalysisDatabase;
+    use clarity::vm::costs::LimitedCostTracker;
+    use clarity::vm::database::MemoryBackingStore;
     use clarity::vm::errors::{CheckErrors, Error};
+    use clarity::vm::types::{QualifiedContractIdentifier, TupleData};
     use clarity::vm::Value;
 
-    use crate::tools::{crosscheck, crosscheck_expect_failure, evaluate};
+    use crate::tools::{
+        crosscheck, crosscheck_expect_failure, evaluate, TestConfig, TestEnvironment,
+    };
+    use crate::{compile, CompileOptions, CompileResult};
 
     //
     // Module with tests that should only be executed
@@ -576,4 +659,214 @@ mod tests {
         let expected = Err(Error::Unchecked(CheckErrors::IncorrectArgumentCount(2, 3)));
         crosscheck(snippet, expected);
     }
+
+    fn tuple(fields: &[(&str, Value)]) -> Value {
+        Value::from(
+            TupleData::from_data(
+                fields
+                    .iter()
+                    .map(|(name, value)| ((*name).into(), value.clone()))
+                    .collect(),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn map_operations_with_tuple_keys_in_any_order() {
+        crosscheck(
+            "(define-map m {a: int, b: int} uint)
+            {
+                first-insert: (map-insert m {b: 2, a: 1} u10),
+                second-insert: (map-insert m {a: 1, b: 2} u20),
+                update: (map-set m (tuple (b 2) (a 1)) u30),
+                entry: (map-get? m {a: 1, b: 2}),
+                first-delete: (map-delete m {b: 2, a: 1}),
+                second-delete: (map-delete m {a: 1, b: 2})
+            }",
+            Ok(Some(tuple(&[
+                ("first-insert", Value::Bool(true)),
+                ("second-insert", Value::Bool(false)),
+                ("update", Value::Bool(true)),
+                ("entry", Value::some(Value::UInt(30)).unwrap()),
+                ("first-delete", Value::Bool(true)),
+                ("second-delete", Value::Bool(false)),
+            ]))),
+        );
+    }
+
+    const TUPLE_KEYS: &str = "
+        (define-map m {name: (string-ascii 8), id: {b: int, a: (optional uint)}} uint)
+        (define-read-only (get-entry (key {name: (string-ascii 8), id: {b: int, a: (optional uint)}}))
+            (map-get? m key))
+        (map-insert m {id: {b: 1, a: none}, name: \"one\"} u1)
+        (map-insert m {name: \"two\", id: {a: (some u2), b: 2}} u2)";
+
+    fn tuple_key(name: &str, b: i128, a: Option<u128>) -> Value {
+        tuple(&[
+            (
+                "id",
+                tuple(&[
+                    ("b", Value::Int(b)),
+                    (
+                        "a",
+                        a.map_or_else(Value::none, |a| Value::some(Value::UInt(a)).unwrap()),
+                    ),
+                ]),
+            ),
+            (
+                "name",
+                Value::string_ascii_from_bytes(name.as_bytes().to_vec()).unwrap(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn compiled_map_entries_are_read_by_the_interpreter() {
+        let mut compiled_env = TestEnvironment::default();
+        compiled_env
+            .init_contract_with_snippet("maps", TUPLE_KEYS)
+            .unwrap();
+        let mut interpreted_env = TestEnvironment::default();
+        interpreted_env
+            .interpret_contract_with_snippet("maps", TUPLE_KEYS)
+            .unwrap();
+
+        for (key, expected) in [
+            (
+                tuple_key("one", 1, None),
+                Value::some(Value::UInt(1)).unwrap(),
+            ),
+            (
+                tuple_key("two", 2, Some(2)),
+                Value::some(Value::UInt(2)).unwrap(),
+            ),
+            (tuple_key("two", 2, None), Value::none()),
+        ] {
+            assert_eq!(
+                compiled_env.get_map_entry("maps", "m", &key),
+                Ok(expected.clone())
+            );
+            assert_eq!(
+                interpreted_env.get_map_entry("maps", "m", &key),
+                Ok(expected)
+            );
+        }
+    }
+
+    #[test]
+    fn interpreter_map_entries_are_read_by_compiled_code() {
+        let mut env = TestEnvironment::default();
+        env.init_contract_with_snippet("maps", TUPLE_KEYS).unwrap();
+
+        assert_eq!(
+            env.set_map_entry("maps", "m", tuple_key("three", 3, Some(3)), Value::UInt(3)),
+            Ok(Value::Bool(true))
+        );
+        assert_eq!(
+            env.set_map_entry("maps", "m", tuple_key("one", 1, None), Value::UInt(10)),
+            Ok(Value::Bool(true))
+        );
+
+        for (key, expected) in [
+            (
+                tuple_key("one", 1, None),
+                Value::some(Value::UInt(10)).unwrap(),
+            ),
+            (
+                tuple_key("two", 2, Some(2)),
+                Value::some(Value::UInt(2)).unwrap(),
+            ),
+            (
+                tuple_key("three", 3, Some(3)),
+                Value::some(Value::UInt(3)).unwrap(),
+            ),
+        ] {
+            assert_eq!(env.call_function("maps", "get-entry", &[key]), Ok(expected));
+        }
+    }
+
+    const SCORES: &str = r#"
+        (define-map scores uint (buff 4))
+        (map-set scores u1 0x01)
+        (map-set scores u3 0x030303)
+        (define-private (score (id uint)) (map-get? scores id))
+        (map score (list u1 u2 u3))
+    "#;
+
+    fn compile_scores(snippet: &str, options: CompileOptions) -> CompileResult {
+        compile(
+            snippet,
+            &QualifiedContractIdentifier::local("scores").unwrap(),
+            LimitedCostTracker::new_free(),
+            TestConfig::clarity_version(),
+            TestConfig::epoch(),
+            &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+            options,
+        )
+        .expect("failed to compile contract")
+    }
+
+    fn imports_map_get_batch(result: &CompileResult) -> bool {
+        result
+            .module
+            .imports
+            .iter()
+            .any(|import| import.name == "map_get_batch")
+    }
+
+    #[test]
+    fn map_get_batch() {
+        let expected = Value::cons_list_unsanitized(vec![
+            Value::some(Value::buff_from_byte(1)).unwrap(),
+            Value::none(),
+            Value::some(Value::buff_from(vec![3, 3, 3]).unwrap()).unwrap(),
+        ])
+        .unwrap();
+        crosscheck(SCORES, Ok(Some(expected.clone())));
+
+        let mut result = compile_scores(SCORES, CompileOptions::default().batch_map_reads(true));
+        assert!(imports_map_get_batch(&result));
+
+        let mut env = TestEnvironment::new(TestConfig::epoch(), TestConfig::clarity_version());
+        assert_eq!(
+            env.deploy_precompiled(
+                &QualifiedContractIdentifier::local("scores").unwrap(),
+                &result.module.emit_wasm(),
+                &result.contract_analysis
+            ),
+            Ok(Some(expected))
+        );
+        let host_calls = env.get_host_calls("scores").unwrap();
+        assert_eq!(host_calls.get("map_get_batch"), Some(&1));
+        assert_eq!(host_calls.get("map_get"), None);
+    }
+
+    #[test]
+    fn map_get_batch_from_host_module() {
+        let result = compile_scores(
+            SCORES,
+            CompileOptions::default()
+                .batch_map_reads(true)
+                .host_module("stacks_host"),
+        );
+        assert!(result
+            .module
+            .imports
+            .find("stacks_host", "map_get_batch")
+            .is_some());
+    }
+
+    #[test]
+    fn map_get_batch_only_for_map_lookups() {
+        let result = compile_scores(SCORES, CompileOptions::default());
+        assert!(!imports_map_get_batch(&result));
+
+        let snippet = SCORES.replace(
+            "(map-get? scores id)",
+            "(default-to 0x (map-get? scores id))",
+        );
+        let result = compile_scores(&snippet, CompileOptions::default().batch_map_reads(true));
+        assert!(!imports_map_get_batch(&result));
+    }
 }
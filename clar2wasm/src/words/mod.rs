@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
+use clarity::vm::functions::NativeFunctions;
 use clarity::vm::types::TypeSignature;
-use clarity::vm::{ClarityName, SymbolicExpression};
+use clarity::vm::{ClarityName, ClarityVersion, SymbolicExpression};
 use lazy_static::lazy_static;
 use walrus::InstrSeqBuilder;
 
+use crate::wasm_utils::ArgumentCountCheck;
 use crate::{GeneratorError, WasmGenerator};
 
 pub mod arithmetic;
@@ -23,6 +25,8 @@ pub mod data_vars;
 pub mod default_to;
 pub mod enums;
 pub mod equal;
+#[cfg(feature = "experimental-words")]
+pub mod experimental;
 pub mod functions;
 pub mod hashing;
 pub mod logical;
@@ -56,6 +60,8 @@ pub(crate) static COMPLEX_WORDS: &[&'static dyn ComplexWord] = &[
     &blockinfo::AtBlock,
     &blockinfo::GetBlockInfo,
     &blockinfo::GetBurnBlockInfo,
+    &blockinfo::GetStacksBlockInfo,
+    &blockinfo::GetTenureInfo,
     &conditionals::And,
     &conditionals::Asserts,
     &conditionals::Filter,
@@ -136,6 +142,18 @@ pub(crate) static COMPLEX_WORDS: &[&'static dyn ComplexWord] = &[
 pub trait SimpleWord: Sync + core::fmt::Debug {
     fn name(&self) -> ClarityName;
 
+    /// The number of arguments the word accepts. It is checked before the
+    /// arguments are traversed, so that a call with a wrong number of
+    /// arguments fails at runtime with the same error as in the interpreter.
+    fn arity(&self) -> (usize, ArgumentCountCheck);
+
+    /// Checks the literal arguments of a call for errors that will always be
+    /// raised at runtime, and returns a message describing the error. The
+    /// call is still compiled, and fails at runtime like in the interpreter.
+    fn check_literal_args(&self, _args: &[SymbolicExpression]) -> Option<String> {
+        None
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -193,46 +211,99 @@ pub(crate) static SIMPLE_VARIADIC_WORDS: &[&'static dyn SimpleWord] = &[
     &arithmetic::Mul,
 ];
 
-lazy_static! {
-    static ref COMPLEX_WORDS_BY_NAME: HashMap<ClarityName, &'static dyn ComplexWord> = {
-        let mut cwbn = HashMap::new();
+/// Proposed builtins, see [`experimental`].
+#[cfg(feature = "experimental-words")]
+pub(crate) static EXPERIMENTAL_WORDS: &[&'static dyn ComplexWord] = &[
+    &experimental::Secp256r1Verify,
+    &experimental::ContractSourceHash,
+    &experimental::ToUpper,
+    &experimental::ToLower,
+    &experimental::Trim,
+    &experimental::MulDiv,
+];
 
-        for word in COMPLEX_WORDS {
-            cwbn.insert(word.name(), &**word);
-        }
+#[cfg(not(feature = "experimental-words"))]
+pub(crate) static EXPERIMENTAL_WORDS: &[&'static dyn ComplexWord] = &[];
 
-        cwbn
-    };
-    static ref SIMPLE_WORDS_BY_NAME: HashMap<ClarityName, &'static dyn SimpleWord> = {
-        let mut swbn = HashMap::new();
+/// The words available for a given Clarity version.
+///
+/// Builtins introduced in later Clarity versions (e.g. `stx-account`,
+/// `slice?` or `bit-and`) are not reserved names in older versions, so a
+/// contract is free to define functions with those names. Those calls must
+/// then resolve to the user-defined function, exactly like the interpreter.
+struct WordTable {
+    complex: HashMap<ClarityName, &'static dyn ComplexWord>,
+    simple: HashMap<ClarityName, &'static dyn SimpleWord>,
+    simple_variadic: HashMap<ClarityName, &'static dyn SimpleWord>,
+}
 
-        for word in SIMPLE_WORDS {
-            swbn.insert(word.name(), &**word);
+impl WordTable {
+    fn for_version(version: ClarityVersion) -> Self {
+        Self {
+            complex: COMPLEX_WORDS
+                .iter()
+                .map(|word| (word.name(), *word))
+                .filter(|(name, _)| is_available_at(name, version))
+                .collect(),
+            simple: SIMPLE_WORDS
+                .iter()
+                .map(|word| (word.name(), *word))
+                .filter(|(name, _)| is_available_at(name, version))
+                .collect(),
+            simple_variadic: SIMPLE_VARIADIC_WORDS
+                .iter()
+                .map(|word| (word.name(), *word))
+                .filter(|(name, _)| is_available_at(name, version))
+                .collect(),
         }
+    }
+}
 
-        swbn
-    };
-    static ref SIMPLE_VARIADIC_WORDS_BY_NAME: HashMap<ClarityName, &'static dyn SimpleWord> = {
-        let mut svwbn = HashMap::new();
+/// Checks if the word `name` exists in Clarity `version`.
+///
+/// Define functions are available in every version, native functions are
+/// checked against the version in which they were introduced.
+fn is_available_at(name: &str, version: ClarityVersion) -> bool {
+    NativeFunctions::lookup_by_name(name).is_none()
+        || NativeFunctions::lookup_by_name_at_version(name, &version).is_some()
+}
 
-        for word in SIMPLE_VARIADIC_WORDS {
-            svwbn.insert(word.name(), &**word);
-        }
+lazy_static! {
+    static ref CLARITY1_WORDS: WordTable = WordTable::for_version(ClarityVersion::Clarity1);
+    static ref CLARITY2_WORDS: WordTable = WordTable::for_version(ClarityVersion::Clarity2);
+    static ref CLARITY3_WORDS: WordTable = WordTable::for_version(ClarityVersion::Clarity3);
+}
 
-        svwbn
-    };
+fn word_table(version: ClarityVersion) -> &'static WordTable {
+    match version {
+        ClarityVersion::Clarity1 => &CLARITY1_WORDS,
+        ClarityVersion::Clarity2 => &CLARITY2_WORDS,
+        ClarityVersion::Clarity3 => &CLARITY3_WORDS,
+    }
 }
 
-pub fn lookup_complex(name: &str) -> Option<&'static dyn ComplexWord> {
-    COMPLEX_WORDS_BY_NAME.get(name).copied()
+pub fn lookup_complex(name: &str, version: ClarityVersion) -> Option<&'static dyn ComplexWord> {
+    word_table(version).complex.get(name).copied()
 }
 
-pub fn lookup_simple(name: &str) -> Option<&'static dyn SimpleWord> {
-    SIMPLE_WORDS_BY_NAME.get(name).copied()
+pub fn lookup_simple(name: &str, version: ClarityVersion) -> Option<&'static dyn SimpleWord> {
+    word_table(version).simple.get(name).copied()
 }
 
-pub fn lookup_variadic_simple(name: &str) -> Option<&'static dyn SimpleWord> {
-    SIMPLE_VARIADIC_WORDS_BY_NAME.get(name).copied()
+pub fn lookup_variadic_simple(
+    name: &str,
+    version: ClarityVersion,
+) -> Option<&'static dyn SimpleWord> {
+    word_table(version).simple_variadic.get(name).copied()
+}
+
+/// Looks up a proposed builtin. Those are not reserved names, so this must
+/// only be used once `name` is known not to be a user-defined function.
+pub fn lookup_experimental(name: &str) -> Option<&'static dyn ComplexWord> {
+    EXPERIMENTAL_WORDS
+        .iter()
+        .find(|word| word.name().as_str() == name)
+        .copied()
 }
 
 #[cfg(test)]
@@ -289,6 +360,61 @@ mod tests {
         }
     }
 
+    #[test]
+    fn check_experimental_words_are_not_natives() {
+        for word in super::EXPERIMENTAL_WORDS {
+            assert!(
+                NativeFunctions::lookup_by_name(&word.name()).is_none()
+                    && DefineFunctions::lookup_by_name(&word.name()).is_none(),
+                "{} is not experimental anymore",
+                word.name()
+            );
+            assert!(super::lookup_experimental(&word.name()).is_some());
+        }
+    }
+
+    #[cfg(feature = "experimental-words")]
+    #[test]
+    fn check_experimental_words_enabled() {
+        assert!(super::lookup_experimental("secp256r1-verify").is_some());
+        assert!(super::lookup_experimental("contract-source-hash?").is_some());
+        assert!(super::lookup_experimental("to-upper").is_some());
+        assert!(super::lookup_experimental("trim").is_some());
+        assert!(super::lookup_experimental("mul-div").is_some());
+    }
+
+    #[test]
+    fn check_words_by_version() {
+        use clarity::vm::ClarityVersion;
+
+        use super::{lookup_complex, lookup_simple};
+
+        // Clarity 2 builtins are not available in Clarity 1
+        for name in ["stx-account", "bit-and", "bit-not"] {
+            assert!(lookup_simple(name, ClarityVersion::Clarity1).is_none());
+            assert!(lookup_simple(name, ClarityVersion::Clarity2).is_some());
+            assert!(lookup_simple(name, ClarityVersion::Clarity3).is_some());
+        }
+        for name in ["slice?", "replace-at?", "element-at?", "index-of?"] {
+            assert!(lookup_complex(name, ClarityVersion::Clarity1).is_none());
+            assert!(lookup_complex(name, ClarityVersion::Clarity2).is_some());
+            assert!(lookup_complex(name, ClarityVersion::Clarity3).is_some());
+        }
+
+        // Clarity 3 builtins are only available in Clarity 3
+        for name in ["get-stacks-block-info?", "get-tenure-info?"] {
+            assert!(lookup_complex(name, ClarityVersion::Clarity1).is_none());
+            assert!(lookup_complex(name, ClarityVersion::Clarity2).is_none());
+            assert!(lookup_complex(name, ClarityVersion::Clarity3).is_some());
+        }
+
+        // Words available since Clarity 1 and define functions are always there
+        for name in ["element-at", "index-of", "define-private", "let"] {
+            assert!(lookup_complex(name, ClarityVersion::Clarity1).is_some());
+        }
+        assert!(lookup_simple("xor", ClarityVersion::Clarity1).is_some());
+    }
+
     #[test]
     fn check_word_classes() {
         for word in super::SIMPLE_WORDS {
@@ -315,4 +441,126 @@ mod tests {
             }
         }
     }
+
+    /// Compiles every word with every combination of argument types accepted
+    /// by the type checker, so that a standard library function built from
+    /// the types of the arguments (e.g. `stdlib.add-uint`) is always in
+    /// `STDLIB_FUNCTIONS`.
+    #[test]
+    fn check_stdlib_functions_of_every_word() {
+        use clarity::vm::analysis::AnalysisDatabase;
+        use clarity::vm::costs::LimitedCostTracker;
+        use clarity::vm::database::MemoryBackingStore;
+        use clarity::vm::types::QualifiedContractIdentifier;
+
+        use crate::tools::TestConfig;
+        use crate::wasm_utils::ArgumentCountCheck;
+        use crate::{compile, CompileError, CompileOptions};
+
+        const ARGUMENTS: &[&str] = &[
+            "1",
+            "u1",
+            "true",
+            "0x01",
+            "\"a\"",
+            "u\"a\"",
+            "'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM",
+            "(list 1)",
+            "(some u1)",
+        ];
+
+        fn combinations(count: usize) -> Vec<Vec<&'static str>> {
+            (0..count).fold(vec![vec![]], |combinations, _| {
+                combinations
+                    .iter()
+                    .flat_map(|args| {
+                        ARGUMENTS.iter().map(move |arg| {
+                            let mut args = args.clone();
+                            args.push(*arg);
+                            args
+                        })
+                    })
+                    .collect()
+            })
+        }
+
+        let version = TestConfig::clarity_version();
+        let simple_words = super::SIMPLE_WORDS
+            .iter()
+            .chain(super::SIMPLE_VARIADIC_WORDS)
+            .filter(|word| super::is_available_at(&word.name(), version))
+            .map(|word| {
+                let counts = match word.arity() {
+                    (count, ArgumentCountCheck::Exact) => count..=count,
+                    (count, ArgumentCountCheck::AtLeast) => count..=count + 1,
+                    (count, ArgumentCountCheck::AtMost) => 0..=count,
+                };
+                (word.name(), counts)
+            });
+        let complex_words = super::COMPLEX_WORDS
+            .iter()
+            .filter(|word| super::is_available_at(&word.name(), version))
+            .map(|word| (word.name(), 0..=2));
+
+        let mut store = MemoryBackingStore::new();
+        for (name, counts) in simple_words.chain(complex_words) {
+            for args in counts.flat_map(combinations) {
+                let snippet = format!("({name} {})", args.join(" "));
+                let result = compile(
+                    &snippet,
+                    &QualifiedContractIdentifier::transient(),
+                    LimitedCostTracker::new_free(),
+                    version,
+                    TestConfig::epoch(),
+                    &mut AnalysisDatabase::new(&mut store),
+                    CompileOptions::default(),
+                );
+                // Snippets rejected by the analysis are skipped, the others
+                // must find all their functions.
+                if let Err(CompileError::Generic { diagnostics, .. }) = result {
+                    assert!(
+                        !diagnostics
+                            .iter()
+                            .any(|diagnostic| diagnostic.message.contains("function not found")),
+                        "{snippet}: {diagnostics:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_simple_word_arities() {
+        use clarity::vm::types::FunctionType;
+
+        use crate::wasm_utils::ArgumentCountCheck;
+
+        for word in super::SIMPLE_WORDS
+            .iter()
+            .chain(super::SIMPLE_VARIADIC_WORDS)
+        {
+            let Some(native) = NativeFunctions::lookup_by_name(word.name().as_str()) else {
+                continue;
+            };
+            // `and` and `or` are special functions in the interpreter
+            let Ok(TypedNativeFunction::Simple(simple)) =
+                TypedNativeFunction::type_native_function(&native)
+            else {
+                continue;
+            };
+            let expected = match simple.0 {
+                FunctionType::Fixed(fixed) => (fixed.args.len(), ArgumentCountCheck::Exact),
+                FunctionType::ArithmeticUnary | FunctionType::UnionArgs(..) => {
+                    (1, ArgumentCountCheck::Exact)
+                }
+                FunctionType::ArithmeticBinary
+                | FunctionType::ArithmeticComparison
+                | FunctionType::Binary(..) => (2, ArgumentCountCheck::Exact),
+                FunctionType::ArithmeticVariadic | FunctionType::Variadic(..) => {
+                    (1, ArgumentCountCheck::AtLeast)
+                }
+            };
+            assert_eq!(word.arity(), expected, "wrong arity for {:?}", word);
+        }
+    }
 }
@@ -18,6 +18,10 @@ impl SimpleWord for ToInt {
         "to-int".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -25,7 +29,7 @@ impl SimpleWord for ToInt {
         _arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        let helper_func = generator.func_by_name("stdlib.to-int");
+        let helper_func = generator.func_by_name("stdlib.to-int")?;
         builder.call(helper_func);
 
         Ok(())
@@ -40,6 +44,10 @@ impl SimpleWord for ToUint {
         "to-uint".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -47,7 +55,7 @@ impl SimpleWord for ToUint {
         _arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        let helper_func = generator.func_by_name("stdlib.to-uint");
+        let helper_func = generator.func_by_name("stdlib.to-uint")?;
         builder.call(helper_func);
 
         Ok(())
@@ -80,10 +88,10 @@ impl ComplexWord for ContractOf {
 #[cfg(test)]
 mod tests {
     use clarity::vm::errors::{Error, RuntimeErrorType};
-    use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier};
+    use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, TupleData};
     use clarity::vm::Value;
 
-    use crate::tools::{crosscheck, evaluate, TestEnvironment};
+    use crate::tools::{crosscheck, crosscheck_multi_contract, evaluate, TestEnvironment};
 
     #[test]
     fn to_int_out_of_range() {
@@ -125,6 +133,40 @@ mod tests {
         )
     }
 
+    #[test]
+    fn to_uint_min_int() {
+        crosscheck(
+            "(to-uint -170141183460469231731687303715884105728)",
+            Err(Error::Runtime(
+                RuntimeErrorType::ArithmeticUnderflow,
+                Some(Vec::new()),
+            )),
+        )
+    }
+
+    #[test]
+    fn to_int_max_uint() {
+        crosscheck(
+            "(to-int u340282366920938463463374607431768211455)",
+            Err(Error::Runtime(
+                RuntimeErrorType::ArithmeticOverflow,
+                Some(Vec::new()),
+            )),
+        )
+    }
+
+    #[test]
+    fn conversions_across_the_64_bit_boundary() {
+        crosscheck(
+            "(to-uint 18446744073709551616)",
+            Ok(Some(Value::UInt(1 << 64))),
+        );
+        crosscheck(
+            "(to-int u18446744073709551615)",
+            Ok(Some(Value::Int(u64::MAX as i128))),
+        );
+    }
+
     #[test]
     fn to_uint() {
         crosscheck("(to-uint 767)", Ok(Some(Value::UInt(767))));
@@ -172,6 +214,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn contract_of_crosscheck() {
+        let user = r#"
+(use-trait my-trait .my-trait.my-trait)
+(define-private (principal-of-trait (t <my-trait>))
+    (contract-of t))
+(define-public (check (t <my-trait>))
+    (ok {
+        principal: (principal-of-trait t),
+        is-impl: (is-eq (contract-of t) .impl),
+    }))
+(check .impl)
+"#;
+        crosscheck_multi_contract(
+            &[
+                (
+                    "my-trait".into(),
+                    "(define-trait my-trait ((get-value () (response uint uint))))",
+                ),
+                (
+                    "impl".into(),
+                    "(impl-trait .my-trait.my-trait) (define-public (get-value) (ok u42))",
+                ),
+                ("user".into(), user),
+            ],
+            Ok(Some(
+                Value::okay(Value::Tuple(
+                    TupleData::from_data(vec![
+                        (
+                            "principal".into(),
+                            Value::Principal(PrincipalData::Contract(
+                                QualifiedContractIdentifier::parse(
+                                    "S1G2081040G2081040G2081040G208105NK8PE5.impl",
+                                )
+                                .unwrap(),
+                            )),
+                        ),
+                        ("is-impl".into(), Value::Bool(true)),
+                    ])
+                    .unwrap(),
+                ))
+                .unwrap(),
+            )),
+        );
+    }
+
     #[test]
     fn test_to_int_oob() {
         crosscheck(
@@ -24,6 +24,10 @@ impl SimpleWord for IsStandard {
         "is-standard".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -53,7 +57,7 @@ impl SimpleWord for IsStandard {
         // TODO: It would be nice if this was a global variable that gets set
         //       at compile time, instead of requiring a host-interface call.
         // Check if we are in mainnet (leaves a boolean on the stack)
-        builder.call(generator.func_by_name("stdlib.is_in_mainnet"));
+        builder.call(generator.func_by_name("stdlib.is_in_mainnet")?);
 
         builder.if_else(
             InstrSeqType::new(
@@ -139,7 +143,7 @@ impl ComplexWord for Construct {
         //   contract_present, contract_offset, contract_length ]
 
         // Call the principal-construct function in the stdlib
-        builder.call(generator.func_by_name("stdlib.principal-construct"));
+        builder.call(generator.func_by_name("stdlib.principal-construct")?);
 
         Ok(())
     }
@@ -197,6 +201,10 @@ impl SimpleWord for Destruct {
         "principal-destruct?".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -229,7 +237,7 @@ impl SimpleWord for Destruct {
         );
 
         // Check if the version matches the network.
-        builder.call(generator.func_by_name("stdlib.is-version-valid"));
+        builder.call(generator.func_by_name("stdlib.is-version-valid")?);
 
         #[allow(clippy::unwrap_used)]
         let tuple_ty = TypeSignature::TupleType(
@@ -305,7 +313,7 @@ impl ComplexWord for PrincipalOf {
             .global_set(generator.stack_pointer);
 
         // Call the host interface function, `principal-of?`
-        builder.call(generator.func_by_name("stdlib.principal_of"));
+        builder.call(generator.func_by_name("stdlib.principal_of")?);
 
         Ok(())
     }
@@ -319,7 +327,7 @@ mod tests {
     };
     use clarity::vm::Value;
 
-    use crate::tools::{crosscheck, evaluate};
+    use crate::tools::{crosscheck, crosscheck_with_network, evaluate, Network};
 
     #[test]
     fn test_principal_of() {
@@ -362,6 +370,75 @@ mod tests {
             Ok(Some(Value::err_uint(1))),
         );
     }
+
+    #[test]
+    fn test_principal_of_invalid_prefix() {
+        crosscheck(
+            "(principal-of? 0x05adb8de4bfb65db2cfd6120d55c6526ae9c52e675db7e47308636534ba7786110)",
+            Ok(Some(Value::err_uint(1))),
+        );
+    }
+
+    #[test]
+    fn test_principal_of_argument() {
+        crosscheck(
+            "
+(define-read-only (address-of (key (buff 33)))
+    (unwrap-panic (principal-of? key)))
+(address-of 0x03adb8de4bfb65db2cfd6120d55c6526ae9c52e675db7e47308636534ba7786110)
+",
+            Ok(Some(
+                PrincipalData::parse("ST1AW6EKPGT61SQ9FNVDS17RKNWT8ZP582VF9HSCP")
+                    .unwrap()
+                    .into(),
+            )),
+        );
+    }
+
+    #[test]
+    fn test_principal_of_empty_key() {
+        crosscheck(
+            "
+(define-read-only (address-of (key (buff 33)))
+    (principal-of? key))
+(address-of 0x)
+",
+            Err(Error::Unchecked(
+                clarity::vm::errors::CheckErrors::TypeValueError(
+                    TypeSignature::SequenceType(SequenceSubtype::BufferType(
+                        BufferLength::try_from(33_u32).unwrap(),
+                    )),
+                    Value::Sequence(SequenceData::Buffer(BuffData { data: vec![] })),
+                ),
+            )),
+        );
+    }
+
+    //
+    // Module with tests that should only be executed
+    // when running Clarity::V1.
+    //
+    #[cfg(feature = "test-clarity-v1")]
+    mod clarity_v1 {
+        use super::*;
+
+        #[test]
+        fn test_principal_of_mainnet() {
+            // Clarity 1 always returns a testnet address (stacks-core#2619).
+            crosscheck_with_network(
+                Network::Mainnet,
+                "(principal-of? 0x03adb8de4bfb65db2cfd6120d55c6526ae9c52e675db7e47308636534ba7786110)",
+                Ok(Some(
+                    Value::okay(
+                        PrincipalData::parse("ST1AW6EKPGT61SQ9FNVDS17RKNWT8ZP582VF9HSCP")
+                            .unwrap()
+                            .into(),
+                    )
+                    .unwrap(),
+                )),
+            )
+        }
+    }
     #[test]
     fn principal_construct_less_than_two_args() {
         let result = evaluate("(principal-construct? 0x1a)");
@@ -415,6 +492,22 @@ mod tests {
 
         use super::*;
 
+        #[test]
+        fn test_principal_of_mainnet() {
+            crosscheck_with_network(
+                Network::Mainnet,
+                "(principal-of? 0x03adb8de4bfb65db2cfd6120d55c6526ae9c52e675db7e47308636534ba7786110)",
+                Ok(Some(
+                    Value::okay(
+                        PrincipalData::parse("SP1AW6EKPGT61SQ9FNVDS17RKNWT8ZP582RS0JSRJ")
+                            .unwrap()
+                            .into(),
+                    )
+                    .unwrap(),
+                )),
+            )
+        }
+
         #[test]
         fn test_is_standard() {
             crosscheck(
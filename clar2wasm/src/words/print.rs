@@ -1,8 +1,10 @@
-use clarity::vm::types::{ASCIIData, CharType};
+use clarity::vm::types::{ASCIIData, CharType, TypeSignature};
 use clarity::vm::{ClarityName, SymbolicExpression};
+use walrus::ValType;
 
 use super::ComplexWord;
 use crate::check_args;
+use crate::debug_print::LogLevel;
 use crate::wasm_generator::{ArgumentsExt, GeneratorError, WasmGenerator};
 use crate::wasm_utils::{check_argument_count, signature_from_string, ArgumentCountCheck};
 
@@ -37,23 +39,7 @@ impl ComplexWord for Print {
             .clone();
         let val_locals = generator.save_to_locals(builder, &ty, true);
 
-        let ty_for_serde = generator.type_for_serialization(&ty);
-        let serialized_ty = ty_for_serde.to_string();
-        // Ensure (at compile time) type can be reconstructed
-        signature_from_string(
-            &serialized_ty,
-            generator.contract_analysis.clarity_version,
-            generator.contract_analysis.epoch,
-        )
-        .map_err(|e| {
-            GeneratorError::TypeError(format!("serialized type cannot be deserialized: {e:?}"))
-        })?;
-        let serialized_ty = serialized_ty.bytes().collect();
-
-        let (serialized_ty_offset, serialized_ty_len) =
-            generator.add_clarity_string_literal(&CharType::ASCII(ASCIIData {
-                data: serialized_ty,
-            }))?;
+        let (serialized_ty_offset, serialized_ty_len) = serialized_type_literal(generator, &ty)?;
 
         // Push the value back onto the data stack
         for val_local in &val_locals {
@@ -72,7 +58,7 @@ impl ComplexWord for Print {
             .i32_const(serialized_ty_len as i32);
 
         // Call the host interface function, `print`
-        builder.call(generator.func_by_name("stdlib.print"));
+        builder.call(generator.func_by_name("stdlib.print")?);
 
         // Print always returns its input, so read the input value back from
         // the locals.
@@ -84,6 +70,79 @@ impl ComplexWord for Print {
     }
 }
 
+/// Adds the serialized type `ty` to the literal memory, so that the host can
+/// read back a value of this type.
+fn serialized_type_literal(
+    generator: &mut WasmGenerator,
+    ty: &TypeSignature,
+) -> Result<(u32, u32), GeneratorError> {
+    let ty_for_serde = generator.type_for_serialization(ty);
+    let serialized_ty = ty_for_serde.to_string();
+    // Ensure (at compile time) type can be reconstructed
+    signature_from_string(
+        &serialized_ty,
+        generator.clarity_version(),
+        generator.epoch(),
+    )
+    .map_err(|e| {
+        GeneratorError::TypeError(format!("serialized type cannot be deserialized: {e:?}"))
+    })?;
+    let serialized_ty = serialized_ty.bytes().collect();
+
+    generator.add_clarity_string_literal(&CharType::ASCII(ASCIIData {
+        data: serialized_ty,
+    }))
+}
+
+/// Traverses a `debug-print` expression compiled in developer mode, see
+/// [`crate::debug_print`]. Like `print`, it returns its input.
+pub(crate) fn traverse_debug_print(
+    generator: &mut WasmGenerator,
+    builder: &mut walrus::InstrSeqBuilder,
+    level: LogLevel,
+    args: &[SymbolicExpression],
+) -> Result<(), GeneratorError> {
+    check_args!(generator, builder, 1, args.len(), ArgumentCountCheck::Exact);
+
+    let value = args.get_expr(0)?;
+    generator.traverse_expr(builder, value)?;
+
+    let ty = generator
+        .get_expr_type(value)
+        .ok_or_else(|| {
+            GeneratorError::TypeError("debug-print value expression must be typed".to_owned())
+        })?
+        .clone();
+    let val_locals = generator.save_to_locals(builder, &ty, true);
+
+    let (serialized_ty_offset, serialized_ty_len) = serialized_type_literal(generator, &ty)?;
+
+    // Storing expr to memory to pass a reference to `debug_print`
+    for val_local in &val_locals {
+        builder.local_get(*val_local);
+    }
+    let (value_offset, value_length) = generator.create_call_stack_local(builder, &ty, false, true);
+    generator.write_to_memory(builder, value_offset, 0, &ty)?;
+
+    builder
+        .i32_const(level as i32)
+        .local_get(value_offset)
+        .i32_const(value_length)
+        .i32_const(serialized_ty_offset as i32)
+        .i32_const(serialized_ty_len as i32);
+
+    // Call the developer-mode host function, `debug_print`
+    let debug_print =
+        generator.import_function("", "debug_print", "debug_print", &[ValType::I32; 5], &[]);
+    builder.call(debug_print);
+
+    for val_local in val_locals {
+        builder.local_get(val_local);
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use clarity::types::StacksEpochId;
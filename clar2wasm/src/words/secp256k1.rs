@@ -38,15 +38,7 @@ impl ComplexWord for Recover {
         builder.local_get(result_local).i32_const(result_size);
 
         // Call the host interface function, `secp256k1_recover`
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.secp256k1_recover")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.secp256k1_recover not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.secp256k1_recover")?);
 
         generator.read_from_memory(builder, result_local, 0, &ret_ty)?;
 
@@ -76,15 +68,7 @@ impl ComplexWord for Verify {
         generator.traverse_expr(builder, args.get_expr(2)?)?;
 
         // Call the host interface function, `secp256k1_verify`
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.secp256k1_verify")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.secp256k1_verify not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.secp256k1_verify")?);
 
         Ok(())
     }
@@ -1,18 +1,18 @@
 use clarity::vm::clarity_wasm::get_type_size;
 use clarity::vm::types::{
-    FunctionType, ListTypeData, SequenceSubtype, StringSubtype, TypeSignature,
+    FunctionType, ListTypeData, SequenceData, SequenceSubtype, StringSubtype, TypeSignature,
 };
-use clarity::vm::{ClarityName, SymbolicExpression};
+use clarity::vm::{ClarityName, SymbolicExpression, SymbolicExpressionType, Value};
 use walrus::ir::{self, BinaryOp, IfElse, InstrSeqType, Loop, UnaryOp};
 use walrus::ValType;
 
 use crate::check_args;
 use crate::error_mapping::ErrorMap;
 use crate::wasm_generator::{
-    add_placeholder_for_clarity_type, clar2wasm_ty, drop_value, type_from_sequence_element,
-    ArgumentsExt, GeneratorError, SequenceElementType, WasmGenerator,
+    add_placeholder_for_clarity_type, clar2wasm_ty, drop_value, referenced_size,
+    type_from_sequence_element, ArgumentsExt, GeneratorError, SequenceElementType, WasmGenerator,
 };
-use crate::wasm_utils::{check_argument_count, ArgumentCountCheck};
+use crate::wasm_utils::{check_argument_count, contains_in_memory_type, ArgumentCountCheck};
 use crate::words::{self, ComplexWord};
 
 #[derive(Debug)]
@@ -47,6 +47,25 @@ impl ComplexWord for ListCons {
         // Allocate space on the data stack for the entire list
         let (offset, _size) = generator.create_call_stack_local(builder, &ty, false, true);
 
+        // A list of literals is laid out at compile time in the literal
+        // memory, and copied at once instead of being written element by
+        // element. The literal memory is only used once the whole list is
+        // known to be made of literals.
+        if !list.is_empty() && is_literal_list(list, elem_ty) {
+            let data = literal_list_data(generator, list, elem_ty)?;
+            let copy_memory = generator.copy_memory()?;
+            let (data_offset, data_len) = generator.add_bytes_literal(&data)?;
+            builder
+                .local_get(offset)
+                .i32_const(data_offset as i32)
+                .i32_const(data_len as i32);
+            copy_memory
+                .emit(builder)
+                .local_get(offset)
+                .i32_const(data_len as i32);
+            return Ok(());
+        }
+
         // Loop through the expressions in the list and store them onto the
         // data stack.
         let mut total_size = 0;
@@ -71,6 +90,111 @@ impl ComplexWord for ListCons {
     }
 }
 
+/// Returns the list of `items`, if it is a `list` expression.
+fn list_items(expr: &SymbolicExpression) -> Option<&[SymbolicExpression]> {
+    match expr.match_list()?.split_first() {
+        Some((name, items)) if name.match_atom().is_some_and(|n| n.as_str() == "list") => {
+            Some(items)
+        }
+        _ => None,
+    }
+}
+
+/// Checks if all the `items` of type `elem_ty` are literals which can be
+/// laid out in the literal memory.
+fn is_literal_list(items: &[SymbolicExpression], elem_ty: &TypeSignature) -> bool {
+    items.iter().all(|item| is_literal_element(item, elem_ty))
+}
+
+fn is_literal_element(expr: &SymbolicExpression, ty: &TypeSignature) -> bool {
+    match (ty, &expr.expr) {
+        (TypeSignature::IntType, SymbolicExpressionType::LiteralValue(Value::Int(_)))
+        | (TypeSignature::UIntType, SymbolicExpressionType::LiteralValue(Value::UInt(_)))
+        | (
+            TypeSignature::PrincipalType,
+            SymbolicExpressionType::LiteralValue(Value::Principal(_)),
+        )
+        | (
+            TypeSignature::SequenceType(SequenceSubtype::BufferType(_)),
+            SymbolicExpressionType::LiteralValue(Value::Sequence(SequenceData::Buffer(_))),
+        )
+        | (
+            TypeSignature::SequenceType(SequenceSubtype::StringType(_)),
+            SymbolicExpressionType::LiteralValue(Value::Sequence(SequenceData::String(_))),
+        ) => true,
+        (TypeSignature::BoolType, SymbolicExpressionType::Atom(name)) => {
+            matches!(name.as_str(), "true" | "false")
+        }
+        (TypeSignature::SequenceType(SequenceSubtype::ListType(list_type)), _) => list_items(expr)
+            .is_some_and(|items| is_literal_list(items, list_type.get_list_item_type())),
+        _ => false,
+    }
+}
+
+/// Returns the in-memory representation of the list of literals `items` of
+/// type `elem_ty`, as written by `write_to_memory`. The in-memory elements
+/// are added to the literal memory, so the list must have been checked with
+/// [`is_literal_list`] first.
+fn literal_list_data(
+    generator: &mut WasmGenerator,
+    items: &[SymbolicExpression],
+    elem_ty: &TypeSignature,
+) -> Result<Vec<u8>, GeneratorError> {
+    let mut data = Vec::new();
+    for item in items {
+        data.extend(literal_element_data(generator, item, elem_ty)?);
+    }
+    Ok(data)
+}
+
+fn literal_element_data(
+    generator: &mut WasmGenerator,
+    expr: &SymbolicExpression,
+    ty: &TypeSignature,
+) -> Result<Vec<u8>, GeneratorError> {
+    let in_memory = |(offset, len): (u32, u32)| {
+        let mut data = offset.to_le_bytes().to_vec();
+        data.extend_from_slice(&len.to_le_bytes());
+        data
+    };
+
+    match (ty, &expr.expr) {
+        (TypeSignature::IntType, SymbolicExpressionType::LiteralValue(Value::Int(i))) => {
+            Ok((*i as u128).to_le_bytes().to_vec())
+        }
+        (TypeSignature::UIntType, SymbolicExpressionType::LiteralValue(Value::UInt(u))) => {
+            Ok(u.to_le_bytes().to_vec())
+        }
+        (TypeSignature::BoolType, SymbolicExpressionType::Atom(name))
+            if matches!(name.as_str(), "true" | "false") =>
+        {
+            Ok(u32::from(name.as_str() == "true").to_le_bytes().to_vec())
+        }
+        (
+            TypeSignature::PrincipalType,
+            SymbolicExpressionType::LiteralValue(value @ Value::Principal(_)),
+        )
+        | (
+            TypeSignature::SequenceType(SequenceSubtype::BufferType(_)),
+            SymbolicExpressionType::LiteralValue(value @ Value::Sequence(SequenceData::Buffer(_))),
+        )
+        | (
+            TypeSignature::SequenceType(SequenceSubtype::StringType(_)),
+            SymbolicExpressionType::LiteralValue(value @ Value::Sequence(SequenceData::String(_))),
+        ) => Ok(in_memory(generator.add_literal(value)?)),
+        (TypeSignature::SequenceType(SequenceSubtype::ListType(list_type)), _) => {
+            let items = list_items(expr).ok_or_else(|| {
+                GeneratorError::InternalError("expected a literal list".to_owned())
+            })?;
+            let data = literal_list_data(generator, items, list_type.get_list_item_type())?;
+            Ok(in_memory(generator.add_bytes_literal(&data)?))
+        }
+        _ => Err(GeneratorError::InternalError(format!(
+            "expected a literal of type {ty}"
+        ))),
+    }
+}
+
 #[derive(Debug)]
 pub struct Fold;
 
@@ -179,6 +303,12 @@ impl ComplexWord for Fold {
         // to pop values from the top of the stack.
         let result_locals = generator.save_to_locals(&mut else_, &result_clar_ty, true);
 
+        // The start of the memory used by the iterations.
+        let loop_start = generator.module.locals.add(ValType::I32);
+        else_
+            .global_get(generator.stack_pointer)
+            .local_set(loop_start);
+
         // Define the body of a loop, to loop over the sequence and make the
         // function call.
         let mut loop_ = else_.dangling_instr_seq(None);
@@ -208,7 +338,10 @@ impl ComplexWord for Fold {
             loop_.local_get(*result_local);
         }
 
-        if let Some(simple) = words::lookup_simple(func).or(words::lookup_variadic_simple(func)) {
+        let version = generator.clarity_version();
+        if let Some(simple) =
+            words::lookup_simple(func, version).or(words::lookup_variadic_simple(func, version))
+        {
             // Call simple builtin
 
             let arg_a_ty = type_from_sequence_element(&elem_ty);
@@ -218,6 +351,13 @@ impl ComplexWord for Fold {
         } else {
             // Call user defined function
             generator.visit_call_user_defined(&mut loop_, &result_clar_ty, func)?;
+
+            // Only the last result is kept: the values in memory it refers to
+            // are moved to the start of the loop, so that the memory used by
+            // each iteration doesn't pile up.
+            if contains_in_memory_type(&result_clar_ty) {
+                generator.compact_value(&mut loop_, &result_clar_ty, loop_start)?;
+            }
         }
         // Save the result into the locals (in reverse order as we pop)
         for result_local in result_locals.iter().rev() {
@@ -301,7 +441,7 @@ impl ComplexWord for Append {
             }
         }
 
-        let memory = generator.get_memory()?;
+        let copy_memory = generator.copy_memory()?;
 
         // Allocate stack space for the new list.
         let (write_ptr, length) = generator.create_call_stack_local(builder, &ty, false, true);
@@ -321,7 +461,7 @@ impl ComplexWord for Append {
         // list. Save a copy of the length for later.
         let src_length = generator.module.locals.add(ValType::I32);
         builder.local_tee(src_length);
-        builder.memory_copy(memory, memory);
+        copy_memory.emit(builder);
 
         // Increment the write pointer by the length of the source list.
         builder
@@ -387,7 +527,7 @@ impl ComplexWord for AsMaxLen {
         // Get the length.
         generator
             .get_expr_type(seq)
-            .ok_or_else(|| GeneratorError::TypeError("append result must be typed".to_string()))
+            .ok_or_else(|| GeneratorError::TypeError("as-max-len? input must be typed".to_string()))
             .and_then(|ty| match ty {
                 TypeSignature::SequenceType(SequenceSubtype::ListType(list)) => {
                     // The length of the list in bytes is on the top of the stack. If we
@@ -465,7 +605,7 @@ impl ComplexWord for Concat {
     ) -> Result<(), GeneratorError> {
         check_args!(generator, builder, 2, args.len(), ArgumentCountCheck::Exact);
 
-        let memory = generator.get_memory()?;
+        let copy_memory = generator.copy_memory()?;
 
         // Create a new sequence to hold the result in the stack frame
         let ty = generator
@@ -487,7 +627,7 @@ impl ComplexWord for Concat {
         builder.local_tee(lhs_length);
 
         // Copy the lhs to the new sequence
-        builder.memory_copy(memory, memory);
+        copy_memory.emit(builder);
 
         // Load the adjusted destination offset
         builder
@@ -506,7 +646,7 @@ impl ComplexWord for Concat {
         builder.local_tee(rhs_length);
 
         // Copy the rhs to the new sequence
-        builder.memory_copy(memory, memory);
+        copy_memory.emit(builder);
 
         // Load the offset of the new sequence
         builder.local_get(offset);
@@ -599,6 +739,19 @@ impl ComplexWord for Map {
                 )));
             };
 
+        // A function only reading a map can fetch all the keys at once.
+        if generator.batch_map_reads && args.len() == 2 {
+            if let Some(map_name) = words::maps::map_lookup_function(generator, fname) {
+                return words::maps::traverse_map_get_batch(
+                    generator,
+                    builder,
+                    &map_name,
+                    args.get_expr(1)?,
+                    &ty,
+                );
+            }
+        }
+
         let return_element_size = get_type_size(return_element_type);
 
         let min_num_elements = generator.module.locals.add(ValType::I32);
@@ -701,11 +854,12 @@ impl ComplexWord for Map {
 
         // See if we're calling a simple function, and if it's variadic
 
-        let mut simple = words::lookup_simple(fname);
+        let version = generator.clarity_version();
+        let mut simple = words::lookup_simple(fname, version);
         let mut variadic = false;
 
         if simple.is_none() {
-            if let Some(simple_variadic) = words::lookup_variadic_simple(fname) {
+            if let Some(simple_variadic) = words::lookup_variadic_simple(fname, version) {
                 variadic = true;
                 simple = Some(simple_variadic)
             }
@@ -771,6 +925,13 @@ impl ComplexWord for Map {
         } else {
             // Call user defined function.
             generator.visit_call_user_defined(&mut loop_, return_element_type, fname)?;
+
+            // The values in memory returned for each element stay in the
+            // frame, the call only accounts for one of them.
+            if let TypeSignature::SequenceType(SequenceSubtype::ListType(list_type)) = &ty {
+                let others = list_type.get_max_len().saturating_sub(1) as i32;
+                generator.grow_frame(others.saturating_mul(referenced_size(return_element_type)));
+            }
         }
 
         // Write the result to the output sequence.
@@ -840,7 +1001,7 @@ impl ComplexWord for Len {
         // Get the length
         generator
             .get_expr_type(seq)
-            .ok_or_else(|| GeneratorError::TypeError("append result must be typed".to_string()))
+            .ok_or_else(|| GeneratorError::TypeError("len input must be typed".to_string()))
             .and_then(|ty| match ty {
                 TypeSignature::SequenceType(SequenceSubtype::ListType(list)) => {
                     // The length of the list in bytes is on the top of the stack. If we
@@ -885,6 +1046,9 @@ impl ComplexWord for Len {
     }
 }
 
+/// `element-at`, available in every Clarity version, and its alias
+/// `element-at?`, introduced in Clarity 2. Both return the element as an
+/// `optional`, `none` when the index is out of range.
 #[derive(Debug)]
 pub enum ElementAt {
     Original,
@@ -1107,10 +1271,10 @@ impl ComplexWord for ReplaceAt {
         // Traverse the list, leaving the offset and length on top of the stack.
         generator.traverse_expr(builder, seq)?;
 
-        let memory = generator.get_memory()?;
+        let copy_memory = generator.copy_memory()?;
 
         // Copy the input list to the new stack local
-        builder.memory_copy(memory, memory);
+        copy_memory.emit(builder);
 
         // Extend the sequence length to 64-bits.
         builder.i32_const(length).unop(UnaryOp::I64ExtendUI32);
@@ -1202,11 +1366,12 @@ impl ComplexWord for ReplaceAt {
             SequenceElementType::Byte | SequenceElementType::UnicodeScalar
         ) {
             let repl_len = generator.module.locals.add(ValType::I32);
+            let runtime_error = generator.func_by_name("stdlib.runtime-error")?;
             builder.local_tee(repl_len).unop(UnaryOp::I32Eqz).if_else(
                 None,
                 |then| {
                     then.i32_const(ErrorMap::BadTypeConstruction as i32)
-                        .call(generator.func_by_name("stdlib.runtime-error"));
+                        .call(runtime_error);
                 },
                 |_| {},
             );
@@ -1286,8 +1451,8 @@ impl ComplexWord for ReplaceAt {
                 else_
                     .local_get(offset_local)
                     .local_get(src_local)
-                    .i32_const(1)
-                    .memory_copy(memory, memory);
+                    .i32_const(1);
+                copy_memory.emit(&mut else_);
             }
             SequenceElementType::UnicodeScalar => {
                 // The element is a 32-bit unicode scalar value, so we
@@ -1303,8 +1468,8 @@ impl ComplexWord for ReplaceAt {
                 else_
                     .local_get(offset_local)
                     .local_get(src_local)
-                    .i32_const(4)
-                    .memory_copy(memory, memory);
+                    .i32_const(4);
+                copy_memory.emit(&mut else_);
             }
             SequenceElementType::Other(elem_ty) => {
                 generator.write_to_memory(&mut else_, offset_local, 0, elem_ty)?;
@@ -2217,12 +2382,250 @@ mod tests {
         crosscheck_compare_only(snippet);
     }
 
+    #[test]
+    fn len_buffer_and_ascii() {
+        crosscheck("(len 0x010203)", Ok(Some(Value::UInt(3))));
+        crosscheck(r#"(len "hello")"#, Ok(Some(Value::UInt(5))));
+        crosscheck(r#"(len "")"#, Ok(Some(Value::UInt(0))));
+    }
+
+    #[test]
+    fn len_utf8_counts_scalars() {
+        crosscheck(r#"(len u"a\u{1F600}\u{10348}")"#, Ok(Some(Value::UInt(3))));
+        crosscheck(r#"(len u"")"#, Ok(Some(Value::UInt(0))));
+    }
+
+    #[test]
+    fn as_max_len_at_declared_max_length() {
+        // For each kind of sequence, the length and which of the bounds from
+        // `u0` to `u4` the values fit in, with a full, a shorter and an empty
+        // value of a type with a max length of 3.
+        for (ty, full, short, empty) in [
+            ("(list 3 int)", "(list 1 2 3)", "(list 1 2)", "(list)"),
+            ("(buff 3)", "0x010203", "0x0102", "0x"),
+            ("(string-ascii 3)", r#""abc""#, r#""ab""#, r#""""#),
+            (
+                "(string-utf8 3)",
+                r#"u"a\u{1F600}\u{10348}""#,
+                r#"u"\u{1F600}\u{10348}""#,
+                r#"u"""#,
+            ),
+        ] {
+            let snippet = format!(
+                "
+(define-private (check (s {ty}))
+  (list
+    (len s)
+    (match (as-max-len? s u0) v u1 u0)
+    (match (as-max-len? s u1) v u1 u0)
+    (match (as-max-len? s u2) v u1 u0)
+    (match (as-max-len? s u3) v u1 u0)
+    (match (as-max-len? s u4) v u1 u0)
+    (if (is-eq (as-max-len? s u3) (some s)) u1 u0)))
+(list (check {full}) (check {short}) (check {empty}))"
+            );
+            crosscheck(
+                &snippet,
+                evaluate(
+                    "(list
+  (list u3 u0 u0 u0 u1 u1 u1)
+  (list u2 u0 u0 u1 u1 u1 u1)
+  (list u0 u1 u1 u1 u1 u1 u1))",
+                ),
+            );
+        }
+    }
+
+    #[test]
+    fn element_at_buffer() {
+        crosscheck("(element-at 0x010203 u2)", evaluate("(some 0x03)"));
+        crosscheck("(element-at 0x010203 u3)", Ok(Some(Value::none())));
+    }
+
+    #[test]
+    fn element_at_ascii() {
+        crosscheck(r#"(element-at "abc" u0)"#, evaluate(r#"(some "a")"#));
+        crosscheck(r#"(element-at "abc" u3)"#, Ok(Some(Value::none())));
+    }
+
+    #[test]
+    fn element_at_utf8_non_bmp() {
+        crosscheck(
+            r#"(element-at u"a\u{1F600}b" u1)"#,
+            evaluate(r#"(some u"\u{1F600}")"#),
+        );
+        crosscheck(
+            r#"(element-at u"a\u{1F600}b" u2)"#,
+            evaluate(r#"(some u"b")"#),
+        );
+        crosscheck(r#"(element-at u"a\u{1F600}b" u3)"#, Ok(Some(Value::none())));
+    }
+
     #[test]
     fn test_large_list() {
         let n = 50000 / 2 + 1;
         crosscheck_compare_only(&format!("(list {})", "9922 ".repeat(n)));
     }
 
+    #[test]
+    fn literal_list_of_ints() {
+        crosscheck(
+            "(list 1 -2 170141183460469231731687303715884105727)",
+            Ok(Some(
+                Value::cons_list_unsanitized(vec![
+                    Value::Int(1),
+                    Value::Int(-2),
+                    Value::Int(i128::MAX),
+                ])
+                .unwrap(),
+            )),
+        );
+    }
+
+    #[test]
+    fn literal_list_of_uints_and_bools() {
+        crosscheck_compare_only("(list u1 u340282366920938463463374607431768211455)");
+        crosscheck_compare_only("(list true false true)");
+    }
+
+    #[test]
+    fn literal_list_of_in_memory_elements() {
+        crosscheck(
+            r#"(list "abc" "" "de")"#,
+            Ok(Some(
+                Value::cons_list_unsanitized(vec![
+                    Value::string_ascii_from_bytes(b"abc".to_vec()).unwrap(),
+                    Value::string_ascii_from_bytes(vec![]).unwrap(),
+                    Value::string_ascii_from_bytes(b"de".to_vec()).unwrap(),
+                ])
+                .unwrap(),
+            )),
+        );
+        crosscheck_compare_only(r#"(list u"a\u{1F600}" u"bc")"#);
+        crosscheck_compare_only("(list 0x0102 0x 0x03)");
+        crosscheck_compare_only(
+            "(list 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.foo)",
+        );
+    }
+
+    #[test]
+    fn literal_list_of_lists() {
+        crosscheck(
+            "(list (list 1 2) (list) (list 3))",
+            Ok(Some(
+                Value::cons_list_unsanitized(vec![
+                    Value::cons_list_unsanitized(vec![Value::Int(1), Value::Int(2)]).unwrap(),
+                    Value::cons_list_unsanitized(vec![]).unwrap(),
+                    Value::cons_list_unsanitized(vec![Value::Int(3)]).unwrap(),
+                ])
+                .unwrap(),
+            )),
+        );
+        crosscheck_compare_only(r#"(list (list "a" "bc") (list "def"))"#);
+        crosscheck_compare_only("(list (list (list 0x01) (list 0x02 0x03)) (list (list)))");
+    }
+
+    #[test]
+    fn literal_list_used_as_value() {
+        crosscheck(
+            "(let ((l (list 1 2 3))) (+ (fold + l 0) (len (append l 4))))",
+            Ok(Some(Value::Int(10))),
+        );
+        crosscheck(
+            "(map + (list 1 2) (list 10 20))",
+            Ok(Some(
+                Value::cons_list_unsanitized(vec![Value::Int(11), Value::Int(22)]).unwrap(),
+            )),
+        );
+    }
+
+    #[test]
+    fn mixed_list_is_not_literal() {
+        crosscheck(
+            "(let ((a 2)) (list 1 a (+ a 1)))",
+            Ok(Some(
+                Value::cons_list_unsanitized(vec![Value::Int(1), Value::Int(2), Value::Int(3)])
+                    .unwrap(),
+            )),
+        );
+        crosscheck(
+            r#"(list (list "a" "bc") (list (concat "d" "e")))"#,
+            Ok(Some(
+                Value::cons_list_unsanitized(vec![
+                    Value::cons_list_unsanitized(vec![
+                        Value::string_ascii_from_bytes(b"a".to_vec()).unwrap(),
+                        Value::string_ascii_from_bytes(b"bc".to_vec()).unwrap(),
+                    ])
+                    .unwrap(),
+                    Value::cons_list_unsanitized(vec![Value::string_ascii_from_bytes(
+                        b"de".to_vec(),
+                    )
+                    .unwrap()])
+                    .unwrap(),
+                ])
+                .unwrap(),
+            )),
+        );
+    }
+
+    //
+    // Module with tests that should only be executed
+    // when running Clarity::V1.
+    //
+    #[cfg(feature = "test-clarity-v1")]
+    mod clarity_v1 {
+        use clarity::types::StacksEpochId;
+        use clarity::vm::errors::{CheckErrors, Error};
+
+        use super::*;
+        use crate::tools::{crosscheck_compare_only_with_expected_error, crosscheck_with_epoch};
+
+        #[test]
+        fn slice_is_not_a_builtin() {
+            // `slice?` was introduced in Clarity 2, before that it is a valid
+            // name for a user-defined function.
+            crosscheck_with_epoch(
+                "(define-private (slice? (a int)) (+ a 1)) (slice? 1)",
+                Ok(Some(Value::Int(2))),
+                StacksEpochId::Epoch20,
+            );
+        }
+
+        #[test]
+        fn element_at_in_clarity1() {
+            crosscheck_with_epoch(
+                "(element-at (list 1 2 3) u1)",
+                Ok(Some(Value::some(Value::Int(2)).unwrap())),
+                StacksEpochId::Epoch20,
+            );
+            crosscheck_with_epoch(
+                "(element-at (list 1 2 3) u3)",
+                Ok(Some(Value::none())),
+                StacksEpochId::Epoch20,
+            );
+            crosscheck_with_epoch(
+                r#"(element-at "abc" u2)"#,
+                evaluate(r#"(some "c")"#),
+                StacksEpochId::Epoch20,
+            );
+        }
+
+        #[test]
+        fn element_at_alias_is_not_a_builtin() {
+            // `element-at?` is an unknown function in Clarity 1...
+            crosscheck_compare_only_with_expected_error(
+                "(element-at? (list 1 2 3) u1)",
+                |e| matches!(e, Error::Unchecked(CheckErrors::UnknownFunction(name)) if name == "element-at?"),
+            );
+            // ...and can be defined by a contract.
+            crosscheck_with_epoch(
+                "(define-private (element-at? (l (list 3 int)) (i uint)) (len l)) (element-at? (list 1 2 3) u1)",
+                Ok(Some(Value::UInt(3))),
+                StacksEpochId::Epoch20,
+            );
+        }
+    }
+
     //
     // Module with tests that should only be executed
     // when running Clarity::V2 or Clarity::v3.
@@ -2371,5 +2774,21 @@ mod tests {
                 )),
             )
         }
+
+        #[test]
+        fn element_at_and_alias_agree() {
+            for (seq, index) in [
+                ("(list 1 2 3)", "u0"),
+                ("(list 1 2 3)", "u3"),
+                ("(list (tuple (a 1) (b u2)) (tuple (a 3) (b u4)))", "u1"),
+                ("0x010203", "u2"),
+                (r#""abc""#, "u4"),
+                (r#"u"a\u{1F600}b""#, "u1"),
+            ] {
+                let original = evaluate(&format!("(element-at {seq} {index})"));
+                assert!(matches!(original, Ok(Some(Value::Optional(_)))));
+                crosscheck(&format!("(element-at? {seq} {index})"), original);
+            }
+        }
     }
 }
@@ -14,6 +14,10 @@ impl SimpleWord for StxBurn {
         "stx-burn?".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (2, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -23,7 +27,7 @@ impl SimpleWord for StxBurn {
     ) -> Result<(), GeneratorError> {
         // Amount and sender are on the stack, so just call the host interface
         // function, `stx_burn`
-        builder.call(generator.func_by_name("stdlib.stx_burn"));
+        builder.call(generator.func_by_name("stdlib.stx_burn")?);
 
         Ok(())
     }
@@ -37,6 +41,10 @@ impl SimpleWord for StxGetBalance {
         "stx-get-balance".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -44,7 +52,7 @@ impl SimpleWord for StxGetBalance {
         _arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        builder.call(generator.func_by_name("stdlib.stx_get_balance"));
+        builder.call(generator.func_by_name("stdlib.stx_get_balance")?);
         Ok(())
     }
 }
@@ -76,7 +84,7 @@ impl ComplexWord for StxTransfer {
 
         // placeholder for memo
         builder.i32_const(0).i32_const(0);
-        builder.call(generator.func_by_name("stdlib.stx_transfer"));
+        builder.call(generator.func_by_name("stdlib.stx_transfer")?);
         Ok(())
     }
 }
@@ -108,7 +116,7 @@ impl ComplexWord for StxTransferMemo {
         generator.traverse_expr(builder, recipient)?;
         generator.traverse_expr(builder, memo)?;
 
-        builder.call(generator.func_by_name("stdlib.stx_transfer"));
+        builder.call(generator.func_by_name("stdlib.stx_transfer")?);
         Ok(())
     }
 }
@@ -121,6 +129,10 @@ impl SimpleWord for StxGetAccount {
         "stx-account".into()
     }
 
+    fn arity(&self) -> (usize, ArgumentCountCheck) {
+        (1, ArgumentCountCheck::Exact)
+    }
+
     fn visit(
         &self,
         generator: &mut WasmGenerator,
@@ -128,14 +140,30 @@ impl SimpleWord for StxGetAccount {
         _arg_types: &[TypeSignature],
         _return_type: &TypeSignature,
     ) -> Result<(), GeneratorError> {
-        builder.call(generator.func_by_name("stdlib.stx_account"));
+        builder.call(generator.func_by_name("stdlib.stx_account")?);
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tools::{crosscheck, evaluate};
+    use clarity::types::StacksEpochId;
+    use clarity::vm::events::{STXEventType, StacksTransactionEvent};
+    use clarity::vm::ClarityVersion;
+
+    use crate::tools::{crosscheck, evaluate, TestEnvironment};
+
+    /// Returns the memo of the single STX transfer event emitted by `snippet`.
+    fn transfer_event_memo(snippet: &str) -> Vec<u8> {
+        let mut env = TestEnvironment::new(StacksEpochId::latest(), ClarityVersion::latest());
+        env.evaluate(snippet).expect("failed to evaluate snippet");
+        match &env.get_events().last().expect("no event batch").events[..] {
+            [StacksTransactionEvent::STXEvent(STXEventType::STXTransferEvent(event))] => {
+                event.memo.data.clone()
+            }
+            events => panic!("expected a single STX transfer event, got {events:?}"),
+        }
+    }
 
     #[test]
     fn stx_transfer_less_than_three_args() {
@@ -190,6 +218,26 @@ mod tests {
         )
     }
 
+    #[test]
+    fn stx_get_balance_contract_principal() {
+        crosscheck(
+            "
+(define-public (fund (amount uint))
+  (stx-transfer? amount tx-sender (as-contract tx-sender)))
+
+(define-read-only (own-balance)
+  (as-contract (stx-get-balance tx-sender)))
+
+(list
+  (own-balance)
+  (begin (unwrap-panic (fund u100)) (own-balance))
+  (stx-get-balance .snippet)
+  (stx-get-balance .other))
+",
+            evaluate("(list u0 u100 u100 u0)"),
+        )
+    }
+
     #[test]
     fn stx_test_burn_ok() {
         crosscheck(
@@ -234,6 +282,13 @@ mod tests {
         )
     }
 
+    #[test]
+    fn stx_transfer_event_has_empty_memo() {
+        let snippet = "(stx-transfer? u100 'S1G2081040G2081040G2081040G208105NK8PE5 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)";
+        crosscheck(snippet, evaluate("(ok true)"));
+        assert!(transfer_event_memo(snippet).is_empty());
+    }
+
     #[test]
     fn stx_transfer_err_1() {
         // not enough balance
@@ -267,6 +322,29 @@ mod tests {
         )
     }
 
+    //
+    // Module with tests that should only be executed
+    // when running Clarity::V1.
+    //
+    #[cfg(feature = "test-clarity-v1")]
+    mod clarity_v1 {
+        use clarity::types::StacksEpochId;
+        use clarity::vm::Value;
+
+        use crate::tools::crosscheck_with_epoch;
+
+        #[test]
+        fn stx_account_is_not_a_builtin() {
+            // `stx-account` was introduced in Clarity 2, before that it is a
+            // valid name for a user-defined function.
+            crosscheck_with_epoch(
+                "(define-private (stx-account (who principal)) u42) (stx-account tx-sender)",
+                Ok(Some(Value::UInt(42))),
+                StacksEpochId::Epoch20,
+            );
+        }
+    }
+
     //
     // Module with tests that should only be executed
     // when running Clarity::V2 or Clarity::v3.
@@ -301,6 +379,34 @@ mod tests {
             )
         }
 
+        #[test]
+        fn stx_account_contract_principal() {
+            crosscheck(
+                "
+(define-public (fund (amount uint))
+  (stx-transfer? amount tx-sender (as-contract tx-sender)))
+
+(define-read-only (own-account)
+  (as-contract (stx-account tx-sender)))
+
+(list
+  (own-account)
+  (begin (unwrap-panic (fund u100)) (own-account))
+  (stx-account .snippet)
+  (stx-account .other))
+",
+                evaluate(
+                    "
+(list
+  {locked: u0, unlock-height: u0, unlocked: u0}
+  {locked: u0, unlock-height: u0, unlocked: u100}
+  {locked: u0, unlock-height: u0, unlocked: u100}
+  {locked: u0, unlock-height: u0, unlocked: u0})
+",
+                ),
+            )
+        }
+
         #[test]
         fn stx_transfer_memo_ok() {
             //
@@ -309,5 +415,42 @@ mod tests {
                 evaluate("(ok true)"),
             )
         }
+
+        #[test]
+        fn stx_transfer_memo_event() {
+            let snippet = "(stx-transfer-memo? u100 'S1G2081040G2081040G2081040G208105NK8PE5 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM 0x12345678)";
+            crosscheck(snippet, evaluate("(ok true)"));
+            assert_eq!(transfer_event_memo(snippet), vec![0x12, 0x34, 0x56, 0x78]);
+        }
+
+        #[test]
+        fn stx_transfer_memo_empty_event() {
+            let snippet = "(stx-transfer-memo? u100 'S1G2081040G2081040G2081040G208105NK8PE5 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM 0x)";
+            crosscheck(snippet, evaluate("(ok true)"));
+            assert!(transfer_event_memo(snippet).is_empty());
+        }
+
+        #[test]
+        fn stx_transfer_memo_max_length_event() {
+            let memo = (0..34).collect::<Vec<u8>>();
+            let snippet = format!(
+                "(stx-transfer-memo? u100 'S1G2081040G2081040G2081040G208105NK8PE5 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM 0x{})",
+                hex::encode(&memo)
+            );
+            crosscheck(&snippet, evaluate("(ok true)"));
+            assert_eq!(transfer_event_memo(&snippet), memo);
+        }
+
+        #[test]
+        fn stx_transfer_memo_computed_event() {
+            let snippet = "
+(define-public (transfer (memo (buff 34)))
+  (stx-transfer-memo? u100 tx-sender 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM (concat memo 0xff)))
+
+(transfer 0x0102)
+";
+            crosscheck(snippet, evaluate("(ok true)"));
+            assert_eq!(transfer_event_memo(snippet), vec![0x01, 0x02, 0xff]);
+        }
     }
 }
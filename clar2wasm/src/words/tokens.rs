@@ -37,13 +37,7 @@ impl ComplexWord for DefineFungibleToken {
         );
 
         let name = args.get_name(0)?;
-        // Making sure if name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         let supply = args.get(1);
 
@@ -64,7 +58,7 @@ impl ComplexWord for DefineFungibleToken {
             builder.i32_const(0).i64_const(0).i64_const(0);
         }
 
-        builder.call(generator.func_by_name("stdlib.define_ft"));
+        builder.call(generator.func_by_name("stdlib.define_ft")?);
         Ok(())
     }
 }
@@ -101,7 +95,7 @@ impl ComplexWord for BurnFungibleToken {
         generator.traverse_expr(builder, sender)?;
 
         // Call the host interface function `ft_burn`
-        builder.call(generator.func_by_name("stdlib.ft_burn"));
+        builder.call(generator.func_by_name("stdlib.ft_burn")?);
 
         Ok(())
     }
@@ -141,7 +135,7 @@ impl ComplexWord for TransferFungibleToken {
         generator.traverse_expr(builder, recipient)?;
 
         // Call the host interface function `ft_transfer`
-        builder.call(generator.func_by_name("stdlib.ft_transfer"));
+        builder.call(generator.func_by_name("stdlib.ft_transfer")?);
 
         Ok(())
     }
@@ -178,7 +172,7 @@ impl ComplexWord for MintFungibleToken {
         generator.traverse_expr(builder, recipient)?;
 
         // Call the host interface function `ft_mint`
-        builder.call(generator.func_by_name("stdlib.ft_mint"));
+        builder.call(generator.func_by_name("stdlib.ft_mint")?);
 
         Ok(())
     }
@@ -208,7 +202,7 @@ impl ComplexWord for GetSupplyOfFungibleToken {
             .i32_const(id_offset as i32)
             .i32_const(id_length as i32);
 
-        builder.call(generator.func_by_name("stdlib.ft_get_supply"));
+        builder.call(generator.func_by_name("stdlib.ft_get_supply")?);
 
         Ok(())
     }
@@ -244,7 +238,7 @@ impl ComplexWord for GetBalanceOfFungibleToken {
         generator.traverse_expr(builder, owner)?;
 
         // Call the host interface function `ft_get_balance`
-        builder.call(generator.func_by_name("stdlib.ft_get_balance"));
+        builder.call(generator.func_by_name("stdlib.ft_get_balance")?);
 
         Ok(())
     }
@@ -270,23 +264,14 @@ impl ComplexWord for DefineNonFungibleToken {
         check_args!(generator, builder, 2, args.len(), ArgumentCountCheck::Exact);
 
         let name = args.get_name(0)?;
-        // Making sure if name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         // we will save the NFT type for reuse with the nft-x functions
         // (a wrong NFT type is an issue only with Clarity1, but it doesn't
         // hurt to use it with all Clarity versions)
-        let nft_type = TypeSignature::parse_type_repr(
-            generator.contract_analysis.epoch,
-            args.get_expr(1)?,
-            &mut (),
-        )
-        .map_err(|e| GeneratorError::TypeError(e.to_string()))?;
+        let nft_type =
+            TypeSignature::parse_type_repr(generator.epoch(), args.get_expr(1)?, &mut ())
+                .map_err(|e| GeneratorError::TypeError(e.to_string()))?;
         generator.nft_types.insert(name.clone(), nft_type);
 
         // Store the identifier as a string literal in the memory
@@ -297,15 +282,7 @@ impl ComplexWord for DefineNonFungibleToken {
             .i32_const(name_offset as i32)
             .i32_const(name_length as i32);
 
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.define_nft")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.define_nft not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.define_nft")?);
         Ok(())
     }
 }
@@ -358,7 +335,7 @@ impl ComplexWord for BurnNonFungibleToken {
         generator.traverse_expr(builder, sender)?;
 
         // Call the host interface function `nft_burn`
-        builder.call(generator.func_by_name("stdlib.nft_burn"));
+        builder.call(generator.func_by_name("stdlib.nft_burn")?);
 
         Ok(())
     }
@@ -416,7 +393,7 @@ impl ComplexWord for TransferNonFungibleToken {
         generator.traverse_expr(builder, recipient)?;
 
         // Call the host interface function `nft_transfer`
-        builder.call(generator.func_by_name("stdlib.nft_transfer"));
+        builder.call(generator.func_by_name("stdlib.nft_transfer")?);
 
         Ok(())
     }
@@ -470,7 +447,7 @@ impl ComplexWord for MintNonFungibleToken {
         generator.traverse_expr(builder, recipient)?;
 
         // Call the host interface function `nft_mint`
-        builder.call(generator.func_by_name("stdlib.nft_mint"));
+        builder.call(generator.func_by_name("stdlib.nft_mint")?);
 
         Ok(())
     }
@@ -529,7 +506,7 @@ impl ComplexWord for GetOwnerOfNonFungibleToken {
         builder.local_get(return_offset).i32_const(return_size);
 
         // Call the host interface function `nft_get_owner`
-        builder.call(generator.func_by_name("stdlib.nft_get_owner"));
+        builder.call(generator.func_by_name("stdlib.nft_get_owner")?);
 
         Ok(())
     }
@@ -537,10 +514,11 @@ impl ComplexWord for GetOwnerOfNonFungibleToken {
 
 #[cfg(test)]
 mod tests {
+    use clarity::vm::errors::{Error, RuntimeErrorType};
     use clarity::vm::types::{PrincipalData, TupleData};
     use clarity::vm::Value;
 
-    use crate::tools::{crosscheck, crosscheck_expect_failure, evaluate};
+    use crate::tools::{crosscheck, crosscheck_compare_only, crosscheck_expect_failure, evaluate};
 
     //
     // Module with tests that should only be executed
@@ -799,12 +777,19 @@ mod tests {
     }
     #[test]
     fn bar_mint_too_many() {
-        crosscheck_expect_failure("(ft-mint? bar u1000001 tx-sender)");
+        crosscheck(
+            "(define-fungible-token bar u1000000) (ft-mint? bar u1000001 tx-sender)",
+            Err(Error::Runtime(
+                RuntimeErrorType::SupplyExceeded(1000001, 1000000),
+                Some(Vec::new()),
+            )),
+        );
     }
 
     #[test]
     fn bar_mint_too_many_2() {
         const ERR: &str = r#"
+          (define-fungible-token bar u10000000)
           (define-public (bar-mint-too-many-2)
             (begin
               (unwrap-panic (ft-mint? bar u5555555 tx-sender))
@@ -812,7 +797,43 @@ mod tests {
           (bar-mint-too-many-2)
         "#;
 
-        crosscheck_expect_failure(ERR);
+        crosscheck(
+            ERR,
+            Err(Error::Runtime(
+                RuntimeErrorType::SupplyExceeded(11111110, 10000000),
+                Some(Vec::new()),
+            )),
+        );
+    }
+
+    #[test]
+    fn bar_mint_up_to_supply() {
+        // The whole supply can be minted, but not a single token more.
+        crosscheck(
+            r#"
+            (define-fungible-token bar u1000000)
+            (unwrap-panic (ft-mint? bar u999999 tx-sender))
+            (unwrap-panic (ft-mint? bar u1 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM))
+            (ft-get-supply bar)
+            "#,
+            Ok(Some(Value::UInt(1000000))),
+        );
+        crosscheck(
+            r#"
+            (define-fungible-token bar u1000000)
+            (unwrap-panic (ft-mint? bar u1000000 tx-sender))
+            (ft-mint? bar u1 tx-sender)
+            "#,
+            Err(Error::Runtime(
+                RuntimeErrorType::SupplyExceeded(1000001, 1000000),
+                Some(Vec::new()),
+            )),
+        );
+    }
+
+    #[test]
+    fn mint_undefined_token() {
+        crosscheck_expect_failure("(ft-mint? bar u1000001 tx-sender)");
     }
 
     #[test]
@@ -872,4 +893,94 @@ mod tests {
 
         crosscheck(snippet, Ok(Some(expected)));
     }
+
+    /// A buffer literal of `len` bytes, all `0xab` but the last one, `last`.
+    fn full_buffer(len: usize, last: u8) -> String {
+        format!("0x{}{last:02x}", "ab".repeat(len - 1))
+    }
+
+    #[test]
+    fn nft_identifiers_differing_in_their_last_byte() {
+        // The whole identifier is written for the host, the two tokens are
+        // distinct.
+        let (first, second) = (full_buffer(4096, 0), full_buffer(4096, 1));
+        let snippet = format!(
+            r#"
+            (define-non-fungible-token big (buff 4096))
+            {{
+                a: (nft-mint? big {first} tx-sender),
+                b: (nft-mint? big {second} 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM),
+                c: (nft-get-owner? big {second}),
+            }}
+        "#
+        );
+
+        let expected = Value::from(
+            TupleData::from_data(vec![
+                ("a".into(), Value::okay_true()),
+                ("b".into(), Value::okay_true()),
+                (
+                    "c".into(),
+                    Value::some(Value::Principal(
+                        PrincipalData::parse_standard_principal(
+                            "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM",
+                        )
+                        .unwrap()
+                        .into(),
+                    ))
+                    .unwrap(),
+                ),
+            ])
+            .unwrap(),
+        );
+
+        crosscheck(&snippet, Ok(Some(expected)));
+    }
+
+    #[test]
+    fn nft_large_tuple_identifier_round_trip() {
+        let identifier = format!(
+            r#"{{ id: u1, data: {}, tags: (list {}), name: "{}" }}"#,
+            full_buffer(4096, 0xff),
+            "-170141183460469231731687303715884105728 ".repeat(100),
+            "z".repeat(1024),
+        );
+        let snippet = format!(
+            r#"
+            (define-non-fungible-token big {{ id: uint, data: (buff 4096), tags: (list 100 int), name: (string-ascii 1024) }})
+            {{
+                a-mint: (nft-mint? big {identifier} tx-sender),
+                b-owner: (nft-get-owner? big {identifier}),
+                c-transfer: (nft-transfer? big {identifier} tx-sender 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM),
+                d-owner: (nft-get-owner? big {identifier}),
+                e-burn: (nft-burn? big {identifier} 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM),
+                f-owner: (nft-get-owner? big {identifier}),
+            }}
+        "#
+        );
+
+        crosscheck_compare_only(&snippet);
+    }
+
+    #[test]
+    fn nft_list_of_tuples_identifier() {
+        // The identifier is shorter than its declared type, and contains
+        // in-memory values at every level.
+        let element = format!(r#"{{ key: {}, memo: (some "memo") }}"#, full_buffer(32, 7));
+        let full = format!("(list {})", format!("{element} ").repeat(50));
+        let short = format!("(list {element})");
+        let snippet = format!(
+            r#"
+            (define-non-fungible-token keys (list 50 {{ key: (buff 32), memo: (optional (string-ascii 8)) }}))
+            {{
+                a-full: (nft-mint? keys {full} tx-sender),
+                b-short: (nft-mint? keys {short} 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM),
+                c-full-owner: (nft-get-owner? keys {full}),
+                d-short-owner: (nft-get-owner? keys {short}),
+            }}
+        "#
+        );
+
+        crosscheck_compare_only(&snippet);
+    }
 }
@@ -23,13 +23,7 @@ impl ComplexWord for DefineTrait {
         check_argument_count(generator, builder, 2, args.len(), ArgumentCountCheck::Exact)?;
 
         let name = args.get_name(0)?;
-        // Making sure if name is not reserved
-        if generator.is_reserved_name(name) {
-            return Err(GeneratorError::InternalError(format!(
-                "Name already used {:?}",
-                name
-            )));
-        }
+        generator.check_reserved_name(name)?;
 
         // Store the identifier as a string literal in the memory
         let (name_offset, name_length) = generator.add_string_literal(name)?;
@@ -39,15 +33,7 @@ impl ComplexWord for DefineTrait {
             .i32_const(name_offset as i32)
             .i32_const(name_length as i32);
 
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.define_trait")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.define_trait not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.define_trait")?);
         Ok(())
     }
 }
@@ -121,15 +107,7 @@ impl ComplexWord for ImplTrait {
             .i32_const(trait_offset as i32)
             .i32_const(trait_length as i32);
 
-        builder.call(
-            generator
-                .module
-                .funcs
-                .by_name("stdlib.impl_trait")
-                .ok_or_else(|| {
-                    GeneratorError::InternalError("stdlib.impl_trait not found".to_owned())
-                })?,
-        );
+        builder.call(generator.func_by_name("stdlib.impl_trait")?);
         Ok(())
     }
 }
@@ -43,3 +43,82 @@ fn test_clar2wasm_with_input() {
 
     temp.close().unwrap();
 }
+
+#[test]
+fn test_clar2wasm_deterministic_across_processes() {
+    let temp = assert_fs::TempDir::new().unwrap();
+
+    for name in ["define-map", "fold", "tuple", "call-private-with-args"] {
+        let input = format!("./tests/contracts/{name}.clar");
+        // Each compilation runs in its own process, with its own random
+        // hasher seeds and allocations.
+        let outputs: Vec<Vec<u8>> = (0..2)
+            .map(|i| {
+                let outfile = temp.join(format!("{name}-{i}.wasm"));
+                assert_cmd::Command::cargo_bin("clar2wasm")
+                    .unwrap()
+                    .arg(&input)
+                    .arg("--deterministic")
+                    .arg("-o")
+                    .arg(&outfile)
+                    .assert()
+                    .success();
+                std::fs::read(outfile).unwrap()
+            })
+            .collect();
+        assert!(outputs[0] == outputs[1], "{name} compiled differently");
+    }
+
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_clar2wasm_report() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    let input = temp.join("counter.clar");
+    std::fs::write(
+        &input,
+        "(define-data-var count uint u0)
+         (define-public (increment) (ok (var-set count (+ (var-get count) u1))))",
+    )
+    .unwrap();
+    let report = temp.join("counter.json");
+
+    assert_cmd::Command::cargo_bin("clar2wasm")
+        .unwrap()
+        .arg(&input)
+        .arg("--report")
+        .arg(&report)
+        .assert()
+        .success();
+
+    let report = std::fs::read_to_string(report).unwrap();
+    assert!(report.contains("\"abi_version\""), "{report}");
+    assert!(report.contains("\"increment\""), "{report}");
+    assert!(temp.join("counter.wasm").exists());
+
+    temp.close().unwrap();
+}
+
+#[test]
+fn test_corpus_report() {
+    let temp = assert_fs::TempDir::new().unwrap();
+    std::fs::write(temp.join("ok.clar"), "(define-read-only (foo) (+ 1 2))").unwrap();
+    std::fs::write(
+        temp.join("unknown.clar"),
+        "(define-read-only (foo) (bar 1))",
+    )
+    .unwrap();
+
+    assert_cmd::Command::cargo_bin("corpus")
+        .unwrap()
+        .arg(temp.path())
+        .assert()
+        .stdout(predicates::str::contains(
+            "2 contracts, 1 compiled, 1 failed",
+        ))
+        .stdout(predicates::str::contains("unknown.clar"))
+        .success();
+
+    temp.close().unwrap();
+}
@@ -47,3 +47,98 @@ test_multi_contract_call_response!(
         assert_eq!(*response.data, Value::UInt(0));
     }
 );
+
+//
+// Conformance suite: the boot contracts are compiled and deployed, and a
+// selection of their read-only functions is called from another contract,
+// in both the compiled and the interpreted environments.
+//
+
+mod conformance {
+    use clar2wasm::tools::TestEnvironment;
+
+    fn boot_contract(name: &str) -> String {
+        std::fs::read_to_string(format!(
+            "{}/tests/contracts/boot-contracts/{name}.clar",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .unwrap_or_else(|_| panic!("Can't find {name} contract"))
+    }
+
+    /// Deploys the boot contract `name`, then runs each of `calls` in its own
+    /// contract, and checks that the compiled and interpreted results match.
+    fn crosscheck_boot_contract(name: &str, calls: &[&str]) {
+        let source = boot_contract(name);
+
+        let mut compiled_env = TestEnvironment::default();
+        let mut interpreted_env = TestEnvironment::default();
+
+        let compiled = compiled_env.init_contract_with_snippet(name, &source);
+        let interpreted = interpreted_env.interpret_contract_with_snippet(name, &source);
+        assert!(compiled.is_ok(), "Failed to deploy {name}: {compiled:?}");
+        assert_eq!(compiled, interpreted, "Deployment of {name} diverges");
+
+        for (i, call) in calls.iter().enumerate() {
+            let caller = format!("{name}-caller-{i}");
+            let compiled = compiled_env.init_contract_with_snippet(&caller, call);
+            let interpreted = interpreted_env.interpret_contract_with_snippet(&caller, call);
+            assert!(compiled.is_ok(), "{call} failed: {compiled:?}");
+            assert_eq!(
+                compiled, interpreted,
+                "Compiled and interpreted results diverge! {call}"
+            );
+        }
+    }
+
+    #[test]
+    fn pox_4() {
+        crosscheck_boot_contract(
+            "pox-4",
+            &[
+                "(contract-call? .pox-4 get-pox-info)",
+                "(contract-call? .pox-4 current-pox-reward-cycle)",
+                "(contract-call? .pox-4 burn-height-to-reward-cycle u4200)",
+                "(contract-call? .pox-4 reward-cycle-to-burn-height u3)",
+                "(contract-call? .pox-4 get-stacking-minimum)",
+                "(contract-call? .pox-4 check-pox-addr-version 0x06)",
+                "(contract-call? .pox-4 check-pox-addr-hashbytes 0x05 0x0102030405060708090a0b0c0d0e0f1011121314)",
+                "(contract-call? .pox-4 check-pox-lock-period u13)",
+                "(contract-call? .pox-4 get-stacker-info 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)",
+                "(contract-call? .pox-4 get-reward-set-size u0)",
+                "(contract-call? .pox-4 get-total-ustx-stacked u0)",
+                "(contract-call? .pox-4 get-delegation-info 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)",
+            ],
+        );
+    }
+
+    #[test]
+    fn costs() {
+        crosscheck_boot_contract(
+            "costs",
+            &[
+                "(contract-call? .costs cost_analysis_type_annotate u10)",
+                "(contract-call? .costs cost_analysis_visit u0)",
+                "(contract-call? .costs cost_ast_parse u1000)",
+                "(contract-call? .costs cost_map u7)",
+                "(contract-call? .costs cost_log2 u1)",
+                "(contract-call? .costs cost_sha256 u64)",
+            ],
+        );
+    }
+
+    #[test]
+    fn bns() {
+        crosscheck_boot_contract(
+            "bns",
+            &[
+                "(contract-call? .bns get-namespace-price 0x61)",
+                "(contract-call? .bns get-namespace-price 0x616263646566676869)",
+                "(contract-call? .bns can-namespace-be-registered 0x62746300)",
+                "(contract-call? .bns get-namespace-properties 0x627463)",
+                "(contract-call? .bns resolve-principal 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)",
+                "(contract-call? .bns can-receive-name 'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM)",
+                "(contract-call? .bns name-resolve 0x627463 0x616c696365)",
+            ],
+        );
+    }
+}
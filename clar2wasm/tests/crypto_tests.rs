@@ -0,0 +1,161 @@
+//! End-to-end checks of the signature verification words: the messages are
+//! signed in Rust with stacks-common keys, and verified by compiled Clarity.
+
+use clar2wasm::tools::{crosscheck, crosscheck_compare_only, TestEnvironment};
+use clarity::vm::Value;
+use stacks_common::types::{PrivateKey, PublicKey};
+use stacks_common::util::hash::{to_hex, Sha256Sum, Sha512Trunc256Sum};
+use stacks_common::util::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
+
+/// Private keys, the last byte `01` selecting a compressed public key.
+const PRIVATE_KEYS: &[&str] = &[
+    "510f96a8efd0b11e211733c1ac5e3fa6f3d3fcdd62869e376c47decb3e14fea101",
+    "010101010101010101010101010101010101010101010101010101010101010101",
+    "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd036414001",
+];
+
+const MESSAGE: &[u8] = b"transfer 100 tokens to bob";
+
+/// A message hash signed in Rust.
+struct Signed {
+    hash: Vec<u8>,
+    /// The signature in the RSV order expected by Clarity.
+    signature: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+fn sign(private_key: &str, hash: &[u8]) -> Signed {
+    let private_key = Secp256k1PrivateKey::from_hex(private_key).unwrap();
+    let signature = private_key.sign(hash).unwrap();
+    // stacks-common puts the recovery id first.
+    let mut rsv = signature.0[1..].to_vec();
+    rsv.push(signature.0[0]);
+    Signed {
+        hash: hash.to_vec(),
+        signature: rsv,
+        public_key: Secp256k1PublicKey::from_private(&private_key).to_bytes(),
+    }
+}
+
+fn signatures(hash: &[u8]) -> impl Iterator<Item = Signed> + '_ {
+    PRIVATE_KEYS.iter().map(move |key| sign(key, hash))
+}
+
+#[test]
+fn verify_signatures_of_rust_keys() {
+    for signed in signatures(Sha256Sum::from_data(MESSAGE).as_bytes()) {
+        let (hash, signature, key) = (
+            to_hex(&signed.hash),
+            to_hex(&signed.signature),
+            to_hex(&signed.public_key),
+        );
+        crosscheck(
+            &format!("(secp256k1-verify 0x{hash} 0x{signature} 0x{key})"),
+            Ok(Some(Value::Bool(true))),
+        );
+        // Without the recovery id.
+        crosscheck(
+            &format!(
+                "(secp256k1-verify 0x{hash} 0x{} 0x{key})",
+                &signature[..128]
+            ),
+            Ok(Some(Value::Bool(true))),
+        );
+
+        let mut tampered = signed.hash.clone();
+        tampered[0] ^= 1;
+        crosscheck(
+            &format!(
+                "(secp256k1-verify 0x{} 0x{signature} 0x{key})",
+                to_hex(&tampered)
+            ),
+            Ok(Some(Value::Bool(false))),
+        );
+    }
+}
+
+#[test]
+fn recover_public_keys_of_rust_keys() {
+    for signed in signatures(Sha256Sum::from_data(MESSAGE).as_bytes()) {
+        crosscheck(
+            &format!(
+                "(secp256k1-recover? 0x{} 0x{})",
+                to_hex(&signed.hash),
+                to_hex(&signed.signature)
+            ),
+            Ok(Some(
+                Value::okay(Value::buff_from(signed.public_key).unwrap()).unwrap(),
+            )),
+        );
+    }
+}
+
+#[test]
+fn principal_of_recovered_keys() {
+    for signed in signatures(Sha512Trunc256Sum::from_data(MESSAGE).as_bytes()) {
+        crosscheck_compare_only(&format!(
+            "(principal-of? (unwrap-panic (secp256k1-recover? 0x{} 0x{})))",
+            to_hex(&signed.hash),
+            to_hex(&signed.signature)
+        ));
+    }
+}
+
+/// Verifies signatures of messages hashed by the contract, with `sha256` and
+/// `sha512/256`, and authorizes their signers by their principal.
+const VERIFIER: &str = r#"
+(define-read-only (verify-sha256 (message (buff 64)) (signature (buff 65)) (key (buff 33)))
+    (secp256k1-verify (sha256 message) signature key))
+
+(define-read-only (verify-sha512-256 (message (buff 64)) (signature (buff 65)) (key (buff 33)))
+    (secp256k1-verify (sha512/256 message) signature key))
+
+(define-read-only (signer (message (buff 64)) (signature (buff 65)))
+    (principal-of? (try! (secp256k1-recover? (sha512/256 message) signature))))
+"#;
+
+#[test]
+fn verify_messages_hashed_by_contract() {
+    let mut env = TestEnvironment::default();
+    env.init_contract_with_snippet("verifier", VERIFIER)
+        .unwrap();
+    let mut interpreted_env = TestEnvironment::default();
+    interpreted_env
+        .interpret_contract_with_snippet("verifier", VERIFIER)
+        .unwrap();
+
+    let message = Value::buff_from(MESSAGE.to_vec()).unwrap();
+    for (function, hash) in [
+        (
+            "verify-sha256",
+            Sha256Sum::from_data(MESSAGE).as_bytes().to_vec(),
+        ),
+        (
+            "verify-sha512-256",
+            Sha512Trunc256Sum::from_data(MESSAGE).as_bytes().to_vec(),
+        ),
+    ] {
+        for signed in signatures(&hash) {
+            let args = [
+                message.clone(),
+                Value::buff_from(signed.signature).unwrap(),
+                Value::buff_from(signed.public_key).unwrap(),
+            ];
+            assert_eq!(
+                env.call_function("verifier", function, &args),
+                Ok(Value::Bool(true)),
+                "{function} rejected a valid signature"
+            );
+        }
+    }
+
+    for signed in signatures(Sha512Trunc256Sum::from_data(MESSAGE).as_bytes()) {
+        let args = [message.clone(), Value::buff_from(signed.signature).unwrap()];
+        let signer = env.call_function("verifier", "signer", &args);
+        assert!(matches!(signer, Ok(Value::Response(ref response)) if response.committed));
+        assert_eq!(
+            signer,
+            interpreted_env.interpret_function("verifier", "signer", &args)
+        );
+    }
+}
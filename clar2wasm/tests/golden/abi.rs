@@ -0,0 +1,145 @@
+//! Golden-file test of the interface between the standard library and the
+//! host.
+//!
+//! The imports and exports of the bundled standard library are described with
+//! their signatures, one per line, and compared to
+//! `tests/golden/expected/standard.abi`, so that an edit of `standard.wat`
+//! changing the ABI shows up as a diff in review. The imports are also checked
+//! against the host functions registered by each linker, which must define
+//! all of them with the same signatures.
+
+use std::path::PathBuf;
+
+use clar2wasm::initialize::ClarityWasmContext;
+use clar2wasm::linker::{
+    link_deterministic_host_functions, link_host_functions, link_stub_host_functions, FixedHeights,
+};
+use clar2wasm::wasm_generator::STANDARD_LIBRARY;
+use walrus::{ExportItem, GlobalId, ImportKind, Module, TypeId, ValType};
+use wasmtime::{Engine, Linker};
+
+use super::{diff, BLESS_VAR};
+
+fn value_types(types: &[ValType]) -> String {
+    types
+        .iter()
+        .map(|ty| format!("{ty:?}").to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn func_type(module: &Module, ty: TypeId) -> String {
+    let ty = module.types.get(ty);
+    let mut out = "(func".to_owned();
+    if !ty.params().is_empty() {
+        out += &format!(" (param {})", value_types(ty.params()));
+    }
+    if !ty.results().is_empty() {
+        out += &format!(" (result {})", value_types(ty.results()));
+    }
+    out + ")"
+}
+
+fn global_type(module: &Module, global: GlobalId) -> String {
+    let global = module.globals.get(global);
+    let ty = value_types(&[global.ty]);
+    if global.mutable {
+        format!("(global (mut {ty}))")
+    } else {
+        format!("(global {ty})")
+    }
+}
+
+/// Describes the imports and the exports of `wasm` in a WAT-like syntax, one
+/// per line, sorted by name.
+fn describe_abi(wasm: &[u8]) -> String {
+    let module = Module::from_buffer(wasm).expect("failed to parse the standard library");
+
+    let mut imports: Vec<_> = module
+        .imports
+        .iter()
+        .map(|import| {
+            let desc = match import.kind {
+                ImportKind::Function(func) => func_type(&module, module.funcs.get(func).ty()),
+                ImportKind::Global(global) => global_type(&module, global),
+                ImportKind::Memory(_) => "(memory)".to_owned(),
+                ImportKind::Table(_) => "(table)".to_owned(),
+            };
+            (import.module.as_str(), import.name.as_str(), desc)
+        })
+        .collect();
+    imports.sort();
+
+    let mut exports: Vec<_> = module
+        .exports
+        .iter()
+        .map(|export| {
+            let desc = match export.item {
+                ExportItem::Function(func) => func_type(&module, module.funcs.get(func).ty()),
+                ExportItem::Global(global) => global_type(&module, global),
+                ExportItem::Memory(_) => "(memory)".to_owned(),
+                ExportItem::Table(_) => "(table)".to_owned(),
+            };
+            (export.name.as_str(), desc)
+        })
+        .collect();
+    exports.sort();
+
+    let imports = imports
+        .into_iter()
+        .map(|(module, name, desc)| format!("import {module:?} {name:?} {desc}\n"));
+    let exports = exports
+        .into_iter()
+        .map(|(name, desc)| format!("export {name:?} {desc}\n"));
+    imports.chain(exports).collect()
+}
+
+#[test]
+fn golden_standard_library_abi() {
+    let expected_path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/expected/standard.abi");
+    let bless = std::env::var_os(BLESS_VAR).is_some();
+    let actual = describe_abi(STANDARD_LIBRARY);
+
+    match std::fs::read_to_string(&expected_path) {
+        Ok(expected) if expected == actual => {}
+        Ok(expected) if !bless => panic!(
+            "the ABI of the standard library changed, increase `sections::ABI_VERSION` and run the tests with {BLESS_VAR}=1 to update the expected file if this is intended\n\n{}",
+            diff(&expected, &actual)
+        ),
+        Err(e) if !bless => panic!(
+            "cannot read {expected_path:?}: {e}, run the tests with {BLESS_VAR}=1 to create it"
+        ),
+        _ => {
+            std::fs::write(&expected_path, &actual)
+                .unwrap_or_else(|e| panic!("failed to write {expected_path:?}: {e}"));
+            println!("updated {}", expected_path.display());
+        }
+    }
+}
+
+#[test]
+fn standard_library_imports_are_linked() {
+    let engine = Engine::default();
+    let module = wasmtime::Module::new(&engine, STANDARD_LIBRARY)
+        .expect("failed to compile the standard library");
+
+    let mut linker: Linker<ClarityWasmContext> = Linker::new(&engine);
+    link_host_functions(&mut linker).expect("failed to link the host functions");
+    if let Err(e) = linker.instantiate_pre(&module) {
+        panic!("the host functions don't match the imports of the standard library: {e:#}");
+    }
+
+    let mut linker: Linker<ClarityWasmContext> = Linker::new(&engine);
+    link_deterministic_host_functions(&mut linker, FixedHeights::default())
+        .expect("failed to link the deterministic host functions");
+    if let Err(e) = linker.instantiate_pre(&module) {
+        panic!("the deterministic host functions don't match the imports of the standard library: {e:#}");
+    }
+
+    let mut linker: Linker<()> = Linker::new(&engine);
+    link_stub_host_functions(&mut linker).expect("failed to link the stub host functions");
+    if let Err(e) = linker.instantiate_pre(&module) {
+        panic!("the stub host functions don't match the imports of the standard library: {e:#}");
+    }
+}
@@ -0,0 +1,79 @@
+//! Golden-file test of the bindings generated by
+//! [`clar2wasm::bindings::contract_bindings`].
+//!
+//! The bindings of `tests/contracts/bindings.clar` are compared to
+//! `tests/golden/expected/bindings.rs`, which is included below, so that the
+//! generated code is compiled with the tests, and called on the contract.
+
+use std::path::PathBuf;
+
+use clar2wasm::bindings::contract_bindings;
+use clar2wasm::tools::TestEnvironment;
+use clar2wasm::{compile, CompileOptions};
+use clarity::types::StacksEpochId;
+use clarity::vm::analysis::AnalysisDatabase;
+use clarity::vm::costs::LimitedCostTracker;
+use clarity::vm::database::MemoryBackingStore;
+use clarity::vm::types::{PrincipalData, QualifiedContractIdentifier, StandardPrincipalData};
+use clarity::vm::{ClarityVersion, Value};
+
+use super::{diff, BLESS_VAR};
+
+include!("expected/bindings.rs");
+
+const CONTRACT: &str = include_str!("../contracts/bindings.clar");
+
+#[test]
+fn golden_bindings() {
+    let expected_path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/golden/expected/bindings.rs");
+    let bless = std::env::var_os(BLESS_VAR).is_some();
+    let analysis = compile(
+        CONTRACT,
+        &QualifiedContractIdentifier::local("counter").unwrap(),
+        LimitedCostTracker::new_free(),
+        ClarityVersion::latest(),
+        StacksEpochId::latest(),
+        &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+        CompileOptions::default(),
+    )
+    .unwrap_or_else(|e| panic!("failed to compile contract: {e:?}"))
+    .contract_analysis;
+    let actual = contract_bindings("counter", &analysis);
+
+    match std::fs::read_to_string(&expected_path) {
+        Ok(expected) if expected == actual => {}
+        Ok(expected) if !bless => panic!(
+            "the generated bindings changed, run the tests with {BLESS_VAR}=1 to update the expected file if this is intended\n\n{}",
+            diff(&expected, &actual)
+        ),
+        Err(e) if !bless => panic!(
+            "cannot read {expected_path:?}: {e}, run the tests with {BLESS_VAR}=1 to create it"
+        ),
+        _ => {
+            std::fs::write(&expected_path, &actual)
+                .unwrap_or_else(|e| panic!("failed to write {expected_path:?}: {e}"));
+            println!("updated {}", expected_path.display());
+        }
+    }
+}
+
+#[test]
+fn bindings_call_the_contract() {
+    let mut env = TestEnvironment::default();
+    env.init_contract_with_snippet("counter", CONTRACT).unwrap();
+
+    // `env`, `a-b` and `a_b` are passed in order.
+    assert_eq!(
+        counter::add(&mut env, 1, 2, 3),
+        Ok(Value::okay(Value::Int(321)).unwrap())
+    );
+    assert_eq!(counter::get_count(&mut env), Ok(Value::Int(321)));
+    assert_eq!(counter::get_count_2(&mut env), Ok(Value::Int(642)));
+
+    let owner = PrincipalData::Standard(StandardPrincipalData::transient());
+    assert_eq!(
+        counter::describe(&mut env, "label", owner, &[1, 2]),
+        Ok(Value::string_ascii_from_bytes(b"label".to_vec()).unwrap())
+    );
+}
@@ -0,0 +1,31 @@
+/// Bindings to the public interface of the contract `counter`.
+pub mod counter {
+
+    /// Calls the public function `add` of `counter`.
+    pub fn add(
+        env: &mut ::clar2wasm::tools::TestEnvironment, env_2: i128, a_b: i128, a_b_2: i128
+    ) -> ::std::result::Result<::clarity::vm::Value, ::clarity::vm::errors::Error> {
+        env.call_function("counter", "add", &[::clarity::vm::Value::Int(env_2), ::clarity::vm::Value::Int(a_b), ::clarity::vm::Value::Int(a_b_2)])
+    }
+
+    /// Calls the read-only function `describe` of `counter`.
+    pub fn describe(
+        env: &mut ::clar2wasm::tools::TestEnvironment, label: &str, owner: ::clarity::vm::types::PrincipalData, data: &[u8]
+    ) -> ::std::result::Result<::clarity::vm::Value, ::clarity::vm::errors::Error> {
+        env.call_function("counter", "describe", &[::clarity::vm::Value::string_ascii_from_bytes(label.as_bytes().to_vec())?, ::clarity::vm::Value::Principal(owner), ::clarity::vm::Value::buff_from(data.to_vec())?])
+    }
+
+    /// Calls the read-only function `get-count` of `counter`.
+    pub fn get_count(
+        env: &mut ::clar2wasm::tools::TestEnvironment
+    ) -> ::std::result::Result<::clarity::vm::Value, ::clarity::vm::errors::Error> {
+        env.call_function("counter", "get-count", &[])
+    }
+
+    /// Calls the read-only function `get_count` of `counter`.
+    pub fn get_count_2(
+        env: &mut ::clar2wasm::tools::TestEnvironment
+    ) -> ::std::result::Result<::clarity::vm::Value, ::clarity::vm::errors::Error> {
+        env.call_function("counter", "get_count", &[])
+    }
+}
@@ -0,0 +1,190 @@
+//! Golden-file tests of the generated code.
+//!
+//! Each contract of [`CONTRACTS`] is compiled, and its WAT is compared to the
+//! expected output in `tests/golden/expected`, so that any change to the
+//! generated code shows up as a diff in review. Only the parts of the module
+//! coming from the contract are kept: the functions, data and types of the
+//! standard library, found by compiling an empty contract, are removed.
+//!
+//! When the code generation changes on purpose, the expected files are
+//! updated by running the tests with `CLAR2WASM_BLESS=1`. A missing expected
+//! file is a failure too, it is only written with `CLAR2WASM_BLESS=1`, and
+//! must be committed.
+//!
+//! The ABI of the standard library and the generated bindings are checked the
+//! same way, see [`abi`] and [`bindings`].
+
+mod abi;
+mod bindings;
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use clar2wasm::{compile, CompileOptions};
+use clarity::types::StacksEpochId;
+use clarity::vm::analysis::AnalysisDatabase;
+use clarity::vm::costs::LimitedCostTracker;
+use clarity::vm::database::MemoryBackingStore;
+use clarity::vm::types::QualifiedContractIdentifier;
+use clarity::vm::ClarityVersion;
+
+/// Contracts of `tests/contracts` whose generated code is checked.
+const CONTRACTS: &[&str] = &[
+    "add-3",
+    "call-private-with-args",
+    "concat",
+    "define-map",
+    "fold",
+    "options",
+    "responses",
+    "tuple",
+    "var-get",
+];
+
+/// Environment variable to set to update the expected files.
+const BLESS_VAR: &str = "CLAR2WASM_BLESS";
+
+/// Lines of context printed around the differences.
+const CONTEXT: usize = 3;
+
+fn compile_to_wat(source: &str) -> String {
+    let module = compile(
+        source,
+        &QualifiedContractIdentifier::transient(),
+        LimitedCostTracker::new_free(),
+        ClarityVersion::latest(),
+        StacksEpochId::latest(),
+        &mut AnalysisDatabase::new(&mut MemoryBackingStore::new()),
+        CompileOptions::default().deterministic(true),
+    )
+    .unwrap_or_else(|e| panic!("failed to compile contract: {e:?}"))
+    .module
+    .emit_wasm();
+    wasmprinter::print_bytes(module).expect("failed to print module")
+}
+
+/// Splits a WAT module into its top-level fields.
+fn fields(wat: &str) -> Vec<String> {
+    let mut fields: Vec<String> = vec![];
+    for line in wat.lines() {
+        if line.starts_with("  (") {
+            fields.push(String::new());
+        }
+        // Skip the `(module` header and closing paren.
+        if let Some(field) = fields.last_mut() {
+            if line != ")" {
+                field.push_str(line);
+                field.push('\n');
+            }
+        }
+    }
+    fields
+}
+
+/// Functions are identified by their name, since their index changes with
+/// the number of imports.
+fn field_key(field: &str) -> &str {
+    match field.strip_prefix("  (func $") {
+        Some(rest) => rest.split_whitespace().next().unwrap_or(field),
+        None => field,
+    }
+}
+
+/// Returns the WAT of the fields of `contract` which are not in `baseline`.
+fn contract_wat(contract: &str, baseline: &str) -> String {
+    let baseline = fields(baseline);
+    let baseline: HashSet<_> = baseline
+        .iter()
+        .map(|field| field_key(field))
+        // The top-level function is always generated from the contract.
+        .filter(|key| *key != ".top-level")
+        .collect();
+
+    fields(contract)
+        .iter()
+        .filter(|field| !baseline.contains(field_key(field)))
+        .map(String::as_str)
+        .collect()
+}
+
+/// Formats the lines which differ between `expected` and `actual`, with some
+/// context.
+fn diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<_> = expected.lines().collect();
+    let actual: Vec<_> = actual.lines().collect();
+
+    let prefix = expected
+        .iter()
+        .zip(&actual)
+        .take_while(|(e, a)| e == a)
+        .count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(e, a)| e == a)
+        .count();
+
+    let start = prefix.saturating_sub(CONTEXT);
+    let mut out = format!("@@ line {} @@\n", start + 1);
+    for line in &expected[start..prefix] {
+        out += &format!("  {line}\n");
+    }
+    for line in &expected[prefix..expected.len() - suffix] {
+        out += &format!("- {line}\n");
+    }
+    for line in &actual[prefix..actual.len() - suffix] {
+        out += &format!("+ {line}\n");
+    }
+    let end = (actual.len() - suffix + CONTEXT).min(actual.len());
+    for line in &actual[actual.len() - suffix..end] {
+        out += &format!("  {line}\n");
+    }
+    out
+}
+
+#[test]
+fn golden_wat() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests");
+    let bless = std::env::var_os(BLESS_VAR).is_some();
+    let baseline = compile_to_wat("");
+
+    let mut failures = vec![];
+    for name in CONTRACTS {
+        let source = std::fs::read_to_string(root.join(format!("contracts/{name}.clar")))
+            .unwrap_or_else(|e| panic!("failed to read contract {name}: {e}"));
+        let actual = contract_wat(&compile_to_wat(&source), &baseline);
+
+        let expected_path = root.join(format!("golden/expected/{name}.wat"));
+        match std::fs::read_to_string(&expected_path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) if !bless => {
+                failures.push(format!("{name}:\n{}", diff(&expected, &actual)));
+            }
+            Err(e) if !bless => {
+                failures.push(format!("{name}: cannot read {expected_path:?}: {e}\n"));
+            }
+            _ => {
+                std::fs::write(&expected_path, &actual)
+                    .unwrap_or_else(|e| panic!("failed to write {expected_path:?}: {e}"));
+                println!("updated {}", expected_path.display());
+            }
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "the generated code changed, run the tests with {BLESS_VAR}=1 to update the expected files if this is intended\n\n{}",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn diff_shows_changed_lines() {
+    let expected = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    let actual = "a\nb\nc\nd\nE\nf\ng\nh\n";
+    assert_eq!(
+        diff(expected, actual),
+        "@@ line 2 @@\n  b\n  c\n  d\n- e\n+ E\n  f\n  g\n  h\n"
+    );
+}
@@ -1,7 +1,11 @@
 #[cfg(test)]
 pub mod bin_tests;
 #[cfg(test)]
+pub mod crypto_tests;
+#[cfg(test)]
 pub mod lib_tests;
+#[cfg(test)]
+pub mod state_tests;
 
 #[cfg(test)]
 mod tests {
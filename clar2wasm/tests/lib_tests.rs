@@ -1,10 +1,10 @@
 use std::collections::HashMap;
 
-use clar2wasm::compile;
 use clar2wasm::datastore::{BurnDatastore, StacksConstants};
 use clar2wasm::initialize::initialize_contract;
 use clar2wasm::tools::execute;
 use clar2wasm::wasm_utils::call_function;
+use clar2wasm::{compile, CompileOptions};
 use clarity::consts::CHAIN_ID_TESTNET;
 use clarity::types::chainstate::BlockHeaderHash;
 use clarity::types::StacksEpochId;
@@ -74,6 +74,7 @@ macro_rules! test_multi_contract_init {
                             ClarityVersion::Clarity2,
                             StacksEpochId::latest(),
                             analysis_db,
+                            CompileOptions::default(),
                         )
                         .map_err(|_| CheckErrors::Expects("Compilation failure".to_string()))
                     })
@@ -109,7 +110,8 @@ macro_rules! test_multi_contract_init {
                     None,
                     &compile_result.contract_analysis,
                 )
-                .expect("Failed to initialize contract.");
+                .expect("Failed to initialize contract.")
+                .value;
 
                 let data_size = contract_context.data_size;
                 global_context
@@ -696,6 +698,21 @@ test_contract_call_response!(
     }
 );
 
+test_contract_call_error!(
+    test_bar_mint_too_many,
+    "tokens",
+    "bar-mint-too-many",
+    |error: Error| {
+        assert_eq!(
+            error,
+            Error::Runtime(
+                RuntimeErrorType::SupplyExceeded(1000001, 1000000),
+                Some(Vec::new())
+            )
+        );
+    }
+);
+
 test_contract_call_response!(
     test_ft_balance_0,
     "tokens",
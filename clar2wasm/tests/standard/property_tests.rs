@@ -528,6 +528,19 @@ fn prop_sha256_buff() {
     )
 }
 
+#[test]
+fn prop_sha256_buff_simd() {
+    test_on_buffer_hash(
+        "stdlib.sha256-buf-simd",
+        2048,
+        END_OF_STANDARD_DATA as usize + 32,
+        300,
+        END_OF_STANDARD_DATA as i32,
+        32,
+        |buf| Sha256Sum::from_data(buf).as_bytes().to_vec(),
+    )
+}
+
 #[test]
 fn prop_sha256_int_on_signed() {
     test_on_int_hash(
@@ -550,6 +563,17 @@ fn prop_sha256_int_on_unsigned() {
     )
 }
 
+#[test]
+fn prop_sha256_int_simd() {
+    test_on_uint_hash(
+        "stdlib.sha256-int-simd",
+        2048,
+        END_OF_STANDARD_DATA as i32,
+        32,
+        |n| Sha256Sum::from_data(&n.to_le_bytes()).as_bytes().to_vec(),
+    )
+}
+
 #[test]
 fn prop_hash160_buff() {
     test_on_buffer_hash(
@@ -634,6 +658,11 @@ fn prop_is_eq_bytes() {
     test_buff_comparison("stdlib.is-eq-bytes", |a, b| a == b)
 }
 
+#[test]
+fn prop_is_eq_bytes_simd() {
+    test_buff_comparison("stdlib.is-eq-bytes-simd", |a, b| a == b)
+}
+
 #[test]
 fn prop_sha512_buff() {
     test_on_buffer_hash(
@@ -1773,6 +1773,110 @@ fn test_is_eq_bytes() {
     test_cmp_buff("stdlib.is-eq-bytes", |a, b| a == b)
 }
 
+#[test]
+fn test_is_eq_bytes_simd() {
+    test_cmp_buff("stdlib.is-eq-bytes-simd", |a, b| a == b)
+}
+
+#[test]
+fn test_is_eq_bytes_simd_chunks() {
+    let (instance, mut store) = load_stdlib().unwrap();
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .expect("Could not find memory");
+    let is_eq = instance
+        .get_func(&mut store, "stdlib.is-eq-bytes-simd")
+        .unwrap();
+    let mut result = [Val::I32(0)];
+
+    // Buffers longer than a chunk, differing in the chunks or in the tail
+    for len in [15, 16, 17, 32, 47, 100] {
+        let buff_a: Vec<u8> = (0..len as u8).collect();
+        for diff in [None, Some(0), Some(15), Some(16), Some(len - 1)] {
+            let mut buff_b = buff_a.clone();
+            if let Some(i) = diff.filter(|&i| i < len) {
+                buff_b[i] ^= 0x80;
+            }
+
+            let offset_a = 1000;
+            let offset_b = offset_a + len;
+            memory
+                .write(&mut store, offset_a, &buff_a)
+                .expect("could not write to memory");
+            memory
+                .write(&mut store, offset_b, &buff_b)
+                .expect("could not write to memory");
+
+            is_eq
+                .call(
+                    &mut store,
+                    &[
+                        Val::I32(offset_a as i32),
+                        Val::I32(len as i32),
+                        Val::I32(offset_b as i32),
+                        Val::I32(len as i32),
+                    ],
+                    &mut result,
+                )
+                .expect("call to is-eq-bytes-simd failed");
+            assert_eq!(
+                result[0].unwrap_i32(),
+                (buff_a == buff_b) as i32,
+                "len {len}, diff {diff:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_is_eq_bytes_utf8() {
+    // utf8 strings are stored as big-endian 4-byte unicode scalars, and
+    // compared with `is-eq-bytes`
+    fn scalars(s: &str) -> Vec<u8> {
+        s.chars().flat_map(|c| (c as u32).to_be_bytes()).collect()
+    }
+
+    let (instance, mut store) = load_stdlib().unwrap();
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .expect("Could not find memory");
+    let is_eq = instance.get_func(&mut store, "stdlib.is-eq-bytes").unwrap();
+    let mut result = [Val::I32(0)];
+
+    let mut test_eq = |a: &str, b: &str| {
+        let (buff_a, buff_b) = (scalars(a), scalars(b));
+        let offset_a = 1000;
+        let offset_b = offset_a + buff_a.len();
+        memory
+            .write(&mut store, offset_a, &buff_a)
+            .expect("could not write to memory");
+        memory
+            .write(&mut store, offset_b, &buff_b)
+            .expect("could not write to memory");
+
+        is_eq
+            .call(
+                &mut store,
+                &[
+                    Val::I32(offset_a as i32),
+                    Val::I32(buff_a.len() as i32),
+                    Val::I32(offset_b as i32),
+                    Val::I32(buff_b.len() as i32),
+                ],
+                &mut result,
+            )
+            .expect("call to is-eq-bytes failed");
+        assert_eq!(result[0].unwrap_i32(), (a == b) as i32, "{a:?} == {b:?}");
+    };
+
+    test_eq("", "");
+    test_eq("\u{1F600}", "\u{1F600}");
+    test_eq("\u{1F600}", "\u{1F601}");
+    test_eq("a\u{10348}b", "a\u{10348}b");
+    test_eq("a\u{10348}b", "a\u{10348}");
+    test_eq("\u{10348}", "\u{48}");
+}
+
 #[test]
 fn test_log2_uint() {
     let (instance, mut store) = load_stdlib().unwrap();
@@ -1,9 +1,9 @@
 use std::cell::RefCell;
-use std::ops::{Deref, DerefMut};
+use std::ops::DerefMut;
 
 use clar2wasm::linker::load_stdlib;
 use clar2wasm::wasm_generator::END_OF_STANDARD_DATA;
-use hex::ToHex;
+use clar2wasm::wasm_utils::memory::MemoryRegion;
 use proptest::prelude::*;
 use wasmtime::Val;
 
@@ -368,84 +368,12 @@ where
     test_export_one_arg_checked(&SIGNED_STRATEGIES, name, closure)
 }
 
-/// The Property Buffer type.
-/// Used for convenience when dealing with buffers, to read them
-/// and write them to memory, and dealing with the pair `(offset, length)`.
-#[derive(Clone)]
-pub(crate) struct PropBuffer {
-    buffer: Vec<u8>,
-    offset: usize,
-}
-
-impl std::fmt::Debug for PropBuffer {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("PropBuffer")
-            .field(
-                "buffer",
-                &format!("0x{}", self.buffer.encode_hex::<String>()),
-            )
-            .field("offset", &self.offset)
-            .finish()
-    }
-}
-
-impl PropBuffer {
-    /// Creates a new PropBuffer.
-    pub(crate) fn new(buffer: Vec<u8>, offset: usize) -> Self {
-        Self { buffer, offset }
-    }
-
-    /// Read a buffer from memory at a specified `offset` and `length`
-    /// , and create a PropBuffer if the operation is a success.
-    pub(crate) fn read_from_memory(
-        memory: wasmtime::Memory,
-        store: impl wasmtime::AsContext,
-        offset: usize,
-        length: usize,
-    ) -> Option<Self> {
-        let mut buffer = vec![0u8; length];
-        memory.read(store, offset, &mut buffer).ok()?;
-        Some(Self { buffer, offset })
-    }
-
-    /// Write a buffer to memory, returning a `(offset, length)` if the
-    /// operation is a success.
-    pub(crate) fn write_to_memory(
-        &self,
-        memory: wasmtime::Memory,
-        store: impl wasmtime::AsContextMut,
-    ) -> Option<(i32, i32)> {
-        memory.write(store, self.offset, &self.buffer).ok()?;
-        Some((self.offset as i32, self.buffer.len() as i32))
-    }
-}
-
-impl From<PropBuffer> for Vec<u8> {
-    fn from(value: PropBuffer) -> Self {
-        value.buffer
-    }
-}
-
-impl Deref for PropBuffer {
-    type Target = Vec<u8>;
-
-    fn deref(&self) -> &Self::Target {
-        &self.buffer
-    }
-}
-
-impl AsRef<[u8]> for PropBuffer {
-    fn as_ref(&self) -> &[u8] {
-        &self.buffer
-    }
-}
-
 prop_compose! {
-    /// Generates random PropBuffer with given `offset`. The length will be between 1 and `max_length`.
+    /// Generates random memory region with given `offset`. The length will be between 1 and `max_length`.
     pub(crate) fn buffer(offset: usize, max_length: usize)
         (buf in proptest::collection::vec(any::<u8>(), 1..max_length))
-        -> PropBuffer {
-            PropBuffer::new(buf, offset)
+        -> MemoryRegion {
+            MemoryRegion::new(buf, offset)
         }
 }
 
@@ -488,7 +416,7 @@ pub(crate) fn test_on_buffer_hash(
 
         let mut res = [Val::I32(0), Val::I32(0)];
 
-        let (offset, len)  = buf.write_to_memory(memory, store.borrow_mut().deref_mut()).expect("could not write buffer to memory");
+        let (offset, len)  = buf.write(memory, store.borrow_mut().deref_mut()).expect("could not write buffer to memory");
 
         fun.call(
             store.borrow_mut().deref_mut(),
@@ -499,7 +427,7 @@ pub(crate) fn test_on_buffer_hash(
         assert_eq!(res[0].unwrap_i32(), result_offset);
         assert_eq!(res[1].unwrap_i32(), result_length);
 
-        let wasm_result = PropBuffer::read_from_memory(memory, store.borrow_mut().deref_mut(), result_offset as usize, result_length as usize).expect("could not read result buffer from memory");
+        let wasm_result = MemoryRegion::read(memory, store.borrow_mut().deref_mut(), result_offset as usize, result_length as usize).expect("could not read result buffer from memory");
 
         prop_assert_eq!(expected_result, wasm_result.as_ref());
     });
@@ -551,7 +479,7 @@ fn test_on_integer_hash(
             assert_eq!(res[0].unwrap_i32(), result_offset);
             assert_eq!(res[1].unwrap_i32(), result_length);
 
-            let wasm_result = PropBuffer::read_from_memory(memory, store.borrow_mut().deref_mut(), result_offset as usize, result_length as usize).expect("could not read result buffer from memory");
+            let wasm_result = MemoryRegion::read(memory, store.borrow_mut().deref_mut(), result_offset as usize, result_length as usize).expect("could not read result buffer from memory");
 
             prop_assert_eq!(expected_result, wasm_result.as_ref());
         })
@@ -621,7 +549,7 @@ pub(crate) fn test_buff_to_uint(
 
         let mut result = [Val::I64(0), Val::I64(0)];
         let (offset, length) = buff
-            .write_to_memory(memory, store.borrow_mut().deref_mut())
+            .write(memory, store.borrow_mut().deref_mut())
             .expect("Could not write to memory");
 
         buff_to_uint
@@ -656,10 +584,10 @@ pub(crate) fn test_buff_comparison(
 
         let mut result = [Val::I32(0)];
         let (offset_a, length_a) = buff_a
-            .write_to_memory(memory, store.borrow_mut().deref_mut())
+            .write(memory, store.borrow_mut().deref_mut())
             .expect("Could not write to memory");
         let (offset_b, length_b) = buff_b
-            .write_to_memory(memory, store.borrow_mut().deref_mut())
+            .write(memory, store.borrow_mut().deref_mut())
             .expect("Could not write to memory");
         cmp
             .call(
@@ -676,10 +604,10 @@ pub(crate) fn test_buff_comparison(
 
         let mut result = [Val::I32(0)];
         let (offset_a, length_a) = buff
-            .write_to_memory(memory, store.borrow_mut().deref_mut())
+            .write(memory, store.borrow_mut().deref_mut())
             .expect("Could not write to memory");
         let (offset_b, length_b) = buff
-            .write_to_memory(memory, store.borrow_mut().deref_mut())
+            .write(memory, store.borrow_mut().deref_mut())
             .expect("Could not write to memory");
         cmp
             .call(
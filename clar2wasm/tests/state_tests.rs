@@ -0,0 +1,92 @@
+//! Checks that the state written by one runtime is stored byte for byte like
+//! the state written by the other, and is read back identically by both.
+
+use clar2wasm::tools::crosscheck_state;
+
+const RECIPIENT: &str = "'ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM";
+
+#[test]
+fn data_vars() {
+    crosscheck_state(
+        r#"
+        (define-data-var counter int 0)
+        (define-data-var label (string-ascii 16) "none")
+        (define-data-var owner (optional principal) none)
+        (var-set counter (+ (var-get counter) 42))
+        (var-set label "updated")
+        (var-set owner (some tx-sender))
+        (define-read-only (get-counter) (var-get counter))
+        (define-read-only (get-label) (var-get label))
+        (define-read-only (get-owner) (var-get owner))
+        "#,
+        &["get-counter", "get-label", "get-owner"],
+    );
+}
+
+#[test]
+fn map_operations() {
+    crosscheck_state(
+        r#"
+        (define-map balances { owner: principal, id: uint } { amount: uint, memo: (buff 4) })
+        (map-insert balances { id: u1, owner: tx-sender } { memo: 0x01, amount: u10 })
+        (map-insert balances { owner: tx-sender, id: u2 } { amount: u20, memo: 0x0203 })
+        (map-set balances { owner: tx-sender, id: u1 } { amount: u11, memo: 0x04 })
+        (map-insert balances { owner: tx-sender, id: u3 } { amount: u30, memo: 0x })
+        (map-delete balances { id: u3, owner: tx-sender })
+        (define-read-only (get-first) (map-get? balances { owner: tx-sender, id: u1 }))
+        (define-read-only (get-second) (map-get? balances { owner: tx-sender, id: u2 }))
+        (define-read-only (get-deleted) (map-get? balances { owner: tx-sender, id: u3 }))
+        "#,
+        &["get-first", "get-second", "get-deleted"],
+    );
+}
+
+#[test]
+fn fungible_tokens() {
+    crosscheck_state(
+        &format!(
+            r#"
+            (define-fungible-token token u1000)
+            (ft-mint? token u500 tx-sender)
+            (ft-transfer? token u120 tx-sender {RECIPIENT})
+            (ft-burn? token u30 tx-sender)
+            (define-read-only (get-sender-balance) (ft-get-balance token tx-sender))
+            (define-read-only (get-recipient-balance) (ft-get-balance token {RECIPIENT}))
+            (define-read-only (get-supply) (ft-get-supply token))
+            "#
+        ),
+        &["get-sender-balance", "get-recipient-balance", "get-supply"],
+    );
+}
+
+#[test]
+fn non_fungible_tokens() {
+    crosscheck_state(
+        &format!(
+            r#"
+            (define-non-fungible-token badge {{ series: uint, name: (string-ascii 8) }})
+            (nft-mint? badge {{ series: u1, name: "first" }} tx-sender)
+            (nft-mint? badge {{ name: "second", series: u2 }} tx-sender)
+            (nft-transfer? badge {{ series: u2, name: "second" }} tx-sender {RECIPIENT})
+            (define-read-only (get-first-owner) (nft-get-owner? badge {{ series: u1, name: "first" }}))
+            (define-read-only (get-second-owner) (nft-get-owner? badge {{ series: u2, name: "second" }}))
+            "#
+        ),
+        &["get-first-owner", "get-second-owner"],
+    );
+}
+
+#[test]
+fn stx_transfers() {
+    crosscheck_state(
+        &format!(
+            r#"
+            (stx-transfer? u1000 tx-sender {RECIPIENT})
+            (stx-burn? u10 tx-sender)
+            (define-read-only (get-sender-balance) (stx-get-balance tx-sender))
+            (define-read-only (get-recipient-balance) (stx-get-balance {RECIPIENT}))
+            "#
+        ),
+        &["get-sender-balance", "get-recipient-balance"],
+    );
+}
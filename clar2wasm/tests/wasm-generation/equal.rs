@@ -1,9 +1,56 @@
 use clar2wasm::tools::crosscheck;
 use clarity::vm::types::OptionalData;
 use clarity::vm::Value;
+use proptest::prelude::*;
 use proptest::proptest;
 
-use crate::PropValue;
+use crate::{prop_signature, PropValue};
+
+/// A layer wrapping a value, chosen independently for each side of a comparison,
+/// so that operands end up with `NoType` in different places.
+#[derive(Debug, Clone, Copy)]
+enum Wrapper {
+    Some,
+    None,
+    Ok,
+    Err,
+}
+
+impl Wrapper {
+    fn apply(self, value: Value) -> Value {
+        match self {
+            Wrapper::Some => Value::some(value).unwrap(),
+            Wrapper::None => Value::none(),
+            Wrapper::Ok => Value::okay(value).unwrap(),
+            Wrapper::Err => Value::error(value).unwrap(),
+        }
+    }
+}
+
+/// Generates a nesting of optionals (`true`) and responses (`false`), and for
+/// each side and layer, the variant used at that layer.
+fn wrappers() -> impl Strategy<Value = (Vec<Wrapper>, Vec<Wrapper>)> {
+    prop::collection::vec(any::<bool>(), 1..=4).prop_flat_map(|layers| {
+        let side = layers
+            .iter()
+            .map(|&is_optional| {
+                if is_optional {
+                    prop_oneof![Just(Wrapper::Some), Just(Wrapper::None)].boxed()
+                } else {
+                    prop_oneof![Just(Wrapper::Ok), Just(Wrapper::Err)].boxed()
+                }
+            })
+            .collect::<Vec<_>>();
+        (side.clone(), side)
+    })
+}
+
+fn wrap(value: Value, wrappers: &[Wrapper]) -> Value {
+    wrappers
+        .iter()
+        .rev()
+        .fold(value, |inner, wrapper| wrapper.apply(inner))
+}
 
 proptest! {
     #![proptest_config(super::runtime_config())]
@@ -71,3 +118,34 @@ proptest! {
         )
     }
 }
+
+proptest! {
+    #![proptest_config(super::runtime_config())]
+
+    #[test]
+    fn is_eq_with_no_type_in_nested_responses_and_optionals(
+        (first, second) in prop_signature()
+            .prop_flat_map(|ty| (PropValue::from_type(ty.clone()), PropValue::from_type(ty))),
+        (first_wrappers, second_wrappers) in wrappers(),
+        same_inner in any::<bool>(),
+    ) {
+        let first_leaf: Value = first.into();
+        let second_leaf = if same_inner {
+            first_leaf.clone()
+        } else {
+            second.into()
+        };
+        let first = wrap(first_leaf, &first_wrappers);
+        let second = wrap(second_leaf, &second_wrappers);
+        let expected = first == second;
+
+        crosscheck(
+            &format!(
+                "(is-eq {} {})",
+                PropValue::from(first),
+                PropValue::from(second)
+            ),
+            Ok(Some(Value::Bool(expected))),
+        );
+    }
+}
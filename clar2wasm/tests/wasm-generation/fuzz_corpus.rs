@@ -0,0 +1,53 @@
+//! Seeds the corpus of the `compile_source` fuzz target with contracts using
+//! the generated values, when `CLAR2WASM_FUZZ_CORPUS` names its directory:
+//!
+//! ```sh
+//! CLAR2WASM_FUZZ_CORPUS=fuzz/corpus/compile_source cargo test --test wasm-generation fuzz_corpus -- --ignored
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::env;
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+
+use proptest::prelude::*;
+
+use crate::{prop_signature, type_string, PropValue};
+
+const FUZZ_CORPUS_VAR: &str = "CLAR2WASM_FUZZ_CORPUS";
+
+/// Writes `source` to the corpus in `dir`, prefixed with the byte selecting
+/// the Clarity version of the fuzz target.
+fn write_seed(dir: &str, version: u8, source: &str) {
+    let mut seed = vec![version];
+    seed.extend(source.as_bytes());
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    std::fs::create_dir_all(dir).unwrap();
+    std::fs::write(
+        Path::new(dir).join(format!("{:016x}", hasher.finish())),
+        seed,
+    )
+    .unwrap();
+}
+
+proptest! {
+    #![proptest_config(super::runtime_config())]
+
+    #[ignore = "only writes the corpus named by CLAR2WASM_FUZZ_CORPUS"]
+    #[test]
+    fn seed_fuzz_corpus(
+        (ty, val) in prop_signature().prop_ind_flat_map2(|ty| PropValue::from_type(ty.clone())),
+        version in 0u8..3
+    ) {
+        if let Ok(dir) = env::var(FUZZ_CORPUS_VAR) {
+            write_seed(&dir, version, &format!(
+                "(define-constant cst {val})
+                 (define-data-var var {ty} {val})
+                 (define-read-only (get-cst) cst)
+                 (define-public (set-var (v {ty})) (ok (var-set var v)))",
+                ty = type_string(&ty)
+            ));
+        }
+    }
+}
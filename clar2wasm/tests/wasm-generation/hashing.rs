@@ -1,5 +1,7 @@
 use clar2wasm::tools::crosscheck_validate;
-use clarity::vm::types::{SequenceSubtype, TypeSignature};
+use clarity::util::hash::{Hash160, Keccak256Hash, Sha256Sum, Sha512Sum, Sha512Trunc256Sum};
+use clarity::vm::types::{SequenceData, SequenceSubtype, TypeSignature};
+use clarity::vm::Value;
 use proptest::strategy::{Just, Strategy};
 use proptest::{prop_oneof, proptest};
 
@@ -16,46 +18,52 @@ fn strategies_for_hashing() -> impl Strategy<Value = PropValue> {
     .prop_flat_map(PropValue::from_type)
 }
 
+/// Checks `(word val)` against the `reference` hash of the bytes of `val`.
+/// Integers are hashed as their little-endian representation.
+fn check_hash(word: &str, val: &PropValue, reference: impl Fn(&[u8]) -> Vec<u8>) {
+    let bytes = match val.inner() {
+        Value::Int(n) => n.to_le_bytes().to_vec(),
+        Value::UInt(n) => n.to_le_bytes().to_vec(),
+        Value::Sequence(SequenceData::Buffer(buff)) => buff.data.clone(),
+        v => panic!("unexpected value to hash: {v}"),
+    };
+    let expected = Value::buff_from(reference(&bytes)).unwrap();
+
+    crosscheck_validate(&format!("({word} {val})"), |result| {
+        assert_eq!(result, expected)
+    })
+}
+
 proptest! {
     #![proptest_config(super::runtime_config())]
 
     #[test]
     fn crossprop_hashing_hash160(val in strategies_for_hashing())
     {
-        crosscheck_validate(
-            &format!("(hash160 {val})"), |_|{}
-        )
+        check_hash("hash160", &val, |bytes| Hash160::from_data(bytes).as_bytes().to_vec())
     }
 
     #[test]
     fn crossprop_hashing_keccak256(val in strategies_for_hashing())
     {
-        crosscheck_validate(
-            &format!("(keccak256 {val})"), |_|{}
-        )
+        check_hash("keccak256", &val, |bytes| Keccak256Hash::from_data(bytes).as_bytes().to_vec())
     }
 
     #[test]
     fn crossprop_hashing_sha256(val in strategies_for_hashing())
     {
-        crosscheck_validate(
-            &format!("(sha256 {val})"), |_|{}
-        )
+        check_hash("sha256", &val, |bytes| Sha256Sum::from_data(bytes).as_bytes().to_vec())
     }
 
     #[test]
     fn crossprop_hashing_sha512(val in strategies_for_hashing())
     {
-        crosscheck_validate(
-            &format!("(sha512 {val})"), |_|{}
-        )
+        check_hash("sha512", &val, |bytes| Sha512Sum::from_data(bytes).as_bytes().to_vec())
     }
 
     #[test]
     fn crossprop_hashing_sha512_256(val in strategies_for_hashing())
     {
-        crosscheck_validate(
-            &format!("(sha512/256 {val})"), |_|{}
-        )
+        check_hash("sha512/256", &val, |bytes| Sha512Trunc256Sum::from_data(bytes).as_bytes().to_vec())
     }
 }
@@ -12,6 +12,7 @@ pub mod control_flow;
 pub mod default_to;
 pub mod equal;
 pub mod functions;
+pub mod fuzz_corpus;
 pub mod hashing;
 pub mod maps;
 pub mod noop;
@@ -2,6 +2,7 @@ use clar2wasm::tools::crosscheck;
 use clarity::vm::errors::{Error, RuntimeErrorType};
 use clarity::vm::Value;
 use proptest::arbitrary::any;
+use proptest::prelude::{prop, Strategy};
 use proptest::proptest;
 
 use crate::PropValue;
@@ -45,3 +46,77 @@ proptest! {
         )
     }
 }
+
+/// Integers close to the boundaries of the 64-bit halves of a 128-bit integer
+/// and of the range of `int`, where `to-uint` changes behavior.
+fn int_boundary() -> impl Strategy<Value = i128> {
+    let anchors = vec![
+        i128::MIN,
+        -(1 << 64),
+        -(1 << 63),
+        0,
+        1 << 63,
+        1 << 64,
+        i128::MAX,
+    ];
+    (prop::sample::select(anchors), -2i128..=2)
+        .prop_map(|(anchor, offset)| anchor.saturating_add(offset))
+}
+
+/// Integers close to the boundaries of the 64-bit halves of a 128-bit integer
+/// and of the range of `int`, where `to-int` changes behavior.
+fn uint_boundary() -> impl Strategy<Value = u128> {
+    let anchors = vec![0, 1 << 63, 1 << 64, 1 << 127, u128::MAX];
+    (prop::sample::select(anchors), -2i128..=2)
+        .prop_map(|(anchor, offset)| anchor.saturating_add_signed(offset))
+}
+
+proptest! {
+    #![proptest_config(super::runtime_config())]
+
+    #[test]
+    fn crosscheck_noop_to_uint_boundaries(val in int_boundary()) {
+        let snippet = format!(
+            "(define-private (convert (n int)) (to-uint n)) (convert {})",
+            PropValue(Value::Int(val))
+        );
+
+        crosscheck(
+            &snippet,
+            match val.try_into() {
+                Ok(v) => Ok(Some(Value::UInt(v))),
+                Err(_) => Err(Error::Runtime(
+                    RuntimeErrorType::ArithmeticUnderflow,
+                    Some(Vec::new()),
+                )),
+            }
+        )
+    }
+
+    #[test]
+    fn crosscheck_noop_to_int_boundaries(val in uint_boundary()) {
+        let snippet = format!(
+            "(define-private (convert (n uint)) (to-int n)) (convert {})",
+            PropValue(Value::UInt(val))
+        );
+
+        crosscheck(
+            &snippet,
+            match val.try_into() {
+                Ok(v) => Ok(Some(Value::Int(v))),
+                Err(_) => Err(Error::Runtime(
+                    RuntimeErrorType::ArithmeticOverflow,
+                    Some(Vec::new()),
+                )),
+            }
+        )
+    }
+
+    #[test]
+    fn crosscheck_noop_conversions_round_trip(val in 0i128..=i128::MAX) {
+        crosscheck(
+            &format!("(to-int (to-uint {}))", PropValue(Value::Int(val))),
+            Ok(Some(Value::Int(val))),
+        )
+    }
+}
@@ -5,7 +5,7 @@ use clarity::vm::types::{
 use clarity::vm::Value;
 use proptest::prelude::*;
 
-use crate::{bool, buffer, int, list, prop_signature, PropValue};
+use crate::{bool, buffer, int, list, prop_signature, string_utf8, PropValue};
 
 proptest! {
     #![proptest_config(super::runtime_config())]
@@ -77,6 +77,18 @@ proptest! {
 
         crosscheck(&snippet, Ok(Some(expected)));
     }
+
+    #[test]
+    fn len_utf8_counts_scalars(seq in (0u32..=16).prop_flat_map(string_utf8)) {
+        let snippet = format!("(len {})", PropValue::from(seq.clone()));
+
+        let expected = {
+            let Value::Sequence(seq_data) = seq else { unreachable!() };
+            Value::UInt(seq_data.len() as u128)
+        };
+
+        crosscheck(&snippet, Ok(Some(expected)));
+    }
 }
 
 proptest! {
@@ -476,6 +488,18 @@ mod clarity_v2_v3 {
             crosscheck(&snippet, Ok(Some(expected)));
         }
 
+        #[test]
+        fn element_at_utf8_crosscheck((seq, idx) in (1u32..=16).prop_flat_map(|max_len| (string_utf8(max_len), (0..max_len as usize + 2)))) {
+            let snippet = format!("(element-at? {} u{idx})", PropValue::from(seq.clone()));
+
+            let expected = {
+                let Value::Sequence(seq_data) = seq else { unreachable!() };
+                seq_data.element_at(idx).expect("element_at failed").map_or_else(Value::none, |v| Value::some(v).unwrap())
+            };
+
+            crosscheck(&snippet, Ok(Some(expected)));
+        }
+
         #[test]
         fn crosscheck_replace_at(
             (seq, source, dest) in (1usize..=20).prop_flat_map(|seq_size| {